@@ -19,6 +19,7 @@ RUST_LOG=debug cargo test --workspace \
 --features avalanche-types/mnemonic \
 --features avalanche-types/subnet \
 --features avalanche-types/subnet_evm \
+--features avalanche-types/subnet_test_support \
 --features avalanche-types/wallet \
 --features avalanche-types/wallet_evm \
 --features avalanche-types/xsvm \
@@ -1,11 +1,12 @@
 use std::{
-    collections::BTreeSet,
+    collections::{BTreeMap, BTreeSet},
     fs::{self, File},
     io::{self, Error, ErrorKind, Write},
     path::Path,
+    str::FromStr,
 };
 
-use crate::{avalanchego::genesis, constants, units};
+use crate::{avalanchego::genesis, constants, ids, units};
 use serde::{Deserialize, Serialize};
 
 /// Represents AvalancheGo configuration.
@@ -722,3 +723,167 @@ fn test_config() {
 
     fs::remove_file(p).unwrap();
 }
+
+/// Validated mapping of chain aliases to chain IDs, as in the JSON object of
+/// chain IDs to their aliases avalanchego accepts for chain aliases (e.g.,
+/// `{"2ebCneCbwthjQ1rYT41nhd7M76Hc6YmosMAQrTFhBq8qeqh6tt": ["myvm", "subnet-evm"]}`).
+/// Names are unique, while a chain may have several aliases.
+/// ref. <https://docs.avax.network/nodes/maintain/chain-config-flags#chain-aliases>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AliasMap {
+    ids: BTreeMap<String, ids::Id>,
+    aliases: BTreeMap<ids::Id, BTreeSet<String>>,
+}
+
+impl AliasMap {
+    /// Parses the JSON object, rejecting chain IDs that do not decode, chain
+    /// IDs listed twice and names given to more than one chain.
+    pub fn from_json(s: &str) -> io::Result<Self> {
+        let entries: AliasEntries = serde_json::from_str(s)
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, format!("invalid JSON: {}", e)))?;
+
+        let mut map = Self::default();
+        for (id, names) in entries.0 {
+            let chain_id = ids::Id::from_str(&id).map_err(|e| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("invalid chain ID '{}' ({})", id, e),
+                )
+            })?;
+            if map.aliases.contains_key(&chain_id) {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("duplicate chain ID '{}'", id),
+                ));
+            }
+
+            let chain_aliases = map.aliases.entry(chain_id).or_default();
+            for name in names {
+                if map.ids.contains_key(&name) {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("duplicate alias '{}'", name),
+                    ));
+                }
+                chain_aliases.insert(name.clone());
+                map.ids.insert(name, chain_id);
+            }
+        }
+        Ok(map)
+    }
+
+    /// Returns the chain ID the alias names.
+    pub fn id(&self, alias: &str) -> Option<ids::Id> {
+        self.ids.get(alias).copied()
+    }
+
+    /// Returns the aliases of the chain in lexicographic order.
+    pub fn aliases(&self, chain_id: &ids::Id) -> Vec<&str> {
+        self.aliases
+            .get(chain_id)
+            .map(|names| names.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the number of aliases.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+}
+
+/// Entries of a JSON object in document order, keeping duplicate keys
+/// that a map would silently overwrite.
+struct AliasEntries(Vec<(String, Vec<String>)>);
+
+impl<'de> Deserialize<'de> for AliasEntries {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct EntriesVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for EntriesVisitor {
+            type Value = AliasEntries;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a map of chain IDs to their aliases")
+            }
+
+            fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                let mut entries = Vec::with_capacity(access.size_hint().unwrap_or(0));
+                while let Some(entry) = access.next_entry::<String, Vec<String>>()? {
+                    entries.push(entry);
+                }
+                Ok(AliasEntries(entries))
+            }
+        }
+
+        deserializer.deserialize_map(EntriesVisitor)
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features avalanchego -- avalanchego::config::test_alias_map --exact --show-output
+#[test]
+fn test_alias_map() {
+    let x_chain_id =
+        ids::Id::from_str("2oYMBNV4eNHyqk2fjjV5nVQLDbtmNJzq5s3qs3Lo6ftnC6FByM").unwrap();
+    let c_chain_id =
+        ids::Id::from_str("2q9e4r6Mu3U68nU1fYjgbR6JvwrRx36CohpAX5UQxse55x1Q5").unwrap();
+
+    let map = AliasMap::from_json(
+        r#"{
+    "2oYMBNV4eNHyqk2fjjV5nVQLDbtmNJzq5s3qs3Lo6ftnC6FByM": ["avm", "X"],
+    "2q9e4r6Mu3U68nU1fYjgbR6JvwrRx36CohpAX5UQxse55x1Q5": ["C"]
+}"#,
+    )
+    .unwrap();
+    assert_eq!(map.len(), 3);
+    assert_eq!(map.id("X"), Some(x_chain_id));
+    assert_eq!(map.id("C"), Some(c_chain_id));
+    assert_eq!(map.id("P"), None);
+    assert_eq!(map.aliases(&x_chain_id), vec!["X", "avm"]);
+    assert_eq!(map.aliases(&c_chain_id), vec!["C"]);
+    assert!(map.aliases(&ids::Id::empty()).is_empty());
+
+    assert!(AliasMap::from_json("{}").unwrap().is_empty());
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features avalanchego -- avalanchego::config::test_alias_map_invalid --exact --show-output
+#[test]
+fn test_alias_map_invalid() {
+    let err = AliasMap::from_json(
+        r#"{
+    "2oYMBNV4eNHyqk2fjjV5nVQLDbtmNJzq5s3qs3Lo6ftnC6FByM": ["X"],
+    "2q9e4r6Mu3U68nU1fYjgbR6JvwrRx36CohpAX5UQxse55x1Q5": ["X"]
+}"#,
+    )
+    .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("duplicate alias 'X'"));
+
+    let err = AliasMap::from_json(
+        r#"{
+    "2oYMBNV4eNHyqk2fjjV5nVQLDbtmNJzq5s3qs3Lo6ftnC6FByM": ["X"],
+    "2oYMBNV4eNHyqk2fjjV5nVQLDbtmNJzq5s3qs3Lo6ftnC6FByM": ["avm"]
+}"#,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("duplicate chain ID"));
+
+    let err = AliasMap::from_json(r#"{"not-an-id": ["X"]}"#).unwrap_err();
+    assert!(err.to_string().contains("invalid chain ID 'not-an-id'"));
+
+    // aliases to chain IDs is not the shape avalanchego reads
+    assert!(
+        AliasMap::from_json(r#"{"X": "2oYMBNV4eNHyqk2fjjV5nVQLDbtmNJzq5s3qs3Lo6ftnC6FByM"}"#)
+            .is_err()
+    );
+    assert!(AliasMap::from_json(r#"["X"]"#).is_err());
+}
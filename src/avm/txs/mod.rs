@@ -140,6 +140,101 @@ impl Tx {
 
         Ok(())
     }
+
+    /// Returns the unsigned transaction bytes (with the codec version and
+    /// type ID) that the credentials sign, re-packed from "base_tx".
+    /// Packing is deterministic, so these match the bytes originally signed
+    /// even if the transaction was parsed without its metadata.
+    pub fn unsigned_bytes(&self) -> Result<Vec<u8>> {
+        let packer = self.base_tx.pack(codec::VERSION, Self::type_id())?;
+        Ok(packer.take_bytes().to_vec())
+    }
+
+    /// Verifies the transaction before accepting it on the network of
+    /// "expected_network_id": it must have been built for that network, and
+    /// burn exactly "fee" of "fee_asset_id" without creating any value.
+    pub fn verify(
+        &self,
+        expected_network_id: u32,
+        fee_asset_id: &ids::Id,
+        fee: u64,
+    ) -> std::io::Result<()> {
+        txs::verify_network(&self.base_tx, expected_network_id)?;
+        txs::verify_conservation(&self.base_tx, fee_asset_id, fee)
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- avm::txs::test_verify --exact --show-output
+#[test]
+fn test_verify() {
+    let avax = ids::Id::from_slice(&[1; 32]);
+    let tx = Tx::new(txs::Tx {
+        network_id: 5,
+        transferable_inputs: Some(vec![txs::transferable::Input {
+            asset_id: avax,
+            transfer_input: Some(key::secp256k1::txs::transfer::Input {
+                amount: 1_000,
+                sig_indices: vec![0],
+            }),
+            ..Default::default()
+        }]),
+        ..txs::Tx::default()
+    });
+    assert!(tx.verify(5, &avax, 1_000).is_ok());
+    assert!(tx.verify(5, &avax, 999).is_err());
+
+    // signed for fuji, replayed on mainnet
+    let err = tx.verify(1, &avax, 1_000).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("does not match expected network ID 1"));
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- avm::txs::test_unsigned_bytes --exact --show-output
+#[test]
+fn test_unsigned_bytes() {
+    use crate::ids::short;
+
+    let test_key = key::secp256k1::private_key::Key::from_cb58(
+        "PrivateKey-24jUJ9vZexUM6expyMcT48LBx27k1m7xpraoV62oSQAHdziao5",
+    )
+    .unwrap();
+    let test_pub_key = test_key.to_public_key();
+    let test_key_short_addr = short::Id::from_slice(&test_pub_key.to_short_bytes().unwrap());
+
+    let mut tx = Tx::new(txs::Tx {
+        network_id: 10,
+        blockchain_id: ids::Id::from_slice(&[5, 4, 3, 2, 1]),
+        transferable_outputs: Some(vec![txs::transferable::Output {
+            asset_id: ids::Id::from_slice(&[1, 2, 3]),
+            transfer_output: Some(key::secp256k1::txs::transfer::Output {
+                amount: 12345,
+                output_owners: key::secp256k1::txs::OutputOwners {
+                    locktime: 0,
+                    threshold: 1,
+                    addresses: vec![test_key_short_addr],
+                },
+            }),
+            ..txs::transferable::Output::default()
+        }]),
+        memo: Some(vec![0x00, 0x01, 0x02, 0x03]),
+        ..txs::Tx::default()
+    });
+    tokio_test::block_on(tx.sign(vec![vec![test_key.clone(), test_key]])).unwrap();
+    let metadata = tx.base_tx.metadata.clone().unwrap();
+
+    // parsed transactions carry no metadata
+    tx.base_tx.metadata = None;
+    let unsigned_bytes = tx.unsigned_bytes().unwrap();
+    assert_eq!(unsigned_bytes, metadata.tx_bytes_with_no_signature);
+
+    let digest = hash::sha256(&unsigned_bytes);
+    for fx_cred in tx.fx_creds.iter() {
+        for sig in fx_cred.cred.signatures.iter() {
+            let recovered = key::secp256k1::public_key::Key::from_signature(&digest, sig).unwrap();
+            assert_eq!(recovered, test_pub_key);
+        }
+    }
 }
 
 /// RUST_LOG=debug cargo test --package avalanche-types --lib -- avm::txs::test_tx_serialization_with_two_signers --exact --show-output
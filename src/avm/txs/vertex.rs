@@ -1,4 +1,15 @@
-use crate::{errors::Result, ids, packer::Packer, txs::raw};
+use crate::{
+    codec,
+    errors::{Error, Result},
+    hash, ids,
+    packer::Packer,
+    txs::raw,
+};
+
+/// Maximum number of parent vertices and of transactions in a vertex.
+/// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/snow/engine/avalanche/vertex#StatelessVertex>
+pub const MAX_PARENTS: usize = 128;
+pub const MAX_TXS: usize = 128;
 
 /// Vertex represents a set of transactions for Avalanche X-chain.
 ///
@@ -13,6 +24,87 @@ pub struct Vertex {
     pub txs: Vec<Vec<u8>>,
 }
 
+impl Vertex {
+    /// Returns the vertex ID, the SHA256 digest of its bytes.
+    pub fn id(&self) -> Result<ids::Id> {
+        let d = self.to_bytes()?;
+        Ok(ids::Id::from_slice(&hash::sha256(d)))
+    }
+
+    /// Validates the vertex structure, including that the parent IDs are
+    /// sorted and unique, and the txs sorted by their hashes.
+    /// ref. "avalanchego/snow/engine/avalanche/vertex.innerStatelessVertex.Verify"
+    pub fn verify(&self) -> Result<()> {
+        let invalid = |message: String| Error::Other {
+            message,
+            retryable: false,
+        };
+        if self.codec_version != codec::VERSION {
+            return Err(invalid(format!(
+                "unknown vertex codec version {}",
+                self.codec_version
+            )));
+        }
+        if self.epoch != 0 {
+            return Err(invalid(format!("invalid vertex epoch {}", self.epoch)));
+        }
+        if self.parent_ids.len() > MAX_PARENTS {
+            return Err(invalid(format!(
+                "vertex contains too many parents {}",
+                self.parent_ids.len()
+            )));
+        }
+        if self.txs.is_empty() || self.txs.len() > MAX_TXS {
+            return Err(invalid(format!(
+                "vertex contains invalid number of txs {}",
+                self.txs.len()
+            )));
+        }
+        if !self.parent_ids.windows(2).all(|w| w[0] < w[1]) {
+            return Err(invalid(
+                "vertex parent IDs are not sorted and unique".to_string(),
+            ));
+        }
+        let txs: Vec<raw::Data> = self
+            .txs
+            .iter()
+            .map(|tx| raw::Data::from_slice(tx))
+            .collect();
+        if !txs.windows(2).all(|w| w[0] < w[1]) {
+            return Err(invalid("vertex txs are not sorted and unique".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Encodes the vertex with [`Packer::pack_vertex`], after checking it is
+    /// valid (and thus already sorted).
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        self.verify()?;
+
+        let packer = Packer::new((1 << 31) - 1, 0);
+        packer.pack_vertex(&mut self.clone())?;
+        Ok(packer.take_bytes().to_vec())
+    }
+
+    /// Parses the vertex with [`Packer::unpack_vertex`], rejecting trailing
+    /// data and invalid (e.g., unsorted) vertices.
+    pub fn from_bytes(d: &[u8]) -> Result<Self> {
+        let packer = Packer::load_bytes_for_unpack(d.len() + 1024, d);
+        let vtx = packer.unpack_vertex()?;
+        if packer.get_offset() != d.len() {
+            return Err(Error::Other {
+                message: format!(
+                    "vertex has {} trailing bytes",
+                    d.len() - packer.get_offset()
+                ),
+                retryable: false,
+            });
+        }
+        vtx.verify()?;
+        Ok(vtx)
+    }
+}
+
 impl Packer {
     /// Encodes vertex fields with codec version and packer.
     ///
@@ -59,6 +151,12 @@ impl Packer {
         let epoch = self.unpack_u32()?;
 
         let parent_ids_size = self.unpack_u32()?;
+        if parent_ids_size as usize > MAX_PARENTS {
+            return Err(Error::Other {
+                message: format!("vertex contains too many parents {}", parent_ids_size),
+                retryable: false,
+            });
+        }
         let mut parent_ids: Vec<ids::Id> = Vec::new();
         for _ in 0..parent_ids_size {
             let parent_id = self.unpack_bytes(ids::LEN)?;
@@ -67,6 +165,12 @@ impl Packer {
         }
 
         let txs_size = self.unpack_u32()?;
+        if txs_size as usize > MAX_TXS {
+            return Err(Error::Other {
+                message: format!("vertex contains too many txs {}", txs_size),
+                retryable: false,
+            });
+        }
         let mut txs: Vec<Vec<u8>> = Vec::new();
         for _ in 0..txs_size {
             let tx_size = self.unpack_u32()?;
@@ -185,3 +289,50 @@ fn test_pack_and_unpack() {
     let vtx_unpacked = packer.unpack_vertex().unwrap();
     assert!(vtx == vtx_unpacked);
 }
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- avm::txs::vertex::test_from_to_bytes --exact --show-output
+#[test]
+fn test_from_to_bytes() {
+    let mut vtx = Vertex {
+        codec_version: 0,
+        chain_id: ids::Id::from_slice(&(1..=32).collect::<Vec<u8>>()),
+        height: 42,
+        epoch: 0,
+        parent_ids: vec![
+            ids::Id::from_slice(&[0x0b; 32]),
+            ids::Id::from_slice(&[0x0a; 32]),
+        ],
+        txs: vec![vec![0xde, 0xad, 0xbe, 0xef], vec![0x01]],
+    };
+    // unsorted vertices are rejected, rather than sorted on encoding
+    assert!(vtx.to_bytes().is_err());
+
+    let packer = Packer::new(1024, 0);
+    packer.pack_vertex(&mut vtx).unwrap();
+    let vtx_bytes = packer.take_bytes().to_vec();
+
+    let parsed = Vertex::from_bytes(&vtx_bytes).unwrap();
+    assert_eq!(parsed, vtx);
+    assert_eq!(
+        parsed.parent_ids,
+        vec![
+            ids::Id::from_slice(&[0x0a; 32]),
+            ids::Id::from_slice(&[0x0b; 32])
+        ]
+    );
+    assert_eq!(parsed.to_bytes().unwrap(), vtx_bytes);
+    assert_eq!(
+        parsed.id().unwrap(),
+        ids::Id::from_slice(&hash::sha256(&vtx_bytes))
+    );
+
+    // parent IDs start after the version, chain ID, height, epoch and count
+    let mut unsorted = vtx_bytes.clone();
+    unsorted[50..82].copy_from_slice(&[0x0b; 32]);
+    unsorted[82..114].copy_from_slice(&[0x0a; 32]);
+    assert!(Vertex::from_bytes(&unsorted).is_err());
+
+    let mut trailing = vtx_bytes;
+    trailing.push(0x00);
+    assert!(Vertex::from_bytes(&trailing).is_err());
+}
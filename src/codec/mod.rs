@@ -1,7 +1,11 @@
 pub mod serde;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
+use crate::{
+    errors::{Error, Result},
+    packer,
+};
 use lazy_static::lazy_static;
 
 pub const VERSION: u16 = 0;
@@ -79,3 +83,101 @@ lazy_static! {
         m
     };
 }
+
+/// Validates the type ID registry (e.g., "X_TYPES", "P_TYPES") against a
+/// known snapshot of avalanchego type IDs, meant as a startup sanity check.
+/// Returns an error listing every missing, mismatched, or unexpected entry.
+pub fn assert_type_ids(registry: &HashMap<String, usize>, expected: &[(u32, &str)]) -> Result<()> {
+    let mut diffs: Vec<String> = Vec::new();
+
+    let mut expected_names = HashSet::new();
+    for (type_id, type_name) in expected.iter() {
+        expected_names.insert(*type_name);
+        match registry.get(*type_name) {
+            Some(v) if *v == *type_id as usize => {}
+            Some(v) => diffs.push(format!(
+                "'{type_name}' expected type ID {type_id}, found {v}"
+            )),
+            None => diffs.push(format!(
+                "'{type_name}' expected type ID {type_id}, found none"
+            )),
+        }
+    }
+
+    let mut unexpected: Vec<(&String, &usize)> = registry
+        .iter()
+        .filter(|(k, _)| !expected_names.contains(k.as_str()))
+        .collect();
+    unexpected.sort_by_key(|(_, v)| **v);
+    for (type_name, type_id) in unexpected {
+        diffs.push(format!("unexpected '{type_name}' with type ID {type_id}"));
+    }
+
+    if diffs.is_empty() {
+        return Ok(());
+    }
+    Err(Error::Other {
+        message: format!("codec type ID drift: {}", diffs.join(", ")),
+        retryable: false,
+    })
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- codec::test_assert_type_ids --exact --show-output
+#[test]
+fn test_assert_type_ids() {
+    // ref. <https://github.com/ava-labs/avalanchego/blob/v1.9.11/vms/avm/txs/codec.go>
+    let mut snapshot: Vec<(u32, &str)> = vec![
+        (0, "avm.BaseTx"),
+        (1, "avm.CreateAssetTx"),
+        (2, "avm.OperationTx"),
+        (3, "avm.ImportTx"),
+        (4, "avm.ExportTx"),
+        (5, "secp256k1fx.TransferInput"),
+        (6, "secp256k1fx.MintOutput"),
+        (7, "secp256k1fx.TransferOutput"),
+        (8, "secp256k1fx.MintOperation"),
+        (9, "secp256k1fx.Credential"),
+        (10, "nftfx.MintOutput"),
+        (11, "nftfx.TransferOutput"),
+        (12, "nftfx.MintOperation"),
+        (13, "nftfx.TransferOperation"),
+        (14, "nftfx.Credential"),
+        (15, "propertyfx.MintOutput"),
+        (16, "propertyfx.OwnedOutput"),
+        (17, "propertyfx.MintOperation"),
+        (18, "propertyfx.BurnOperation"),
+        (19, "propertyfx.Credential"),
+    ];
+    assert_type_ids(&X_TYPES, &snapshot).unwrap();
+
+    // tampered type ID
+    snapshot[4] = (5, "avm.ExportTx");
+    let err = assert_type_ids(&X_TYPES, &snapshot).unwrap_err();
+    assert!(err.contains("'avm.ExportTx' expected type ID 5, found 4"));
+
+    // missing from the snapshot
+    snapshot.truncate(4);
+    let err = assert_type_ids(&X_TYPES, &snapshot).unwrap_err();
+    assert!(err.contains("unexpected 'avm.ExportTx' with type ID 4"));
+}
+
+/// Unpacks the codec version and the type ID that prefix
+/// the marshaled bytes of a Go interface type (e.g., "avm.ExportTx").
+/// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/codec#Manager.Unmarshal>
+pub fn unpack_version_and_type_id(d: &[u8]) -> Result<(u16, u32)> {
+    let packer = packer::Packer::load_bytes_for_unpack(d.len() + 1024, d);
+    let version = packer.unpack_u16()?;
+    let type_id = packer.unpack_u32()?;
+    Ok((version, type_id))
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- codec::test_unpack_version_and_type_id --exact --show-output
+#[test]
+fn test_unpack_version_and_type_id() {
+    let (version, type_id) =
+        unpack_version_and_type_id(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x01]).unwrap();
+    assert_eq!(version, VERSION);
+    assert_eq!(type_id, 4);
+
+    assert!(unpack_version_and_type_id(&[0x00, 0x00, 0x00]).is_err());
+}
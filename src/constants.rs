@@ -6,6 +6,49 @@ pub const DEFAULT_CUSTOM_NETWORK_ID: u32 = 1000000;
 
 pub const FALLBACK_HRP: &str = "custom";
 
+/// Well-known networks, as named by "info.getNetworkName".
+/// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/utils/constants>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Network {
+    Mainnet,
+    Cascade,
+    Denali,
+    Everest,
+    Fuji,
+    Local,
+}
+
+impl Network {
+    /// Returns the network of the name, or "None" for custom networks
+    /// (e.g., "network-1000000").
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "mainnet" => Some(Self::Mainnet),
+            "cascade" => Some(Self::Cascade),
+            "denali" => Some(Self::Denali),
+            "everest" => Some(Self::Everest),
+            "fuji" => Some(Self::Fuji),
+            "local" => Some(Self::Local),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Mainnet => "mainnet",
+            Self::Cascade => "cascade",
+            Self::Denali => "denali",
+            Self::Everest => "everest",
+            Self::Fuji => "fuji",
+            Self::Local => "local",
+        }
+    }
+
+    pub fn network_id(&self) -> u32 {
+        NETWORK_NAME_TO_NETWORK_ID[self.name()]
+    }
+}
+
 lazy_static! {
     /// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/utils/constants>
     pub static ref NETWORK_ID_TO_NETWORK_NAME: HashMap<u32, &'static str> = {
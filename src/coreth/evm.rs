@@ -0,0 +1,4 @@
+//! C-chain transaction helpers.
+//! The EIP-1559 transaction and its fee bump live in [`crate::evm::eip1559`],
+//! as they are shared with subnet-evm.
+pub use crate::evm::eip1559::{bump_fees, meets_price_bump, DEFAULT_PRICE_BUMP_PERCENT};
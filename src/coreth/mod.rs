@@ -1,2 +1,5 @@
 pub mod chain_config;
+#[cfg(feature = "evm")]
+#[cfg_attr(docsrs, doc(cfg(feature = "evm")))]
+pub mod evm;
 pub mod genesis;
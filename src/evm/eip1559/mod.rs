@@ -199,6 +199,143 @@ pub fn decode_and_verify_signed_rlp(
     Ok((decoded_tx, tx_hash, signer_addr, sig))
 }
 
+/// Minimum fee increase, in percent, the coreth mempool requires to replace
+/// a pending transaction with the same nonce.
+/// ref. <https://pkg.go.dev/github.com/ava-labs/coreth/core/txpool#DefaultConfig>
+pub const DEFAULT_PRICE_BUMP_PERCENT: u64 = 10;
+
+/// Returns the replacement of a pending transaction: the same transaction
+/// and nonce, with both the max fee and the priority fee raised by at least
+/// "min_increase_percent" (and by at least 1 wei, since the mempool requires
+/// strictly higher fees). Errors if the bump is below the mempool threshold
+/// [`DEFAULT_PRICE_BUMP_PERCENT`], or if the original does not set its nonce
+/// and fees.
+pub fn bump_fees(original: &Transaction, min_increase_percent: u64) -> io::Result<Transaction> {
+    if min_increase_percent < DEFAULT_PRICE_BUMP_PERCENT {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "fee bump {}% is below the replacement threshold {}%",
+                min_increase_percent, DEFAULT_PRICE_BUMP_PERCENT
+            ),
+        ));
+    }
+    if original.signer_nonce.is_none() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "replacement requires the original nonce",
+        ));
+    }
+    let (max_priority_fee_per_gas, max_fee_per_gas) =
+        match (original.max_priority_fee_per_gas, original.max_fee_per_gas) {
+            (Some(tip), Some(fee_cap)) => (tip, fee_cap),
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "replacement requires the original max fee and priority fee",
+                ))
+            }
+        };
+
+    let max_priority_fee_per_gas = bump(max_priority_fee_per_gas, min_increase_percent)?;
+    let max_fee_per_gas = bump(max_fee_per_gas, min_increase_percent)?;
+
+    let mut replacement = original.clone();
+    replacement.max_priority_fee_per_gas = Some(max_priority_fee_per_gas);
+    // the fee cap may not fall below the priority fee
+    replacement.max_fee_per_gas = Some(max_fee_per_gas.max(max_priority_fee_per_gas));
+    Ok(replacement)
+}
+
+/// Returns true if the replacement fee is strictly higher than the original
+/// and at least "percent" above it, as the mempool checks.
+pub fn meets_price_bump(original: U256, replacement: U256, percent: u64) -> bool {
+    if replacement <= original {
+        return false;
+    }
+    match original.checked_mul(U256::from(100 + percent)) {
+        Some(threshold) => replacement >= threshold / 100,
+        None => false,
+    }
+}
+
+/// Raises the fee by the percent, rounding up.
+fn bump(fee: U256, percent: u64) -> io::Result<U256> {
+    let bumped = fee
+        .checked_mul(U256::from(100 + percent))
+        .map(|f| (f + 99) / 100)
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "bumped fee overflows U256"))?;
+    Ok(bumped.max(fee + 1))
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features="evm" -- evm::eip1559::test_bump_fees --exact --show-output
+#[test]
+fn test_bump_fees() {
+    let original = Transaction::new()
+        .chain_id(43114_u64)
+        .signer_nonce(7_u64)
+        .max_priority_fee_per_gas(1_500_000_001_u64)
+        .max_fee_per_gas(25_000_000_000_u64)
+        .gas_limit(21000_u64)
+        .recipient(H160::repeat_byte(1))
+        .value(1_u64);
+
+    for percent in [DEFAULT_PRICE_BUMP_PERCENT, 12, 50, 100] {
+        let replacement = bump_fees(&original, percent).unwrap();
+        assert_eq!(replacement.signer_nonce, original.signer_nonce);
+        assert_eq!(replacement.recipient, original.recipient);
+        assert_eq!(replacement.value, original.value);
+        assert_eq!(replacement.gas_limit, original.gas_limit);
+
+        let (old_tip, new_tip) = (
+            original.max_priority_fee_per_gas.unwrap(),
+            replacement.max_priority_fee_per_gas.unwrap(),
+        );
+        let (old_cap, new_cap) = (
+            original.max_fee_per_gas.unwrap(),
+            replacement.max_fee_per_gas.unwrap(),
+        );
+        assert!(new_tip * 100 >= old_tip * (100 + percent));
+        assert!(new_cap * 100 >= old_cap * (100 + percent));
+        assert!(meets_price_bump(
+            old_tip,
+            new_tip,
+            DEFAULT_PRICE_BUMP_PERCENT
+        ));
+        assert!(meets_price_bump(
+            old_cap,
+            new_cap,
+            DEFAULT_PRICE_BUMP_PERCENT
+        ));
+    }
+
+    // 10% of 25 gwei rounds to exactly 27.5 gwei
+    let replacement = bump_fees(&original, 10).unwrap();
+    assert_eq!(
+        replacement.max_fee_per_gas.unwrap(),
+        U256::from(27_500_000_000_u64)
+    );
+    assert_eq!(
+        replacement.max_priority_fee_per_gas.unwrap(),
+        U256::from(1_650_000_002_u64)
+    );
+
+    // tiny fees still go up
+    let tiny = original
+        .clone()
+        .max_priority_fee_per_gas(0_u64)
+        .max_fee_per_gas(1_u64);
+    let replacement = bump_fees(&tiny, 10).unwrap();
+    assert_eq!(replacement.max_priority_fee_per_gas.unwrap(), U256::from(1));
+    assert_eq!(replacement.max_fee_per_gas.unwrap(), U256::from(2));
+
+    assert!(!meets_price_bump(U256::from(100), U256::from(109), 10));
+    assert!(!meets_price_bump(U256::from(0), U256::from(0), 10));
+    assert!(bump_fees(&original, 9).is_err());
+    assert!(bump_fees(&Transaction::new().signer_nonce(1_u64), 10).is_err());
+    assert!(bump_fees(&original.clone().max_fee_per_gas(U256::MAX), 10).is_err());
+}
+
 /// RUST_LOG=debug cargo test --package avalanche-types --lib --features="evm" -- evm::eip1559::test_transaction --exact --show-output
 #[test]
 fn test_transaction() {
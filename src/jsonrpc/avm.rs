@@ -1,6 +1,11 @@
 use std::io::{self, Error, ErrorKind};
 
-use crate::{choices, codec::serde::hex_0x_utxo::Hex0xUtxo, ids, jsonrpc, txs};
+use crate::{
+    avm::txs::vertex::Vertex,
+    choices,
+    codec::{self, serde::hex_0x_utxo::Hex0xUtxo},
+    formatting, ids, jsonrpc, txs,
+};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 
@@ -209,6 +214,276 @@ fn test_get_tx_status() {
     assert_eq!(resp, expected);
 }
 
+/// ref. <https://docs.avax.network/apis/avalanchego/apis/x-chain#avmgettx>
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct GetAtomicTxResponse {
+    pub jsonrpc: String,
+    pub id: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<GetAtomicTxResult>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<jsonrpc::ResponseError>,
+}
+
+impl Default for GetAtomicTxResponse {
+    fn default() -> Self {
+        Self::default()
+    }
+}
+
+impl GetAtomicTxResponse {
+    pub fn default() -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id: 1,
+            result: None,
+            error: None,
+        }
+    }
+
+    /// Returns the decoded signed transaction with its status from the
+    /// "avm.getTxStatus" response, or the JSON-RPC error of either call.
+    pub fn atomic_tx(&self, status: &GetTxStatusResponse) -> io::Result<AtomicTx> {
+        if let Some(e) = &self.error {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!("avm.getTx failed ({}: {})", e.code, e.message),
+            ));
+        }
+        if let Some(e) = &status.error {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!("avm.getTxStatus failed ({}: {})", e.code, e.message),
+            ));
+        }
+        let tx_bytes = match &self.result {
+            Some(result) => result.tx_bytes()?,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "avm.getTx returned no result",
+                ))
+            }
+        };
+        match &status.result {
+            Some(result) => Ok(AtomicTx {
+                tx_bytes,
+                status: result.status.clone(),
+            }),
+            None => Err(Error::new(
+                ErrorKind::InvalidData,
+                "avm.getTxStatus returned no result",
+            )),
+        }
+    }
+}
+
+/// ref. <https://docs.avax.network/apis/avalanchego/apis/x-chain#avmgettx>
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct GetAtomicTxResult {
+    /// Signed transaction bytes in "hex" encoding with the checksum.
+    pub tx: String,
+    pub encoding: String,
+}
+
+impl Default for GetAtomicTxResult {
+    fn default() -> Self {
+        Self::default()
+    }
+}
+
+impl GetAtomicTxResult {
+    pub fn default() -> Self {
+        Self {
+            tx: String::new(),
+            encoding: String::new(),
+        }
+    }
+
+    /// Decodes the "hex"-encoded transaction into its signed bytes.
+    pub fn tx_bytes(&self) -> io::Result<Vec<u8>> {
+        formatting::decode_hex_with_checksum(self.tx.trim_start_matches("0x").as_bytes())
+    }
+}
+
+/// Signed atomic transaction returned by "avm.getTx", with its status.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct AtomicTx {
+    /// Signed transaction bytes, with the "hex" checksum verified.
+    pub tx_bytes: Vec<u8>,
+    pub status: choices::status::Status,
+}
+
+impl AtomicTx {
+    /// Returns the codec type ID of the transaction (e.g., "avm.ExportTx").
+    pub fn type_id(&self) -> io::Result<u32> {
+        let (_, type_id) = codec::unpack_version_and_type_id(&self.tx_bytes)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e.message()))?;
+        Ok(type_id)
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- jsonrpc::avm::test_get_atomic_tx --exact --show-output
+#[test]
+fn test_get_atomic_tx() {
+    // signed "avm.ExportTx" from "avm::txs::export::test_export_tx_serialization_with_two_signers"
+    let resp: GetAtomicTxResponse = serde_json::from_str(
+        "
+
+{
+    \"jsonrpc\": \"2.0\",
+    \"result\": {
+        \"tx\": \"0x00000000000400000002ffffffffeeeeeeeeddddddddccccccccbbbbbbbbaaaaaaaa999999998888888800000000000000010f2f4f6f8eaeceee0d2d4d6d8cacccec0b2b4b6b8aaacaea0929496988a8c8e8000000001f3f5f7f9ebedefe1d3d5d7d9cbcdcfc1b3b5b7b9abadafa1939597998b8d8f80000000500000000000003e8000000010000000000000004000102031f8f9f0f1e8e9e0e2d7dadfd2c7cacfc3b6bbbeb3a6abaea4959c9d94858c8d80000000000000002000000090000000261dd9bffc049956ed7f8cd92ecda036eacb8169e5383c03a2e885b5fc6ef2ebe5059728d0fa65966932888b4563b777c59a58fe02af3cc3132effe7d3d9f14940161dd9bffc049956ed7f8cd92ecda036eacb8169e5383c03a2e885b5fc6ef2ebe5059728d0fa65966932888b4563b777c59a58fe02af3cc3132effe7d3d9f149401000000090000000261dd9bffc049956ed7f8cd92ecda036eacb8169e5383c03a2e885b5fc6ef2ebe5059728d0fa65966932888b4563b777c59a58fe02af3cc3132effe7d3d9f14940161dd9bffc049956ed7f8cd92ecda036eacb8169e5383c03a2e885b5fc6ef2ebe5059728d0fa65966932888b4563b777c59a58fe02af3cc3132effe7d3d9f149401669556cd\",
+        \"encoding\": \"hex\"
+    },
+    \"id\": 1
+}
+
+",
+    )
+    .unwrap();
+
+    assert_eq!(resp.result.as_ref().unwrap().encoding, "hex");
+
+    let status = GetTxStatusResponse {
+        result: Some(GetTxStatusResult {
+            status: choices::status::Status::Accepted,
+        }),
+        ..GetTxStatusResponse::default()
+    };
+    let atomic_tx = resp.atomic_tx(&status).unwrap();
+    assert_eq!(atomic_tx.tx_bytes.len(), 462);
+    assert_eq!(atomic_tx.status, choices::status::Status::Accepted);
+    assert_eq!(
+        atomic_tx.type_id().unwrap(),
+        crate::avm::txs::export::Tx::type_id()
+    );
+
+    // a corrupted checksum fails the decoding
+    let mut corrupted = resp.clone();
+    let tx = &mut corrupted.result.as_mut().unwrap().tx;
+    tx.replace_range(tx.len() - 2.., "00");
+    assert!(corrupted.atomic_tx(&status).is_err());
+
+    let not_found = GetTxStatusResponse {
+        result: None,
+        error: Some(jsonrpc::ResponseError {
+            code: -32000,
+            message: "not found".to_string(),
+            data: None,
+        }),
+        ..GetTxStatusResponse::default()
+    };
+    assert!(resp.atomic_tx(&not_found).is_err());
+}
+
+/// Response for "avm.getVertexByHeight".
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct GetVertexByHeightResponse {
+    pub jsonrpc: String,
+    pub id: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<GetVertexByHeightResult>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<jsonrpc::ResponseError>,
+}
+
+impl GetVertexByHeightResponse {
+    /// Returns the parsed vertex, or "None" if no vertex is accepted at the
+    /// height yet.
+    pub fn vertex(&self) -> io::Result<Option<Vertex>> {
+        if let Some(e) = &self.error {
+            if e.message.contains("not found") {
+                return Ok(None);
+            }
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!("avm.getVertexByHeight failed ({}: {})", e.code, e.message),
+            ));
+        }
+        match &self.result {
+            Some(result) => result.vertex().map(Some),
+            None => Err(Error::new(
+                ErrorKind::InvalidData,
+                "avm.getVertexByHeight returned no result",
+            )),
+        }
+    }
+}
+
+/// Result of "avm.getVertexByHeight".
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct GetVertexByHeightResult {
+    /// Vertex bytes in "hex" encoding with the checksum.
+    pub bytes: String,
+    pub encoding: String,
+}
+
+impl GetVertexByHeightResult {
+    /// Decodes the "hex"-encoded vertex into its bytes.
+    pub fn vertex_bytes(&self) -> io::Result<Vec<u8>> {
+        formatting::decode_hex_with_checksum(self.bytes.trim_start_matches("0x").as_bytes())
+    }
+
+    /// Parses and verifies the vertex from its bytes, rejecting trailing bytes.
+    pub fn vertex(&self) -> io::Result<Vertex> {
+        let d = self.vertex_bytes()?;
+        Vertex::from_bytes(&d).map_err(|e| Error::new(ErrorKind::InvalidData, e.message()))
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- jsonrpc::avm::test_get_vertex_by_height --exact --show-output
+#[test]
+fn test_get_vertex_by_height() {
+    let mut vtx = Vertex {
+        codec_version: 0,
+        chain_id: ids::Id::from_slice(&[0x3d, 0x0a, 0xd1, 0x2b]),
+        height: 1234567,
+        epoch: 0,
+        parent_ids: vec![ids::Id::from_slice(&[0x01, 0x0a, 0xd1, 0x2b])],
+        txs: vec![vec![0x01, 0x02, 0x03]],
+    };
+    let packer = crate::packer::Packer::new(1024, 0);
+    packer.pack_vertex(&mut vtx).unwrap();
+    let vtx_bytes = packer.take_bytes().to_vec();
+    let bytes = formatting::encode_hex_with_checksum(&vtx_bytes);
+
+    let resp: GetVertexByHeightResponse = serde_json::from_str(&format!(
+        r#"{{"jsonrpc":"2.0","result":{{"bytes":"0x{}","encoding":"hex"}},"id":1}}"#,
+        bytes
+    ))
+    .unwrap();
+    let parsed = resp.vertex().unwrap().unwrap();
+    assert_eq!(parsed.height, 1234567);
+    assert_eq!(parsed, vtx);
+
+    // trailing bytes are rejected
+    let mut trailing = vtx_bytes.clone();
+    trailing.push(0x00);
+    let resp: GetVertexByHeightResponse = serde_json::from_str(&format!(
+        r#"{{"jsonrpc":"2.0","result":{{"bytes":"0x{}","encoding":"hex"}},"id":1}}"#,
+        formatting::encode_hex_with_checksum(&trailing)
+    ))
+    .unwrap();
+    assert!(resp.vertex().is_err());
+
+    let resp: GetVertexByHeightResponse = serde_json::from_str(
+        r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"couldn't get vertex at height 99999999: not found"},"id":1}"#,
+    )
+    .unwrap();
+    assert_eq!(resp.vertex().unwrap(), None);
+
+    let resp: GetVertexByHeightResponse = serde_json::from_str(
+        r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"database closed"},"id":1}"#,
+    )
+    .unwrap();
+    assert!(resp.vertex().is_err());
+}
+
 /// ref. <https://docs.avax.network/build/avalanchego-apis/issuing-api-calls>
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct GetUtxosRequest {
@@ -485,6 +760,30 @@ pub struct GetAssetDescriptionResponse {
     pub error: Option<jsonrpc::ResponseError>,
 }
 
+impl GetAssetDescriptionResponse {
+    /// Returns the asset description, or the JSON-RPC error if the call failed.
+    pub fn asset_description(&self) -> io::Result<AssetDescription> {
+        if let Some(e) = &self.error {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!("avm.getAssetDescription failed ({}: {})", e.code, e.message),
+            ));
+        }
+        match &self.result {
+            Some(result) => Ok(AssetDescription {
+                asset_id: result.asset_id,
+                name: result.name.clone(),
+                symbol: result.symbol.clone(),
+                denomination: result.denomination,
+            }),
+            None => Err(Error::new(
+                ErrorKind::InvalidData,
+                "avm.getAssetDescription returned no result",
+            )),
+        }
+    }
+}
+
 /// ref. <https://docs.avax.network/build/avalanchego-apis/x-chain/#avmgetassetdescription>
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
@@ -516,6 +815,16 @@ impl GetAssetDescriptionResult {
     }
 }
 
+/// Immutable metadata of an X-chain asset.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct AssetDescription {
+    pub asset_id: ids::Id,
+    pub name: String,
+    pub symbol: String,
+    /// Number of decimal places of the asset amounts (e.g., 9 for AVAX).
+    pub denomination: usize,
+}
+
 /// RUST_LOG=debug cargo test --package avalanche-types --lib -- jsonrpc::avm::test_get_asset_description --exact --show-output
 #[test]
 fn test_get_asset_description() {
@@ -553,6 +862,26 @@ fn test_get_asset_description() {
         error: None,
     };
     assert_eq!(resp, expected);
+
+    let desc = resp.asset_description().unwrap();
+    assert_eq!(desc.symbol, "AVAX");
+    assert_eq!(desc.denomination, 9);
+    assert_eq!(
+        desc.asset_id.to_string(),
+        "2fombhL7aGPwj3KH4bfrmJwW6PVnMobf9Y2fn9GwxiAAJyFDbe"
+    );
+
+    let failed = GetAssetDescriptionResponse {
+        jsonrpc: "2.0".to_string(),
+        id: 1,
+        result: None,
+        error: Some(jsonrpc::ResponseError {
+            code: -32000,
+            message: String::from("asset not found"),
+            data: None,
+        }),
+    };
+    assert!(failed.asset_description().is_err());
 }
 
 /// ref. <https://docs.avax.network/build/avalanchego-apis/issuing-api-calls>
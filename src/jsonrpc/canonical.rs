@@ -0,0 +1,92 @@
+//! Canonical JSON encoding for byte-stable request bodies.
+use std::io::{self, Error, ErrorKind};
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// Serializes the value with object keys sorted at every level and without
+/// insignificant whitespace, so semantically-equal values (e.g., structs and
+/// hash maps with the same fields) encode to identical bytes.
+pub fn to_canonical_json<T>(value: &T) -> io::Result<String>
+where
+    T: Serialize + ?Sized,
+{
+    let value = serde_json::to_value(value)
+        .map_err(|e| Error::new(ErrorKind::Other, format!("failed to serialize JSON {}", e)))?;
+
+    let mut out = String::new();
+    write_value(&value, &mut out)?;
+    Ok(out)
+}
+
+fn write_value(value: &Value, out: &mut String) -> io::Result<()> {
+    match value {
+        Value::Array(values) => {
+            out.push('[');
+            for (i, v) in values.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(v, out)?;
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+
+            out.push('{');
+            for (i, (k, v)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&encode_scalar(&Value::String(k.clone()))?);
+                out.push(':');
+                write_value(v, out)?;
+            }
+            out.push('}');
+        }
+        scalar => out.push_str(&encode_scalar(scalar)?),
+    }
+    Ok(())
+}
+
+fn encode_scalar(value: &Value) -> io::Result<String> {
+    serde_json::to_string(value)
+        .map_err(|e| Error::new(ErrorKind::Other, format!("failed to serialize JSON {}", e)))
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- jsonrpc::canonical::test_to_canonical_json --exact --show-output
+#[test]
+fn test_to_canonical_json() {
+    use std::collections::HashMap;
+
+    let mut params = HashMap::new();
+    params.insert(String::from("txID"), String::from("abc"));
+    params.insert(String::from("encoding"), String::from("hex"));
+    let req = super::Request {
+        method: String::from("platform.getTx"),
+        params: Some(params),
+        ..Default::default()
+    };
+
+    // same request, fields declared in a different order
+    let mut other: HashMap<&str, Value> = HashMap::new();
+    other.insert(
+        "params",
+        serde_json::json!({"encoding": "hex", "txID": "abc"}),
+    );
+    other.insert("method", Value::from("platform.getTx"));
+    other.insert("id", Value::from(1));
+    other.insert("jsonrpc", Value::from("2.0"));
+
+    let expected = r#"{"id":1,"jsonrpc":"2.0","method":"platform.getTx","params":{"encoding":"hex","txID":"abc"}}"#;
+    assert_eq!(to_canonical_json(&req).unwrap(), expected);
+    assert_eq!(to_canonical_json(&other).unwrap(), expected);
+
+    let batch = super::encode_batch_json(&[req.clone(), super::Request::default()]).unwrap();
+    assert_eq!(
+        batch,
+        format!(r#"[{},{{"id":1,"jsonrpc":"2.0","method":""}}]"#, expected)
+    );
+}
@@ -1,8 +1,17 @@
-use std::time::Duration;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 
-use crate::errors::{Error, Result};
+use crate::{
+    errors::{Error, Result},
+    jsonrpc::{self, evm},
+    utils,
+};
 use ethers_providers::{Http, Middleware, Provider};
-use primitive_types::{H160, U256};
+use primitive_types::{H160, H256, U256};
+use reqwest::{header::CONTENT_TYPE, ClientBuilder};
+use tokio::time::sleep;
 
 /// Fetches the chain Id from "{http_rpc}/ext/bc/{chain_id_alias}/rpc".
 /// "chain_id_alias" is "C" for C-chain, and blockchain Id for subnet-evm.
@@ -48,3 +57,326 @@ pub async fn get_balance(rpc_ep: &str, eth_addr: H160) -> Result<U256> {
                 retryable: false,
             })
 }
+
+/// "eth_getBalance" at the given block on the EVM RPC endpoint
+/// (e.g., "http://[ADDR]:9650/ext/bc/C/rpc"). Returns the balance in wei.
+/// Historical blocks require the node to keep the state (e.g., an archival node).
+/// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_getbalance>
+pub async fn get_balance_at_block(
+    rpc_ep: &str,
+    eth_addr: H160,
+    block: evm::BlockTag,
+) -> Result<u128> {
+    log::info!(
+        "getting balances for {} at {} via {rpc_ep}",
+        eth_addr,
+        block
+    );
+
+    let mut data = jsonrpc::RequestWithParamsArray::default();
+    data.method = String::from("eth_getBalance");
+    data.params = Some(vec![
+        format!("0x{}", hex::encode(eth_addr.as_bytes())),
+        block.to_string(),
+    ]);
+
+    let out = post_json(rpc_ep, data.encode_json())
+        .await
+        .map_err(|e| Error::API {
+            message: e.to_string(),
+            retryable: false,
+        })?;
+
+    let resp: evm::GetBalanceResponse = serde_json::from_slice(&out).map_err(|e| Error::Other {
+        message: format!("failed serde_json::from_slice '{}'", e),
+        retryable: false,
+    })?;
+    resp.balance_wei().map_err(|e| Error::Other {
+        message: e.to_string(),
+        retryable: false,
+    })
+}
+
+/// "eth_getCode" at the given block on the EVM RPC endpoint
+/// (e.g., "http://[ADDR]:9650/ext/bc/C/rpc"). Returns the contract bytecode,
+/// empty for externally owned accounts.
+/// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_getcode>
+pub async fn get_code(rpc_ep: &str, address: H160, block: evm::BlockTag) -> Result<Vec<u8>> {
+    log::info!("getting code of {} at {} via {rpc_ep}", address, block);
+
+    let mut data = jsonrpc::RequestWithParamsArray::default();
+    data.method = String::from("eth_getCode");
+    data.params = Some(vec![
+        format!("0x{}", hex::encode(address.as_bytes())),
+        block.to_string(),
+    ]);
+
+    let out = post_json(rpc_ep, data.encode_json())
+        .await
+        .map_err(|e| Error::API {
+            message: e.to_string(),
+            retryable: false,
+        })?;
+    let resp: evm::GetCodeResponse = serde_json::from_slice(&out).map_err(|e| Error::Other {
+        message: format!("failed serde_json::from_slice '{}'", e),
+        retryable: false,
+    })?;
+    resp.code().map_err(|e| Error::API {
+        message: e.to_string(),
+        retryable: false,
+    })
+}
+
+/// "eth_getStorageAt" at the given block on the EVM RPC endpoint
+/// (e.g., "http://[ADDR]:9650/ext/bc/C/rpc"). Returns the 32-byte word
+/// stored in the slot of the contract.
+/// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_getstorageat>
+pub async fn get_storage_at(
+    rpc_ep: &str,
+    address: H160,
+    slot: H256,
+    block: evm::BlockTag,
+) -> Result<H256> {
+    log::info!(
+        "getting storage slot {:?} of {} at {} via {rpc_ep}",
+        slot,
+        address,
+        block
+    );
+
+    let mut data = jsonrpc::RequestWithParamsArray::default();
+    data.method = String::from("eth_getStorageAt");
+    data.params = Some(vec![
+        format!("0x{}", hex::encode(address.as_bytes())),
+        format!("0x{}", hex::encode(slot.as_bytes())),
+        block.to_string(),
+    ]);
+
+    let out = post_json(rpc_ep, data.encode_json())
+        .await
+        .map_err(|e| Error::API {
+            message: e.to_string(),
+            retryable: false,
+        })?;
+    let resp: evm::GetStorageAtResponse =
+        serde_json::from_slice(&out).map_err(|e| Error::Other {
+            message: format!("failed serde_json::from_slice '{}'", e),
+            retryable: false,
+        })?;
+    resp.word().map_err(|e| Error::API {
+        message: e.to_string(),
+        retryable: false,
+    })
+}
+
+/// "avax.getAtomicTx" on "http://[ADDR]:9650" and "/ext/bc/C/avax" path,
+/// along with the "avax.getAtomicTxStatus" of the transaction.
+/// ref. <https://docs.avax.network/apis/avalanchego/apis/c-chain#avaxgetatomictx>
+pub async fn get_atomic_tx(http_rpc: &str, tx_id: &str) -> Result<evm::AtomicTx> {
+    let (scheme, host, port, _, _) =
+        utils::urls::extract_scheme_host_port_path_chain_alias(http_rpc).map_err(|e| {
+            Error::Other {
+                message: format!("failed extract_scheme_host_port_path_chain_alias '{}'", e),
+                retryable: false,
+            }
+        })?;
+    let u = if let Some(scheme) = scheme {
+        if let Some(port) = port {
+            format!("{scheme}://{host}:{port}/ext/bc/C/avax")
+        } else {
+            format!("{scheme}://{host}/ext/bc/C/avax")
+        }
+    } else {
+        format!("http://{host}/ext/bc/C/avax")
+    };
+    log::info!("getting atomic tx via {u}");
+
+    let mut data = jsonrpc::Request::default();
+    data.method = String::from("avax.getAtomicTx");
+    let mut params = HashMap::new();
+    params.insert(String::from("txID"), String::from(tx_id));
+    params.insert(String::from("encoding"), String::from("hex"));
+    data.params = Some(params);
+    let out = post_json(&u, data.encode_json())
+        .await
+        .map_err(|e| Error::API {
+            message: e.to_string(),
+            retryable: false,
+        })?;
+
+    let resp: evm::GetAtomicTxResponse =
+        serde_json::from_slice(&out).map_err(|e| Error::Other {
+            message: format!("failed serde_json::from_slice '{}'", e),
+            retryable: false,
+        })?;
+    let status = get_atomic_tx_status(http_rpc, tx_id).await?;
+    resp.atomic_tx(&status).map_err(|e| Error::API {
+        message: e.to_string(),
+        retryable: false,
+    })
+}
+
+/// "avax.getAtomicTxStatus" on "http://[ADDR]:9650" and "/ext/bc/C/avax" path.
+/// ref. <https://docs.avax.network/apis/avalanchego/apis/c-chain#avaxgetatomictxstatus>
+pub async fn get_atomic_tx_status(
+    http_rpc: &str,
+    tx_id: &str,
+) -> Result<evm::GetAtomicTxStatusResponse> {
+    let (scheme, host, port, _, _) =
+        utils::urls::extract_scheme_host_port_path_chain_alias(http_rpc).map_err(|e| {
+            Error::Other {
+                message: format!("failed extract_scheme_host_port_path_chain_alias '{}'", e),
+                retryable: false,
+            }
+        })?;
+    let u = if let Some(scheme) = scheme {
+        if let Some(port) = port {
+            format!("{scheme}://{host}:{port}/ext/bc/C/avax")
+        } else {
+            format!("{scheme}://{host}/ext/bc/C/avax")
+        }
+    } else {
+        format!("http://{host}/ext/bc/C/avax")
+    };
+    log::info!("getting atomic tx status via {u}");
+
+    let mut data = jsonrpc::Request::default();
+    data.method = String::from("avax.getAtomicTxStatus");
+    let mut params = HashMap::new();
+    params.insert(String::from("txID"), String::from(tx_id));
+    data.params = Some(params);
+    let out = post_json(&u, data.encode_json())
+        .await
+        .map_err(|e| Error::API {
+            message: e.to_string(),
+            retryable: false,
+        })?;
+
+    serde_json::from_slice(&out).map_err(|e| Error::Other {
+        message: format!("failed serde_json::from_slice '{}'", e),
+        retryable: false,
+    })
+}
+
+/// "eth_sendRawTransaction" on the EVM RPC endpoint (e.g., "http://[ADDR]:9650/ext/bc/C/rpc").
+/// Returns the transaction hash, or the typed error if the node rejected it.
+/// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_sendrawtransaction>
+pub async fn send_raw_transaction(
+    rpc_ep: &str,
+    signed_rlp: &[u8],
+) -> std::result::Result<H256, evm::SendTransactionError> {
+    log::info!("sending raw transaction via {rpc_ep}");
+
+    let mut data = jsonrpc::RequestWithParamsArray::default();
+    data.method = String::from("eth_sendRawTransaction");
+    data.params = Some(vec![format!("0x{}", hex::encode(signed_rlp))]);
+
+    let out = post_json(rpc_ep, data.encode_json()).await?;
+    let resp: evm::SendRawTransactionResponse =
+        serde_json::from_slice(&out).map_err(|e| evm::SendTransactionError::Other {
+            message: format!("failed serde_json::from_slice '{}'", e),
+        })?;
+    resp.tx_hash()
+}
+
+/// "eth_estimateGas" on the EVM RPC endpoint (e.g., "http://[ADDR]:9650/ext/bc/C/rpc").
+/// Returns the estimated gas, or [`evm::SendTransactionError::Reverted`]
+/// with the revert data if the call reverts.
+/// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_estimategas>
+pub async fn estimate_gas(
+    rpc_ep: &str,
+    call: &evm::CallRequest,
+) -> std::result::Result<u64, evm::SendTransactionError> {
+    log::info!("estimating gas via {rpc_ep}");
+
+    let mut data = jsonrpc::RequestWithParamsHashMapArray::default();
+    data.method = String::from("eth_estimateGas");
+    data.params = Some(vec![call.params()]);
+
+    let out = post_json(rpc_ep, data.encode_json()).await?;
+    let resp: evm::EstimateGasResponse =
+        serde_json::from_slice(&out).map_err(|e| evm::SendTransactionError::Other {
+            message: format!("failed serde_json::from_slice '{}'", e),
+        })?;
+    resp.gas()
+}
+
+/// Sends the raw transaction and polls "eth_getTransactionReceipt" every
+/// "poll_interval" until it is included, or until "timeout" elapses.
+/// Returns [`evm::SendTransactionError::Reverted`] if the included
+/// transaction failed.
+pub async fn send_and_wait(
+    rpc_ep: &str,
+    signed_rlp: &[u8],
+    poll_interval: Duration,
+    timeout: Duration,
+) -> std::result::Result<evm::GetTransactionReceiptResult, evm::SendTransactionError> {
+    let tx_hash = send_raw_transaction(rpc_ep, signed_rlp).await?;
+    let tx_hash = format!("0x{}", hex::encode(tx_hash.as_bytes()));
+
+    let start = Instant::now();
+    loop {
+        let mut data = jsonrpc::RequestWithParamsArray::default();
+        data.method = String::from("eth_getTransactionReceipt");
+        data.params = Some(vec![tx_hash.clone()]);
+
+        let out = post_json(rpc_ep, data.encode_json()).await?;
+        let resp: evm::GetTransactionReceiptResponse =
+            serde_json::from_slice(&out).map_err(|e| evm::SendTransactionError::Other {
+                message: format!("failed serde_json::from_slice '{}'", e),
+            })?;
+
+        if let Some(receipt) = resp.result {
+            if receipt.status.is_zero() {
+                return Err(evm::SendTransactionError::Reverted {
+                    message: format!("transaction {tx_hash} reverted"),
+                    data: None,
+                });
+            }
+            return Ok(receipt);
+        }
+
+        if start.elapsed() + poll_interval > timeout {
+            return Err(evm::SendTransactionError::Other {
+                message: format!("transaction {tx_hash} not included after {:?}", timeout),
+            });
+        }
+        log::info!("transaction {tx_hash} not yet included, polling again");
+        sleep(poll_interval).await;
+    }
+}
+
+async fn post_json(
+    rpc_ep: &str,
+    body: std::io::Result<String>,
+) -> std::result::Result<Vec<u8>, evm::SendTransactionError> {
+    let d = body.map_err(|e| evm::SendTransactionError::Other {
+        message: format!("failed encode_json '{}'", e),
+    })?;
+
+    let req_cli_builder = ClientBuilder::new()
+        .user_agent(env!("CARGO_PKG_NAME"))
+        .danger_accept_invalid_certs(true)
+        .timeout(Duration::from_secs(15))
+        .connection_verbose(true)
+        .build()
+        .map_err(|e| evm::SendTransactionError::Other {
+            message: format!("failed reqwest::ClientBuilder.build '{}'", e),
+        })?;
+    let resp = req_cli_builder
+        .post(rpc_ep)
+        .header(CONTENT_TYPE, "application/json")
+        .body(d)
+        .send()
+        .await
+        .map_err(|e| evm::SendTransactionError::Other {
+            message: format!("failed reqwest::Client.send '{}'", e),
+        })?;
+    let out = resp
+        .bytes()
+        .await
+        .map_err(|e| evm::SendTransactionError::Other {
+            message: format!("failed reqwest response bytes '{}'", e),
+        })?;
+    Ok(out.into())
+}
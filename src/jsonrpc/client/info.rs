@@ -1,6 +1,10 @@
-use std::{collections::HashMap, time::Duration};
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Duration,
+};
 
 use crate::{
+    constants,
     errors::{Error, Result},
     ids,
     jsonrpc::{self, info},
@@ -76,6 +80,18 @@ pub async fn get_network_name(http_rpc: &str) -> Result<info::GetNetworkNameResp
     })
 }
 
+/// e.g., "info.getNetworkName", returning the network name and the
+/// well-known network it maps to ("None" for custom networks).
+pub async fn get_network(http_rpc: &str) -> Result<(String, Option<constants::Network>)> {
+    let resp = get_network_name(http_rpc).await?;
+    let network_name = resp.network_name().map_err(|e| Error::API {
+        message: e.to_string(),
+        retryable: false,
+    })?;
+    let network = constants::Network::from_name(&network_name);
+    Ok((network_name, network))
+}
+
 /// e.g., "info.getNetworkID".
 /// ref. <https://docs.avax.network/build/avalanchego-apis/info/#infogetnetworkid>
 pub async fn get_network_id(http_rpc: &str) -> Result<info::GetNetworkIdResponse> {
@@ -453,6 +469,18 @@ pub async fn get_vms(http_rpc: &str) -> Result<info::GetVmsResponse> {
     })
 }
 
+/// Same as [`get_vms`] but returns the aliases of each VM keyed by the
+/// parsed VM ID, or the JSON-RPC error.
+pub async fn get_vm_aliases(http_rpc: &str) -> Result<BTreeMap<ids::Id, Vec<String>>> {
+    get_vms(http_rpc)
+        .await?
+        .vm_aliases()
+        .map_err(|e| Error::API {
+            message: e.to_string(),
+            retryable: false,
+        })
+}
+
 /// e.g., "info.isBootstrapped".
 /// ref. <https://docs.avax.network/build/avalanchego-apis/info/#infoisbootstrapped>
 pub async fn is_bootstrapped(http_rpc: &str) -> Result<info::IsBootstrappedResponse> {
@@ -670,3 +698,77 @@ pub async fn peers(
         retryable: false,
     })
 }
+
+/// e.g., "info.uptime".
+/// Set "subnet_id" to "None" for the primary network.
+/// ref. <https://docs.avax.network/apis/avalanchego/apis/info#infouptime>
+pub async fn uptime(http_rpc: &str, subnet_id: Option<ids::Id>) -> Result<info::UptimeResponse> {
+    let (scheme, host, port, _, _) =
+        utils::urls::extract_scheme_host_port_path_chain_alias(http_rpc).map_err(|e| {
+            Error::Other {
+                message: format!("failed extract_scheme_host_port_path_chain_alias '{}'", e),
+                retryable: false,
+            }
+        })?;
+    let u = if let Some(scheme) = scheme {
+        if let Some(port) = port {
+            format!("{scheme}://{host}:{port}/ext/info")
+        } else {
+            format!("{scheme}://{host}/ext/info")
+        }
+    } else {
+        format!("http://{host}/ext/info")
+    };
+    log::info!("getting uptime via {u}");
+
+    let mut data = jsonrpc::Request::default();
+    data.method = String::from("info.uptime");
+    let mut params = HashMap::new();
+    if let Some(subnet_id) = subnet_id {
+        params.insert(String::from("subnetID"), subnet_id.to_string());
+    }
+    data.params = Some(params);
+    let d = data.encode_json().map_err(|e| Error::Other {
+        message: format!("failed encode_json '{}'", e),
+        retryable: false,
+    })?;
+
+    let req_cli_builder = ClientBuilder::new()
+        .user_agent(env!("CARGO_PKG_NAME"))
+        .danger_accept_invalid_certs(true)
+        .timeout(Duration::from_secs(15))
+        .connection_verbose(true)
+        .build()
+        .map_err(|e| {
+            // TODO: check retryable
+            Error::Other {
+                message: format!("failed reqwest::ClientBuilder.build '{}'", e),
+                retryable: false,
+            }
+        })?;
+    let resp = req_cli_builder
+        .post(&u)
+        .header(CONTENT_TYPE, "application/json")
+        .body(d)
+        .send()
+        .await
+        .map_err(|e|
+            // TODO: check retryable
+            Error::API {
+                message: format!("failed reqwest::Client.send '{}'", e),
+                retryable: false,
+            })?;
+    let out = resp.bytes().await.map_err(|e| {
+        // TODO: check retryable
+        Error::Other {
+            message: format!("failed reqwest response bytes '{}'", e),
+            retryable: false,
+        }
+    })?;
+    let out: Vec<u8> = out.into();
+
+    serde_json::from_slice(&out).map_err(|e| Error::Other {
+        message: format!("failed serde_json::from_slice '{}'", e),
+        retryable: false,
+    })
+}
@@ -4,3 +4,157 @@ pub mod health;
 pub mod info;
 pub mod p;
 pub mod x;
+
+use crate::{
+    errors::{Error, Result},
+    ids,
+};
+
+/// Signed transaction to issue as part of [`issue_batch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchTx {
+    /// Alias of the chain to issue on (e.g., "X", "P").
+    pub chain: String,
+    /// Hex-encoded signed transaction.
+    pub tx: String,
+    /// If true, the previous transaction must be accepted before this one
+    /// is issued (e.g., an import spending the outputs of an export).
+    pub depends_on_previous: bool,
+}
+
+/// Issues transactions and waits for their acceptance, typically by
+/// dispatching on the chain to the "issue_tx" and "get_tx_status" clients.
+#[async_trait::async_trait]
+pub trait TxIssuer {
+    async fn issue_tx(&self, tx: &BatchTx) -> Result<ids::Id>;
+    async fn wait_accepted(&self, tx: &BatchTx, tx_id: &ids::Id) -> Result<()>;
+}
+
+/// First failure of [`issue_batch`].
+#[derive(Debug)]
+pub struct BatchFailure {
+    /// Index of the transaction that failed to be issued or accepted.
+    pub index: usize,
+    /// IDs of the transactions issued before the failure.
+    pub issued: Vec<ids::Id>,
+    pub error: Error,
+}
+
+/// Issues the transactions in order, waiting for the acceptance of a
+/// transaction before issuing the next one if it depends on it. Returns the
+/// IDs of all the transactions, or stops at the first failure.
+pub async fn issue_batch<T>(
+    issuer: &T,
+    txs_in_order: &[BatchTx],
+) -> std::result::Result<Vec<ids::Id>, BatchFailure>
+where
+    T: TxIssuer + Sync,
+{
+    let mut issued: Vec<ids::Id> = Vec::with_capacity(txs_in_order.len());
+    for (index, tx) in txs_in_order.iter().enumerate() {
+        if tx.depends_on_previous && index > 0 {
+            let prev = index - 1;
+            if let Err(error) = issuer
+                .wait_accepted(&txs_in_order[prev], &issued[prev])
+                .await
+            {
+                return Err(BatchFailure {
+                    index: prev,
+                    issued,
+                    error,
+                });
+            }
+        }
+
+        match issuer.issue_tx(tx).await {
+            Ok(tx_id) => {
+                log::info!(
+                    "issued tx {} ({}/{}) on {}",
+                    tx_id,
+                    index + 1,
+                    txs_in_order.len(),
+                    tx.chain
+                );
+                issued.push(tx_id);
+            }
+            Err(error) => {
+                return Err(BatchFailure {
+                    index,
+                    issued,
+                    error,
+                })
+            }
+        }
+    }
+    Ok(issued)
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features jsonrpc_client -- jsonrpc::client::test_issue_batch --exact --show-output
+#[tokio::test]
+async fn test_issue_batch() {
+    use std::sync::Mutex;
+
+    /// Records the calls, failing to issue the txs listed in "failing".
+    struct TestIssuer {
+        calls: Mutex<Vec<String>>,
+        failing: Vec<String>,
+    }
+
+    #[async_trait::async_trait]
+    impl TxIssuer for TestIssuer {
+        async fn issue_tx(&self, tx: &BatchTx) -> Result<ids::Id> {
+            self.calls.lock().unwrap().push(format!("issue {}", tx.tx));
+            if self.failing.contains(&tx.tx) {
+                return Err(Error::API {
+                    message: "insufficient funds".to_string(),
+                    retryable: false,
+                });
+            }
+            Ok(ids::Id::sha256(tx.tx.as_bytes()))
+        }
+        async fn wait_accepted(&self, tx: &BatchTx, tx_id: &ids::Id) -> Result<()> {
+            assert_eq!(*tx_id, ids::Id::sha256(tx.tx.as_bytes()));
+            self.calls.lock().unwrap().push(format!("wait {}", tx.tx));
+            Ok(())
+        }
+    }
+
+    let export = BatchTx {
+        chain: "X".to_string(),
+        tx: "0xexport".to_string(),
+        depends_on_previous: false,
+    };
+    let import = BatchTx {
+        chain: "P".to_string(),
+        tx: "0ximport".to_string(),
+        depends_on_previous: true,
+    };
+
+    // the import is only issued once the export is accepted
+    let issuer = TestIssuer {
+        calls: Mutex::new(Vec::new()),
+        failing: Vec::new(),
+    };
+    let tx_ids = issue_batch(&issuer, &[export.clone(), import.clone()])
+        .await
+        .unwrap();
+    assert_eq!(
+        tx_ids,
+        vec![ids::Id::sha256(b"0xexport"), ids::Id::sha256(b"0ximport")]
+    );
+    assert_eq!(
+        *issuer.calls.lock().unwrap(),
+        vec!["issue 0xexport", "wait 0xexport", "issue 0ximport"]
+    );
+
+    // a failed export stops the import
+    let issuer = TestIssuer {
+        calls: Mutex::new(Vec::new()),
+        failing: vec!["0xexport".to_string()],
+    };
+    let failure = issue_batch(&issuer, &[export, import]).await.unwrap_err();
+    assert_eq!(failure.index, 0);
+    assert!(failure.issued.is_empty());
+    assert!(failure.error.contains("insufficient funds"));
+    assert_eq!(*issuer.calls.lock().unwrap(), vec!["issue 0xexport"]);
+}
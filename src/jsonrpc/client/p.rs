@@ -1,4 +1,8 @@
-use std::{collections::HashMap, time::Duration};
+use std::{
+    collections::HashMap,
+    future::Future,
+    time::{Duration, Instant},
+};
 
 use crate::{
     errors::{Error, Result},
@@ -7,6 +11,7 @@ use crate::{
     utils,
 };
 use reqwest::{header::CONTENT_TYPE, ClientBuilder};
+use tokio::time::sleep;
 
 /// "platform.issueTx" on "http://[ADDR]:9650" and "/ext/P" path.
 /// ref. <https://docs.avax.network/build/avalanchego-apis/p-chain/#platformgetcurrentvalidators>
@@ -153,6 +158,114 @@ pub async fn get_tx(http_rpc: &str, tx_id: &str) -> Result<platformvm::GetTxResp
     })
 }
 
+/// "platform.getTx" on "http://[ADDR]:9650" and "/ext/P" path, with the
+/// given encoding ("hex" for the raw signed bytes, "json" for the parsed tx).
+/// ref. <https://docs.avax.network/apis/avalanchego/apis/p-chain/#platformgettx>
+pub async fn get_tx_with_encoding(
+    http_rpc: &str,
+    tx_id: &str,
+    encoding: &str,
+) -> Result<platformvm::GetTxEncodedResponse> {
+    if encoding != "hex" && encoding != "json" {
+        return Err(Error::Other {
+            message: format!(
+                "unsupported encoding '{}' (expected 'hex' or 'json')",
+                encoding
+            ),
+            retryable: false,
+        });
+    }
+    get_tx_encoded(http_rpc, tx_id, encoding).await
+}
+
+/// "platform.getTx" with "hex" encoding on "http://[ADDR]:9650" and "/ext/P" path.
+/// Used for fetching the signed bytes of atomic (import/export) transactions,
+/// along with their "platform.getTxStatus".
+/// ref. <https://docs.avax.network/apis/avalanchego/apis/p-chain/#platformgettx>
+pub async fn get_atomic_tx(http_rpc: &str, tx_id: &str) -> Result<platformvm::AtomicTx> {
+    let resp = get_tx_encoded(http_rpc, tx_id, "hex").await?;
+    let status = get_tx_status(http_rpc, tx_id).await?;
+    resp.atomic_tx(&status).map_err(|e| Error::API {
+        message: e.to_string(),
+        retryable: false,
+    })
+}
+
+/// Sends "platform.getTx" with the encoding, and decodes the response.
+async fn get_tx_encoded(
+    http_rpc: &str,
+    tx_id: &str,
+    encoding: &str,
+) -> Result<platformvm::GetTxEncodedResponse> {
+    let (scheme, host, port, _, _) =
+        utils::urls::extract_scheme_host_port_path_chain_alias(http_rpc).map_err(|e| {
+            Error::Other {
+                message: format!("failed extract_scheme_host_port_path_chain_alias '{}'", e),
+                retryable: false,
+            }
+        })?;
+    let u = if let Some(scheme) = scheme {
+        if let Some(port) = port {
+            format!("{scheme}://{host}:{port}/ext/P")
+        } else {
+            format!("{scheme}://{host}/ext/P")
+        }
+    } else {
+        format!("http://{host}/ext/P")
+    };
+    log::info!("getting tx {tx_id} with encoding {encoding} via {u}");
+
+    let mut data = jsonrpc::Request::default();
+    data.method = String::from("platform.getTx");
+    let mut params = HashMap::new();
+    params.insert(String::from("txID"), String::from(tx_id));
+    params.insert(String::from("encoding"), String::from(encoding));
+    data.params = Some(params);
+    let d = data.encode_json().map_err(|e| Error::Other {
+        message: format!("failed encode_json '{}'", e),
+        retryable: false,
+    })?;
+
+    let req_cli_builder = ClientBuilder::new()
+        .user_agent(env!("CARGO_PKG_NAME"))
+        .danger_accept_invalid_certs(true)
+        .timeout(Duration::from_secs(15))
+        .connection_verbose(true)
+        .build()
+        .map_err(|e| {
+            // TODO: check retryable
+            Error::Other {
+                message: format!("failed reqwest::ClientBuilder.build '{}'", e),
+                retryable: false,
+            }
+        })?;
+    let resp = req_cli_builder
+        .post(&u)
+        .header(CONTENT_TYPE, "application/json")
+        .body(d)
+        .send()
+        .await
+        .map_err(|e|
+            // TODO: check retryable
+            Error::API {
+                message: format!("failed reqwest::Client.send '{}'", e),
+                retryable: false,
+            })?;
+    let out = resp.bytes().await.map_err(|e| {
+        // TODO: check retryable
+        Error::Other {
+            message: format!("failed reqwest response bytes '{}'", e),
+            retryable: false,
+        }
+    })?;
+    let out: Vec<u8> = out.into();
+
+    serde_json::from_slice(&out).map_err(|e| Error::Other {
+        message: format!("failed serde_json::from_slice '{}'", e),
+        retryable: false,
+    })
+}
+
 /// "platform.getTxStatus" on "http://[ADDR]:9650" and "/ext/P" path.
 /// ref. <https://docs.avax.network/apis/avalanchego/apis/p-chain/#platformgettxstatus>
 pub async fn get_tx_status(http_rpc: &str, tx_id: &str) -> Result<platformvm::GetTxStatusResponse> {
@@ -295,10 +408,196 @@ pub async fn get_height(http_rpc: &str) -> Result<platformvm::GetHeightResponse>
     })
 }
 
+/// "platform.getStakingAssetID" on "http://[ADDR]:9650" and "/ext/P" path.
+/// Returns the ID of the asset used to stake on the subnet
+/// (AVAX for the primary network, i.e., the empty subnet ID).
+/// ref. <https://docs.avax.network/apis/avalanchego/apis/p-chain#platformgetstakingassetid>
+pub async fn get_staking_asset_id(http_rpc: &str, subnet_id: &ids::Id) -> Result<ids::Id> {
+    let (scheme, host, port, _, _) =
+        utils::urls::extract_scheme_host_port_path_chain_alias(http_rpc).map_err(|e| {
+            Error::Other {
+                message: format!("failed extract_scheme_host_port_path_chain_alias '{}'", e),
+                retryable: false,
+            }
+        })?;
+    let u = if let Some(scheme) = scheme {
+        if let Some(port) = port {
+            format!("{scheme}://{host}:{port}/ext/P")
+        } else {
+            format!("{scheme}://{host}/ext/P")
+        }
+    } else {
+        format!("http://{host}/ext/P")
+    };
+    log::info!("getting staking asset ID for subnet {} via {u}", subnet_id);
+
+    let mut data = jsonrpc::Request::default();
+    data.method = String::from("platform.getStakingAssetID");
+
+    let mut params = HashMap::new();
+    params.insert(String::from("subnetID"), subnet_id.to_string());
+    data.params = Some(params);
+    let d = data.encode_json().map_err(|e| Error::Other {
+        message: format!("failed encode_json '{}'", e),
+        retryable: false,
+    })?;
+
+    let req_cli_builder = ClientBuilder::new()
+        .user_agent(env!("CARGO_PKG_NAME"))
+        .danger_accept_invalid_certs(true)
+        .timeout(Duration::from_secs(15))
+        .connection_verbose(true)
+        .build()
+        .map_err(|e| {
+            // TODO: check retryable
+            Error::Other {
+                message: format!("failed reqwest::ClientBuilder.build '{}'", e),
+                retryable: false,
+            }
+        })?;
+    let resp = req_cli_builder
+        .post(&u)
+        .header(CONTENT_TYPE, "application/json")
+        .body(d)
+        .send()
+        .await
+        .map_err(|e|
+            // TODO: check retryable
+            Error::API {
+                message: format!("failed reqwest::Client.send '{}'", e),
+                retryable: false,
+            })?;
+    let out = resp.bytes().await.map_err(|e| {
+        // TODO: check retryable
+        Error::Other {
+            message: format!("failed reqwest response bytes '{}'", e),
+            retryable: false,
+        }
+    })?;
+    let out: Vec<u8> = out.into();
+
+    let resp: platformvm::GetStakingAssetIdResponse =
+        serde_json::from_slice(&out).map_err(|e| Error::Other {
+            message: format!("failed serde_json::from_slice '{}'", e),
+            retryable: false,
+        })?;
+    resp.asset_id().map_err(|e| Error::API {
+        message: e.to_string(),
+        retryable: false,
+    })
+}
+
+/// Polls "platform.getHeight" every "poll_interval" until the P-chain reaches
+/// "target_height", returning the observed height. Errors after "timeout".
+pub async fn wait_for_height(
+    http_rpc: &str,
+    target_height: u64,
+    poll_interval: Duration,
+    timeout: Duration,
+) -> Result<u64> {
+    poll_height(
+        || async {
+            let resp = get_height(http_rpc).await?;
+            resp.height().map_err(|e| Error::API {
+                message: e.to_string(),
+                retryable: true,
+            })
+        },
+        target_height,
+        poll_interval,
+        timeout,
+    )
+    .await
+}
+
+async fn poll_height<F, Fut>(
+    fetch: F,
+    target_height: u64,
+    poll_interval: Duration,
+    timeout: Duration,
+) -> Result<u64>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<u64>>,
+{
+    let start = Instant::now();
+    loop {
+        let elapsed = start.elapsed();
+        if elapsed.gt(&timeout) {
+            return Err(Error::API {
+                message: format!("P-chain did not reach height {} in time", target_height),
+                retryable: true,
+            });
+        }
+
+        match fetch().await {
+            Ok(height) if height >= target_height => return Ok(height),
+            Ok(height) => log::info!(
+                "P-chain height {} (waiting for {}, elapsed {:?})",
+                height,
+                target_height,
+                elapsed
+            ),
+            Err(e) => log::warn!("failed to get P-chain height '{}'", e),
+        }
+        sleep(poll_interval).await;
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features jsonrpc_client -- jsonrpc::client::p::test_poll_height --exact --show-output
+#[tokio::test]
+async fn test_poll_height() {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    let height = AtomicU64::new(0);
+    let fetch = || async { Ok(height.fetch_add(1, Ordering::SeqCst) + 1) };
+
+    let reached = poll_height(fetch, 3, Duration::from_millis(1), Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert_eq!(reached, 3);
+
+    let never = || async { Ok(1_u64) };
+    assert!(poll_height(
+        never,
+        3,
+        Duration::from_millis(5),
+        Duration::from_millis(30)
+    )
+    .await
+    .is_err());
+}
+
 /// "platform.getBalance" on "http://[ADDR]:9650" and "/ext/P" path.
 /// ref. <https://docs.avax.network/build/avalanchego-apis/p-chain/#platformgetbalance>
 /// ref. <https://github.com/ava-labs/avalanchego/blob/45ec88151f8a0e3bca1d43fe902fd632c41cd956/vms/platformvm/service.go#L192-L194>
 pub async fn get_balance(http_rpc: &str, paddr: &str) -> Result<platformvm::GetBalanceResponse> {
+    get_balances(http_rpc, &[paddr.to_string()]).await
+}
+
+/// "platform.getBalance" on "http://[ADDR]:9650" and "/ext/P" path,
+/// returning the balance per locked bucket of all the addresses.
+/// ref. <https://docs.avax.network/build/avalanchego-apis/p-chain/#platformgetbalance>
+pub async fn get_balance_breakdown(
+    http_rpc: &str,
+    paddrs: &[String],
+) -> Result<platformvm::BalanceBreakdown> {
+    get_balances(http_rpc, paddrs)
+        .await?
+        .breakdown()
+        .map_err(|e| Error::API {
+            message: e.to_string(),
+            retryable: false,
+        })
+}
+
+/// "platform.getBalance" on "http://[ADDR]:9650" and "/ext/P" path, for
+/// several addresses at once.
+/// ref. <https://docs.avax.network/build/avalanchego-apis/p-chain/#platformgetbalance>
+pub async fn get_balances(
+    http_rpc: &str,
+    paddrs: &[String],
+) -> Result<platformvm::GetBalanceResponse> {
     let (scheme, host, port, _, _) =
         utils::urls::extract_scheme_host_port_path_chain_alias(http_rpc).map_err(|e| {
             Error::Other {
@@ -315,12 +614,12 @@ pub async fn get_balance(http_rpc: &str, paddr: &str) -> Result<platformvm::GetB
     } else {
         format!("http://{host}/ext/P")
     };
-    log::info!("getting balance via {u} for {}", paddr);
+    log::info!("getting balance via {u} for {:?}", paddrs);
 
     let mut data = jsonrpc::RequestWithParamsHashMapToArray::default();
     data.method = String::from("platform.getBalance");
     let mut params = HashMap::new();
-    params.insert(String::from("addresses"), vec![paddr.to_string()]);
+    params.insert(String::from("addresses"), paddrs.to_vec());
     data.params = Some(params);
     let d = data.encode_json().map_err(|e| Error::Other {
         message: format!("failed encode_json '{}'", e),
@@ -669,6 +968,22 @@ pub async fn get_subnets(
     })
 }
 
+/// Same as [`get_subnets`] but returns the typed subnets, or the JSON-RPC error.
+/// Bech32 control keys must have the HRP of "network_id".
+pub async fn get_subnet_infos(
+    http_rpc: &str,
+    network_id: u32,
+    subnet_ids: Option<Vec<ids::Id>>,
+) -> Result<Vec<platformvm::SubnetInfo>> {
+    get_subnets(http_rpc, subnet_ids)
+        .await?
+        .subnet_infos(network_id)
+        .map_err(|e| Error::API {
+            message: e.to_string(),
+            retryable: false,
+        })
+}
+
 /// "platform.getBlockchains" on "http://[ADDR]:9650" and "/ext/P" path.
 /// ref. <https://docs.avax.network/apis/avalanchego/apis/p-chain#platformgetblockchains>
 pub async fn get_blockchains(http_rpc: &str) -> Result<platformvm::GetBlockchainsResponse> {
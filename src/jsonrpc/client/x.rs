@@ -1,6 +1,12 @@
-use std::{collections::HashMap, time::Duration};
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use crate::{
+    avm::txs::vertex::Vertex,
     errors::{Error, Result},
     jsonrpc::{self, avm},
     utils,
@@ -151,6 +157,163 @@ pub async fn get_tx_status(http_rpc: &str, tx_id: &str) -> Result<avm::GetTxStat
     })
 }
 
+/// e.g., "avm.getTx" with "hex" encoding on "http://[ADDR]:9650" and "/ext/bc/X" path.
+/// Used for fetching the signed bytes of atomic (import/export) transactions,
+/// along with their "avm.getTxStatus".
+/// ref. <https://docs.avax.network/apis/avalanchego/apis/x-chain/#avmgettx>
+pub async fn get_atomic_tx(http_rpc: &str, tx_id: &str) -> Result<avm::AtomicTx> {
+    let (scheme, host, port, _, _) =
+        utils::urls::extract_scheme_host_port_path_chain_alias(http_rpc).map_err(|e| {
+            Error::Other {
+                message: format!("failed extract_scheme_host_port_path_chain_alias '{}'", e),
+                retryable: false,
+            }
+        })?;
+    let u = if let Some(scheme) = scheme {
+        if let Some(port) = port {
+            format!("{scheme}://{host}:{port}/ext/bc/X")
+        } else {
+            format!("{scheme}://{host}/ext/bc/X")
+        }
+    } else {
+        format!("http://{host}/ext/bc/X")
+    };
+    log::info!("getting atomic tx via {u}");
+
+    let mut data = jsonrpc::Request::default();
+    data.method = String::from("avm.getTx");
+    let mut params = HashMap::new();
+    params.insert(String::from("txID"), String::from(tx_id));
+    params.insert(String::from("encoding"), String::from("hex"));
+    data.params = Some(params);
+    let d = data.encode_json().map_err(|e| Error::Other {
+        message: format!("failed encode_json '{}'", e),
+        retryable: false,
+    })?;
+
+    let req_cli_builder = ClientBuilder::new()
+        .user_agent(env!("CARGO_PKG_NAME"))
+        .danger_accept_invalid_certs(true)
+        .timeout(Duration::from_secs(15))
+        .connection_verbose(true)
+        .build()
+        .map_err(|e| {
+            // TODO: check retryable
+            Error::Other {
+                message: format!("failed reqwest::ClientBuilder.build '{}'", e),
+                retryable: false,
+            }
+        })?;
+    let resp = req_cli_builder
+        .post(&u)
+        .header(CONTENT_TYPE, "application/json")
+        .body(d)
+        .send()
+        .await
+        .map_err(|e|
+            // TODO: check retryable
+            Error::API {
+                message: format!("failed reqwest::Client.send '{}'", e),
+                retryable: false,
+            })?;
+    let out = resp.bytes().await.map_err(|e| {
+        // TODO: check retryable
+        Error::Other {
+            message: format!("failed reqwest response bytes '{}'", e),
+            retryable: false,
+        }
+    })?;
+    let out: Vec<u8> = out.into();
+
+    let resp: avm::GetAtomicTxResponse =
+        serde_json::from_slice(&out).map_err(|e| Error::Other {
+            message: format!("failed serde_json::from_slice '{}'", e),
+            retryable: false,
+        })?;
+    let status = get_tx_status(http_rpc, tx_id).await?;
+    resp.atomic_tx(&status).map_err(|e| Error::API {
+        message: e.to_string(),
+        retryable: false,
+    })
+}
+
+/// e.g., "avm.getVertexByHeight" with "hex" encoding on "http://[ADDR]:9650" and "/ext/bc/X" path.
+/// Returns "None" if no vertex is accepted at the height yet.
+pub async fn get_vertex_by_height(http_rpc: &str, height: u64) -> Result<Option<Vertex>> {
+    let (scheme, host, port, _, _) =
+        utils::urls::extract_scheme_host_port_path_chain_alias(http_rpc).map_err(|e| {
+            Error::Other {
+                message: format!("failed extract_scheme_host_port_path_chain_alias '{}'", e),
+                retryable: false,
+            }
+        })?;
+    let u = if let Some(scheme) = scheme {
+        if let Some(port) = port {
+            format!("{scheme}://{host}:{port}/ext/bc/X")
+        } else {
+            format!("{scheme}://{host}/ext/bc/X")
+        }
+    } else {
+        format!("http://{host}/ext/bc/X")
+    };
+    log::info!("getting vertex at height {height} via {u}");
+
+    let mut data = jsonrpc::Request::default();
+    data.method = String::from("avm.getVertexByHeight");
+    let mut params = HashMap::new();
+    params.insert(String::from("height"), height.to_string());
+    params.insert(String::from("encoding"), String::from("hex"));
+    data.params = Some(params);
+    let d = data.encode_json().map_err(|e| Error::Other {
+        message: format!("failed encode_json '{}'", e),
+        retryable: false,
+    })?;
+
+    let req_cli_builder = ClientBuilder::new()
+        .user_agent(env!("CARGO_PKG_NAME"))
+        .danger_accept_invalid_certs(true)
+        .timeout(Duration::from_secs(15))
+        .connection_verbose(true)
+        .build()
+        .map_err(|e| {
+            // TODO: check retryable
+            Error::Other {
+                message: format!("failed reqwest::ClientBuilder.build '{}'", e),
+                retryable: false,
+            }
+        })?;
+    let resp = req_cli_builder
+        .post(&u)
+        .header(CONTENT_TYPE, "application/json")
+        .body(d)
+        .send()
+        .await
+        .map_err(|e|
+            // TODO: check retryable
+            Error::API {
+                message: format!("failed reqwest::Client.send '{}'", e),
+                retryable: false,
+            })?;
+    let out = resp.bytes().await.map_err(|e| {
+        // TODO: check retryable
+        Error::Other {
+            message: format!("failed reqwest response bytes '{}'", e),
+            retryable: false,
+        }
+    })?;
+    let out: Vec<u8> = out.into();
+
+    let resp: avm::GetVertexByHeightResponse =
+        serde_json::from_slice(&out).map_err(|e| Error::Other {
+            message: format!("failed serde_json::from_slice '{}'", e),
+            retryable: false,
+        })?;
+    resp.vertex().map_err(|e| Error::API {
+        message: e.to_string(),
+        retryable: false,
+    })
+}
+
 /// e.g., "avm.getBalance" on "http://[ADDR]:9650" and "/ext/bc/X" path.
 /// ref. <https://docs.avax.network/build/avalanchego-apis/x-chain#avmgetbalance>
 pub async fn get_balance(http_rpc: &str, xaddr: &str) -> Result<avm::GetBalanceResponse> {
@@ -297,6 +460,50 @@ pub async fn get_asset_description(
     })
 }
 
+/// Caches "avm.getAssetDescription" results of an X-chain endpoint.
+/// Asset descriptions are immutable, so each asset is fetched at most once
+/// on success.
+#[derive(Debug, Clone)]
+pub struct AssetDescriptionCache {
+    http_rpc: String,
+    cache: Arc<Mutex<HashMap<String, avm::AssetDescription>>>,
+}
+
+impl AssetDescriptionCache {
+    pub fn new(http_rpc: &str) -> Self {
+        Self {
+            http_rpc: http_rpc.to_string(),
+            cache: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Returns the description of the asset (ID or alias, e.g., "AVAX").
+    pub async fn get_asset_description(&self, asset_id: &str) -> Result<avm::AssetDescription> {
+        self.get_or_fetch(asset_id, || get_asset_description(&self.http_rpc, asset_id))
+            .await
+    }
+
+    async fn get_or_fetch<F, Fut>(&self, asset_id: &str, fetch: F) -> Result<avm::AssetDescription>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<avm::GetAssetDescriptionResponse>>,
+    {
+        if let Some(desc) = self.cache.lock().unwrap().get(asset_id) {
+            return Ok(desc.clone());
+        }
+
+        let desc = fetch().await?.asset_description().map_err(|e| Error::API {
+            message: e.to_string(),
+            retryable: false,
+        })?;
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(asset_id.to_string(), desc.clone());
+        Ok(desc)
+    }
+}
+
 /// e.g., "avm.getUTXOs" on "http://[ADDR]:9650" and "/ext/bc/X" path.
 /// TODO: support paginated calls
 /// ref. <https://docs.avax.network/apis/avalanchego/apis/x-chain/#avmgetutxos>
@@ -437,3 +644,32 @@ pub async fn issue_stop_vertex(http_rpc: &str) -> Result<()> {
 
     Ok(())
 }
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features jsonrpc_client -- jsonrpc::client::x::test_asset_description_cache --exact --show-output
+#[tokio::test]
+async fn test_asset_description_cache() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let resp: avm::GetAssetDescriptionResponse = serde_json::from_str(
+        r#"{"jsonrpc":"2.0","result":{"assetID":"2fombhL7aGPwj3KH4bfrmJwW6PVnMobf9Y2fn9GwxiAAJyFDbe","name":"Avalanche","symbol":"AVAX","denomination":"9"},"id":1}"#,
+    )
+    .unwrap();
+
+    let cache = AssetDescriptionCache::new("http://127.0.0.1:9650");
+    let fetches = AtomicUsize::new(0);
+    let fetch = || async {
+        fetches.fetch_add(1, Ordering::SeqCst);
+        Ok(resp.clone())
+    };
+
+    let desc = cache.get_or_fetch("AVAX", fetch).await.unwrap();
+    assert_eq!(desc.name, "Avalanche");
+    assert_eq!(desc.symbol, "AVAX");
+    assert_eq!(desc.denomination, 9);
+    assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+    // second call is served from the cache
+    let cached = cache.get_or_fetch("AVAX", fetch).await.unwrap();
+    assert_eq!(cached, desc);
+    assert_eq!(fetches.load(Ordering::SeqCst), 1);
+}
@@ -1,6 +1,20 @@
-use crate::codec::serde::{hex_0x_bytes::Hex0xBytes, hex_0x_primitive_types_h256::Hex0xH256};
+use std::{
+    collections::HashMap,
+    io::{self, Error, ErrorKind},
+};
+
+use crate::{
+    choices,
+    codec::{
+        self,
+        serde::{
+            hex_0x_bytes::Hex0xBytes, hex_0x_primitive_types_h256::Hex0xH256, hex_0x_u64::Hex0xU64,
+        },
+    },
+    formatting, jsonrpc,
+};
 use serde::{Deserialize, Serialize};
-use serde_with::serde_as;
+use serde_with::{serde_as, DisplayFromStr};
 
 /// Response for "eth_blockNumber".
 /// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_blocknumber>
@@ -159,6 +173,218 @@ fn test_get_balance() {
     assert_eq!(resp, expected);
 }
 
+impl GetBalanceResponse {
+    /// Returns the balance in wei.
+    /// Errors if the balance does not fit in u128.
+    pub fn balance_wei(&self) -> io::Result<u128> {
+        if self.result > primitive_types::U256::from(u128::MAX) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("balance {} overflows u128", self.result),
+            ));
+        }
+        Ok(self.result.as_u128())
+    }
+}
+
+/// Block parameter of the state queries (e.g., "eth_getBalance").
+/// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#default-block>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockTag {
+    Latest,
+    Pending,
+    Earliest,
+    Number(u64),
+}
+
+impl std::fmt::Display for BlockTag {
+    /// Formats the tag as the JSON-RPC parameter (e.g., "latest", "0x1b4").
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BlockTag::Latest => write!(f, "latest"),
+            BlockTag::Pending => write!(f, "pending"),
+            BlockTag::Earliest => write!(f, "earliest"),
+            BlockTag::Number(n) => write!(f, "0x{:x}", n),
+        }
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- jsonrpc::evm::test_get_balance_at_block --exact --show-output
+#[test]
+fn test_get_balance_at_block() {
+    assert_eq!(BlockTag::Latest.to_string(), "latest");
+    assert_eq!(BlockTag::Pending.to_string(), "pending");
+    assert_eq!(BlockTag::Earliest.to_string(), "earliest");
+    assert_eq!(BlockTag::Number(436).to_string(), "0x1b4");
+    assert_eq!(BlockTag::Number(0).to_string(), "0x0");
+
+    let mut req = jsonrpc::RequestWithParamsArray::default();
+    req.method = String::from("eth_getBalance");
+    req.params = Some(vec![
+        String::from("0x8db97c7cece249c2b98bdc0226cc4c2a57bf52fc"),
+        BlockTag::Number(436).to_string(),
+    ]);
+    assert_eq!(
+        req.encode_json().unwrap(),
+        r#"{"jsonrpc":"2.0","id":1,"method":"eth_getBalance","params":["0x8db97c7cece249c2b98bdc0226cc4c2a57bf52fc","0x1b4"]}"#
+    );
+
+    // balance at block 436
+    let resp: GetBalanceResponse =
+        serde_json::from_str(r#"{"jsonrpc":"2.0","id":1,"result":"0x0234c8a3397aab58"}"#).unwrap();
+    assert_eq!(resp.balance_wei().unwrap(), 158972490234375000);
+
+    // balance at latest, beyond u64
+    let resp: GetBalanceResponse =
+        serde_json::from_str(r#"{"jsonrpc":"2.0","id":1,"result":"0x33b2e3c9fd0803ce8000000"}"#)
+            .unwrap();
+    assert_eq!(
+        resp.balance_wei().unwrap(),
+        1_000_000_000_000_000_000_000_000_000
+    );
+
+    let resp = GetBalanceResponse {
+        jsonrpc: "2.0".to_string(),
+        id: 1,
+        result: primitive_types::U256::MAX,
+    };
+    assert!(resp.balance_wei().is_err());
+}
+
+/// Response for "eth_getCode".
+/// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_getcode>
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct GetCodeResponse {
+    pub jsonrpc: String,
+    pub id: u32,
+
+    /// Contract bytecode, empty for externally owned accounts.
+    #[serde_as(as = "Option<Hex0xBytes>")]
+    pub result: Option<Vec<u8>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<jsonrpc::ResponseError>,
+}
+
+impl GetCodeResponse {
+    /// Returns the bytecode at the address.
+    pub fn code(&self) -> io::Result<Vec<u8>> {
+        if let Some(e) = &self.error {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!("eth_getCode failed ({}: {})", e.code, e.message),
+            ));
+        }
+        self.result
+            .clone()
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "eth_getCode returned no result"))
+    }
+}
+
+/// Response for "eth_getStorageAt".
+/// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_getstorageat>
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct GetStorageAtResponse {
+    pub jsonrpc: String,
+    pub id: u32,
+
+    /// Storage word, as returned by the node.
+    #[serde_as(as = "Option<Hex0xBytes>")]
+    pub result: Option<Vec<u8>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<jsonrpc::ResponseError>,
+}
+
+impl GetStorageAtResponse {
+    /// Returns the 32-byte storage word, left-padding shorter values
+    /// (e.g., "0x0" for an unset slot).
+    pub fn word(&self) -> io::Result<primitive_types::H256> {
+        if let Some(e) = &self.error {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!("eth_getStorageAt failed ({}: {})", e.code, e.message),
+            ));
+        }
+        let value = self.result.as_ref().ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                "eth_getStorageAt returned no result",
+            )
+        })?;
+        if value.len() > 32 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "storage word has {} bytes, expected at most 32",
+                    value.len()
+                ),
+            ));
+        }
+        let mut word = [0u8; 32];
+        word[32 - value.len()..].copy_from_slice(value);
+        Ok(primitive_types::H256::from(word))
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- jsonrpc::evm::test_get_code --exact --show-output
+#[test]
+fn test_get_code() {
+    // ref. https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_getcode
+    let resp: GetCodeResponse = serde_json::from_str(
+        r#"{"jsonrpc":"2.0","id":1,"result":"0x600160008035811a818181146012578301005b601b6001356025565b8060005260206000f25b600060078202905091905056"}"#,
+    )
+    .unwrap();
+    let code = resp.code().unwrap();
+    assert_eq!(code.len(), 50);
+    assert_eq!(&code[..4], &[0x60, 0x01, 0x60, 0x00]);
+
+    // externally owned account
+    let resp: GetCodeResponse =
+        serde_json::from_str(r#"{"jsonrpc":"2.0","id":1,"result":"0x"}"#).unwrap();
+    assert!(resp.code().unwrap().is_empty());
+
+    let resp: GetCodeResponse = serde_json::from_str(
+        r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32000,"message":"missing trie node"}}"#,
+    )
+    .unwrap();
+    assert!(resp
+        .code()
+        .unwrap_err()
+        .to_string()
+        .contains("missing trie node"));
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- jsonrpc::evm::test_get_storage_at --exact --show-output
+#[test]
+fn test_get_storage_at() {
+    // ref. https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_getstorageat
+    let resp: GetStorageAtResponse = serde_json::from_str(
+        r#"{"jsonrpc":"2.0","id":1,"result":"0x00000000000000000000000000000000000000000000000000000000000004d2"}"#,
+    )
+    .unwrap();
+    let word = resp.word().unwrap();
+    assert_eq!(
+        primitive_types::U256::from_big_endian(word.as_bytes()),
+        1234.into()
+    );
+
+    // unset slot
+    let resp: GetStorageAtResponse =
+        serde_json::from_str(r#"{"jsonrpc":"2.0","id":1,"result":"0x00"}"#).unwrap();
+    assert_eq!(resp.word().unwrap(), primitive_types::H256::zero());
+
+    let resp = GetStorageAtResponse {
+        jsonrpc: "2.0".to_string(),
+        id: 1,
+        result: Some(vec![1; 33]),
+        error: None,
+    };
+    assert!(resp.word().is_err());
+}
+
 /// Response for "eth_getTransactionCount".
 /// Returns the number of transactions send from this address.
 /// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_gettransactioncount>
@@ -309,6 +535,64 @@ pub struct SendRawTransactionResponse {
     /// Transaction hash.
     #[serde_as(as = "Option<Hex0xH256>")]
     pub result: Option<primitive_types::H256>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<jsonrpc::ResponseError>,
+}
+
+impl SendRawTransactionResponse {
+    /// Returns the transaction hash, or the typed error if the node rejected
+    /// the transaction.
+    pub fn tx_hash(&self) -> std::result::Result<primitive_types::H256, SendTransactionError> {
+        if let Some(e) = &self.error {
+            return Err(SendTransactionError::from_response_error(e));
+        }
+        self.result.ok_or_else(|| SendTransactionError::Other {
+            message: "eth_sendRawTransaction returned no result".to_string(),
+        })
+    }
+}
+
+/// Failure of sending a transaction, or of waiting for its receipt.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SendTransactionError {
+    /// The sender nonce is lower than its next nonce (e.g., already used).
+    #[error("nonce too low ({message})")]
+    NonceTooLow { message: String },
+    /// The transaction execution reverted, with the revert data if known.
+    #[error("execution reverted ({message})")]
+    Reverted {
+        message: String,
+        data: Option<String>,
+    },
+    /// Any other JSON-RPC error.
+    #[error("JSON-RPC error {code} ({message})")]
+    Rpc { code: i32, message: String },
+    /// Failed to reach the node or to decode its response.
+    #[error("failed to send transaction ({message})")]
+    Other { message: String },
+}
+
+impl SendTransactionError {
+    /// Maps the JSON-RPC error, as returned by coreth/geth, to a typed error.
+    pub fn from_response_error(e: &jsonrpc::ResponseError) -> Self {
+        let lower = e.message.to_lowercase();
+        if lower.contains("nonce too low") {
+            SendTransactionError::NonceTooLow {
+                message: e.message.clone(),
+            }
+        } else if lower.contains("execution reverted") {
+            SendTransactionError::Reverted {
+                message: e.message.clone(),
+                data: e.data.clone(),
+            }
+        } else {
+            SendTransactionError::Rpc {
+                code: e.code,
+                message: e.message.clone(),
+            }
+        }
+    }
 }
 
 /// RUST_LOG=debug cargo test --package avalanche-types --lib -- jsonrpc::evm::test_send_raw_transaction --exact --show-output
@@ -337,6 +621,323 @@ fn test_send_raw_transaction() {
             )
             .unwrap(),
         ),
+        error: None,
     };
     assert_eq!(resp, expected);
+    assert_eq!(resp.tx_hash().unwrap(), expected.result.unwrap());
+
+    let resp: SendRawTransactionResponse = serde_json::from_str(
+        r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32000,"message":"nonce too low: address 0x8db97C7cEcE249c2b98bDC0226Cc4C2A57BF52FC, tx: 0 state: 3"}}"#,
+    )
+    .unwrap();
+    assert!(matches!(
+        resp.tx_hash().unwrap_err(),
+        SendTransactionError::NonceTooLow { .. }
+    ));
+
+    let resp: SendRawTransactionResponse = serde_json::from_str(
+        r#"{"jsonrpc":"2.0","id":1,"error":{"code":3,"message":"execution reverted","data":"0x08c379a0"}}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        resp.tx_hash().unwrap_err(),
+        SendTransactionError::Reverted {
+            message: String::from("execution reverted"),
+            data: Some(String::from("0x08c379a0")),
+        }
+    );
+
+    let resp: SendRawTransactionResponse = serde_json::from_str(
+        r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32000,"message":"insufficient funds for gas * price + value"}}"#,
+    )
+    .unwrap();
+    assert!(matches!(
+        resp.tx_hash().unwrap_err(),
+        SendTransactionError::Rpc { code: -32000, .. }
+    ));
+}
+
+/// Call object of "eth_estimateGas" (and "eth_call"), with the fields left
+/// unset omitted from the request.
+/// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_estimategas>
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct CallRequest {
+    pub from: Option<primitive_types::H160>,
+    pub to: Option<primitive_types::H160>,
+    pub value: Option<primitive_types::U256>,
+    pub data: Option<Vec<u8>>,
+}
+
+impl CallRequest {
+    /// Returns the call object as JSON-RPC params, hex-encoded with "0x".
+    pub fn params(&self) -> HashMap<String, String> {
+        let mut params = HashMap::new();
+        if let Some(from) = &self.from {
+            params.insert(String::from("from"), format!("0x{:x}", from));
+        }
+        if let Some(to) = &self.to {
+            params.insert(String::from("to"), format!("0x{:x}", to));
+        }
+        if let Some(value) = &self.value {
+            params.insert(String::from("value"), format!("0x{:x}", value));
+        }
+        if let Some(data) = &self.data {
+            params.insert(String::from("data"), format!("0x{}", hex::encode(data)));
+        }
+        params
+    }
+}
+
+/// Response for "eth_estimateGas".
+/// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_estimategas>
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct EstimateGasResponse {
+    pub jsonrpc: String,
+    pub id: u32,
+
+    /// Estimated gas.
+    #[serde_as(as = "Option<Hex0xU64>")]
+    pub result: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<jsonrpc::ResponseError>,
+}
+
+impl EstimateGasResponse {
+    /// Returns the estimated gas, or the typed error (e.g.,
+    /// [`SendTransactionError::Reverted`] with the revert data) if the
+    /// call fails.
+    pub fn gas(&self) -> std::result::Result<u64, SendTransactionError> {
+        if let Some(e) = &self.error {
+            return Err(SendTransactionError::from_response_error(e));
+        }
+        self.result.ok_or_else(|| SendTransactionError::Other {
+            message: "eth_estimateGas returned no result".to_string(),
+        })
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- jsonrpc::evm::test_estimate_gas --exact --show-output
+#[test]
+fn test_estimate_gas() {
+    use std::str::FromStr;
+
+    let call = CallRequest {
+        from: Some(
+            primitive_types::H160::from_str("0x8db97C7cEcE249c2b98bDC0226Cc4C2A57BF52FC").unwrap(),
+        ),
+        value: Some(primitive_types::U256::from(1_000_000_000_u64)),
+        data: Some(vec![0xa9, 0x05, 0x9c, 0xbb]),
+        ..Default::default()
+    };
+    let params = call.params();
+    assert_eq!(
+        params.get("from").unwrap(),
+        "0x8db97c7cece249c2b98bdc0226cc4c2a57bf52fc"
+    );
+    assert_eq!(params.get("value").unwrap(), "0x3b9aca00");
+    assert_eq!(params.get("data").unwrap(), "0xa9059cbb");
+    assert!(!params.contains_key("to"));
+
+    let resp: EstimateGasResponse =
+        serde_json::from_str(r#"{"jsonrpc":"2.0","id":1,"result":"0x5208"}"#).unwrap();
+    assert_eq!(resp.result, Some(21000));
+    assert_eq!(resp.gas().unwrap(), 21000);
+
+    let resp: EstimateGasResponse = serde_json::from_str(
+        r#"{"jsonrpc":"2.0","id":1,"error":{"code":3,"message":"execution reverted: insufficient balance","data":"0x08c379a00000000000000000000000000000000000000000000000000000000000000020"}}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        resp.gas().unwrap_err(),
+        SendTransactionError::Reverted {
+            message: String::from("execution reverted: insufficient balance"),
+            data: Some(String::from(
+                "0x08c379a00000000000000000000000000000000000000000000000000000000000000020"
+            )),
+        }
+    );
+
+    let resp: EstimateGasResponse = serde_json::from_str(r#"{"jsonrpc":"2.0","id":1}"#).unwrap();
+    assert!(matches!(
+        resp.gas().unwrap_err(),
+        SendTransactionError::Other { .. }
+    ));
+}
+
+/// Response for "avax.getAtomicTx".
+/// ref. <https://docs.avax.network/apis/avalanchego/apis/c-chain#avaxgetatomictx>
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct GetAtomicTxResponse {
+    pub jsonrpc: String,
+    pub id: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<GetAtomicTxResult>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<jsonrpc::ResponseError>,
+}
+
+/// ref. <https://docs.avax.network/apis/avalanchego/apis/c-chain#avaxgetatomictx>
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GetAtomicTxResult {
+    /// Signed transaction bytes in "hex" encoding with the checksum.
+    pub tx: String,
+    pub encoding: String,
+
+    /// Height of the block that accepted the transaction.
+    #[serde_as(as = "DisplayFromStr")]
+    pub block_height: u64,
+}
+
+impl GetAtomicTxResponse {
+    /// Returns the decoded signed transaction with its status from the
+    /// "avax.getAtomicTxStatus" response, or the JSON-RPC error of either call.
+    pub fn atomic_tx(&self, status: &GetAtomicTxStatusResponse) -> io::Result<AtomicTx> {
+        if let Some(e) = &self.error {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!("avax.getAtomicTx failed ({}: {})", e.code, e.message),
+            ));
+        }
+        if let Some(e) = &status.error {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!("avax.getAtomicTxStatus failed ({}: {})", e.code, e.message),
+            ));
+        }
+        let result = match &self.result {
+            Some(result) => result,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "avax.getAtomicTx returned no result",
+                ))
+            }
+        };
+        match &status.result {
+            Some(status) => Ok(AtomicTx {
+                tx_bytes: result.tx_bytes()?,
+                status: status.status.clone(),
+                block_height: result.block_height,
+            }),
+            None => Err(Error::new(
+                ErrorKind::InvalidData,
+                "avax.getAtomicTxStatus returned no result",
+            )),
+        }
+    }
+}
+
+impl GetAtomicTxResult {
+    /// Decodes the "hex"-encoded transaction into its signed bytes.
+    pub fn tx_bytes(&self) -> io::Result<Vec<u8>> {
+        formatting::decode_hex_with_checksum(self.tx.trim_start_matches("0x").as_bytes())
+    }
+}
+
+/// Signed atomic transaction returned by "avax.getAtomicTx", with its status.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct AtomicTx {
+    /// Signed transaction bytes, with the "hex" checksum verified.
+    pub tx_bytes: Vec<u8>,
+    pub status: choices::status::Status,
+    /// Height of the block that accepted the transaction.
+    pub block_height: u64,
+}
+
+impl AtomicTx {
+    /// Returns the codec type ID of the transaction (e.g., "evm.UnsignedExportTx").
+    pub fn type_id(&self) -> io::Result<u32> {
+        let (_, type_id) = codec::unpack_version_and_type_id(&self.tx_bytes)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e.message()))?;
+        Ok(type_id)
+    }
+}
+
+/// Response for "avax.getAtomicTxStatus".
+/// ref. <https://docs.avax.network/apis/avalanchego/apis/c-chain#avaxgetatomictxstatus>
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct GetAtomicTxStatusResponse {
+    pub jsonrpc: String,
+    pub id: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<GetAtomicTxStatusResult>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<jsonrpc::ResponseError>,
+}
+
+/// ref. <https://docs.avax.network/apis/avalanchego/apis/c-chain#avaxgetatomictxstatus>
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GetAtomicTxStatusResult {
+    #[serde_as(as = "DisplayFromStr")]
+    pub status: choices::status::Status,
+
+    /// Only set when the transaction is accepted.
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_height: Option<u64>,
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- jsonrpc::evm::test_get_atomic_tx --exact --show-output
+#[test]
+fn test_get_atomic_tx() {
+    // "evm.UnsignedExportTx" with no inputs and outputs
+    let resp: GetAtomicTxResponse = serde_json::from_str(
+        "
+
+{
+    \"jsonrpc\": \"2.0\",
+    \"result\": {
+        \"tx\": \"0x000000000001000000010000000000000000000000000000000000000000000000000000000000000000abababababababababababababababababababababababababababababababab0000000000000000689e4236\",
+        \"encoding\": \"hex\",
+        \"blockHeight\": \"19\"
+    },
+    \"id\": 1
+}
+
+",
+    )
+    .unwrap();
+
+    assert_eq!(resp.result.as_ref().unwrap().encoding, "hex");
+
+    let status: GetAtomicTxStatusResponse = serde_json::from_str(
+        "
+
+{
+    \"jsonrpc\": \"2.0\",
+    \"result\": {
+        \"status\": \"Accepted\",
+        \"blockHeight\": \"19\"
+    },
+    \"id\": 1
+}
+
+",
+    )
+    .unwrap();
+    let expected = GetAtomicTxStatusResponse {
+        jsonrpc: "2.0".to_string(),
+        id: 1,
+        result: Some(GetAtomicTxStatusResult {
+            status: choices::status::Status::Accepted,
+            block_height: Some(19),
+        }),
+        error: None,
+    };
+    assert_eq!(status, expected);
+
+    let atomic_tx = resp.atomic_tx(&status).unwrap();
+    assert_eq!(atomic_tx.status, choices::status::Status::Accepted);
+    assert_eq!(atomic_tx.block_height, 19);
+    assert_eq!(atomic_tx.type_id().unwrap(), 1);
 }
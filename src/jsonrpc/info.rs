@@ -1,10 +1,12 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     io::{self, Error, ErrorKind},
     net::{IpAddr, Ipv4Addr, SocketAddr},
+    str::FromStr,
 };
 
 use crate::{
+    constants::Network,
     ids::{self, node},
     jsonrpc,
     key::bls,
@@ -47,6 +49,81 @@ impl GetNetworkNameResult {
     }
 }
 
+impl GetNetworkNameResult {
+    /// Returns the well-known network of the name, if recognized.
+    pub fn network(&self) -> Option<Network> {
+        Network::from_name(&self.network_name)
+    }
+}
+
+impl GetNetworkNameResponse {
+    /// Returns the network name, or an error if the call failed.
+    pub fn network_name(&self) -> io::Result<String> {
+        if let Some(e) = &self.error {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!("info.getNetworkName failed ({}: {})", e.code, e.message),
+            ));
+        }
+        match &self.result {
+            Some(result) => Ok(result.network_name.clone()),
+            None => Err(Error::new(
+                ErrorKind::InvalidData,
+                "info.getNetworkName returned no result",
+            )),
+        }
+    }
+
+    /// Returns the well-known network of the name, or "None" for custom networks.
+    pub fn network(&self) -> io::Result<Option<Network>> {
+        self.network_name().map(|name| Network::from_name(&name))
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- jsonrpc::info::test_get_network_name --exact --show-output
+#[test]
+fn test_get_network_name() {
+    // ref. https://docs.avax.network/build/avalanchego-apis/info/#infogetnetworkname
+    let resp: GetNetworkNameResponse = serde_json::from_str(
+        "
+
+{
+    \"jsonrpc\": \"2.0\",
+    \"result\": {
+        \"networkName\": \"mainnet\"
+    },
+    \"id\": 1
+}
+
+",
+    )
+    .unwrap();
+    assert_eq!(resp.network_name().unwrap(), "mainnet");
+    assert_eq!(resp.network().unwrap(), Some(Network::Mainnet));
+    assert_eq!(Network::Mainnet.network_id(), 1);
+
+    let resp: GetNetworkNameResponse = serde_json::from_str(
+        "{\"jsonrpc\":\"2.0\",\"result\":{\"networkName\":\"fuji\"},\"id\":1}",
+    )
+    .unwrap();
+    assert_eq!(resp.result.as_ref().unwrap().network(), Some(Network::Fuji));
+    assert_eq!(Network::Fuji.network_id(), 5);
+
+    // custom networks have no well-known name
+    let resp: GetNetworkNameResponse = serde_json::from_str(
+        "{\"jsonrpc\":\"2.0\",\"result\":{\"networkName\":\"network-1000000\"},\"id\":1}",
+    )
+    .unwrap();
+    assert_eq!(resp.network_name().unwrap(), "network-1000000");
+    assert_eq!(resp.network().unwrap(), None);
+
+    let resp: GetNetworkNameResponse = serde_json::from_str(
+        "{\"jsonrpc\":\"2.0\",\"error\":{\"code\":-32601,\"message\":\"method not found\"},\"id\":1}",
+    )
+    .unwrap();
+    assert!(resp.network().is_err());
+}
+
 /// ref. <https://docs.avax.network/build/avalanchego-apis/info/#infogetnetworkid>
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct GetNetworkIdResponse {
@@ -421,6 +498,43 @@ pub struct GetVmsResponse {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub result: Option<GetVmsResult>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<jsonrpc::ResponseError>,
+}
+
+impl GetVmsResponse {
+    /// Returns the aliases registered for each installed VM, keyed by VM ID,
+    /// or the JSON-RPC error if the call failed.
+    pub fn vm_aliases(&self) -> io::Result<BTreeMap<ids::Id, Vec<String>>> {
+        if let Some(e) = &self.error {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!("info.getVMs failed ({}: {})", e.code, e.message),
+            ));
+        }
+        let vms = match &self.result {
+            Some(result) => result.vms.clone().unwrap_or_default(),
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "info.getVMs returned no result",
+                ))
+            }
+        };
+
+        let mut aliases = BTreeMap::new();
+        for (vm_id, names) in vms {
+            let id = ids::Id::from_str(&vm_id).map_err(|e| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("info.getVMs returned invalid VM ID '{}' ({})", vm_id, e),
+                )
+            })?;
+            aliases.insert(id, names);
+        }
+        Ok(aliases)
+    }
 }
 
 /// ref. <https://docs.avax.network/build/avalanchego-apis/info/#infogetvms>
@@ -443,6 +557,54 @@ impl GetVmsResult {
     }
 }
 
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- jsonrpc::info::test_get_vms --exact --show-output
+#[test]
+fn test_get_vms() {
+    // ref. https://docs.avax.network/build/avalanchego-apis/info/#infogetvms
+    let resp: GetVmsResponse = serde_json::from_str(
+        "
+
+{
+    \"jsonrpc\": \"2.0\",
+    \"result\": {
+        \"vms\": {
+            \"jvYyfQTxGMJLuGWa55kdP2p2zSUYsQ5Raupu4TW34ZAUBAbtq\": [\"avm\"],
+            \"srEXiWaHuhNyGwPUi444Tu47ZEDwxTWrbQiuD7FmgSAQ6X7Dy\": [\"subnetevm\", \"subnet-evm\"]
+        }
+    },
+    \"id\": 1
+}
+
+",
+    )
+    .unwrap();
+
+    let aliases = resp.vm_aliases().unwrap();
+    assert_eq!(aliases.len(), 2);
+    assert_eq!(
+        aliases[&ids::Id::from_str("jvYyfQTxGMJLuGWa55kdP2p2zSUYsQ5Raupu4TW34ZAUBAbtq").unwrap()],
+        vec!["avm".to_string()]
+    );
+    assert_eq!(
+        aliases[&ids::Id::from_str("srEXiWaHuhNyGwPUi444Tu47ZEDwxTWrbQiuD7FmgSAQ6X7Dy").unwrap()],
+        vec!["subnetevm".to_string(), "subnet-evm".to_string()]
+    );
+
+    let resp: GetVmsResponse =
+        serde_json::from_str(r#"{"jsonrpc":"2.0","id":1,"result":{"vms":{"not-an-id":["avm"]}}}"#)
+            .unwrap();
+    assert_eq!(
+        resp.vm_aliases().unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+
+    let resp: GetVmsResponse = serde_json::from_str(
+        r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32000,"message":"boom"}}"#,
+    )
+    .unwrap();
+    assert!(resp.vm_aliases().unwrap_err().to_string().contains("boom"));
+}
+
 /// ref. <https://docs.avax.network/build/avalanchego-apis/info/#infoisbootstrapped>
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct IsBootstrappedResponse {
@@ -651,6 +813,31 @@ fn test_uptime() {
     assert_eq!(resp, expected);
 }
 
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- jsonrpc::info::test_uptime_fractional --exact --show-output
+#[test]
+fn test_uptime_fractional() {
+    // ref. https://docs.avax.network/apis/avalanchego/apis/info#infouptime
+    let resp: UptimeResponse = serde_json::from_str(
+        "
+
+{
+    \"jsonrpc\": \"2.0\",
+    \"result\": {
+        \"rewardingStakePercentage\": \"87.5000\",
+        \"weightedAveragePercentage\": \"93.1234\"
+    },
+    \"id\": 1
+}
+
+",
+    )
+    .unwrap();
+
+    let result = resp.result.unwrap();
+    assert!((result.rewarding_stake_percentage - 87.5_f64).abs() < f64::EPSILON);
+    assert!((result.weighted_average_percentage - 93.1234_f64).abs() < f64::EPSILON);
+}
+
 /// ref. <https://docs.avax.network/apis/avalanchego/apis/info#infopeers>
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct PeersRequest {
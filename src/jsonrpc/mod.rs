@@ -1,5 +1,6 @@
 pub mod admin;
 pub mod avm;
+pub mod canonical;
 pub mod common;
 pub mod evm;
 pub mod health;
@@ -55,6 +56,14 @@ impl Request {
     }
 }
 
+/// Encodes the requests as a JSON-RPC batch in canonical form, so that the
+/// same batch always produces the same bytes (e.g., for signing or for
+/// correlating responses).
+/// ref. <https://www.jsonrpc.org/specification#batch>
+pub fn encode_batch_json<T: Serialize>(requests: &[T]) -> io::Result<String> {
+    canonical::to_canonical_json(requests)
+}
+
 /// ref. <https://docs.avax.network/build/avalanchego-apis/c-chain#eth_getassetbalance>
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct RequestWithParamsArray {
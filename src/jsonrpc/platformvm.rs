@@ -4,10 +4,11 @@ use std::{
 };
 
 use crate::{
-    codec::serde::hex_0x_utxo::Hex0xUtxo,
+    codec::{self, serde::hex_0x_utxo::Hex0xUtxo},
+    constants, formatting,
     ids::{self, node},
     jsonrpc,
-    key::bls,
+    key::{self, bls},
     platformvm, txs,
 };
 use serde::{Deserialize, Serialize};
@@ -257,6 +258,235 @@ fn test_get_tx() {
     assert_eq!(parsed_resp.result.clone().unwrap().encoding, "json");
 }
 
+/// Signed atomic transaction returned by "platform.getTx" with "hex"
+/// encoding, with its status.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct AtomicTx {
+    /// Signed transaction bytes, with the "hex" checksum verified.
+    pub tx_bytes: Vec<u8>,
+    pub status: platformvm::txs::status::Status,
+}
+
+impl AtomicTx {
+    /// Returns the codec type ID of the transaction (e.g., "platformvm.ExportTx").
+    pub fn type_id(&self) -> io::Result<u32> {
+        let (_, type_id) = codec::unpack_version_and_type_id(&self.tx_bytes)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e.message()))?;
+        Ok(type_id)
+    }
+}
+
+/// Response for "platform.getTx" with either "hex" or "json" encoding.
+/// ref. <https://docs.avax.network/apis/avalanchego/apis/p-chain/#platformgettx>
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct GetTxEncodedResponse {
+    pub jsonrpc: String,
+    pub id: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<GetTxEncodedResult>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<jsonrpc::ResponseError>,
+}
+
+impl Default for GetTxEncodedResponse {
+    fn default() -> Self {
+        Self::default()
+    }
+}
+
+impl GetTxEncodedResponse {
+    pub fn default() -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id: 1,
+            result: None,
+            error: None,
+        }
+    }
+
+    /// Returns the decoded signed transaction of the "hex"-encoded response
+    /// with its status from the "platform.getTxStatus" response, or the
+    /// JSON-RPC error of either call.
+    pub fn atomic_tx(&self, status: &GetTxStatusResponse) -> io::Result<AtomicTx> {
+        if let Some(e) = &self.error {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!("platform.getTx failed ({}: {})", e.code, e.message),
+            ));
+        }
+        if let Some(e) = &status.error {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!("platform.getTxStatus failed ({}: {})", e.code, e.message),
+            ));
+        }
+        let tx_bytes = match &self.result {
+            Some(result) => result.tx_bytes()?,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "platform.getTx returned no result",
+                ))
+            }
+        };
+        match &status.result {
+            Some(result) => Ok(AtomicTx {
+                tx_bytes,
+                status: result.status.clone(),
+            }),
+            None => Err(Error::new(
+                ErrorKind::InvalidData,
+                "platform.getTxStatus returned no result",
+            )),
+        }
+    }
+}
+
+/// ref. <https://docs.avax.network/apis/avalanchego/apis/p-chain/#platformgettx>
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct GetTxEncodedResult {
+    pub tx: EncodedTx,
+    pub encoding: String,
+}
+
+/// Transaction returned by "platform.getTx", depending on the requested encoding.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+#[serde(untagged)]
+pub enum EncodedTx {
+    /// Signed transaction bytes in "hex" encoding with the checksum.
+    Hex(String),
+    /// Parsed transaction for "json" encoding.
+    Json(Box<platformvm::txs::Tx>),
+}
+
+impl GetTxEncodedResult {
+    /// Decodes the "hex"-encoded transaction into its signed bytes.
+    /// Returns an error if the transaction was fetched with "json" encoding.
+    pub fn tx_bytes(&self) -> io::Result<Vec<u8>> {
+        match &self.tx {
+            EncodedTx::Hex(s) => {
+                formatting::decode_hex_with_checksum(s.trim_start_matches("0x").as_bytes())
+            }
+            EncodedTx::Json(_) => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("tx has '{}' encoding, not 'hex'", self.encoding),
+            )),
+        }
+    }
+
+    /// Returns the codec type ID of the "hex"-encoded transaction.
+    pub fn type_id(&self) -> io::Result<u32> {
+        let d = self.tx_bytes()?;
+        let (_, type_id) = codec::unpack_version_and_type_id(&d)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e.message()))?;
+        Ok(type_id)
+    }
+
+    /// Returns the parsed transaction, if fetched with "json" encoding.
+    pub fn json_tx(&self) -> Option<&platformvm::txs::Tx> {
+        match &self.tx {
+            EncodedTx::Json(tx) => Some(tx),
+            EncodedTx::Hex(_) => None,
+        }
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- jsonrpc::platformvm::test_get_tx_encoded --exact --show-output
+#[test]
+fn test_get_tx_encoded() {
+    // signed "platformvm.AddValidatorTx" from "test_add_validator_tx_serialization_with_one_signer"
+    let parsed_resp: GetTxEncodedResponse = serde_json::from_str(
+        "
+
+{
+    \"jsonrpc\": \"2.0\",
+    \"result\": {
+        \"tx\": \"0x00000000000c000f424000000000000000000000000000000000000000000000000000000000000000000000000188eec2e099c6a528e689618e8721e04ae85ea574c7a15a7968644d14d54780140000000702c6874d687fc0000000000000000000000000010000000165844a05405f3662c1928142c6c2a783ef871de900000001783b22c6a8d6834c8930aeac3db60263c12e98160ef7221b4d5e622e870f92d90000000088eec2e099c6a528e689618e8721e04ae85ea574c7a15a7968644d14d54780140000000502c6891f11c9e0000000000100000000000000009cd7b3e47904f67cc48eb5b9afdb03e6d18acf6c00000000623d72670000000063c91062000001d1a94a20000000000188eec2e099c6a528e689618e8721e04ae85ea574c7a15a7968644d14d547801400000007000001d1a94a20000000000000000000000000010000000165844a05405f3662c1928142c6c2a783ef871de90000000b0000000000000000000000010000000165844a05405f3662c1928142c6c2a783ef871de900004e2000000001000000090000000183a863c89002ab70a12c37802284b703c1653a93a0a25e0451f0daa07916a32471b165bb4b1bd1b6edc6b494bc6aac63c24fccfd9a547b5f03a60252d45c2480005ffd4ae5\",
+        \"encoding\": \"hex\"
+    },
+    \"id\": 1
+}
+
+",
+    )
+    .unwrap();
+
+    let result = parsed_resp.result.clone().unwrap();
+    assert_eq!(result.encoding, "hex");
+    assert!(result.json_tx().is_none());
+
+    let tx_bytes = result.tx_bytes().unwrap();
+    assert_eq!(tx_bytes.len(), 471);
+    assert_eq!(
+        result.type_id().unwrap(),
+        platformvm::txs::add_validator::Tx::type_id()
+    );
+
+    let tx = platformvm::txs::add_validator::Tx::unpack(&tx_bytes).unwrap();
+    assert_eq!(
+        tx.validator.node_id,
+        node::Id::from_slice(&[
+            0x9c, 0xd7, 0xb3, 0xe4, 0x79, 0x04, 0xf6, 0x7c, 0xc4, 0x8e, //
+            0xb5, 0xb9, 0xaf, 0xdb, 0x03, 0xe6, 0xd1, 0x8a, 0xcf, 0x6c, //
+        ])
+    );
+    assert_eq!(tx.validator.weight, 2_000_000_000_000);
+    assert_eq!(tx.shares, 20000);
+    assert_eq!(tx.creds.len(), 1);
+
+    // the "hex" response carries the signed bytes of atomic transactions
+    let status = GetTxStatusResponse {
+        result: Some(GetTxStatusResult {
+            status: platformvm::txs::status::Status::Committed,
+            height: Some(1),
+        }),
+        ..Default::default()
+    };
+    let atomic_tx = parsed_resp.atomic_tx(&status).unwrap();
+    assert_eq!(atomic_tx.tx_bytes, tx_bytes);
+    assert_eq!(atomic_tx.status, platformvm::txs::status::Status::Committed);
+    assert!(parsed_resp
+        .atomic_tx(&GetTxStatusResponse::default())
+        .is_err());
+
+    let parsed_resp: GetTxEncodedResponse = serde_json::from_str(
+        "
+
+{
+    \"jsonrpc\": \"2.0\",
+    \"result\": {
+        \"tx\": {
+            \"unsignedTx\": {
+                \"networkID\": 1000000,
+                \"blockchainID\": \"11111111111111111111111111111111LpoYY\",
+                \"outputs\": [],
+                \"inputs\": [],
+                \"memo\": \"0x\",
+                \"owner\": {
+                    \"addresses\": [],
+                    \"locktime\": 0,
+                    \"threshold\": 0
+                }
+            },
+            \"credentials\": []
+        },
+        \"encoding\": \"json\"
+    },
+    \"id\": 1
+}
+
+",
+    )
+    .unwrap();
+
+    let result = parsed_resp.result.unwrap();
+    assert_eq!(result.encoding, "json");
+    assert_eq!(result.json_tx().unwrap().unsigned_tx.network_id, 1000000);
+    assert!(result.tx_bytes().is_err());
+}
+
 /// ref. <https://docs.avax.network/apis/avalanchego/apis/p-chain/#platformgettxstatus>
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct GetTxStatusResponse {
@@ -293,6 +523,12 @@ impl GetTxStatusResponse {
 pub struct GetTxStatusResult {
     #[serde_as(as = "DisplayFromStr")]
     pub status: platformvm::txs::status::Status,
+
+    /// Height of the block that accepted the transaction,
+    /// only set once the transaction is committed.
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u64>,
 }
 
 impl Default for GetTxStatusResult {
@@ -305,6 +541,7 @@ impl GetTxStatusResult {
     pub fn default() -> Self {
         Self {
             status: platformvm::txs::status::Status::Unknown(String::new()),
+            height: None,
         }
     }
 }
@@ -332,10 +569,24 @@ fn test_get_tx_status() {
         id: 1,
         result: Some(GetTxStatusResult {
             status: platformvm::txs::status::Status::Committed,
+            height: None,
         }),
         error: None,
     };
     assert_eq!(resp, expected);
+
+    let resp: GetTxStatusResponse = serde_json::from_str(
+        r#"{"jsonrpc":"2.0","result":{"status":"Committed","height":"1234567"},"id":1}"#,
+    )
+    .unwrap();
+    let result = resp.result.unwrap();
+    assert_eq!(result.status, platformvm::txs::status::Status::Committed);
+    assert_eq!(result.height, Some(1234567));
+
+    let resp: GetTxStatusResponse =
+        serde_json::from_str(r#"{"jsonrpc":"2.0","result":{"status":"Processing"},"id":1}"#)
+            .unwrap();
+    assert_eq!(resp.result.unwrap().height, None);
 }
 
 /// ref. <https://docs.avax.network/build/avalanchego-apis/p-chain/#platformgetheight>
@@ -351,6 +602,25 @@ pub struct GetHeightResponse {
     pub error: Option<jsonrpc::ResponseError>,
 }
 
+impl GetHeightResponse {
+    /// Returns the P-chain height, or the JSON-RPC error if the call failed.
+    pub fn height(&self) -> io::Result<u64> {
+        if let Some(e) = &self.error {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!("platform.getHeight failed ({}: {})", e.code, e.message),
+            ));
+        }
+        match &self.result {
+            Some(result) => Ok(result.height),
+            None => Err(Error::new(
+                ErrorKind::InvalidData,
+                "platform.getHeight returned no result",
+            )),
+        }
+    }
+}
+
 /// ref. <https://docs.avax.network/build/avalanchego-apis/p-chain/#platformgetheight>
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
@@ -399,6 +669,121 @@ fn test_get_height() {
     assert_eq!(resp, expected);
 }
 
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- jsonrpc::platformvm::test_get_height_value --exact --show-output
+#[test]
+fn test_get_height_value() {
+    let resp: GetHeightResponse = serde_json::from_str(
+        "
+
+{
+    \"jsonrpc\": \"2.0\",
+    \"result\": {
+        \"height\": \"1234567\"
+    },
+    \"id\": 1
+}
+
+",
+    )
+    .unwrap();
+    assert_eq!(resp.height().unwrap(), 1234567);
+
+    let resp: GetHeightResponse = serde_json::from_str(
+        "
+
+{
+    \"jsonrpc\": \"2.0\",
+    \"error\": {
+        \"code\": -32000,
+        \"message\": \"not bootstrapped\"
+    },
+    \"id\": 1
+}
+
+",
+    )
+    .unwrap();
+    assert!(resp.height().is_err());
+}
+
+/// ref. <https://docs.avax.network/apis/avalanchego/apis/p-chain#platformgetstakingassetid>
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct GetStakingAssetIdResponse {
+    pub jsonrpc: String,
+    pub id: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<GetStakingAssetIdResult>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<jsonrpc::ResponseError>,
+}
+
+impl GetStakingAssetIdResponse {
+    /// Returns the staking asset ID, or the JSON-RPC error if the call failed.
+    pub fn asset_id(&self) -> io::Result<ids::Id> {
+        if let Some(e) = &self.error {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!(
+                    "platform.getStakingAssetID failed ({}: {})",
+                    e.code, e.message
+                ),
+            ));
+        }
+        match &self.result {
+            Some(result) => Ok(result.asset_id),
+            None => Err(Error::new(
+                ErrorKind::InvalidData,
+                "platform.getStakingAssetID returned no result",
+            )),
+        }
+    }
+}
+
+/// ref. <https://docs.avax.network/apis/avalanchego/apis/p-chain#platformgetstakingassetid>
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Default)]
+pub struct GetStakingAssetIdResult {
+    #[serde(rename = "assetID")]
+    pub asset_id: ids::Id,
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- jsonrpc::platformvm::test_get_staking_asset_id --exact --show-output
+#[test]
+fn test_get_staking_asset_id() {
+    use std::str::FromStr;
+
+    // ref. https://docs.avax.network/apis/avalanchego/apis/p-chain#platformgetstakingassetid
+    let resp: GetStakingAssetIdResponse = serde_json::from_str(
+        "
+
+{
+    \"jsonrpc\": \"2.0\",
+    \"result\": {
+        \"assetID\": \"FvwEAhmxKfeiG8SnEvq42hc6whRyY3EFYAvebMqDNDGCgxN5Z\"
+    },
+    \"id\": 1
+}
+
+",
+    )
+    .unwrap();
+    assert_eq!(
+        resp.asset_id().unwrap(),
+        ids::Id::from_str("FvwEAhmxKfeiG8SnEvq42hc6whRyY3EFYAvebMqDNDGCgxN5Z").unwrap()
+    );
+
+    let resp: GetStakingAssetIdResponse = serde_json::from_str(
+        r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32000,"message":"subnet not found"}}"#,
+    )
+    .unwrap();
+    assert!(resp
+        .asset_id()
+        .unwrap_err()
+        .to_string()
+        .contains("subnet not found"));
+}
+
 /// ref. <https://docs.avax.network/build/avalanchego-apis/issuing-api-calls>
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct GetUtxosRequest {
@@ -640,6 +1025,63 @@ impl GetBalanceResult {
     }
 }
 
+/// P-chain balance of the addresses, split into its locked buckets.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BalanceBreakdown {
+    pub total: u64,
+    pub unlocked: u64,
+    pub locked_stakeable: u64,
+    pub locked_not_stakeable: u64,
+    pub utxo_ids: Vec<txs::utxo::Id>,
+}
+
+impl GetBalanceResponse {
+    /// Returns the balance per bucket, or the JSON-RPC error if the call failed.
+    /// Fails if the buckets do not add up to the total balance.
+    pub fn breakdown(&self) -> io::Result<BalanceBreakdown> {
+        if let Some(e) = &self.error {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!("platform.getBalance failed ({}: {})", e.code, e.message),
+            ));
+        }
+        let result = match &self.result {
+            Some(result) => result,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "platform.getBalance returned no result",
+                ))
+            }
+        };
+
+        let breakdown = BalanceBreakdown {
+            total: result.balance,
+            unlocked: result.unlocked,
+            locked_stakeable: result.locked_stakeable.unwrap_or_default(),
+            locked_not_stakeable: result.locked_not_stakeable.unwrap_or_default(),
+            utxo_ids: result.utxo_ids.clone().unwrap_or_default(),
+        };
+        let sum = breakdown
+            .unlocked
+            .checked_add(breakdown.locked_stakeable)
+            .and_then(|sum| sum.checked_add(breakdown.locked_not_stakeable));
+        if sum != Some(breakdown.total) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "platform.getBalance buckets (unlocked {}, locked stakeable {}, locked not stakeable {}) do not add up to balance {}",
+                    breakdown.unlocked,
+                    breakdown.locked_stakeable,
+                    breakdown.locked_not_stakeable,
+                    breakdown.total
+                ),
+            ));
+        }
+        Ok(breakdown)
+    }
+}
+
 /// RUST_LOG=debug cargo test --package avalanche-types --lib -- jsonrpc::platformvm::test_get_balance --exact --show-output
 #[test]
 fn test_get_balance() {
@@ -716,6 +1158,43 @@ fn test_get_balance() {
         error: None,
     };
     assert_eq!(resp, expected);
+
+    let breakdown = resp.breakdown().unwrap();
+    assert_eq!(breakdown.total, 20000000000000000);
+    assert_eq!(breakdown.unlocked, 10000000000000000);
+    assert_eq!(breakdown.locked_stakeable, 10000000000000000);
+    assert_eq!(breakdown.locked_not_stakeable, 0);
+    assert_eq!(
+        breakdown.unlocked + breakdown.locked_stakeable + breakdown.locked_not_stakeable,
+        breakdown.total
+    );
+    assert_eq!(breakdown.utxo_ids.len(), 2);
+    assert_eq!(breakdown.utxo_ids[0].output_index, 1);
+
+    // all three buckets set
+    let resp: GetBalanceResponse = serde_json::from_str(
+        r#"{"jsonrpc":"2.0","id":1,"result":{"balance":"6000","unlocked":"1000","lockedStakeable":"2000","lockedNotStakeable":"3000"}}"#,
+    )
+    .unwrap();
+    let breakdown = resp.breakdown().unwrap();
+    assert_eq!(
+        (
+            breakdown.unlocked,
+            breakdown.locked_stakeable,
+            breakdown.locked_not_stakeable
+        ),
+        (1000, 2000, 3000)
+    );
+    assert!(breakdown.utxo_ids.is_empty());
+
+    // inconsistent buckets are rejected
+    let resp: GetBalanceResponse = serde_json::from_str(
+        r#"{"jsonrpc":"2.0","id":1,"result":{"balance":"6001","unlocked":"1000","lockedStakeable":"2000","lockedNotStakeable":"3000"}}"#,
+    )
+    .unwrap();
+    let err = resp.breakdown().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(err.to_string().contains("do not add up to balance 6001"));
 }
 
 /// ref. <https://docs.avax.network/apis/avalanchego/apis/p-chain#platformgetcurrentvalidators>
@@ -1254,16 +1733,80 @@ impl GetSubnetsResult {
     }
 }
 
+impl GetSubnetsResponse {
+    /// Returns the subnets, or the JSON-RPC error if the call failed.
+    /// Fails if a bech32 control key has the HRP of another network than
+    /// "network_id".
+    pub fn subnet_infos(&self, network_id: u32) -> io::Result<Vec<SubnetInfo>> {
+        if let Some(e) = &self.error {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!("platform.getSubnets failed ({}: {})", e.code, e.message),
+            ));
+        }
+        let subnets = match &self.result {
+            Some(result) => result.subnets.clone().unwrap_or_default(),
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "platform.getSubnets returned no result",
+                ))
+            }
+        };
+
+        let expected_hrp = match constants::NETWORK_ID_TO_HRP.get(&network_id) {
+            Some(v) => v,
+            None => constants::FALLBACK_HRP,
+        };
+        let mut infos = Vec::with_capacity(subnets.len());
+        for subnet in subnets.into_iter() {
+            if let Some(hrp) = &subnet.control_keys_hrp {
+                if hrp != expected_hrp {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!(
+                            "subnet {} control keys have HRP '{}' but network {} expects HRP '{}'",
+                            subnet.id, hrp, network_id, expected_hrp
+                        ),
+                    ));
+                }
+            }
+            infos.push(SubnetInfo {
+                id: subnet.id,
+                control_keys: subnet.control_keys.unwrap_or_default(),
+                threshold: subnet.threshold,
+            });
+        }
+        Ok(infos)
+    }
+}
+
+/// Subnet and the owners allowed to manage it.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SubnetInfo {
+    pub id: ids::Id,
+    pub control_keys: Vec<ids::short::Id>,
+    /// Number of control key signatures required to manage the subnet.
+    pub threshold: u32,
+}
+
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+#[serde(try_from = "RawSubnet")]
 pub struct Subnet {
     pub id: ids::Id,
 
+    /// Accepts both bech32 addresses (e.g., "P-avax1...") and CB58 short IDs.
     #[serde(rename = "controlKeys", skip_serializing_if = "Option::is_none")]
     pub control_keys: Option<Vec<ids::short::Id>>,
 
     #[serde_as(as = "DisplayFromStr")]
     pub threshold: u32,
+
+    /// HRP of the bech32 control keys, if any, so that
+    /// [`GetSubnetsResponse::subnet_infos`] can check them against the network.
+    #[serde(skip)]
+    pub control_keys_hrp: Option<String>,
 }
 
 impl Default for Subnet {
@@ -1278,7 +1821,79 @@ impl Subnet {
             id: ids::Id::empty(),
             control_keys: None,
             threshold: 0,
+            control_keys_hrp: None,
+        }
+    }
+}
+
+/// "platform.getSubnets" subnet as returned by the node, before the control
+/// keys are parsed.
+#[serde_as]
+#[derive(Deserialize)]
+struct RawSubnet {
+    id: ids::Id,
+    #[serde(rename = "controlKeys", default)]
+    control_keys: Option<Vec<String>>,
+    #[serde_as(as = "DisplayFromStr")]
+    threshold: u32,
+}
+
+impl TryFrom<RawSubnet> for Subnet {
+    type Error = io::Error;
+
+    fn try_from(raw: RawSubnet) -> io::Result<Self> {
+        let keys = match raw.control_keys {
+            Some(keys) => keys,
+            None => {
+                return Ok(Self {
+                    id: raw.id,
+                    threshold: raw.threshold,
+                    ..Self::default()
+                })
+            }
+        };
+
+        let mut control_keys = Vec::with_capacity(keys.len());
+        let mut control_keys_hrp: Option<String> = None;
+        for control_key in keys.iter() {
+            if let Ok(short_id) = control_key.parse::<ids::short::Id>() {
+                control_keys.push(short_id);
+                continue;
+            }
+            let bech32_addr = match control_key.split_once('-') {
+                Some((_, addr)) => addr,
+                None => control_key.as_str(),
+            };
+            let (hrp, short_bytes) =
+                key::secp256k1::address::avax_address_to_short_bytes("", bech32_addr).map_err(
+                    |e| {
+                        Error::new(
+                            ErrorKind::InvalidData,
+                            format!("invalid control key '{}': {}", control_key, e),
+                        )
+                    },
+                )?;
+            match &control_keys_hrp {
+                Some(prev) if *prev != hrp => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!(
+                            "control key '{}' has HRP '{}' but other control keys have HRP '{}'",
+                            control_key, hrp, prev
+                        ),
+                    ));
+                }
+                _ => control_keys_hrp = Some(hrp),
+            }
+            control_keys.push(ids::short::Id::from_slice(&short_bytes));
         }
+
+        Ok(Self {
+            id: raw.id,
+            control_keys: Some(control_keys),
+            threshold: raw.threshold,
+            control_keys_hrp,
+        })
     }
 }
 
@@ -1330,6 +1945,68 @@ fn test_get_subnets() {
         error: None,
     };
     assert_eq!(resp, expected);
+
+    // current avalanchego returns control keys as bech32 addresses
+    let resp: GetSubnetsResponse = serde_json::from_str(
+        r#"{
+    "jsonrpc": "2.0",
+    "result": {
+        "subnets": [
+            {
+                "id": "hW8Ma7dLMA7o4xmJf3AXBbo17bXzE7xnThUd3ypM4VAWo1sNJ",
+                "controlKeys": [
+                    "P-avax18jma8ppw3nhx5r4ap8clazz0dps7rv5ukulre5",
+                    "P-avax1myazn9837mrey0zxmqrzjfgt605e72feaxwrcf"
+                ],
+                "threshold": "2"
+            },
+            {
+                "id": "2bRCr6B4MiEfSjidDwxDpdCyviwnfUVqB2HGwhm947w9YYqb7r",
+                "controlKeys": [
+                    "avax1myazn9837mrey0zxmqrzjfgt605e72feaxwrcf"
+                ],
+                "threshold": "1"
+            }
+        ]
+    },
+    "id": 1
+}"#,
+    )
+    .unwrap();
+    let subnets = resp.subnet_infos(1).unwrap();
+    assert_eq!(subnets.len(), 2);
+    assert_eq!(
+        subnets[0].control_keys,
+        vec![
+            ids::short::Id::from_str("6Y3kysjF9jnHnYkdS9yGAuoHyae2eNmeV").unwrap(),
+            ids::short::Id::from_str("LobJpmdncFrFRYtUj4oiUKKWzg9eR4KSX").unwrap(),
+        ]
+    );
+    assert_eq!(subnets[0].threshold, 2);
+    assert_eq!(
+        subnets[1].id,
+        ids::Id::from_str("2bRCr6B4MiEfSjidDwxDpdCyviwnfUVqB2HGwhm947w9YYqb7r").unwrap()
+    );
+    assert_eq!(
+        subnets[1].control_keys,
+        vec![subnets[0].control_keys[1].clone()]
+    );
+    assert_eq!(subnets[1].threshold, 1);
+
+    // mainnet control keys must not be accepted for another network
+    assert!(resp.subnet_infos(5).is_err());
+    assert!(resp.subnet_infos(9999).is_err());
+
+    // control keys of different networks
+    assert!(serde_json::from_str::<GetSubnetsResponse>(
+        r#"{"jsonrpc":"2.0","id":1,"result":{"subnets":[{"id":"hW8Ma7dLMA7o4xmJf3AXBbo17bXzE7xnThUd3ypM4VAWo1sNJ","controlKeys":["P-fuji18jma8ppw3nhx5r4ap8clazz0dps7rv5u6wmu4t","P-avax1myazn9837mrey0zxmqrzjfgt605e72feaxwrcf"],"threshold":"2"}]}}"#
+    )
+    .is_err());
+
+    assert!(serde_json::from_str::<GetSubnetsResponse>(
+        r#"{"jsonrpc":"2.0","id":1,"result":{"subnets":[{"id":"hW8Ma7dLMA7o4xmJf3AXBbo17bXzE7xnThUd3ypM4VAWo1sNJ","controlKeys":["P-avax1invalid"],"threshold":"1"}]}}"#
+    )
+    .is_err());
 }
 
 /// ref. <https://docs.avax.network/apis/avalanchego/apis/p-chain#platformgetblockchains>
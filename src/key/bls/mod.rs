@@ -2,6 +2,8 @@ pub mod private_key;
 pub mod public_key;
 pub mod signature;
 
+pub use public_key::Key as PublicKey;
+
 use std::io::{self, Error, ErrorKind};
 
 use crate::codec::serde::hex_0x_bytes::Hex0xBytes;
@@ -35,6 +35,41 @@ impl Key {
         Ok(Self(pubkey))
     }
 
+    /// Loads the public key from its 48-byte compressed form, rejecting
+    /// any other length, points not on the curve, and points outside of
+    /// the prime-order subgroup.
+    /// ref. "avalanchego/utils/crypto/bls.PublicKeyFromBytes"
+    pub fn from_compressed_bytes(compressed: &[u8]) -> io::Result<Self> {
+        if compressed.len() != LEN {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "invalid compressed public key length {} (expected {})",
+                    compressed.len(),
+                    LEN
+                ),
+            ));
+        }
+        Self::from_bytes(compressed)
+    }
+
+    /// Hex-encodes the compressed public key to string with "0x" prefix.
+    pub fn to_hex(&self) -> String {
+        format!("0x{}", hex::encode(self.to_compressed_bytes()))
+    }
+
+    /// Loads the public key from the hex-encoded compressed bytes,
+    /// with or without "0x" prefix.
+    pub fn from_hex(s: &str) -> io::Result<Self> {
+        let b = hex::decode(s.trim_start_matches("0x")).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("failed hex::decode '{}'", e),
+            )
+        })?;
+        Self::from_compressed_bytes(&b)
+    }
+
     /// Verifies the message and the validity of its signature.
     /// Invariant: [self.0] and [sig] have both been validated.
     /// ref. "avalanchego/utils/crypto/bls.Verify"
@@ -110,3 +145,36 @@ fn test_key() {
     let pubkey2 = Key::from_bytes(&b).unwrap();
     assert_eq!(pubkey, pubkey2);
 }
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- key::bls::public_key::test_key_encoding --exact --show-output
+#[test]
+fn test_key_encoding() {
+    let sk = crate::key::bls::private_key::Key::generate().unwrap();
+    let pubkey = sk.to_public_key();
+
+    let b = pubkey.to_compressed_bytes();
+    assert_eq!(Key::from_compressed_bytes(&b).unwrap(), pubkey);
+
+    let h = pubkey.to_hex();
+    assert!(h.starts_with("0x"));
+    assert_eq!(h.len(), 2 + 2 * LEN);
+    assert_eq!(Key::from_hex(&h).unwrap(), pubkey);
+    assert_eq!(Key::from_hex(h.trim_start_matches("0x")).unwrap(), pubkey);
+
+    // wrong length
+    assert!(Key::from_compressed_bytes(&b[..LEN - 1]).is_err());
+    assert!(Key::from_hex("0x1234").is_err());
+    assert!(Key::from_hex("0xzz").is_err());
+
+    // x = 1 is not on the curve
+    let mut not_on_curve = [0u8; LEN];
+    not_on_curve[0] = 0x80;
+    not_on_curve[LEN - 1] = 0x01;
+    assert!(Key::from_compressed_bytes(&not_on_curve).is_err());
+
+    // (4, sqrt(68)) is on the curve but not in the prime-order subgroup
+    let mut non_subgroup = [0u8; LEN];
+    non_subgroup[0] = 0x80;
+    non_subgroup[LEN - 1] = 0x04;
+    assert!(Key::from_compressed_bytes(&non_subgroup).is_err());
+}
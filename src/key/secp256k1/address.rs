@@ -1,6 +1,6 @@
 use std::io::{self, Error, ErrorKind};
 
-use crate::hash;
+use crate::{constants, hash};
 use primitive_types::H160;
 
 /// ref. <https://eips.ethereum.org/EIPS/eip-55>
@@ -58,6 +58,60 @@ pub fn avax_address_to_short_bytes(chain_alias: &str, addr: &str) -> io::Result<
     Ok((hrp, convert))
 }
 
+/// Checks that the HRP of the "bech32::encode"d AVAX address (with or without
+/// chain alias prefix, e.g., "X-fuji1...") matches the HRP of the network,
+/// so that an address of another network is never sent funds.
+pub fn validate_network(addr: &str, network_id: u32) -> io::Result<()> {
+    let trimmed = addr.trim();
+    let bech32_addr = match trimmed.split_once('-') {
+        Some((_, rest)) => rest,
+        None => trimmed,
+    };
+    let (hrp, _, _) = bech32::decode(bech32_addr).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("failed bech32::decode '{}'", e),
+        )
+    })?;
+
+    let expected = match constants::NETWORK_ID_TO_HRP.get(&network_id) {
+        Some(v) => v,
+        None => constants::FALLBACK_HRP,
+    };
+    if hrp != expected {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "address '{}' has HRP '{}' but network {} expects HRP '{}'",
+                addr, hrp, network_id, expected
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- key::secp256k1::address::test_validate_network --exact --show-output
+#[test]
+fn test_validate_network() {
+    let pubkey = crate::key::secp256k1::test_keys::ewoq().to_public_key();
+    let mainnet_addr = pubkey.to_hrp_address(1, "X").unwrap();
+    let fuji_addr = pubkey.to_hrp_address(5, "X").unwrap();
+    let custom_addr = pubkey.to_hrp_address(9999, "P").unwrap();
+
+    assert!(validate_network(&mainnet_addr, 1).is_ok());
+    assert!(validate_network(mainnet_addr.trim_start_matches("X-"), 1).is_ok());
+    assert!(validate_network(&fuji_addr, 5).is_ok());
+    assert!(validate_network(&custom_addr, 9999).is_ok());
+
+    let err = validate_network(&fuji_addr, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("HRP 'fuji'"));
+    assert!(err.to_string().contains("expects HRP 'avax'"));
+
+    assert!(validate_network(&custom_addr, 1).is_err());
+    assert!(validate_network("X-avax1invalid", 1).is_err());
+}
+
 /// RUST_LOG=debug cargo test --package avalanche-types --lib -- key::secp256k1::address::test_avax_address_to_short_bytes --exact --show-output
 #[test]
 fn test_avax_address_to_short_bytes() {
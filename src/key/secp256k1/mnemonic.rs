@@ -1,5 +1,6 @@
 use crate::errors::{Error, Result};
-use bip32::{DerivationPath, Language, Mnemonic, XPrv};
+pub use bip32::{DerivationPath, Mnemonic};
+use bip32::{Language, XPrv};
 use rand_core::OsRng;
 
 /// ref. <https://github.com/ava-labs/avax-js-cli-tools/blob/3e3f714e4227aca83dc3978fcb6a4fd698e09065/address_gen.js>
@@ -37,10 +38,15 @@ impl crate::key::secp256k1::private_key::Key {
             message: format!("failed to read mnemonic phrase ({})", e),
             retryable: false,
         })?;
+        Self::from_mnemonic_with_path(&mnemonic, &deriv)
+    }
+
+    /// Loads the private key at the derivation path of the mnemonic.
+    pub fn from_mnemonic_with_path(mnemonic: &Mnemonic, deriv: &DerivationPath) -> Result<Self> {
         let seed = mnemonic.to_seed("password");
 
         // ref. https://github.com/ava-labs/avalanche-wallet/blob/v0.3.8/src/js/wallets/MnemonicWallet.ts
-        let child_xprv = XPrv::derive_from_path(&seed, &deriv).map_err(|e| Error::Other {
+        let child_xprv = XPrv::derive_from_path(&seed, deriv).map_err(|e| Error::Other {
             message: format!("failed to derive AVAX account path ({})", e),
             retryable: false,
         })?;
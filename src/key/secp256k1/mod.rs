@@ -4,6 +4,7 @@ pub mod kms;
 pub mod private_key;
 pub mod public_key;
 pub mod signature;
+pub mod test_keys;
 pub mod txs;
 
 #[cfg(feature = "libsecp256k1")]
@@ -0,0 +1,57 @@
+//! Well-known deterministic keys for tests and local networks.
+//! NEVER use these keys to hold real funds.
+use crate::{hash, key::secp256k1::private_key::Key};
+
+/// "ewoq" key pre-funded by the avalanchego local network genesis.
+/// ref. <https://github.com/ava-labs/avalanchego/blob/master/genesis/genesis_local.go>
+pub const EWOQ_PRIVATE_KEY_HEX: &str =
+    "0x56289e99c94b6912bfc12adc093c9b51124f0dc54ac7a766b2bc5ccf558d8027";
+
+/// Returns the avalanchego "ewoq" key.
+pub fn ewoq() -> Key {
+    Key::from_hex(EWOQ_PRIVATE_KEY_HEX).unwrap()
+}
+
+/// Returns the deterministic test key at the index: the embedded
+/// [`crate::key::secp256k1::TEST_KEYS`] first (index 0 being "ewoq"),
+/// then keys derived from the SHA256 of the "ewoq" key and the index.
+pub fn test_key(index: usize) -> Key {
+    if let Some(k) = crate::key::secp256k1::TEST_KEYS.get(index) {
+        return k.clone();
+    }
+
+    let mut seed = ewoq().to_bytes().to_vec();
+    seed.extend_from_slice(&(index as u64).to_be_bytes());
+    let mut d = hash::sha256(&seed);
+    // re-hash in the negligible case the digest is not a valid scalar
+    loop {
+        if let Ok(k) = Key::from_bytes(&d) {
+            return k;
+        }
+        d = hash::sha256(&d);
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- key::secp256k1::test_keys::test_ewoq --exact --show-output
+#[test]
+fn test_ewoq() {
+    let k = ewoq();
+    assert_eq!(
+        k.to_cb58(),
+        "PrivateKey-ewoqjP7PxY4yr3iLTpLisriqt94hdyDFNgchSxGGztUrTXtNN"
+    );
+    assert_eq!(
+        k.to_public_key().to_hrp_address(1, "X").unwrap(),
+        "X-avax18jma8ppw3nhx5r4ap8clazz0dps7rv5ukulre5"
+    );
+    assert_eq!(
+        k.to_public_key().to_hrp_address(9999, "X").unwrap(),
+        "X-custom18jma8ppw3nhx5r4ap8clazz0dps7rv5u9xde7p"
+    );
+
+    assert_eq!(test_key(0), k);
+    let n = crate::key::secp256k1::TEST_KEYS.len();
+    assert_eq!(test_key(n), test_key(n));
+    assert_ne!(test_key(n), test_key(n + 1));
+    assert_ne!(test_key(n), k);
+}
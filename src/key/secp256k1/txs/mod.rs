@@ -1,6 +1,6 @@
 pub mod transfer;
 
-use std::cmp::Ordering;
+use std::{cmp::Ordering, io};
 
 use crate::{
     codec::{self, serde::hex_0x_bytes::Hex0xBytes},
@@ -262,6 +262,36 @@ impl OutputOwners {
         }
     }
 
+    /// Creates the owners of a "threshold"-of-N multisig output, with the
+    /// addresses sorted and deduplicated as avalanchego requires.
+    /// Errors unless 0 < threshold <= the number of distinct addresses.
+    /// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/vms/secp256k1fx#OutputOwners.Verify>
+    pub fn multisig(
+        threshold: u32,
+        mut addresses: Vec<short::Id>,
+        locktime: u64,
+    ) -> io::Result<Self> {
+        addresses.sort();
+        addresses.dedup();
+
+        if threshold == 0 || threshold as usize > addresses.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "invalid threshold {} for {} distinct addresses",
+                    threshold,
+                    addresses.len()
+                ),
+            ));
+        }
+
+        Ok(Self {
+            locktime,
+            threshold,
+            addresses,
+        })
+    }
+
     pub fn type_name() -> String {
         "secp256k1fx.OutputOwners".to_string()
     }
@@ -414,6 +444,34 @@ impl PartialEq for Input {
     }
 }
 
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- key::secp256k1::txs::test_output_owners_multisig --exact --show-output
+#[test]
+fn test_output_owners_multisig() {
+    let a = short::Id::from_slice(&[1; 20]);
+    let b = short::Id::from_slice(&[2; 20]);
+    let c = short::Id::from_slice(&[3; 20]);
+
+    // 2-of-3 vault, with unsorted and duplicate addresses
+    let owners =
+        OutputOwners::multisig(2, vec![c.clone(), a.clone(), b.clone(), a.clone()], 10).unwrap();
+    assert_eq!(owners.threshold, 2);
+    assert_eq!(owners.locktime, 10);
+    assert_eq!(owners.addresses, vec![a.clone(), b.clone(), c.clone()]);
+    assert_eq!(
+        owners,
+        OutputOwners::new(10, 2, &[a.clone(), b.clone(), c.clone()])
+    );
+
+    // 3-of-3 is the most a vault of three can require
+    assert!(OutputOwners::multisig(3, vec![a.clone(), b.clone(), c.clone()], 0).is_ok());
+
+    let err = OutputOwners::multisig(4, vec![a.clone(), b.clone(), c.clone()], 0).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert!(OutputOwners::multisig(3, vec![a.clone(), b, a.clone()], 0).is_err());
+    assert!(OutputOwners::multisig(0, vec![a], 0).is_err());
+    assert!(OutputOwners::multisig(1, vec![], 0).is_err());
+}
+
 /// RUST_LOG=debug cargo test --package avalanche-types --lib -- key::secp256k1::txs::test_sort_inputs --exact --show-output
 #[test]
 fn test_sort_inputs() {
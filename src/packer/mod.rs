@@ -31,6 +31,21 @@ pub const U64_SENTINEL: u64 = 0;
 pub const BOOL_LEN: usize = 1;
 pub const BOOL_SENTINEL: bool = false;
 
+/// Returns the packed size of a byte slice of "payload_len" bytes,
+/// including its 4-byte length header.
+/// ref. "avalanchego/utils/wrappers.Packer.PackBytes"
+pub const fn prefixed_len(payload_len: usize) -> usize {
+    payload_len + U32_LEN
+}
+
+/// Returns the bytes an array of "count" length-prefixed byte slices adds
+/// on top of their payloads: the 4-byte array length and each 4-byte
+/// length header.
+/// ref. "avalanchego/utils/wrappers.Packer.Pack2DByteSlice"
+pub const fn bytes_overhead(count: usize) -> usize {
+    U32_LEN + count * U32_LEN
+}
+
 /// Packer packs and unpacks the underlying bytes array.
 /// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/utils/wrappers#Packer>
 /// ref. <https://doc.rust-lang.org/1.7.0/book/mutability.html>
@@ -1300,3 +1315,23 @@ fn test_pack_str() {
     let expected = vec![0x00, 0x00, 0x00, 0x06, 0x00, 0x04, 65, 118, 97, 120];
     assert_eq!(&packer.take_bytes()[..], &expected[..]);
 }
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- packer::test_prefixed_len --exact --show-output
+#[test]
+fn test_prefixed_len() {
+    let payload = b"avalanche".to_vec();
+    let packer = Packer::new(1024, 0);
+    packer.pack_bytes_with_header(&payload).unwrap();
+    assert_eq!(prefixed_len(payload.len()), 13);
+    assert_eq!(packer.bytes_len(), prefixed_len(payload.len()));
+
+    let slices = vec![vec![1; 3], vec![], vec![2; 32]];
+    let payloads: usize = slices.iter().map(|s| s.len()).sum();
+    let packer = Packer::new(1024, 0);
+    packer.pack_2d_bytes_with_header(slices.clone()).unwrap();
+    assert_eq!(bytes_overhead(slices.len()), 16);
+    assert_eq!(packer.bytes_len(), payloads + bytes_overhead(slices.len()));
+
+    // an empty array still has its length
+    assert_eq!(bytes_overhead(0), U32_LEN);
+}
@@ -0,0 +1,227 @@
+//! P-chain transaction fees before and after the Etna upgrade.
+//!
+//! Before Etna, each transaction type pays a fixed fee. From Etna on, the fee
+//! is the gas of the transaction complexity times the current gas price.
+//! ref. <https://github.com/avalanche-foundation/ACPs/tree/main/ACPs/103-dynamic-fees>
+use crate::{
+    codec,
+    errors::{Error, Result},
+    jsonrpc::info::GetTxFeeResult,
+};
+
+/// When the dynamic fees activate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Activation {
+    /// First P-chain height using the dynamic fees.
+    Height(u64),
+    /// First Unix timestamp (in seconds) using the dynamic fees.
+    Timestamp(u64),
+}
+
+/// Resource usage of a transaction, and the gas weight of each resource.
+/// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/vms/components/gas#Dimensions>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Dimensions {
+    pub bandwidth: u64,
+    pub db_read: u64,
+    pub db_write: u64,
+    pub compute: u64,
+}
+
+impl Dimensions {
+    /// Weights used on the primary network at Etna activation.
+    pub fn etna_weights() -> Self {
+        Self {
+            bandwidth: 1,
+            db_read: 1000,
+            db_write: 1000,
+            compute: 4,
+        }
+    }
+
+    /// Returns the gas of the complexity under the weights.
+    pub fn to_gas(&self, weights: &Dimensions) -> Result<u64> {
+        [
+            (self.bandwidth, weights.bandwidth),
+            (self.db_read, weights.db_read),
+            (self.db_write, weights.db_write),
+            (self.compute, weights.compute),
+        ]
+        .iter()
+        .try_fold(0u64, |gas, (complexity, weight)| {
+            complexity
+                .checked_mul(*weight)
+                .and_then(|g| gas.checked_add(g))
+        })
+        .ok_or_else(|| Error::Other {
+            message: "gas overflows u64".to_string(),
+            retryable: false,
+        })
+    }
+}
+
+/// Fee-relevant view of a P-chain transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FeeTx {
+    /// Codec type ID of the unsigned transaction (e.g., 17 for "platformvm.ImportTx").
+    pub type_id: u32,
+    /// P-chain height the transaction is included at.
+    pub height: u64,
+    /// Unix timestamp (in seconds) of the block the transaction is included in.
+    pub timestamp: u64,
+    /// Complexity of the transaction, only used after the fork.
+    pub complexity: Dimensions,
+}
+
+/// Network fee configuration on both sides of the Etna fork.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeeContext {
+    pub activation: Activation,
+    /// Pre-fork fees, as returned by "info.getTxFee".
+    pub static_fees: GetTxFeeResult,
+    /// Post-fork gas weight of each resource.
+    pub weights: Dimensions,
+    /// Post-fork price of a unit of gas, in nAVAX.
+    pub gas_price: u64,
+}
+
+impl FeeContext {
+    /// Returns true if the dynamic fees apply to the transaction.
+    pub fn is_activated(&self, tx: &FeeTx) -> bool {
+        match self.activation {
+            Activation::Height(height) => tx.height >= height,
+            Activation::Timestamp(timestamp) => tx.timestamp >= timestamp,
+        }
+    }
+
+    /// Returns the pre-fork fee of the transaction type.
+    /// ref. "avalanchego/vms/platformvm/txs/fee.staticCalculator"
+    pub fn static_fee(&self, type_id: u32) -> Result<u64> {
+        let fees = &self.static_fees;
+        let fee = match type_name(type_id) {
+            Some("platformvm.AddValidatorTx") => fees.add_primary_network_validator_fee,
+            Some("platformvm.AddDelegatorTx") => fees.add_primary_network_delegator_fee,
+            Some("platformvm.AddSubnetValidatorTx") => fees.add_subnet_validator_fee,
+            Some("platformvm.CreateChainTx") => fees.create_blockchain_tx_fee,
+            Some("platformvm.CreateSubnetTx") => fees.create_subnet_tx_fee,
+            Some("platformvm.TransformSubnetTx") => fees.transform_subnet_tx_fee,
+            // assumes the primary network; subnet stakers pay the subnet fees
+            Some("platformvm.AddPermissionlessValidatorTx") => {
+                fees.add_primary_network_validator_fee
+            }
+            Some("platformvm.AddPermissionlessDelegatorTx") => {
+                fees.add_primary_network_delegator_fee
+            }
+            Some("platformvm.ImportTx")
+            | Some("platformvm.ExportTx")
+            | Some("platformvm.RemoveSubnetValidatorTx") => fees.tx_fee,
+            // block proposals are issued by the network itself
+            Some("platformvm.AdvanceTimeTx") | Some("platformvm.RewardValidatorTx") => 0,
+            _ => {
+                return Err(Error::Other {
+                    message: format!("unknown P-chain tx type ID {}", type_id),
+                    retryable: false,
+                })
+            }
+        };
+        Ok(fee)
+    }
+
+    /// Returns the post-fork fee of the transaction complexity.
+    pub fn dynamic_fee(&self, complexity: &Dimensions) -> Result<u64> {
+        complexity
+            .to_gas(&self.weights)?
+            .checked_mul(self.gas_price)
+            .ok_or_else(|| Error::Other {
+                message: "fee overflows u64".to_string(),
+                retryable: false,
+            })
+    }
+}
+
+/// Returns the fee the transaction pays, picking the static or the dynamic
+/// fee depending on which side of the fork it is included on.
+pub fn effective_fee(tx: &FeeTx, context: &FeeContext) -> Result<u64> {
+    if context.is_activated(tx) {
+        context.dynamic_fee(&tx.complexity)
+    } else {
+        context.static_fee(tx.type_id)
+    }
+}
+
+fn type_name(type_id: u32) -> Option<&'static str> {
+    codec::P_TYPES
+        .iter()
+        .find(|(_, id)| **id as u32 == type_id)
+        .map(|(name, _)| name.as_str())
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- platformvm::fees::test_effective_fee --exact --show-output
+#[test]
+fn test_effective_fee() {
+    use crate::platformvm::txs::{create_subnet, import};
+
+    let static_fees = GetTxFeeResult {
+        tx_fee: 1_000_000,
+        create_subnet_tx_fee: 100_000_000,
+        ..Default::default()
+    };
+    let complexity = Dimensions {
+        bandwidth: 500,
+        db_read: 3,
+        db_write: 4,
+        compute: 250,
+    };
+
+    for activation in [Activation::Height(100), Activation::Timestamp(1_000)] {
+        let context = FeeContext {
+            activation,
+            static_fees: static_fees.clone(),
+            weights: Dimensions::etna_weights(),
+            gas_price: 2,
+        };
+
+        // pre-fork pays the static fee of its type
+        let mut tx = FeeTx {
+            type_id: import::Tx::type_id(),
+            height: 99,
+            timestamp: 999,
+            complexity,
+        };
+        assert!(!context.is_activated(&tx));
+        assert_eq!(effective_fee(&tx, &context).unwrap(), 1_000_000);
+
+        tx.type_id = create_subnet::Tx::type_id();
+        assert_eq!(effective_fee(&tx, &context).unwrap(), 100_000_000);
+
+        // post-fork pays for its gas: (500 + 3000 + 4000 + 1000) * 2
+        tx.height = 100;
+        tx.timestamp = 1_000;
+        assert!(context.is_activated(&tx));
+        assert_eq!(effective_fee(&tx, &context).unwrap(), 17_000);
+    }
+
+    let context = FeeContext {
+        activation: Activation::Height(0),
+        static_fees,
+        weights: Dimensions::etna_weights(),
+        gas_price: u64::MAX,
+    };
+    assert!(effective_fee(&FeeTx::default(), &context).is_ok());
+    let tx = FeeTx {
+        complexity,
+        ..Default::default()
+    };
+    assert!(effective_fee(&tx, &context).is_err());
+
+    // unknown tx types are rejected before the fork
+    let context = FeeContext {
+        activation: Activation::Height(u64::MAX),
+        ..context
+    };
+    let tx = FeeTx {
+        type_id: 9999,
+        ..Default::default()
+    };
+    assert!(effective_fee(&tx, &context).is_err());
+}
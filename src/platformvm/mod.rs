@@ -1,4 +1,7 @@
+pub mod fees;
+pub mod staking;
 pub mod txs;
+pub mod validators;
 
 use crate::ids;
 
@@ -0,0 +1,46 @@
+//! Staking period helpers shared by the validator and delegator transactions.
+use crate::platformvm::txs::Validator;
+
+/// Unix timestamps (in seconds) bounding a validation or delegation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StakingPeriod {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl StakingPeriod {
+    pub fn new(start: u64, end: u64) -> Self {
+        Self { start, end }
+    }
+}
+
+impl From<&Validator> for StakingPeriod {
+    fn from(validator: &Validator) -> Self {
+        Self::new(validator.start, validator.end)
+    }
+}
+
+/// Returns the locktime of the reward owner outputs for the staking period.
+/// Rewards are only issued once the stake ends, so the reward UTXOs become
+/// spendable at the end time.
+pub fn reward_unlock_time(staking_period: &StakingPeriod) -> u64 {
+    staking_period.end
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- platformvm::staking::test_reward_unlock_time --exact --show-output
+#[test]
+fn test_reward_unlock_time() {
+    use crate::ids::node;
+
+    let period = StakingPeriod::new(1_700_000_000, 1_700_000_000 + 14 * 24 * 60 * 60);
+    assert_eq!(reward_unlock_time(&period), 1_701_209_600);
+
+    let validator = Validator {
+        node_id: node::Id::empty(),
+        start: period.start,
+        end: period.end,
+        weight: 2_000_000_000_000,
+    };
+    assert_eq!(StakingPeriod::from(&validator), period);
+    assert_eq!(reward_unlock_time(&(&validator).into()), validator.end);
+}
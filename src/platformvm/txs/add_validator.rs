@@ -1,7 +1,9 @@
 use crate::{
     codec,
     errors::{Error, Result},
-    hash, ids, key, platformvm, txs,
+    hash,
+    ids::{self, node},
+    key, packer, platformvm, txs,
 };
 use serde::{Deserialize, Serialize};
 
@@ -281,6 +283,66 @@ impl Tx {
 
         Ok(())
     }
+
+    /// Parses the signed transaction bytes, the inverse of [`Tx::sign`].
+    /// The metadata is set from the unsigned and signed bytes.
+    /// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/vms/platformvm/txs#Parse>
+    pub fn unpack(d: &[u8]) -> Result<Self> {
+        let (tx_bytes_with_no_signature, creds) = txs::split(d)?;
+        let packer = packer::Packer::load_bytes_for_unpack(
+            tx_bytes_with_no_signature.len() + 1024,
+            &tx_bytes_with_no_signature,
+        );
+
+        let _codec_version = packer.unpack_u16()?;
+        let type_id = packer.unpack_u32()?;
+        if type_id != Self::type_id() {
+            return Err(Error::Other {
+                message: format!(
+                    "unexpected type ID {} (expected {})",
+                    type_id,
+                    Self::type_id()
+                ),
+                retryable: false,
+            });
+        }
+
+        let mut base_tx = txs::Tx::unpack_from(&packer)?;
+        base_tx.metadata = Some(txs::Metadata::new(&tx_bytes_with_no_signature, d));
+
+        let validator = platformvm::txs::Validator {
+            node_id: node::Id::from_slice(&packer.unpack_bytes(node::LEN)?),
+            start: packer.unpack_u64()?,
+            end: packer.unpack_u64()?,
+            weight: packer.unpack_u64()?,
+        };
+
+        let stake_len = packer.unpack_u32()?;
+        let mut stake_transferable_outputs = Vec::new();
+        for _ in 0..stake_len {
+            stake_transferable_outputs.push(txs::transferable::Output::unpack_from(&packer)?);
+        }
+
+        let owners_type_id = packer.unpack_u32()?;
+        if owners_type_id != key::secp256k1::txs::OutputOwners::type_id() {
+            return Err(Error::Other {
+                message: format!("unexpected rewards owner type ID {}", owners_type_id),
+                retryable: false,
+            });
+        }
+        let rewards_owner = txs::transferable::unpack_output_owners(&packer)?;
+        let shares = packer.unpack_u32()?;
+
+        Ok(Self {
+            base_tx,
+            validator,
+            stake_transferable_outputs: (!stake_transferable_outputs.is_empty())
+                .then_some(stake_transferable_outputs),
+            rewards_owner,
+            shares,
+            creds,
+        })
+    }
 }
 
 /// RUST_LOG=debug cargo test --package avalanche-types --lib -- platformvm::txs::add_validator::test_add_validator_tx_serialization_with_one_signer --exact --show-output
@@ -562,4 +624,8 @@ fn test_add_validator_tx_serialization_with_one_signer() {
         expected_signed_bytes,
         &tx_bytes_with_signatures
     ));
+
+    let unpacked = Tx::unpack(&tx_bytes_with_signatures).unwrap();
+    assert_eq!(unpacked, tx);
+    assert!(Tx::unpack(&tx_bytes_with_signatures[..100]).is_err());
 }
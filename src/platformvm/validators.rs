@@ -0,0 +1,6 @@
+//! Validator weights of the P-chain.
+//!
+//! Weights are diffed between canonical warp validator sets, whose nodes
+//! sharing a BLS public key are merged, so that changes are reported per
+//! public key rather than per node.
+pub use crate::warp::validators::{weight_diff, CanonicalValidatorSet, Validator, WeightChange};
@@ -0,0 +1,96 @@
+//! Bootstrapping progress of a subnet VM.
+use std::{
+    io::Result,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use crate::subnet::rpc::health::HealthResult;
+
+/// Number of containers (e.g., blocks) fetched while bootstrapping, out of
+/// the total known so far. Clones share the same counters, so that the VM
+/// can update the progress while its health check reports it.
+#[derive(Debug, Clone, Default)]
+pub struct Progress {
+    fetched: Arc<AtomicU64>,
+    total: Arc<AtomicU64>,
+}
+
+impl Progress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of containers to fetch, which may grow as the
+    /// ancestors of the accepted frontier are discovered.
+    pub fn set_total(&self, total: u64) {
+        self.total.store(total, Ordering::Relaxed);
+    }
+
+    /// Records "n" more fetched containers.
+    pub fn add_fetched(&self, n: u64) {
+        self.fetched.fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub fn fetched(&self) -> u64 {
+        self.fetched.load(Ordering::Relaxed)
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total.load(Ordering::Relaxed)
+    }
+
+    /// Returns the fetched share of the total in [0, 100], which is 0 while
+    /// the total is unknown.
+    pub fn percentage(&self) -> f64 {
+        let total = self.total();
+        if total == 0 {
+            return 0.0;
+        }
+        (self.fetched() as f64 / total as f64 * 100.0).min(100.0)
+    }
+
+    /// Returns the progress as health check details, e.g.,
+    /// {"fetched":50,"total":200,"percentage":25.0}.
+    pub fn details(&self) -> serde_json::Value {
+        serde_json::json!({
+            "fetched": self.fetched(),
+            "total": self.total(),
+            "percentage": self.percentage(),
+        })
+    }
+
+    /// Returns the encoded health of a VM that is making progress, for
+    /// [`crate::subnet::rpc::health::Checkable::health_check`].
+    pub fn health_check(&self) -> Result<Vec<u8>> {
+        HealthResult::healthy(Some(self.details())).to_vec()
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::bootstrap::test_progress --exact --show-output
+#[test]
+fn test_progress() {
+    let progress = Progress::new();
+    assert_eq!(progress.percentage(), 0.0);
+
+    let vm_progress = progress.clone();
+    vm_progress.set_total(200);
+    vm_progress.add_fetched(30);
+    vm_progress.add_fetched(20);
+    assert_eq!(progress.fetched(), 50);
+    assert_eq!(progress.total(), 200);
+    assert_eq!(progress.percentage(), 25.0);
+
+    let health: HealthResult = serde_json::from_slice(&progress.health_check().unwrap()).unwrap();
+    assert!(health.healthy);
+    assert_eq!(
+        health.details.unwrap(),
+        serde_json::json!({ "fetched": 50, "total": 200, "percentage": 25.0 })
+    );
+
+    // the total may lag behind the fetched containers
+    vm_progress.add_fetched(250);
+    assert_eq!(progress.percentage(), 100.0);
+}
@@ -1 +1,4 @@
+pub mod snowball;
 pub mod snowman;
+
+pub use snowball::Parameters;
@@ -0,0 +1,202 @@
+use std::{io, time::Duration};
+
+/// Snowball consensus parameters accepted by a VM.
+/// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/snow/consensus/snowball#Parameters>
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Parameters {
+    /// Sample size.
+    pub k: i32,
+    /// Vote threshold to change the preference.
+    pub alpha_preference: i32,
+    /// Vote threshold to increase the confidence.
+    pub alpha_confidence: i32,
+    /// Number of consecutive successful polls required for finalization.
+    pub beta: i32,
+    /// Number of outstanding polls the engine will target to have while there is
+    /// something processing.
+    pub concurrent_repolls: i32,
+    /// Optimal number of processing containers in consensus.
+    pub optimal_processing: i32,
+    /// Maximum number of processing items to be considered healthy.
+    pub max_outstanding_items: i32,
+    /// Maximum amount of time an item should be processing and still be healthy.
+    pub max_item_processing_time: Duration,
+}
+
+impl Default for Parameters {
+    fn default() -> Self {
+        Self::default()
+    }
+}
+
+impl Parameters {
+    /// Matches the defaults in avalanchego.
+    /// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/snow/consensus/snowball#DefaultParameters>
+    pub fn default() -> Self {
+        Self {
+            k: 20,
+            alpha_preference: 15,
+            alpha_confidence: 15,
+            beta: 20,
+            concurrent_repolls: 4,
+            optimal_processing: 10,
+            max_outstanding_items: 256,
+            max_item_processing_time: Duration::from_secs(30),
+        }
+    }
+
+    /// Verifies the inter-parameter constraints, returning the first one violated.
+    /// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/snow/consensus/snowball#Parameters.Verify>
+    pub fn validate(&self) -> Result<(), Violation> {
+        if self.alpha_preference <= self.k / 2 {
+            return Err(Violation::AlphaPreferenceTooSmall);
+        }
+        if self.alpha_confidence < self.alpha_preference {
+            return Err(Violation::AlphaConfidenceTooSmall);
+        }
+        if self.k < self.alpha_confidence {
+            return Err(Violation::AlphaConfidenceTooLarge);
+        }
+        if self.beta <= 0 {
+            return Err(Violation::BetaNotPositive);
+        }
+        if self.concurrent_repolls <= 0 {
+            return Err(Violation::ConcurrentRepollsNotPositive);
+        }
+        if self.concurrent_repolls > self.beta {
+            return Err(Violation::ConcurrentRepollsTooLarge);
+        }
+        if self.optimal_processing <= 0 {
+            return Err(Violation::OptimalProcessingNotPositive);
+        }
+        if self.max_outstanding_items <= 0 {
+            return Err(Violation::MaxOutstandingItemsNotPositive);
+        }
+        if self.max_item_processing_time.is_zero() {
+            return Err(Violation::MaxItemProcessingTimeNotPositive);
+        }
+        Ok(())
+    }
+}
+
+/// Consensus parameter constraint that failed validation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Violation {
+    AlphaPreferenceTooSmall,
+    AlphaConfidenceTooSmall,
+    AlphaConfidenceTooLarge,
+    BetaNotPositive,
+    ConcurrentRepollsNotPositive,
+    ConcurrentRepollsTooLarge,
+    OptimalProcessingNotPositive,
+    MaxOutstandingItemsNotPositive,
+    MaxItemProcessingTimeNotPositive,
+}
+
+impl Violation {
+    /// Returns the condition that the parameters fail to satisfy.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Violation::AlphaPreferenceTooSmall => "k/2 < alphaPreference",
+            Violation::AlphaConfidenceTooSmall => "alphaPreference <= alphaConfidence",
+            Violation::AlphaConfidenceTooLarge => "alphaConfidence <= k",
+            Violation::BetaNotPositive => "0 < beta",
+            Violation::ConcurrentRepollsNotPositive => "0 < concurrentRepolls",
+            Violation::ConcurrentRepollsTooLarge => "concurrentRepolls <= beta",
+            Violation::OptimalProcessingNotPositive => "0 < optimalProcessing",
+            Violation::MaxOutstandingItemsNotPositive => "0 < maxOutstandingItems",
+            Violation::MaxItemProcessingTimeNotPositive => "0 < maxItemProcessingTime",
+        }
+    }
+
+    /// Returns corresponding io::Error.
+    pub fn to_err(&self) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "invalid parameters: fails the condition that: {}",
+                self.as_str()
+            ),
+        )
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::consensus::snowball::test_validate --exact --show-output
+#[test]
+fn test_validate() {
+    assert!(Parameters::default().validate().is_ok());
+
+    let cases: Vec<(Parameters, Violation)> = vec![
+        (
+            Parameters {
+                alpha_preference: 10,
+                ..Parameters::default()
+            },
+            Violation::AlphaPreferenceTooSmall,
+        ),
+        (
+            Parameters {
+                alpha_confidence: 14,
+                ..Parameters::default()
+            },
+            Violation::AlphaConfidenceTooSmall,
+        ),
+        (
+            Parameters {
+                alpha_confidence: 21,
+                ..Parameters::default()
+            },
+            Violation::AlphaConfidenceTooLarge,
+        ),
+        (
+            Parameters {
+                beta: 0,
+                ..Parameters::default()
+            },
+            Violation::BetaNotPositive,
+        ),
+        (
+            Parameters {
+                concurrent_repolls: 0,
+                ..Parameters::default()
+            },
+            Violation::ConcurrentRepollsNotPositive,
+        ),
+        (
+            Parameters {
+                concurrent_repolls: 21,
+                ..Parameters::default()
+            },
+            Violation::ConcurrentRepollsTooLarge,
+        ),
+        (
+            Parameters {
+                optimal_processing: 0,
+                ..Parameters::default()
+            },
+            Violation::OptimalProcessingNotPositive,
+        ),
+        (
+            Parameters {
+                max_outstanding_items: 0,
+                ..Parameters::default()
+            },
+            Violation::MaxOutstandingItemsNotPositive,
+        ),
+        (
+            Parameters {
+                max_item_processing_time: Duration::ZERO,
+                ..Parameters::default()
+            },
+            Violation::MaxItemProcessingTimeNotPositive,
+        ),
+    ];
+    for (params, expected) in cases {
+        assert_eq!(params.validate().unwrap_err(), expected);
+    }
+
+    assert!(Violation::ConcurrentRepollsTooLarge
+        .to_err()
+        .to_string()
+        .contains("concurrentRepolls <= beta"));
+}
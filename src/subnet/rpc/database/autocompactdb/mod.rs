@@ -0,0 +1,390 @@
+//! Database wrapper which compacts the underlying database on close.
+//!
+//! Some backends reclaim space and speed up the next start when compacted
+//! once before shutdown. The compaction is bounded by a timeout so that it
+//! cannot hang the shutdown, and the database is closed either way.
+//!
+//! Compactions can also be triggered in the background. Only one runs at a
+//! time: triggers arriving during a compaction are coalesced into a single
+//! follow-up run, since overlapping compactions only waste IO.
+use std::{
+    io,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use super::{batch::BoxedBatch, iterator::BoxedIterator, Compacter};
+
+/// Default bound on the compaction run on close.
+pub const DEFAULT_COMPACT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Database which optionally runs a full-range "compact" on the underlying
+/// database before closing it. All other calls pass through.
+#[derive(Clone)]
+pub struct Database<D> {
+    db: D,
+    compact_on_close: bool,
+    compact_timeout: Duration,
+    coalesce_triggers: bool,
+
+    /// Held for the duration of every compaction and of "close", so that
+    /// compactions never overlap each other and "close" waits them out.
+    compacting: Arc<tokio::sync::Mutex<()>>,
+    triggers: Arc<Mutex<Triggers>>,
+}
+
+/// State of the background compactions.
+#[derive(Debug, Default)]
+struct Triggers {
+    running: bool,
+    pending: bool,
+}
+
+impl<D> Database<D>
+where
+    D: crate::subnet::rpc::database::Database + Compacter + Send + Sync,
+{
+    /// Creates a database which does not compact on close.
+    pub fn new(db: D) -> Self {
+        Self {
+            db,
+            compact_on_close: false,
+            compact_timeout: DEFAULT_COMPACT_TIMEOUT,
+            coalesce_triggers: true,
+            compacting: Arc::new(tokio::sync::Mutex::new(())),
+            triggers: Arc::new(Mutex::new(Triggers::default())),
+        }
+    }
+
+    /// Sets whether "close" compacts the full key range first.
+    #[must_use]
+    pub fn with_compact_on_close(mut self, compact_on_close: bool) -> Self {
+        self.compact_on_close = compact_on_close;
+        self
+    }
+
+    /// Bounds each compaction, after which it is abandoned.
+    #[must_use]
+    pub fn with_compact_timeout(mut self, compact_timeout: Duration) -> Self {
+        self.compact_timeout = compact_timeout;
+        self
+    }
+
+    /// Sets whether triggers arriving during a background compaction queue
+    /// a single follow-up run (the default), or are dropped.
+    #[must_use]
+    pub fn with_coalesce_triggers(mut self, coalesce_triggers: bool) -> Self {
+        self.coalesce_triggers = coalesce_triggers;
+        self
+    }
+
+    /// Returns true while a background compaction is running.
+    pub fn is_compacting(&self) -> bool {
+        self.triggers.lock().unwrap().running
+    }
+
+    /// Compacts the full key range within the timeout; the caller holds the
+    /// guard. Compaction is best effort, so failures are only logged.
+    async fn compact_full_range(&self) {
+        match tokio::time::timeout(self.compact_timeout, self.db.compact(&[], &[])).await {
+            Ok(Ok(())) => log::debug!("compacted database"),
+            Ok(Err(e)) => log::warn!("failed to compact database ({})", e),
+            Err(_) => log::warn!("abandoned compaction after {:?}", self.compact_timeout),
+        }
+    }
+}
+
+impl<D> Database<D>
+where
+    D: crate::subnet::rpc::database::Database + Compacter + Clone + Send + Sync + 'static,
+{
+    /// Triggers a full-range compaction in the background. Returns the
+    /// handle of the new run, or "None" if a compaction is already running,
+    /// in which case a follow-up run is queued (if coalescing).
+    pub fn trigger_compaction(&self) -> Option<tokio::task::JoinHandle<()>> {
+        {
+            let mut triggers = self.triggers.lock().unwrap();
+            if triggers.running {
+                if self.coalesce_triggers {
+                    triggers.pending = true;
+                }
+                return None;
+            }
+            triggers.running = true;
+        }
+
+        // taken right away if free, so that a "close" right after the
+        // trigger waits for this run
+        let mut guard = Arc::clone(&self.compacting).try_lock_owned().ok();
+        let db = self.clone();
+        Some(tokio::spawn(async move {
+            loop {
+                let _guard = match guard.take() {
+                    Some(guard) => guard,
+                    None => Arc::clone(&db.compacting).lock_owned().await,
+                };
+                db.compact_full_range().await;
+
+                let mut triggers = db.triggers.lock().unwrap();
+                if !triggers.pending {
+                    triggers.running = false;
+                    return;
+                }
+                triggers.pending = false;
+            }
+        }))
+    }
+}
+
+#[tonic::async_trait]
+impl<D> crate::subnet::rpc::database::KeyValueReaderWriterDeleter for Database<D>
+where
+    D: crate::subnet::rpc::database::Database + Compacter + Send + Sync,
+{
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn has(&self, key: &[u8]) -> io::Result<bool> {
+        self.db.has(key).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn get(&self, key: &[u8]) -> io::Result<Vec<u8>> {
+        self.db.get(key).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn put(&mut self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        self.db.put(key, value).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn delete(&mut self, key: &[u8]) -> io::Result<()> {
+        self.db.delete(key).await
+    }
+}
+
+#[tonic::async_trait]
+impl<D> Compacter for Database<D>
+where
+    D: crate::subnet::rpc::database::Database + Compacter + Send + Sync,
+{
+    /// Compacts the key range once no other compaction is running.
+    async fn compact(&self, start: &[u8], limit: &[u8]) -> io::Result<()> {
+        let _guard = self.compacting.lock().await;
+        self.db.compact(start, limit).await
+    }
+}
+
+#[tonic::async_trait]
+impl<D> crate::subnet::rpc::database::Closer for Database<D>
+where
+    D: crate::subnet::rpc::database::Database + Compacter + Send + Sync,
+{
+    /// Waits out any compaction in progress, compacts the full key range
+    /// if enabled, then closes the underlying database. A follow-up
+    /// background run still queued is dropped. Failures and timeouts of
+    /// the compaction do not prevent the close.
+    async fn close(&self) -> io::Result<()> {
+        let _guard = self.compacting.lock().await;
+        self.triggers.lock().unwrap().pending = false;
+        if self.compact_on_close {
+            self.compact_full_range().await;
+        }
+        self.db.close().await
+    }
+}
+
+#[tonic::async_trait]
+impl<D> crate::subnet::rpc::health::Checkable for Database<D>
+where
+    D: crate::subnet::rpc::database::Database + Compacter + Send + Sync,
+{
+    /// Implements the [`crate::subnet::rpc::health::Checkable`] trait.
+    async fn health_check(&self) -> io::Result<Vec<u8>> {
+        self.db.health_check().await
+    }
+}
+
+#[tonic::async_trait]
+impl<D> crate::subnet::rpc::database::iterator::Iteratee for Database<D>
+where
+    D: crate::subnet::rpc::database::Database + Compacter + Send + Sync,
+{
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator(&self) -> io::Result<BoxedIterator> {
+        self.db.new_iterator().await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_start(&self, start: &[u8]) -> io::Result<BoxedIterator> {
+        self.db.new_iterator_with_start(start).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_prefix(&self, prefix: &[u8]) -> io::Result<BoxedIterator> {
+        self.db.new_iterator_with_prefix(prefix).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_start_and_prefix(
+        &self,
+        start: &[u8],
+        prefix: &[u8],
+    ) -> io::Result<BoxedIterator> {
+        self.db
+            .new_iterator_with_start_and_prefix(start, prefix)
+            .await
+    }
+}
+
+#[tonic::async_trait]
+impl<D> crate::subnet::rpc::database::batch::Batcher for Database<D>
+where
+    D: crate::subnet::rpc::database::Database + Compacter + Send + Sync,
+{
+    /// Implements the [`crate::subnet::rpc::database::batch::Batcher`] trait.
+    async fn new_batch(&self) -> io::Result<BoxedBatch> {
+        self.db.new_batch().await
+    }
+}
+
+impl<D> crate::subnet::rpc::database::Database for Database<D> where
+    D: crate::subnet::rpc::database::Database + Compacter + Clone + Send + Sync + 'static
+{
+}
+
+/// Counts the full-range "compact" calls of a [`compacting_db`], and the
+/// most that ever ran concurrently.
+#[cfg(test)]
+#[derive(Clone, Default)]
+struct Compactions {
+    compacts: Arc<std::sync::atomic::AtomicUsize>,
+    running: Arc<std::sync::atomic::AtomicUsize>,
+    max_running: Arc<std::sync::atomic::AtomicUsize>,
+}
+
+/// Returns a database whose "compact" calls take "delay" each.
+#[cfg(test)]
+fn compacting_db(delay: Duration) -> (super::hookdb::Database, Compactions) {
+    use std::sync::atomic::Ordering;
+
+    use super::hookdb::Method;
+
+    let compactions = Compactions::default();
+    let db = super::hookdb::Database::new(super::memdb::Database::new())
+        .with_before({
+            let compactions = compactions.clone();
+            move |method, start| {
+                if method == Method::Compact {
+                    assert!(start.is_empty());
+                    let running = compactions.running.fetch_add(1, Ordering::SeqCst) + 1;
+                    compactions.max_running.fetch_max(running, Ordering::SeqCst);
+                }
+                Ok(())
+            }
+        })
+        .with_after({
+            let compactions = compactions.clone();
+            move |method| {
+                if method == Method::Compact {
+                    compactions.running.fetch_sub(1, Ordering::SeqCst);
+                    compactions.compacts.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+        })
+        .with_delay(Method::Compact, delay);
+    (db, compactions)
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::autocompactdb::test_compact_on_close --exact --show-output
+#[tokio::test]
+async fn test_compact_on_close() {
+    use std::sync::atomic::Ordering;
+
+    use crate::subnet::rpc::database::{Closer, KeyValueReaderWriterDeleter};
+
+    // disabled by default
+    let (inner, compactions) = compacting_db(Duration::ZERO);
+    let mut db = Database::new(inner);
+    db.put(b"foo", b"bar").await.unwrap();
+    db.close().await.unwrap();
+    assert_eq!(compactions.compacts.load(Ordering::SeqCst), 0);
+    assert!(db.close().await.is_err());
+
+    let (inner, compactions) = compacting_db(Duration::ZERO);
+    let db = Database::new(inner).with_compact_on_close(true);
+    db.close().await.unwrap();
+    assert_eq!(compactions.compacts.load(Ordering::SeqCst), 1);
+    assert!(db.close().await.is_err());
+
+    // a compaction outliving the timeout does not hold up the close
+    let (inner, compactions) = compacting_db(Duration::from_secs(10));
+    let db = Database::new(inner)
+        .with_compact_on_close(true)
+        .with_compact_timeout(Duration::from_millis(10));
+    tokio::time::timeout(Duration::from_secs(5), db.close())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(compactions.compacts.load(Ordering::SeqCst), 0);
+    assert!(db.close().await.is_err());
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::autocompactdb::test_coalesce_triggers --exact --show-output
+#[tokio::test]
+async fn test_coalesce_triggers() {
+    use std::sync::atomic::Ordering;
+
+    use crate::subnet::rpc::database::{Closer, Compacter};
+
+    let (inner, compactions) = compacting_db(Duration::from_millis(50));
+    let db = Database::new(inner).with_compact_on_close(true);
+
+    let handle = db.trigger_compaction().unwrap();
+    assert!(db.is_compacting());
+    let mut tasks = Vec::new();
+    for _ in 0..20 {
+        let db = db.clone();
+        tasks.push(tokio::spawn(
+            async move { db.trigger_compaction().is_none() },
+        ));
+    }
+    for task in tasks {
+        assert!(task.await.unwrap());
+    }
+
+    // the triggers during the first run are coalesced into one follow-up
+    handle.await.unwrap();
+    assert!(!db.is_compacting());
+    assert_eq!(compactions.compacts.load(Ordering::SeqCst), 2);
+    assert_eq!(compactions.max_running.load(Ordering::SeqCst), 1);
+
+    // close waits out the background compaction instead of overlapping it
+    let handle = db.trigger_compaction().unwrap();
+    db.close().await.unwrap();
+    handle.await.unwrap();
+    assert_eq!(compactions.compacts.load(Ordering::SeqCst), 4);
+    assert_eq!(compactions.max_running.load(Ordering::SeqCst), 1);
+
+    // without coalescing, the triggers during a run are dropped
+    let (inner, compactions) = compacting_db(Duration::from_millis(50));
+    let db = Database::new(inner).with_coalesce_triggers(false);
+    let handle = db.trigger_compaction().unwrap();
+    for _ in 0..5 {
+        assert!(db.trigger_compaction().is_none());
+    }
+    handle.await.unwrap();
+    assert_eq!(compactions.compacts.load(Ordering::SeqCst), 1);
+
+    // direct compactions do not overlap the background ones either
+    let handle = db.trigger_compaction().unwrap();
+    db.compact(&[], &[]).await.unwrap();
+    handle.await.unwrap();
+    assert_eq!(compactions.compacts.load(Ordering::SeqCst), 3);
+    assert_eq!(compactions.max_running.load(Ordering::SeqCst), 1);
+
+    // close waits out the background compaction even if not compacting
+    let _handle = db.trigger_compaction().unwrap();
+    db.close().await.unwrap();
+    assert_eq!(compactions.compacts.load(Ordering::SeqCst), 4);
+    assert_eq!(compactions.max_running.load(Ordering::SeqCst), 1);
+}
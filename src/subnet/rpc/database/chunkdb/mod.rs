@@ -0,0 +1,385 @@
+//! Database wrapper which splits large values into fixed-size chunks.
+//!
+//! Useful in front of backends that limit the size of a single value. Each
+//! key is stored as a manifest recording its number of chunks, and the chunks
+//! under their own keys:
+//!
+//! - manifest: 0x00 || key => chunk count (u32, big-endian)
+//! - chunk:    0x01 || key length (u32) || key || chunk index (u32) => chunk
+//!
+//! A "put" or "delete" writes several keys, so use a batch (or versiondb) to
+//! apply them atomically.
+use std::io;
+
+use super::{
+    batch::BoxedBatch, iterator::BoxedIterator, BoxedDatabase, KeyValueReaderWriterDeleter,
+};
+use crate::subnet::rpc::errors;
+
+const MANIFEST_PREFIX: u8 = 0x00;
+const CHUNK_PREFIX: u8 = 0x01;
+
+/// Database which stores each value over as many keys as needed to keep
+/// every stored value at most "chunk_size" bytes.
+#[derive(Clone)]
+pub struct Database {
+    db: BoxedDatabase,
+    chunk_size: usize,
+}
+
+/// Write to the underlying database or batch.
+enum Op {
+    Put(Vec<u8>, Vec<u8>),
+    Delete(Vec<u8>),
+}
+
+impl Database {
+    /// Creates a database with values split into "chunk_size" bytes.
+    /// A zero chunk size is treated as one byte.
+    pub fn new(db: BoxedDatabase, chunk_size: usize) -> Self {
+        Self {
+            db,
+            chunk_size: chunk_size.max(1),
+        }
+    }
+
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Returns the number of chunks of the key, or none if it does not exist.
+    async fn chunk_count(&self, key: &[u8]) -> io::Result<Option<u32>> {
+        match self.db.get(&manifest_key(key)).await {
+            Ok(d) => decode_count(&d).map(Some),
+            Err(e) if errors::is_not_found(&e) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns the writes storing the value, including the deletes of the
+    /// chunks left over from a longer previous value.
+    async fn put_ops(&self, key: &[u8], value: &[u8]) -> io::Result<Vec<Op>> {
+        let chunks: Vec<&[u8]> = value.chunks(self.chunk_size).collect();
+        let count = u32::try_from(chunks.len()).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("value of {} bytes has too many chunks", value.len()),
+            )
+        })?;
+        let prev = self.chunk_count(key).await?.unwrap_or(0);
+
+        let mut ops = Vec::with_capacity(chunks.len() + 1);
+        for (i, chunk) in chunks.iter().enumerate() {
+            ops.push(Op::Put(chunk_key(key, i as u32), chunk.to_vec()));
+        }
+        for i in count..prev {
+            ops.push(Op::Delete(chunk_key(key, i)));
+        }
+        ops.push(Op::Put(manifest_key(key), count.to_be_bytes().to_vec()));
+        Ok(ops)
+    }
+
+    /// Returns the deletes of the manifest and all the chunks of the key.
+    async fn delete_ops(&self, key: &[u8]) -> io::Result<Vec<Op>> {
+        let count = self.chunk_count(key).await?.unwrap_or(0);
+
+        let mut ops: Vec<Op> = (0..count).map(|i| Op::Delete(chunk_key(key, i))).collect();
+        ops.push(Op::Delete(manifest_key(key)));
+        Ok(ops)
+    }
+
+    async fn apply(&mut self, ops: Vec<Op>) -> io::Result<()> {
+        for op in ops {
+            match op {
+                Op::Put(k, v) => self.db.put(&k, &v).await?,
+                Op::Delete(k) => self.db.delete(&k).await?,
+            }
+        }
+        Ok(())
+    }
+
+    fn wrap_iterator(&self, iterator: BoxedIterator) -> BoxedIterator {
+        Box::new(Iterator {
+            db: self.clone(),
+            iterator,
+            key: Vec::new(),
+            value: Vec::new(),
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::KeyValueReaderWriterDeleter for Database {
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn has(&self, key: &[u8]) -> io::Result<bool> {
+        self.db.has(&manifest_key(key)).await
+    }
+
+    /// Reassembles the value from its chunks.
+    async fn get(&self, key: &[u8]) -> io::Result<Vec<u8>> {
+        let count = self
+            .chunk_count(key)
+            .await?
+            .ok_or_else(|| errors::Error::NotFound.to_err())?;
+
+        let mut value = Vec::new();
+        for i in 0..count {
+            let chunk = self.db.get(&chunk_key(key, i)).await.map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("failed to get chunk {} of {} ({})", i, count, e),
+                )
+            })?;
+            value.extend_from_slice(&chunk);
+        }
+        Ok(value)
+    }
+
+    /// Writes the chunks, then the manifest.
+    async fn put(&mut self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        let ops = self.put_ops(key, value).await?;
+        self.apply(ops).await
+    }
+
+    /// Deletes the manifest and all the chunks.
+    async fn delete(&mut self, key: &[u8]) -> io::Result<()> {
+        let ops = self.delete_ops(key).await?;
+        self.apply(ops).await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::Closer for Database {
+    /// Implements the [`crate::subnet::rpc::database::Closer`] trait.
+    async fn close(&self) -> io::Result<()> {
+        self.db.close().await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::health::Checkable for Database {
+    /// Implements the [`crate::subnet::rpc::health::Checkable`] trait.
+    async fn health_check(&self) -> io::Result<Vec<u8>> {
+        self.db.health_check().await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::iterator::Iteratee for Database {
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator(&self) -> io::Result<BoxedIterator> {
+        self.new_iterator_with_start_and_prefix(&[], &[]).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_start(&self, start: &[u8]) -> io::Result<BoxedIterator> {
+        self.new_iterator_with_start_and_prefix(start, &[]).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_prefix(&self, prefix: &[u8]) -> io::Result<BoxedIterator> {
+        self.new_iterator_with_start_and_prefix(&[], prefix).await
+    }
+
+    /// Iterates over the manifests, reassembling each value.
+    async fn new_iterator_with_start_and_prefix(
+        &self,
+        start: &[u8],
+        prefix: &[u8],
+    ) -> io::Result<BoxedIterator> {
+        let iterator = self
+            .db
+            .new_iterator_with_start_and_prefix(&manifest_key(start), &manifest_key(prefix))
+            .await?;
+        Ok(self.wrap_iterator(iterator))
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::batch::Batcher for Database {
+    /// Returns a batch which splits its values into chunks.
+    async fn new_batch(&self) -> io::Result<BoxedBatch> {
+        Ok(Box::new(Batch {
+            db: self.clone(),
+            batch: self.db.new_batch().await?,
+        }))
+    }
+}
+
+impl crate::subnet::rpc::database::Database for Database {}
+
+/// Iterator which yields the reassembled values, in key order.
+struct Iterator {
+    db: Database,
+    iterator: BoxedIterator,
+    key: Vec<u8>,
+    value: Vec<u8>,
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::iterator::Iterator for Iterator {
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iterator`] trait.
+    async fn next(&mut self) -> io::Result<bool> {
+        if !self.iterator.next().await? {
+            self.key.clear();
+            self.value.clear();
+            return Ok(false);
+        }
+        let key = self.iterator.key().await?[1..].to_vec();
+        self.value = self.db.get(&key).await?;
+        self.key = key;
+        Ok(true)
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iterator`] trait.
+    async fn error(&mut self) -> io::Result<()> {
+        self.iterator.error().await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iterator`] trait.
+    async fn key(&self) -> io::Result<&[u8]> {
+        Ok(&self.key)
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iterator`] trait.
+    async fn value(&self) -> io::Result<&[u8]> {
+        Ok(&self.value)
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iterator`] trait.
+    async fn release(&mut self) {
+        self.key.clear();
+        self.value.clear();
+        self.iterator.release().await
+    }
+}
+
+/// Batch which queues the chunk writes of each operation. The chunks left
+/// over by a previous value are looked up when the operation is queued.
+#[derive(Clone)]
+struct Batch {
+    db: Database,
+    batch: BoxedBatch,
+}
+
+impl Batch {
+    async fn apply(&mut self, ops: Vec<Op>) -> io::Result<()> {
+        for op in ops {
+            match op {
+                Op::Put(k, v) => self.batch.put(&k, &v).await?,
+                Op::Delete(k) => self.batch.delete(&k).await?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::batch::Batch for Batch {
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn put(&mut self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        let ops = self.db.put_ops(key, value).await?;
+        self.apply(ops).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn delete(&mut self, key: &[u8]) -> io::Result<()> {
+        let ops = self.db.delete_ops(key).await?;
+        self.apply(ops).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn size(&self) -> io::Result<usize> {
+        self.batch.size().await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn write(&self) -> io::Result<()> {
+        self.batch.write().await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn reset(&mut self) {
+        self.batch.reset().await
+    }
+
+    /// Replays the chunk writes, as stored in the underlying database.
+    async fn replay(&self, k: std::sync::Arc<tokio::sync::Mutex<BoxedDatabase>>) -> io::Result<()> {
+        self.batch.replay(k).await
+    }
+}
+
+fn manifest_key(key: &[u8]) -> Vec<u8> {
+    let mut k = Vec::with_capacity(1 + key.len());
+    k.push(MANIFEST_PREFIX);
+    k.extend_from_slice(key);
+    k
+}
+
+fn chunk_key(key: &[u8], index: u32) -> Vec<u8> {
+    let mut k = Vec::with_capacity(1 + 4 + key.len() + 4);
+    k.push(CHUNK_PREFIX);
+    k.extend_from_slice(&(key.len() as u32).to_be_bytes());
+    k.extend_from_slice(key);
+    k.extend_from_slice(&index.to_be_bytes());
+    k
+}
+
+fn decode_count(d: &[u8]) -> io::Result<u32> {
+    let d: [u8; 4] = d.try_into().map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid chunk manifest length {}", d.len()),
+        )
+    })?;
+    Ok(u32::from_be_bytes(d))
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::chunkdb::test_chunked_value --exact --show-output
+#[tokio::test]
+async fn test_chunked_value() {
+    use crate::subnet::rpc::database::{batch::Batcher, iterator::Iteratee, memdb};
+
+    let inner = memdb::Database::new();
+    let mut db = Database::new(inner.clone(), 4);
+
+    // three chunks, the last one partial
+    let value = b"0123456789".to_vec();
+    db.put(b"blob", &value).await.unwrap();
+    assert!(db.has(b"blob").await.unwrap());
+    assert_eq!(db.get(b"blob").await.unwrap(), value);
+    assert_eq!(inner.get(&chunk_key(b"blob", 0)).await.unwrap(), b"0123");
+    assert_eq!(inner.get(&chunk_key(b"blob", 2)).await.unwrap(), b"89");
+    assert_eq!(
+        inner.get(&manifest_key(b"blob")).await.unwrap(),
+        3u32.to_be_bytes()
+    );
+
+    // shrinking the value drops the stale chunks
+    db.put(b"blob", b"01234").await.unwrap();
+    assert_eq!(db.get(b"blob").await.unwrap(), b"01234");
+    assert!(!inner.has(&chunk_key(b"blob", 2)).await.unwrap());
+
+    let mut batch = db.new_batch().await.unwrap();
+    batch.put(b"other", b"abcdefghij").await.unwrap();
+    batch.write().await.unwrap();
+    assert_eq!(db.get(b"other").await.unwrap(), b"abcdefghij");
+
+    let mut iter = db.new_iterator().await.unwrap();
+    assert!(iter.next().await.unwrap());
+    assert_eq!(iter.key().await.unwrap(), b"blob");
+    assert_eq!(iter.value().await.unwrap(), b"01234");
+    assert!(iter.next().await.unwrap());
+    assert_eq!(iter.key().await.unwrap(), b"other");
+    assert_eq!(iter.value().await.unwrap(), b"abcdefghij");
+    assert!(!iter.next().await.unwrap());
+    iter.release().await;
+
+    // deletion removes every chunk
+    db.delete(b"blob").await.unwrap();
+    db.delete(b"other").await.unwrap();
+    assert!(!db.has(b"blob").await.unwrap());
+    assert!(errors::is_not_found(&db.get(b"blob").await.unwrap_err()));
+    let mut iter = inner.new_iterator().await.unwrap();
+    assert!(!iter.next().await.unwrap());
+}
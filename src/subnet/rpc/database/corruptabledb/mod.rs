@@ -1,109 +1,470 @@
 //! Database corruption manager.
-use std::{io, sync::Arc};
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
-use super::{batch::BoxedBatch, iterator::BoxedIterator, BoxedDatabase};
-use crate::subnet::rpc::{errors, utils};
+use super::{
+    batch::BoxedBatch, iterator::BoxedIterator, rpcdb::client::cache::ValueCache, BoxedDatabase,
+};
+use crate::subnet::rpc::{errors, health::HealthResult, utils};
 
-use tokio::sync::Mutex;
+use tokio::sync::{mpsc, Mutex, RwLock};
+
+/// Number of operations buffered for the observer before new ones are dropped.
+pub const OBSERVER_CAPACITY: usize = 1024;
+
+/// Default number of values kept to serve stale reads.
+pub const DEFAULT_MAX_STALE_READS: usize = 1024;
+
+/// Attempts to recover from a latched corruption by opening a fresh database.
+#[tonic::async_trait]
+pub trait RecoveryStrategy: Send + Sync {
+    async fn recover(&self) -> io::Result<BoxedDatabase>;
+}
+
+/// Key-value method of an observed operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Has,
+    Get,
+    Put,
+    Delete,
+}
+
+/// Result kind of an observed operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Ok,
+    NotFound,
+    /// Failed with a corruptible error, or was rejected by a latched corruption.
+    Corrupted,
+    /// Failed with a non-corruptible error (e.g., database closed).
+    Error,
+}
+
+/// Operation reported to the [`OperationObserver`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Operation {
+    pub method: Method,
+    pub key_len: usize,
+    pub outcome: Outcome,
+}
+
+/// Invoked after each key-value operation, off the hot path.
+pub type OperationObserver = Arc<dyn Fn(Operation) + Send + Sync>;
+
+/// What "get" does for a cached key once corruption latches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StaleReadPolicy {
+    /// Fails like any other call (default).
+    #[default]
+    Fail,
+    /// Serves the last value read or written through this database.
+    ServeCached,
+}
+
+/// Value returned by [`Database::get_with_staleness`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Read {
+    pub value: Vec<u8>,
+    /// True if served from the cache because corruption latched.
+    pub stale: bool,
+}
 
 /// Database wrapper which blocks further calls to the database at first sign of corruption.
 ///
 /// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/database/corruptabledb#Database>
 #[derive(Clone)]
 pub struct Database {
-    db: BoxedDatabase,
+    db: Arc<RwLock<BoxedDatabase>>,
     /// Stores a corrupted error if observed.
     corrupted: Arc<Mutex<utils::Errors>>,
+    /// Tracks the last successful call if the staleness check is enabled.
+    staleness: Option<Staleness>,
+    /// Invoked once per latched corruption to swap in a fresh database.
+    recovery: Option<Arc<dyn RecoveryStrategy>>,
+    /// True while a recovery task is running.
+    recovering: Arc<AtomicBool>,
+    /// Sends each operation to the task running the observer.
+    observer: Option<Observer>,
+    /// Values kept to serve "get" once corruption latches, if enabled.
+    read_cache: Option<ReadCache>,
+}
+
+/// Observer whose task is spawned on the first observed operation, so that
+/// the database can be created outside of a tokio runtime.
+#[derive(Clone)]
+struct Observer {
+    observer: OperationObserver,
+    tx: Arc<std::sync::Mutex<Option<mpsc::Sender<Operation>>>>,
+}
+
+impl Observer {
+    /// Returns the sender to the observer task, spawning it if needed. Only
+    /// called from the database calls, thus within a runtime.
+    fn sender(&self) -> mpsc::Sender<Operation> {
+        let mut tx = self.tx.lock().unwrap();
+        tx.get_or_insert_with(|| {
+            let (tx, mut rx) = mpsc::channel(OBSERVER_CAPACITY);
+            let observer = self.observer.clone();
+            tokio::spawn(async move {
+                while let Some(op) = rx.recv().await {
+                    observer(op);
+                }
+            });
+            tx
+        })
+        .clone()
+    }
+}
+
+#[derive(Clone)]
+struct ReadCache {
+    policy: StaleReadPolicy,
+    values: Arc<std::sync::RwLock<ValueCache>>,
+}
+
+impl ReadCache {
+    /// Caches the value of the key, or drops the key on "None".
+    fn set(&self, key: &[u8], value: Option<&[u8]>) {
+        let mut values = self.values.write().unwrap();
+        match value {
+            Some(value) => values.insert(key.to_vec(), value.to_vec()),
+            None => values.remove(key),
+        }
+    }
+}
+
+/// Reports the database as degraded when no call has succeeded within the window.
+#[derive(Clone)]
+struct Staleness {
+    window: Duration,
+    last_success: Arc<std::sync::Mutex<Instant>>,
+    clock: Arc<dyn Fn() -> Instant + Send + Sync>,
 }
 
 impl Database {
     pub fn new(db: BoxedDatabase) -> BoxedDatabase {
-        Box::new(Self {
-            db,
+        Box::new(Self::new_database(db))
+    }
+
+    /// Returns the database itself rather than a [`BoxedDatabase`], to
+    /// configure with the "with_*" methods.
+    pub fn new_database(db: BoxedDatabase) -> Self {
+        Self {
+            db: Arc::new(RwLock::new(db)),
             corrupted: Arc::new(Mutex::new(utils::Errors::new())),
-        })
+            staleness: None,
+            recovery: None,
+            recovering: Arc::new(AtomicBool::new(false)),
+            observer: None,
+            read_cache: None,
+        }
+    }
+
+    /// When corruption latches, runs the recovery strategy on a background
+    /// task. On success, the fresh database replaces the corrupted one and
+    /// the corruption is cleared. On failure, the corruption stays latched
+    /// and calls keep returning the stored error; recovery is not retried.
+    #[must_use]
+    pub fn with_recovery(mut self, recovery: Arc<dyn RecoveryStrategy>) -> Self {
+        self.recovery = Some(recovery);
+        self
+    }
+
+    /// Fails the health check if no call has succeeded within the "window",
+    /// even without an observed corruption error.
+    #[must_use]
+    pub fn with_staleness(self, window: Duration) -> Self {
+        self.with_clock(window, Arc::new(Instant::now))
+    }
+
+    fn with_clock(
+        mut self,
+        window: Duration,
+        clock: Arc<dyn Fn() -> Instant + Send + Sync>,
+    ) -> Self {
+        self.staleness = Some(Staleness {
+            window,
+            last_success: Arc::new(std::sync::Mutex::new(clock())),
+            clock,
+        });
+        self
+    }
+
+    /// Reports the outcome of every "has", "get", "put" and "delete" to the
+    /// observer. The observer runs on a spawned task fed by a bounded
+    /// channel, so a slow observer never blocks the database calls:
+    /// operations are dropped while the channel is full. The task is
+    /// spawned on the first operation, not here.
+    #[must_use]
+    pub fn with_observer(mut self, observer: OperationObserver) -> Self {
+        self.observer = Some(Observer {
+            observer,
+            tx: Arc::new(std::sync::Mutex::new(None)),
+        });
+        self
+    }
+
+    /// Keeps a read-through cache of at most "max_entries" values read or
+    /// written through the database, evicting the oldest first. Once
+    /// corruption latches, cached keys are served per the policy by
+    /// [`Database::get_with_staleness`] and "get", while writes still fail.
+    /// Successful puts, deletes and batch writes update the cache; a write
+    /// that fails in the underlying database drops its keys, since their
+    /// values are then unknown.
+    #[must_use]
+    pub fn with_stale_reads(mut self, policy: StaleReadPolicy, max_entries: usize) -> Self {
+        self.read_cache = Some(ReadCache {
+            policy,
+            values: Arc::new(std::sync::RwLock::new(ValueCache::new(max_entries))),
+        });
+        self
+    }
+
+    /// Returns the value of the key, flagged as stale if it was served from
+    /// the cache because corruption latched.
+    pub async fn get_with_staleness(&self, key: &[u8]) -> io::Result<Read> {
+        let err = match self.get_unobserved(key).await {
+            Ok(value) => {
+                self.cache(key, Some(&value));
+                return Ok(Read {
+                    value,
+                    stale: false,
+                });
+            }
+            Err(err) => err,
+        };
+        if errors::is_not_found(&err) {
+            self.cache(key, None);
+        }
+
+        let cache = match &self.read_cache {
+            Some(cache) if cache.policy == StaleReadPolicy::ServeCached => cache,
+            _ => return Err(err),
+        };
+        if !self.corrupted.lock().await.is_some() {
+            return Err(err);
+        }
+        match cache.values.read().unwrap().get(key) {
+            Some(value) => Ok(Read {
+                value: value.clone(),
+                stale: true,
+            }),
+            None => Err(err),
+        }
+    }
+
+    /// Updates the cached value of the key, if the cache is enabled.
+    fn cache(&self, key: &[u8], value: Option<&[u8]>) {
+        if let Some(cache) = &self.read_cache {
+            cache.set(key, value);
+        }
+    }
+
+    /// Reports the operation to the observer, if any, without waiting.
+    fn observe<T>(&self, method: Method, key: &[u8], resp: &io::Result<T>) {
+        let observer = match &self.observer {
+            Some(observer) => observer,
+            None => return,
+        };
+        let outcome = match resp {
+            Ok(_) => Outcome::Ok,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Outcome::NotFound,
+            Err(e) if errors::is_corruptible(e) => Outcome::Corrupted,
+            Err(_) => Outcome::Error,
+        };
+        // drops the operation rather than blocking if the observer lags behind
+        let _ = observer.sender().try_send(Operation {
+            method,
+            key_len: key.len(),
+            outcome,
+        });
+    }
+
+    /// Latches the corruption error and, if this is a new latch, starts recovery.
+    fn latch(&self, corrupted: &mut utils::Errors, err: &io::Error) {
+        let newly_latched = !corrupted.is_some();
+        corrupted.add(&io::Error::new(
+            io::ErrorKind::Other,
+            format!("closed to avoid possible corruption, init error: {err}"),
+        ));
+        if newly_latched {
+            self.spawn_recovery();
+        }
+    }
+
+    /// Runs the recovery strategy in the background, at most one at a time.
+    fn spawn_recovery(&self) {
+        let recovery = match &self.recovery {
+            Some(recovery) => recovery.clone(),
+            None => return,
+        };
+        if self
+            .recovering
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return;
+        }
+
+        let db = self.db.clone();
+        let corrupted = self.corrupted.clone();
+        let recovering = self.recovering.clone();
+        tokio::spawn(async move {
+            match recovery.recover().await {
+                Ok(fresh) => {
+                    // same lock order as the database calls
+                    let mut corrupted = corrupted.lock().await;
+                    *db.write().await = fresh;
+                    *corrupted = utils::Errors::new();
+                    log::info!("recovered corrupted database");
+                }
+                Err(e) => log::warn!("failed to recover corrupted database: {}", e),
+            }
+            recovering.store(false, Ordering::SeqCst);
+        });
+    }
+
+    /// Records the time of a successful call, if the staleness check is enabled.
+    fn record_success(&self) {
+        if let Some(staleness) = &self.staleness {
+            *staleness.last_success.lock().unwrap() = (staleness.clock)();
+        }
+    }
+
+    /// Returns an error if the last successful call is older than the window.
+    fn check_staleness(&self) -> io::Result<()> {
+        if let Some(staleness) = &self.staleness {
+            let elapsed = (staleness.clock)()
+                .saturating_duration_since(*staleness.last_success.lock().unwrap());
+            if elapsed > staleness.window {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    format!(
+                        "database degraded: no successful call in {:?} (window {:?})",
+                        elapsed, staleness.window
+                    ),
+                ));
+            }
+        }
+        Ok(())
     }
 }
 
-#[tonic::async_trait]
-impl crate::subnet::rpc::database::KeyValueReaderWriterDeleter for Database {
+impl Database {
     /// Attempts to return if the database has a key with the provided value.
-    async fn has(&self, key: &[u8]) -> io::Result<bool> {
+    async fn has_unobserved(&self, key: &[u8]) -> io::Result<bool> {
         let mut corrupted = self.corrupted.lock().await;
-        let _ = corrupted.err()?;
+        corrupted.err()?;
 
-        let db = &self.db;
+        let db = self.db.read().await;
         let has = db.has(key).await.map_err(|err| {
             if errors::is_corruptible(&err) {
-                corrupted.add(&io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("closed to avoid possible corruption, init error: {err}"),
-                ));
+                self.latch(&mut corrupted, &err);
             }
             err
         })?;
+        self.record_success();
 
         Ok(has)
     }
 
     /// Attempts to return the value that was mapped to the key that was provided.
-    async fn get(&self, key: &[u8]) -> io::Result<Vec<u8>> {
+    /// A missing key is a successful call, for the staleness check.
+    async fn get_unobserved(&self, key: &[u8]) -> io::Result<Vec<u8>> {
         let mut corrupted = self.corrupted.lock().await;
-        let _ = corrupted.err()?;
+        corrupted.err()?;
 
-        let db = &self.db;
+        let db = self.db.read().await;
         let value = db.get(key).await.map_err(|err| {
-            if errors::is_corruptible(&err) {
-                corrupted.add(&io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("closed to avoid possible corruption, init error: {err}"),
-                ));
+            if errors::is_not_found(&err) {
+                self.record_success();
+            } else if errors::is_corruptible(&err) {
+                self.latch(&mut corrupted, &err);
             }
             err
         })?;
+        self.record_success();
 
         Ok(value)
     }
 
     /// Attempts to set the value this key maps to.
-    async fn put(&mut self, key: &[u8], value: &[u8]) -> io::Result<()> {
+    async fn put_unobserved(&self, key: &[u8], value: &[u8]) -> io::Result<()> {
         let mut corrupted = self.corrupted.lock().await;
-        let _ = corrupted.err()?;
+        corrupted.err()?;
 
-        let db = &mut self.db;
+        let mut db = self.db.write().await;
         db.put(key, value).await.map_err(|err| {
+            self.cache(key, None);
             if errors::is_corruptible(&err) {
-                corrupted.add(&io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("closed to avoid possible corruption, init error: {err}"),
-                ));
+                self.latch(&mut corrupted, &err);
             }
             err
         })?;
+        self.cache(key, Some(value));
+        self.record_success();
 
         Ok(())
     }
 
     /// Attempts to remove any mapping from the key.
-    async fn delete(&mut self, key: &[u8]) -> io::Result<()> {
+    async fn delete_unobserved(&self, key: &[u8]) -> io::Result<()> {
         let mut corrupted = self.corrupted.lock().await;
-        let _ = corrupted.err()?;
+        corrupted.err()?;
 
-        let db = &mut self.db;
+        let mut db = self.db.write().await;
         db.delete(key).await.map_err(|err| {
+            self.cache(key, None);
             if errors::is_corruptible(&err) {
-                corrupted.add(&io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("closed to avoid possible corruption, init error: {err}"),
-                ));
+                self.latch(&mut corrupted, &err);
             }
             err
         })?;
+        self.cache(key, None);
+        self.record_success();
 
         Ok(())
     }
 }
 
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::KeyValueReaderWriterDeleter for Database {
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn has(&self, key: &[u8]) -> io::Result<bool> {
+        let resp = self.has_unobserved(key).await;
+        self.observe(Method::Has, key, &resp);
+        resp
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn get(&self, key: &[u8]) -> io::Result<Vec<u8>> {
+        let resp = self.get_with_staleness(key).await.map(|read| read.value);
+        self.observe(Method::Get, key, &resp);
+        resp
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn put(&mut self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        let resp = self.put_unobserved(key, value).await;
+        self.observe(Method::Put, key, &resp);
+        resp
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn delete(&mut self, key: &[u8]) -> io::Result<()> {
+        let resp = self.delete_unobserved(key).await;
+        self.observe(Method::Delete, key, &resp);
+        resp
+    }
+}
+
 #[tonic::async_trait]
 impl crate::subnet::rpc::database::Closer for Database {
     /// Attempts to close the database.
@@ -111,16 +472,14 @@ impl crate::subnet::rpc::database::Closer for Database {
         let mut corrupted = self.corrupted.lock().await;
         let _ = corrupted.err()?;
 
-        let db = &self.db;
+        let db = self.db.read().await;
         db.close().await.map_err(|err| {
             if errors::is_corruptible(&err) {
-                corrupted.add(&io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("closed to avoid possible corruption, init error: {err}"),
-                ));
+                self.latch(&mut corrupted, &err);
             }
             err
         })?;
+        self.record_success();
 
         Ok(())
     }
@@ -128,23 +487,35 @@ impl crate::subnet::rpc::database::Closer for Database {
 
 #[tonic::async_trait]
 impl crate::subnet::rpc::health::Checkable for Database {
-    /// Checks if the database has been closed.
+    /// Checks if the database has been closed, or has not serviced
+    /// a successful call within the staleness window (if enabled).
     async fn health_check(&self) -> io::Result<Vec<u8>> {
         let mut corrupted = self.corrupted.lock().await;
         let _ = corrupted.err()?;
 
-        let db = &self.db;
+        let db = self.db.read().await;
         let check = db.health_check().await.map_err(|err| {
             if errors::is_corruptible(&err) {
-                corrupted.add(&io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("closed to avoid possible corruption, init error: {err}"),
-                ));
+                self.latch(&mut corrupted, &err);
             }
             err
         })?;
+        self.check_staleness()?;
+
+        // reports the health of the underlying database as details, if JSON
+        HealthResult::healthy(serde_json::from_slice(&check).ok()).to_vec()
+    }
 
-        Ok(check)
+    /// Reports alive as long as the underlying database is, as a latched
+    /// corruption only stops serving and is recovered from without a restart.
+    async fn liveness(&self) -> io::Result<()> {
+        let db = self.db.read().await;
+        db.liveness().await
+    }
+
+    /// Reports not ready while a corruption is latched.
+    async fn readiness(&self) -> io::Result<()> {
+        self.health_check().await.map(|_| ())
     }
 }
 
@@ -174,9 +545,15 @@ impl crate::subnet::rpc::database::iterator::Iteratee for Database {
         let corrupted = self.corrupted.lock().await;
         let _ = corrupted.err()?;
 
-        self.db
-            .new_iterator_with_start_and_prefix(start, prefix)
+        let iterator = self
+            .db
+            .read()
             .await
+            .new_iterator_with_start_and_prefix(start, prefix)
+            .await?;
+        self.record_success();
+
+        Ok(iterator)
     }
 }
 
@@ -184,21 +561,413 @@ impl crate::subnet::rpc::database::iterator::Iteratee for Database {
 impl crate::subnet::rpc::database::batch::Batcher for Database {
     /// Implements the [`crate::subnet::rpc::database::batch::Batcher`] trait.
     async fn new_batch(&self) -> io::Result<BoxedBatch> {
-        let db = &self.db;
         let mut corrupted = self.corrupted.lock().await;
+        let db = self.db.read().await;
 
         let batch = db.new_batch().await.map_err(|err| {
             if errors::is_corruptible(&err) {
-                corrupted.add(&io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("closed to avoid possible corruption, init error: {err}"),
-                ));
+                self.latch(&mut corrupted, &err);
             }
             err
         })?;
+        self.record_success();
 
-        Ok(batch)
+        match &self.read_cache {
+            Some(cache) => Ok(Box::new(Batch {
+                batch,
+                cache: cache.clone(),
+                ops: Vec::new(),
+            })),
+            None => Ok(batch),
+        }
     }
 }
 
 impl crate::subnet::rpc::database::Database for Database {}
+
+/// Batch which applies its writes to the read cache once written.
+#[derive(Clone)]
+struct Batch {
+    batch: BoxedBatch,
+    cache: ReadCache,
+    /// Keys with their new value, "None" for deletes, in order.
+    ops: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::batch::Batch for Batch {
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn put(&mut self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        self.batch.put(key, value).await?;
+        self.ops.push((key.to_vec(), Some(value.to_vec())));
+        Ok(())
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn delete(&mut self, key: &[u8]) -> io::Result<()> {
+        self.batch.delete(key).await?;
+        self.ops.push((key.to_vec(), None));
+        Ok(())
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn size(&self) -> io::Result<usize> {
+        self.batch.size().await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn write(&self) -> io::Result<()> {
+        let resp = self.batch.write().await;
+        for (key, value) in self.ops.iter() {
+            match &resp {
+                Ok(_) => self.cache.set(key, value.as_deref()),
+                Err(_) => self.cache.set(key, None),
+            }
+        }
+        resp
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn reset(&mut self) {
+        self.batch.reset().await;
+        self.ops.clear();
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn replay(&self, k: Arc<Mutex<BoxedDatabase>>) -> io::Result<()> {
+        self.batch.replay(k).await
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::corruptabledb::test_staleness_health_check --exact --show-output
+#[tokio::test]
+async fn test_staleness_health_check() {
+    use crate::subnet::rpc::{
+        database::{memdb, KeyValueReaderWriterDeleter},
+        health::Checkable,
+    };
+
+    // mock clock advanced manually by the test
+    let start = Instant::now();
+    let offset = Arc::new(std::sync::Mutex::new(Duration::ZERO));
+    let clock_offset = offset.clone();
+    let clock = Arc::new(move || start + *clock_offset.lock().unwrap());
+
+    let mut db =
+        Database::new_database(memdb::Database::new()).with_clock(Duration::from_secs(60), clock);
+    let health: HealthResult = serde_json::from_slice(&db.health_check().await.unwrap()).unwrap();
+    assert!(health.healthy);
+    assert!(health.error.is_none());
+    // nests the health of the underlying database
+    assert_eq!(health.details.unwrap()["healthy"], true);
+
+    *offset.lock().unwrap() = Duration::from_secs(30);
+    db.put(b"foo", b"bar").await.unwrap();
+
+    // 50 seconds since the last success
+    *offset.lock().unwrap() = Duration::from_secs(80);
+    assert!(db.health_check().await.is_ok());
+
+    // past the window with no successful calls
+    *offset.lock().unwrap() = Duration::from_secs(91);
+    assert!(db.health_check().await.is_err());
+
+    // a successful call recovers the health
+    assert_eq!(db.get(b"foo").await.unwrap(), b"bar");
+    assert!(db.health_check().await.is_ok());
+
+    // so does a read of a missing key, as the database answered
+    *offset.lock().unwrap() = Duration::from_secs(200);
+    assert!(db.health_check().await.is_err());
+    let err = db.get(b"missing").await.unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert!(db.health_check().await.is_ok());
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::corruptabledb::test_recovery --exact --show-output
+#[tokio::test]
+async fn test_recovery() {
+    use crate::subnet::rpc::database::{memdb, rpcdb, KeyValueReaderWriterDeleter};
+
+    struct Replace {
+        healthy: bool,
+        calls: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    #[tonic::async_trait]
+    impl RecoveryStrategy for Replace {
+        async fn recover(&self) -> io::Result<BoxedDatabase> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.healthy {
+                Ok(memdb::Database::new())
+            } else {
+                Err(io::Error::new(io::ErrorKind::Other, "backend still down"))
+            }
+        }
+    }
+
+    // rpcdb client without a server, so every call fails with a corruptible error
+    let broken = || {
+        rpcdb::client::DatabaseClient::new(
+            tonic::transport::Channel::from_static("http://127.0.0.1:1").connect_lazy(),
+        )
+    };
+
+    // healthy replacement
+    let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let mut db = Database::new_database(broken()).with_recovery(Arc::new(Replace {
+        healthy: true,
+        calls: calls.clone(),
+    }));
+    assert!(db.put(b"foo", b"bar").await.is_err());
+
+    let mut recovered = false;
+    for _ in 0..50 {
+        if db.put(b"foo", b"bar").await.is_ok() {
+            recovered = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert!(recovered);
+    assert_eq!(db.get(b"foo").await.unwrap(), b"bar");
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    // failed recovery leaves the corruption latched
+    let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let mut db = Database::new_database(broken()).with_recovery(Arc::new(Replace {
+        healthy: false,
+        calls: calls.clone(),
+    }));
+    assert!(db.put(b"foo", b"bar").await.is_err());
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let err = db.put(b"foo", b"bar").await.unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("closed to avoid possible corruption"));
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    // no retry until a new corruption latches
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(db.put(b"foo", b"bar").await.is_err());
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::corruptabledb::test_live_but_not_ready --exact --show-output
+#[tokio::test]
+async fn test_live_but_not_ready() {
+    use crate::subnet::rpc::database::{memdb, rpcdb};
+
+    let db = Database::new(memdb::Database::new());
+    assert!(db.liveness().await.is_ok());
+    assert!(db.readiness().await.is_ok());
+
+    // rpcdb client without a server, so every call fails with a corruptible error
+    let mut db = Database::new(rpcdb::client::DatabaseClient::new(
+        tonic::transport::Channel::from_static("http://127.0.0.1:1").connect_lazy(),
+    ));
+    assert!(db.put(b"foo", b"bar").await.is_err());
+
+    assert!(db.liveness().await.is_ok());
+    let err = db.readiness().await.unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("closed to avoid possible corruption"));
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::corruptabledb::test_operation_observer --exact --show-output
+#[tokio::test]
+async fn test_operation_observer() {
+    use crate::subnet::rpc::database::{memdb, rpcdb, KeyValueReaderWriterDeleter};
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let observer: OperationObserver = Arc::new(move |op| {
+        let _ = tx.send(op);
+    });
+
+    // created outside of the runtime, as the observer task starts on the
+    // first operation
+    let mut db = std::thread::spawn({
+        let observer = observer.clone();
+        move || Database::new_database(memdb::Database::new()).with_observer(observer)
+    })
+    .join()
+    .unwrap();
+    db.put(b"foo", b"bar").await.unwrap();
+    assert_eq!(db.get(b"foo").await.unwrap(), b"bar");
+    assert!(db.get(b"missing").await.is_err());
+
+    let mut ops = Vec::new();
+    for _ in 0..3 {
+        ops.push(rx.recv().await.unwrap());
+    }
+    assert_eq!(
+        ops,
+        vec![
+            Operation {
+                method: Method::Put,
+                key_len: 3,
+                outcome: Outcome::Ok,
+            },
+            Operation {
+                method: Method::Get,
+                key_len: 3,
+                outcome: Outcome::Ok,
+            },
+            Operation {
+                method: Method::Get,
+                key_len: 7,
+                outcome: Outcome::NotFound,
+            },
+        ]
+    );
+
+    // rpcdb client without a server, so every call fails with a corruptible error
+    let mut db = Database::new_database(rpcdb::client::DatabaseClient::new(
+        tonic::transport::Channel::from_static("http://127.0.0.1:1").connect_lazy(),
+    ))
+    .with_observer(observer);
+    assert!(db.put(b"foo", b"bar").await.is_err());
+    assert!(db.has(b"foo").await.is_err());
+    for method in [Method::Put, Method::Has] {
+        let op = rx.recv().await.unwrap();
+        assert_eq!(op.method, method);
+        assert_eq!(op.outcome, Outcome::Corrupted);
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::corruptabledb::test_stale_reads --exact --show-output
+#[tokio::test]
+async fn test_stale_reads() {
+    use crate::subnet::rpc::database::{hookdb, memdb, KeyValueReaderWriterDeleter};
+
+    let new_db = |policy| {
+        let mut inner = memdb::Database::new();
+        let broken = Arc::new(AtomicBool::new(false));
+        // fails every call with a corruptible error once "broken" is set
+        let flaky = hookdb::Database::new(inner.clone()).with_before({
+            let broken = broken.clone();
+            move |_, _| {
+                if broken.load(Ordering::SeqCst) {
+                    return Err(io::Error::new(io::ErrorKind::Other, "disk failure"));
+                }
+                Ok(())
+            }
+        });
+        let db = Database::new_database(Box::new(flaky)).with_stale_reads(policy, 2);
+        async move {
+            inner.put(b"foo", b"bar").await.unwrap();
+            inner.put(b"baz", b"qux").await.unwrap();
+            (db, broken)
+        }
+    };
+
+    let (mut db, broken) = new_db(StaleReadPolicy::ServeCached).await;
+    assert_eq!(
+        db.get_with_staleness(b"foo").await.unwrap(),
+        Read {
+            value: b"bar".to_vec(),
+            stale: false,
+        }
+    );
+
+    // trip the corruption
+    broken.store(true, Ordering::SeqCst);
+    assert!(db.has(b"foo").await.is_err());
+
+    assert_eq!(
+        db.get_with_staleness(b"foo").await.unwrap(),
+        Read {
+            value: b"bar".to_vec(),
+            stale: true,
+        }
+    );
+    assert_eq!(db.get(b"foo").await.unwrap(), b"bar");
+
+    // keys never read are not served, and writes still fail
+    let err = db.get_with_staleness(b"baz").await.unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("closed to avoid possible corruption"));
+    assert!(db.put(b"foo", b"new").await.is_err());
+    assert!(db.delete(b"foo").await.is_err());
+
+    // the cache keeps at most 2 values, oldest evicted first
+    let (db, broken) = new_db(StaleReadPolicy::ServeCached).await;
+    assert_eq!(db.get(b"foo").await.unwrap(), b"bar");
+    assert_eq!(db.get(b"baz").await.unwrap(), b"qux");
+    assert!(db.get(b"missing").await.is_err());
+    let mut writer = db.clone();
+    writer.put(b"new", b"value").await.unwrap();
+    broken.store(true, Ordering::SeqCst);
+    assert!(db.has(b"foo").await.is_err());
+    assert!(db.get(b"foo").await.is_err());
+    assert_eq!(db.get(b"baz").await.unwrap(), b"qux");
+    assert_eq!(db.get(b"new").await.unwrap(), b"value");
+
+    // the default policy fails hard
+    let (db, broken) = new_db(StaleReadPolicy::Fail).await;
+    assert_eq!(db.get(b"foo").await.unwrap(), b"bar");
+    broken.store(true, Ordering::SeqCst);
+    assert!(db.get(b"foo").await.is_err());
+    assert!(db.get_with_staleness(b"foo").await.is_err());
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::corruptabledb::test_stale_reads_after_writes --exact --show-output
+#[tokio::test]
+async fn test_stale_reads_after_writes() {
+    use crate::subnet::rpc::database::{
+        batch::Batcher, hookdb, memdb, KeyValueReaderWriterDeleter,
+    };
+
+    let new_db = || {
+        let broken = Arc::new(AtomicBool::new(false));
+        let flaky = hookdb::Database::new(memdb::Database::new()).with_before({
+            let broken = broken.clone();
+            move |_, _| {
+                if broken.load(Ordering::SeqCst) {
+                    return Err(io::Error::new(io::ErrorKind::Other, "disk failure"));
+                }
+                Ok(())
+            }
+        });
+        let db = Database::new_database(Box::new(flaky))
+            .with_stale_reads(StaleReadPolicy::ServeCached, 16);
+        (db, broken)
+    };
+
+    // put, then corrupt, then get serves the value put
+    let (mut db, broken) = new_db();
+    db.put(b"foo", b"old").await.unwrap();
+    assert_eq!(db.get(b"foo").await.unwrap(), b"old");
+    db.put(b"foo", b"new").await.unwrap();
+    db.put(b"bar", b"baz").await.unwrap();
+    db.delete(b"bar").await.unwrap();
+
+    let mut batch = db.new_batch().await.unwrap();
+    batch.put(b"batched", b"value").await.unwrap();
+    batch.delete(b"foo").await.unwrap();
+    batch.put(b"foo", b"newest").await.unwrap();
+    batch.write().await.unwrap();
+
+    broken.store(true, Ordering::SeqCst);
+    assert!(db.has(b"foo").await.is_err());
+    assert_eq!(db.get(b"foo").await.unwrap(), b"newest");
+    assert_eq!(db.get(b"batched").await.unwrap(), b"value");
+    assert!(db.get(b"bar").await.is_err());
+
+    // a put failing in the underlying database drops the cached value
+    let (mut db, broken) = new_db();
+    db.put(b"foo", b"old").await.unwrap();
+    broken.store(true, Ordering::SeqCst);
+    assert!(db.put(b"foo", b"new").await.is_err());
+    assert!(db.get(b"foo").await.is_err());
+
+    // a put rejected by the latched corruption keeps it
+    let (mut db, broken) = new_db();
+    db.put(b"foo", b"old").await.unwrap();
+    broken.store(true, Ordering::SeqCst);
+    assert!(db.has(b"foo").await.is_err());
+    assert!(db.put(b"foo", b"new").await.is_err());
+    assert_eq!(db.get(b"foo").await.unwrap(), b"old");
+}
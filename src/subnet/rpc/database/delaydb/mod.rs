@@ -0,0 +1,303 @@
+//! Database wrapper which delays every call by a random duration.
+//!
+//! Useful to exercise timeouts and interleavings against an in-memory
+//! database as if it were remote. Delays come from OS entropy unless a seed
+//! is set with [`Database::with_seed`], which makes them reproducible.
+//! [`LatencyModel::Multiply`] instead amplifies the latency of the
+//! underlying database.
+use std::{
+    io,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use super::{batch::BoxedBatch, iterator::BoxedIterator, BoxedDatabase};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// How each call to the underlying database is delayed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LatencyModel {
+    /// Sleeps for a uniformly random duration in [0, "max_delay"] before the call.
+    Random { max_delay: Duration },
+    /// Measures the call and then sleeps for ("factor" - 1) times as long,
+    /// so that the call takes "factor" times its own latency. Factors of
+    /// at most 1 add no delay.
+    Multiply(f64),
+}
+
+/// Database which delays each call to the underlying database per its
+/// [`LatencyModel`].
+#[derive(Clone)]
+pub struct Database {
+    db: BoxedDatabase,
+    model: LatencyModel,
+    /// Shared by the clones, so that the delay sequence is per database.
+    rng: Arc<Mutex<StdRng>>,
+}
+
+impl Database {
+    /// Delays each call by a random duration in [0, "max_delay"].
+    pub fn new(db: BoxedDatabase, max_delay: Duration) -> Self {
+        Self {
+            db,
+            model: LatencyModel::Random { max_delay },
+            rng: Arc::new(Mutex::new(StdRng::from_entropy())),
+        }
+    }
+
+    /// Fails if the factor of [`LatencyModel::Multiply`] is negative or not
+    /// finite, as it could not scale a duration.
+    pub fn new_with_latency_model(db: BoxedDatabase, model: LatencyModel) -> io::Result<Self> {
+        if let LatencyModel::Multiply(factor) = model {
+            if !factor.is_finite() || factor < 0.0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("invalid latency factor {}", factor),
+                ));
+            }
+        }
+        Ok(Self {
+            db,
+            model,
+            rng: Arc::new(Mutex::new(StdRng::from_entropy())),
+        })
+    }
+
+    /// Draws the delays from a generator seeded with "seed", so that two
+    /// databases with the same seed delay the same sequence of calls alike.
+    #[must_use]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = Arc::new(Mutex::new(StdRng::seed_from_u64(seed)));
+        self
+    }
+
+    /// Returns the random delay of the next call, which is always zero
+    /// with [`LatencyModel::Multiply`].
+    pub fn next_delay(&self) -> Duration {
+        let max_delay = match self.model {
+            LatencyModel::Random { max_delay } => max_delay,
+            LatencyModel::Multiply(_) => return Duration::ZERO,
+        };
+        let max_nanos = max_delay.as_nanos().min(u64::MAX as u128) as u64;
+        let nanos = self.rng.lock().unwrap().gen_range(0..=max_nanos);
+        Duration::from_nanos(nanos)
+    }
+
+    /// Sleeps before the call, returning when the call starts.
+    async fn delay(&self) -> Instant {
+        let delay = self.next_delay();
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+        Instant::now()
+    }
+
+    /// Sleeps after the call that began at "began", if amplifying.
+    async fn amplify(&self, began: Instant) {
+        if let LatencyModel::Multiply(factor) = self.model {
+            if factor > 1.0 {
+                // saturates where the product overflows a duration
+                let delay =
+                    Duration::try_from_secs_f64(began.elapsed().as_secs_f64() * (factor - 1.0))
+                        .unwrap_or(Duration::MAX);
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::KeyValueReaderWriterDeleter for Database {
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn has(&self, key: &[u8]) -> io::Result<bool> {
+        let began = self.delay().await;
+        let resp = self.db.has(key).await;
+        self.amplify(began).await;
+        resp
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn get(&self, key: &[u8]) -> io::Result<Vec<u8>> {
+        let began = self.delay().await;
+        let resp = self.db.get(key).await;
+        self.amplify(began).await;
+        resp
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn put(&mut self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        let began = self.delay().await;
+        let resp = self.db.put(key, value).await;
+        self.amplify(began).await;
+        resp
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn delete(&mut self, key: &[u8]) -> io::Result<()> {
+        let began = self.delay().await;
+        let resp = self.db.delete(key).await;
+        self.amplify(began).await;
+        resp
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::Closer for Database {
+    /// Implements the [`crate::subnet::rpc::database::Closer`] trait.
+    async fn close(&self) -> io::Result<()> {
+        let began = self.delay().await;
+        let resp = self.db.close().await;
+        self.amplify(began).await;
+        resp
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::health::Checkable for Database {
+    /// Implements the [`crate::subnet::rpc::health::Checkable`] trait.
+    async fn health_check(&self) -> io::Result<Vec<u8>> {
+        let began = self.delay().await;
+        let resp = self.db.health_check().await;
+        self.amplify(began).await;
+        resp
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::iterator::Iteratee for Database {
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator(&self) -> io::Result<BoxedIterator> {
+        let began = self.delay().await;
+        let resp = self.db.new_iterator().await;
+        self.amplify(began).await;
+        resp
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_start(&self, start: &[u8]) -> io::Result<BoxedIterator> {
+        let began = self.delay().await;
+        let resp = self.db.new_iterator_with_start(start).await;
+        self.amplify(began).await;
+        resp
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_prefix(&self, prefix: &[u8]) -> io::Result<BoxedIterator> {
+        let began = self.delay().await;
+        let resp = self.db.new_iterator_with_prefix(prefix).await;
+        self.amplify(began).await;
+        resp
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_start_and_prefix(
+        &self,
+        start: &[u8],
+        prefix: &[u8],
+    ) -> io::Result<BoxedIterator> {
+        let began = self.delay().await;
+        let resp = self
+            .db
+            .new_iterator_with_start_and_prefix(start, prefix)
+            .await;
+        self.amplify(began).await;
+        resp
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::batch::Batcher for Database {
+    /// Implements the [`crate::subnet::rpc::database::batch::Batcher`] trait.
+    async fn new_batch(&self) -> io::Result<BoxedBatch> {
+        let began = self.delay().await;
+        let resp = self.db.new_batch().await;
+        self.amplify(began).await;
+        resp
+    }
+}
+
+impl crate::subnet::rpc::database::Database for Database {}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::delaydb::test_seeded_delays --exact --show-output
+#[tokio::test]
+async fn test_seeded_delays() {
+    use crate::subnet::rpc::database::{memdb, KeyValueReaderWriterDeleter};
+
+    let max_delay = Duration::from_millis(2);
+    let delays = |seed: u64| {
+        let db = Database::new(memdb::Database::new(), max_delay).with_seed(seed);
+        (0..20).map(|_| db.next_delay()).collect::<Vec<_>>()
+    };
+
+    let first = delays(42);
+    assert_eq!(first, delays(42));
+    assert_ne!(first, delays(43));
+    assert!(first.iter().all(|d| *d <= max_delay));
+
+    // calls draw from the same sequence, and still reach the database
+    let mut db = Database::new(memdb::Database::new(), max_delay).with_seed(42);
+    let clone = db.clone();
+    db.put(b"foo", b"bar").await.unwrap();
+    assert_eq!(clone.get(b"foo").await.unwrap(), b"bar");
+    assert_eq!(db.next_delay(), first[2]);
+
+    let db = Database::new(memdb::Database::new(), Duration::ZERO);
+    assert_eq!(db.next_delay(), Duration::ZERO);
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::delaydb::test_multiply_latency --exact --show-output
+#[tokio::test]
+async fn test_multiply_latency() {
+    use crate::subnet::rpc::database::{hookdb, memdb, KeyValueReaderWriterDeleter};
+
+    const BASE: Duration = Duration::from_millis(20);
+
+    let mut inner = memdb::Database::new();
+    inner.put(b"foo", b"bar").await.unwrap();
+    // sleeps for "BASE" in every "get"
+    let db = Database::new_with_latency_model(
+        Box::new(hookdb::Database::new(inner).with_delay(hookdb::Method::Get, BASE)),
+        LatencyModel::Multiply(3.0),
+    )
+    .unwrap();
+    assert_eq!(db.next_delay(), Duration::ZERO);
+
+    let start = Instant::now();
+    assert_eq!(db.get(b"foo").await.unwrap(), b"bar");
+    let elapsed = start.elapsed();
+    assert!(elapsed >= BASE * 3, "{:?}", elapsed);
+    assert!(elapsed < BASE * 6, "{:?}", elapsed);
+
+    // factors of at most 1 leave the latency as is
+    let mut db =
+        Database::new_with_latency_model(memdb::Database::new(), LatencyModel::Multiply(0.5))
+            .unwrap();
+    db.put(b"foo", b"bar").await.unwrap();
+    assert!(db.has(b"foo").await.unwrap());
+
+    // factors that cannot scale a duration are rejected
+    for factor in [f64::INFINITY, f64::NEG_INFINITY, f64::NAN, -1.0] {
+        let err = Database::new_with_latency_model(
+            memdb::Database::new(),
+            LatencyModel::Multiply(factor),
+        )
+        .err()
+        .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    // finite but huge factors saturate rather than overflow
+    let db = Database::new_with_latency_model(
+        Box::new(
+            hookdb::Database::new(memdb::Database::new())
+                .with_delay(hookdb::Method::Get, Duration::from_millis(1)),
+        ),
+        LatencyModel::Multiply(f64::MAX),
+    )
+    .unwrap();
+    assert!(
+        tokio::time::timeout(Duration::from_millis(50), db.get(b"foo"))
+            .await
+            .is_err()
+    );
+}
@@ -0,0 +1,212 @@
+//! Database wrapper which fails its health check when the disk runs low.
+//!
+//! A disk-backed database nearing full fails writes soon after, so the
+//! health check reports it ahead of time while reads and writes still pass
+//! through, and the database stays ready.
+use std::{io, sync::Arc};
+
+use super::{batch::BoxedBatch, iterator::BoxedIterator, BoxedDatabase};
+use crate::subnet::rpc::health::HealthResult;
+
+/// Default minimum of available disk space, in bytes.
+/// ref. avalanchego "--system-tracker-disk-warning-threshold-available-space"
+pub const DEFAULT_MIN_FREE_SPACE: u64 = 1024 * 1024 * 1024;
+
+/// Returns the available disk space in bytes (e.g., from "statvfs" on the
+/// database directory).
+pub type FreeSpaceProbe = Arc<dyn Fn() -> io::Result<u64> + Send + Sync>;
+
+/// Database whose health check fails when the probed free space is below
+/// the minimum. All other calls pass through.
+#[derive(Clone)]
+pub struct Database {
+    db: BoxedDatabase,
+    probe: FreeSpaceProbe,
+    min_free_space: u64,
+}
+
+impl Database {
+    pub fn new(db: BoxedDatabase, probe: FreeSpaceProbe) -> Self {
+        Self {
+            db,
+            probe,
+            min_free_space: DEFAULT_MIN_FREE_SPACE,
+        }
+    }
+
+    /// Sets the free space in bytes below which the health check fails.
+    #[must_use]
+    pub fn with_min_free_space(mut self, min_free_space: u64) -> Self {
+        self.min_free_space = min_free_space;
+        self
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::KeyValueReaderWriterDeleter for Database {
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn has(&self, key: &[u8]) -> io::Result<bool> {
+        self.db.has(key).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn get(&self, key: &[u8]) -> io::Result<Vec<u8>> {
+        self.db.get(key).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn put(&mut self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        self.db.put(key, value).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn delete(&mut self, key: &[u8]) -> io::Result<()> {
+        self.db.delete(key).await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::Closer for Database {
+    /// Implements the [`crate::subnet::rpc::database::Closer`] trait.
+    async fn close(&self) -> io::Result<()> {
+        self.db.close().await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::health::Checkable for Database {
+    /// Checks the underlying database first, whose errors are returned as
+    /// is (e.g., a latched corruption), then the free space. Low free space
+    /// fails the check with the JSON-encoded unhealthy [`HealthResult`],
+    /// including the free and minimum bytes as details, as the message.
+    async fn health_check(&self) -> io::Result<Vec<u8>> {
+        let check = self.db.health_check().await?;
+        let free_space = (self.probe)()?;
+
+        // reports the health of the underlying database as details, if JSON
+        let details = serde_json::json!({
+            "freeSpace": free_space,
+            "minFreeSpace": self.min_free_space,
+            "database": serde_json::from_slice::<serde_json::Value>(&check).ok(),
+        });
+        if free_space < self.min_free_space {
+            let err = io::Error::new(
+                io::ErrorKind::Other,
+                format!(
+                    "available disk space {} bytes is below the minimum {} bytes",
+                    free_space, self.min_free_space
+                ),
+            );
+            let result = HealthResult {
+                details: Some(details),
+                ..HealthResult::unhealthy(&err)
+            }
+            .to_vec()?;
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                String::from_utf8_lossy(&result).into_owned(),
+            ));
+        }
+        HealthResult::healthy(Some(details)).to_vec()
+    }
+
+    /// Ready as long as the underlying database is and the free space can
+    /// be probed, even when it is low, so that the database keeps serving.
+    async fn readiness(&self) -> io::Result<()> {
+        self.db.readiness().await?;
+        (self.probe)().map(|_| ())
+    }
+
+    /// Implements the [`crate::subnet::rpc::health::Checkable`] trait.
+    async fn liveness(&self) -> io::Result<()> {
+        self.db.liveness().await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::iterator::Iteratee for Database {
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator(&self) -> io::Result<BoxedIterator> {
+        self.db.new_iterator().await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_start(&self, start: &[u8]) -> io::Result<BoxedIterator> {
+        self.db.new_iterator_with_start(start).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_prefix(&self, prefix: &[u8]) -> io::Result<BoxedIterator> {
+        self.db.new_iterator_with_prefix(prefix).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_start_and_prefix(
+        &self,
+        start: &[u8],
+        prefix: &[u8],
+    ) -> io::Result<BoxedIterator> {
+        self.db
+            .new_iterator_with_start_and_prefix(start, prefix)
+            .await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::batch::Batcher for Database {
+    /// Implements the [`crate::subnet::rpc::database::batch::Batcher`] trait.
+    async fn new_batch(&self) -> io::Result<BoxedBatch> {
+        self.db.new_batch().await
+    }
+}
+
+impl crate::subnet::rpc::database::Database for Database {}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::diskspacedb::test_low_free_space --exact --show-output
+#[tokio::test]
+async fn test_low_free_space() {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use crate::subnet::rpc::{
+        database::{corruptabledb, memdb, Closer, KeyValueReaderWriterDeleter},
+        health::Checkable,
+    };
+
+    let free_space = Arc::new(AtomicU64::new(10 * 1024));
+    let probe: FreeSpaceProbe = {
+        let free_space = Arc::clone(&free_space);
+        Arc::new(move || Ok(free_space.load(Ordering::SeqCst)))
+    };
+    let inner = corruptabledb::Database::new(memdb::Database::new());
+    let mut db = Database::new(inner, probe).with_min_free_space(4 * 1024);
+
+    let health: HealthResult = serde_json::from_slice(&db.health_check().await.unwrap()).unwrap();
+    assert!(health.healthy);
+    let details = health.details.unwrap();
+    assert_eq!(details["freeSpace"], 10 * 1024);
+    assert!(details["database"]["healthy"].as_bool().unwrap());
+
+    // unhealthy, yet still ready and serving
+    free_space.store(1024, Ordering::SeqCst);
+    let err = db.health_check().await.unwrap_err();
+    let health: HealthResult = serde_json::from_str(&err.to_string()).unwrap();
+    assert!(!health.healthy);
+    assert!(health
+        .error
+        .unwrap()
+        .contains("below the minimum 4096 bytes"));
+    assert_eq!(health.details.unwrap()["freeSpace"], 1024);
+    assert!(db.readiness().await.is_ok());
+    db.put(b"foo", b"bar").await.unwrap();
+
+    // the underlying database failing takes precedence
+    db.close().await.unwrap();
+    let err = db.health_check().await.unwrap_err();
+    assert!(serde_json::from_str::<HealthResult>(&err.to_string()).is_err());
+    assert!(db.readiness().await.is_err());
+
+    let failing: FreeSpaceProbe =
+        Arc::new(|| Err(io::Error::new(io::ErrorKind::Other, "statvfs failed")));
+    let db = Database::new(memdb::Database::new(), failing);
+    assert!(db.health_check().await.is_err());
+}
@@ -0,0 +1,137 @@
+//! Portable dump of the keys and values of a database, e.g., to move the
+//! state of a VM between backends or to seed a test from a snapshot.
+//!
+//! A dump starts with the 4-byte [`MAGIC`] and the 2-byte big-endian
+//! [`VERSION`]. In version 1 the header is followed by each key and value,
+//! in key order, both prefixed by their big-endian u32 length.
+use std::io::{Error, ErrorKind, Result};
+
+use super::BoxedDatabase;
+
+/// Identifies a dump.
+pub const MAGIC: [u8; 4] = *b"AVDB";
+
+/// Version of the dump format written by [`dump`].
+pub const VERSION: u16 = 1;
+
+const HEADER_LEN: usize = MAGIC.len() + 2;
+
+/// Returns the dump of every key and value of the database.
+pub async fn dump(db: &BoxedDatabase) -> Result<Vec<u8>> {
+    let mut b = Vec::with_capacity(HEADER_LEN);
+    b.extend_from_slice(&MAGIC);
+    b.extend_from_slice(&VERSION.to_be_bytes());
+
+    let mut iter = db.new_iterator().await?;
+    while iter.next().await? {
+        for v in [iter.key().await?, iter.value().await?] {
+            let len = u32::try_from(v.len()).map_err(|_| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("{}-byte entry does not fit a dump", v.len()),
+                )
+            })?;
+            b.extend_from_slice(&len.to_be_bytes());
+            b.extend_from_slice(v);
+        }
+    }
+    iter.error().await?;
+    iter.release().await;
+
+    Ok(b)
+}
+
+/// Writes the entries of the dump to the database in a single batch,
+/// returning the number of keys written. Rejects dumps with an unknown
+/// magic or version, and truncated ones, without writing anything.
+pub async fn load(db: &BoxedDatabase, b: &[u8]) -> Result<u64> {
+    if b.len() < HEADER_LEN || b[..MAGIC.len()] != MAGIC {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "not a database dump (bad magic)",
+        ));
+    }
+    let version = u16::from_be_bytes([b[MAGIC.len()], b[MAGIC.len() + 1]]);
+    if version != VERSION {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "unsupported dump version {} (supports {})",
+                version, VERSION
+            ),
+        ));
+    }
+
+    let mut entries = Vec::new();
+    let mut rest = &b[HEADER_LEN..];
+    while !rest.is_empty() {
+        let key = take_prefixed(&mut rest)?;
+        let value = take_prefixed(&mut rest)?;
+        entries.push((key, value));
+    }
+
+    let mut batch = db.new_batch().await?;
+    for (key, value) in entries.iter() {
+        batch.put(key, value).await?;
+    }
+    batch.write().await?;
+
+    Ok(entries.len() as u64)
+}
+
+/// Splits the next length-prefixed entry off the front of "rest".
+fn take_prefixed<'a>(rest: &mut &'a [u8]) -> Result<&'a [u8]> {
+    let truncated = || Error::new(ErrorKind::UnexpectedEof, "truncated database dump");
+
+    if rest.len() < 4 {
+        return Err(truncated());
+    }
+    let len = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+    let v = rest.get(4..4 + len).ok_or_else(truncated)?;
+    *rest = &rest[4 + len..];
+    Ok(v)
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::dump::test_dump_load --exact --show-output
+#[tokio::test]
+async fn test_dump_load() {
+    use super::memdb;
+
+    let mut db = memdb::Database::new();
+    db.put(b"foo", b"bar").await.unwrap();
+    db.put(b"empty", b"").await.unwrap();
+    db.put(&[0; 300], &[1; 70_000]).await.unwrap();
+
+    let b = dump(&db).await.unwrap();
+    assert_eq!(&b[..4], b"AVDB");
+    assert_eq!(&b[4..6], &[0, 1]);
+
+    let restored = memdb::Database::new();
+    assert_eq!(load(&restored, &b).await.unwrap(), 3);
+    assert_eq!(restored.get(b"foo").await.unwrap(), b"bar");
+    assert!(restored.get(b"empty").await.unwrap().is_empty());
+    assert_eq!(restored.get(&[0; 300]).await.unwrap(), vec![1; 70_000]);
+    assert_eq!(dump(&restored).await.unwrap(), b);
+
+    // an empty database dumps to the header alone
+    let empty = dump(&memdb::Database::new()).await.unwrap();
+    assert_eq!(empty.len(), 6);
+    assert_eq!(load(&memdb::Database::new(), &empty).await.unwrap(), 0);
+
+    let untouched = memdb::Database::new();
+    let mut tampered = b.clone();
+    tampered[0] = b'X';
+    let err = load(&untouched, &tampered).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(err.to_string().contains("bad magic"));
+    assert!(load(&untouched, b"AV").await.is_err());
+
+    let mut tampered = b.clone();
+    tampered[5] = 2;
+    let err = load(&untouched, &tampered).await.unwrap_err();
+    assert!(err.to_string().contains("unsupported dump version 2"));
+
+    let err = load(&untouched, &b[..b.len() - 1]).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    assert!(!untouched.has(b"foo").await.unwrap());
+}
@@ -0,0 +1,176 @@
+//! Database wrapper which reads through an ordered chain of databases.
+//!
+//! Useful during migrations where reads should be served by the new store
+//! first and fall back to the old store until it has been fully drained.
+use std::io;
+
+use super::{batch::BoxedBatch, iterator::BoxedIterator, BoxedDatabase};
+use crate::subnet::rpc::errors;
+
+/// Database which tries each underlying database in order on reads
+/// and only writes to the first (primary) database.
+#[derive(Clone)]
+pub struct Database {
+    /// Ordered list of databases, where the first one is the write target.
+    dbs: Vec<BoxedDatabase>,
+}
+
+impl Database {
+    /// Creates a database which writes to "primary" and reads from "primary"
+    /// and then from each of "fallbacks" in the given order.
+    pub fn new(primary: BoxedDatabase, fallbacks: Vec<BoxedDatabase>) -> BoxedDatabase {
+        let mut dbs = Vec::with_capacity(1 + fallbacks.len());
+        dbs.push(primary);
+        dbs.extend(fallbacks);
+        Box::new(Self { dbs })
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::KeyValueReaderWriterDeleter for Database {
+    /// Returns true if any of the databases has the key.
+    /// Returns early on the first non-"not found" error.
+    async fn has(&self, key: &[u8]) -> io::Result<bool> {
+        for db in self.dbs.iter() {
+            match db.has(key).await {
+                Ok(true) => return Ok(true),
+                Ok(false) => continue,
+                Err(e) if errors::is_not_found(&e) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(false)
+    }
+
+    /// Returns the value from the first database that has the key.
+    /// Returns "not found" only if none of the databases has the key.
+    async fn get(&self, key: &[u8]) -> io::Result<Vec<u8>> {
+        for db in self.dbs.iter() {
+            match db.get(key).await {
+                Ok(value) => return Ok(value),
+                Err(e) if errors::is_not_found(&e) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Err(errors::Error::NotFound.to_err())
+    }
+
+    /// Writes to the primary database only.
+    async fn put(&mut self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        self.dbs[0].put(key, value).await
+    }
+
+    /// Deletes from the primary database only.
+    async fn delete(&mut self, key: &[u8]) -> io::Result<()> {
+        self.dbs[0].delete(key).await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::Closer for Database {
+    /// Closes all databases, returning the first error observed.
+    async fn close(&self) -> io::Result<()> {
+        let mut errs = crate::subnet::rpc::utils::Errors::new();
+        for db in self.dbs.iter() {
+            if let Err(e) = db.close().await {
+                errs.add(&e);
+            }
+        }
+        errs.err()
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::health::Checkable for Database {
+    /// Checks the health of all databases, returning the first error observed.
+    async fn health_check(&self) -> io::Result<Vec<u8>> {
+        for db in self.dbs.iter() {
+            db.health_check().await?;
+        }
+        Ok(vec![])
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::iterator::Iteratee for Database {
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator(&self) -> io::Result<BoxedIterator> {
+        self.new_iterator_with_start_and_prefix(&[], &[]).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_start(&self, start: &[u8]) -> io::Result<BoxedIterator> {
+        self.new_iterator_with_start_and_prefix(start, &[]).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_prefix(&self, prefix: &[u8]) -> io::Result<BoxedIterator> {
+        self.new_iterator_with_start_and_prefix(&[], prefix).await
+    }
+
+    /// Iterates over the primary database only.
+    async fn new_iterator_with_start_and_prefix(
+        &self,
+        start: &[u8],
+        prefix: &[u8],
+    ) -> io::Result<BoxedIterator> {
+        self.dbs[0]
+            .new_iterator_with_start_and_prefix(start, prefix)
+            .await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::batch::Batcher for Database {
+    /// Creates a batch against the primary database.
+    async fn new_batch(&self) -> io::Result<BoxedBatch> {
+        self.dbs[0].new_batch().await
+    }
+}
+
+impl crate::subnet::rpc::database::Database for Database {}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::fallbackdb::test_fallback_read --exact --show-output
+#[tokio::test]
+async fn test_fallback_read() {
+    use crate::subnet::rpc::database::memdb;
+
+    let new_db = memdb::Database::new();
+    let mut old_db = memdb::Database::new();
+    old_db
+        .put("foo".as_bytes(), "bar".as_bytes())
+        .await
+        .unwrap();
+
+    let db = Database::new(new_db.clone(), vec![old_db.clone()]);
+
+    // served by the second store
+    assert!(db.has("foo".as_bytes()).await.unwrap());
+    assert_eq!(db.get("foo".as_bytes()).await.unwrap(), "bar".as_bytes());
+
+    // missing from all stores
+    assert!(!db.has("baz".as_bytes()).await.unwrap());
+    let resp = db.get("baz".as_bytes()).await;
+    assert!(errors::is_not_found(&resp.unwrap_err()));
+
+    // non "not found" errors short-circuit
+    old_db.close().await.unwrap();
+    let resp = db.get("foo".as_bytes()).await;
+    assert_eq!(resp.unwrap_err().to_string(), "database closed");
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::fallbackdb::test_fallback_write --exact --show-output
+#[tokio::test]
+async fn test_fallback_write() {
+    use crate::subnet::rpc::database::memdb;
+
+    let new_db = memdb::Database::new();
+    let old_db = memdb::Database::new();
+
+    let mut db = Database::new(new_db.clone(), vec![old_db.clone()]);
+    db.put("foo".as_bytes(), "bar".as_bytes()).await.unwrap();
+
+    assert!(new_db.has("foo".as_bytes()).await.unwrap());
+    assert!(!old_db.has("foo".as_bytes()).await.unwrap());
+    assert_eq!(db.get("foo".as_bytes()).await.unwrap(), "bar".as_bytes());
+}
@@ -0,0 +1,329 @@
+//! Database wrapper which fences off writes from stale writers.
+//!
+//! Useful when two VM instances may transiently both act as the primary:
+//! each writes with a fence token, and once a newer token has written, the
+//! writes of any older token are rejected instead of clobbering its state.
+//! The newest token is stored in the underlying database under [`FENCE_KEY`],
+//! so that the fence holds across processes and restarts sharing that
+//! database.
+//! ref. <https://martin.kleppmann.com/2016/02/08/how-to-do-distributed-locking.html>
+use std::{io, sync::Arc};
+
+use tokio::sync::{Mutex, MutexGuard};
+
+use super::{batch::BoxedBatch, iterator::BoxedIterator, BoxedDatabase};
+use crate::subnet::rpc::errors;
+
+/// Prefix of the error returned for writes with a stale token.
+pub const ERR_FENCED: &str = "fenced";
+
+/// Reserved key of the underlying database which stores the newest token
+/// that has written, as a big-endian u64.
+pub const FENCE_KEY: &[u8] = b"\x00fencedb/last_seen";
+
+/// Database which rejects "put", "delete" and batch writes made with a
+/// token older than the newest token that has written so far. The fence is
+/// shared by every handle over the same underlying database; reads are not
+/// fenced.
+#[derive(Clone)]
+pub struct Database {
+    db: BoxedDatabase,
+    /// Token this handle writes with.
+    token: u64,
+    /// Held across the check of the stored fence and the write, so that
+    /// handles sharing it cannot write in between.
+    lock: Arc<Mutex<()>>,
+}
+
+impl Database {
+    pub fn new(db: BoxedDatabase, token: u64) -> Self {
+        Self {
+            db,
+            token,
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Returns a handle over the same database and fence writing with
+    /// "token" (e.g., after winning a new leader election).
+    pub fn with_token(&self, token: u64) -> Self {
+        Self {
+            db: self.db.clone(),
+            token,
+            lock: Arc::clone(&self.lock),
+        }
+    }
+
+    pub fn token(&self) -> u64 {
+        self.token
+    }
+
+    /// Returns the newest token that has written so far.
+    pub async fn last_seen(&self) -> io::Result<u64> {
+        last_seen(&self.db).await
+    }
+}
+
+/// Reads the newest token stored in the database, or 0 if none has written.
+async fn last_seen(db: &BoxedDatabase) -> io::Result<u64> {
+    let d = match db.get(FENCE_KEY).await {
+        Ok(d) => d,
+        Err(e) if errors::is_not_found(&e) => return Ok(0),
+        Err(e) => return Err(e),
+    };
+    let d: [u8; 8] = d.as_slice().try_into().map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid fence token ({} bytes)", d.len()),
+        )
+    })?;
+    Ok(u64::from_be_bytes(d))
+}
+
+/// Locks the fence and checks it against "token", or errors if a newer token
+/// has already written. The write must happen while the guard is held, and
+/// store "token" under [`FENCE_KEY`] along with it.
+async fn fence<'a>(
+    lock: &'a Mutex<()>,
+    db: &BoxedDatabase,
+    token: u64,
+) -> io::Result<MutexGuard<'a, ()>> {
+    let guard = lock.lock().await;
+    let last_seen = last_seen(db).await?;
+    if token < last_seen {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "{}: token {} is older than the last seen token {}",
+                ERR_FENCED, token, last_seen
+            ),
+        ));
+    }
+    Ok(guard)
+}
+
+/// Returns true if the error is from a write with a stale token.
+pub fn is_fenced(error: &io::Error) -> bool {
+    error.kind() == io::ErrorKind::PermissionDenied && error.to_string().starts_with(ERR_FENCED)
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::KeyValueReaderWriterDeleter for Database {
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn has(&self, key: &[u8]) -> io::Result<bool> {
+        self.db.has(key).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn get(&self, key: &[u8]) -> io::Result<Vec<u8>> {
+        self.db.get(key).await
+    }
+
+    /// Writes the key unless a newer token has written.
+    async fn put(&mut self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        let _fence = fence(&self.lock, &self.db, self.token).await?;
+        let mut batch = self.db.new_batch().await?;
+        batch.put(key, value).await?;
+        batch.put(FENCE_KEY, &self.token.to_be_bytes()).await?;
+        batch.write().await
+    }
+
+    /// Deletes the key unless a newer token has written.
+    async fn delete(&mut self, key: &[u8]) -> io::Result<()> {
+        let _fence = fence(&self.lock, &self.db, self.token).await?;
+        let mut batch = self.db.new_batch().await?;
+        batch.delete(key).await?;
+        batch.put(FENCE_KEY, &self.token.to_be_bytes()).await?;
+        batch.write().await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::Closer for Database {
+    /// Implements the [`crate::subnet::rpc::database::Closer`] trait.
+    async fn close(&self) -> io::Result<()> {
+        self.db.close().await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::health::Checkable for Database {
+    /// Implements the [`crate::subnet::rpc::health::Checkable`] trait.
+    async fn health_check(&self) -> io::Result<Vec<u8>> {
+        self.db.health_check().await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::iterator::Iteratee for Database {
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator(&self) -> io::Result<BoxedIterator> {
+        self.db.new_iterator().await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_start(&self, start: &[u8]) -> io::Result<BoxedIterator> {
+        self.db.new_iterator_with_start(start).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_prefix(&self, prefix: &[u8]) -> io::Result<BoxedIterator> {
+        self.db.new_iterator_with_prefix(prefix).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_start_and_prefix(
+        &self,
+        start: &[u8],
+        prefix: &[u8],
+    ) -> io::Result<BoxedIterator> {
+        self.db
+            .new_iterator_with_start_and_prefix(start, prefix)
+            .await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::batch::Batcher for Database {
+    /// Returns a batch which checks the fence when it is written, not
+    /// when it is created.
+    async fn new_batch(&self) -> io::Result<BoxedBatch> {
+        Ok(Box::new(Batch {
+            batch: self.db.new_batch().await?,
+            db: self.db.clone(),
+            token: self.token,
+            lock: Arc::clone(&self.lock),
+        }))
+    }
+}
+
+impl crate::subnet::rpc::database::Database for Database {}
+
+/// Batch which is written only if no newer token has written.
+#[derive(Clone)]
+struct Batch {
+    batch: BoxedBatch,
+    db: BoxedDatabase,
+    token: u64,
+    lock: Arc<Mutex<()>>,
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::batch::Batch for Batch {
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn put(&mut self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        self.batch.put(key, value).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn delete(&mut self, key: &[u8]) -> io::Result<()> {
+        self.batch.delete(key).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn size(&self) -> io::Result<usize> {
+        self.batch.size().await
+    }
+
+    /// Writes the batch unless a newer token has written.
+    async fn write(&self) -> io::Result<()> {
+        let _fence = fence(&self.lock, &self.db, self.token).await?;
+        // written on a copy, so that the fence is not replayed or written
+        // again after a "reset"
+        let mut batch = self.batch.clone();
+        batch.put(FENCE_KEY, &self.token.to_be_bytes()).await?;
+        batch.write().await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn reset(&mut self) {
+        self.batch.reset().await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn replay(&self, k: Arc<Mutex<BoxedDatabase>>) -> io::Result<()> {
+        self.batch.replay(k).await
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::fencedb::test_fenced_writes --exact --show-output
+#[tokio::test]
+async fn test_fenced_writes() {
+    use crate::subnet::rpc::database::{batch::Batcher, memdb, KeyValueReaderWriterDeleter};
+
+    let base = memdb::Database::new();
+    let mut old_primary = Database::new(base.clone(), 1);
+    old_primary.put(b"state", b"v1").await.unwrap();
+    assert_eq!(old_primary.last_seen().await.unwrap(), 1);
+
+    // the new primary takes over with a newer token
+    // a batch created before the takeover is fenced when written
+    let mut stale_batch = old_primary.new_batch().await.unwrap();
+    stale_batch.put(b"batched", b"v1").await.unwrap();
+
+    let mut new_primary = old_primary.with_token(2);
+    new_primary.put(b"state", b"v2").await.unwrap();
+    assert_eq!(new_primary.last_seen().await.unwrap(), 2);
+
+    assert!(is_fenced(&stale_batch.write().await.unwrap_err()));
+    assert!(!base.has(b"batched").await.unwrap());
+
+    // the stale primary can no longer write, but can still read
+    let err = old_primary.put(b"state", b"v1'").await.unwrap_err();
+    assert!(is_fenced(&err));
+    assert!(is_fenced(&old_primary.delete(b"state").await.unwrap_err()));
+    assert_eq!(old_primary.get(b"state").await.unwrap(), b"v2");
+    assert_eq!(base.get(b"state").await.unwrap(), b"v2");
+
+    // the current token keeps writing, and creating a batch does not
+    // advance the fence
+    let mut batch = old_primary.new_batch().await.unwrap();
+    batch.put(b"batched", b"v1'").await.unwrap();
+    assert!(is_fenced(&batch.write().await.unwrap_err()));
+    assert_eq!(new_primary.last_seen().await.unwrap(), 2);
+
+    let mut batch = new_primary.new_batch().await.unwrap();
+    batch.put(b"batched", b"v2").await.unwrap();
+    batch.write().await.unwrap();
+    assert_eq!(base.get(b"batched").await.unwrap(), b"v2");
+
+    new_primary.delete(b"state").await.unwrap();
+    assert!(!base.has(b"state").await.unwrap());
+    assert!(!is_fenced(&io::Error::new(io::ErrorKind::Other, "fenced")));
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::fencedb::test_fenced_across_handles --exact --show-output
+#[tokio::test]
+async fn test_fenced_across_handles() {
+    use crate::subnet::rpc::database::{batch::Batcher, memdb, KeyValueReaderWriterDeleter};
+
+    // two instances sharing the database, each with its own handle
+    let base = memdb::Database::new();
+    let mut old_primary = Database::new(base.clone(), 1);
+    let mut new_primary = Database::new(base.clone(), 2);
+    old_primary.put(b"state", b"v1").await.unwrap();
+    new_primary.put(b"state", b"v2").await.unwrap();
+
+    assert!(is_fenced(
+        &old_primary.put(b"state", b"v1'").await.unwrap_err()
+    ));
+    assert!(is_fenced(&old_primary.delete(b"state").await.unwrap_err()));
+    let mut batch = old_primary.new_batch().await.unwrap();
+    batch.put(b"state", b"v1'").await.unwrap();
+    assert!(is_fenced(&batch.write().await.unwrap_err()));
+    assert_eq!(base.get(b"state").await.unwrap(), b"v2");
+    assert_eq!(old_primary.last_seen().await.unwrap(), 2);
+
+    // the fence survives a restart of the stale instance
+    let mut restarted = Database::new(base.clone(), 1);
+    assert!(is_fenced(
+        &restarted.put(b"state", b"v1'").await.unwrap_err()
+    ));
+    assert_eq!(base.get(FENCE_KEY).await.unwrap(), 2_u64.to_be_bytes());
+
+    // a corrupted fence fails the writes instead of opening it
+    let mut inner = base.clone();
+    inner.put(FENCE_KEY, b"bad").await.unwrap();
+    let err = new_primary.put(b"state", b"v3").await.unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
@@ -0,0 +1,248 @@
+//! Database wrapper which forbids selected operations.
+//!
+//! Useful to hand out a handle that may, for instance, write but never
+//! delete. Reads ("has", "get") are always allowed.
+use std::{io, sync::Arc};
+
+use super::{batch::BoxedBatch, iterator::BoxedIterator, BoxedDatabase, Compacter};
+
+/// Prefix of the error returned for forbidden operations.
+pub const ERR_FORBIDDEN: &str = "forbidden";
+
+/// Operations a [`Database`] handle allows. All are allowed by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Permissions {
+    pub put: bool,
+    pub delete: bool,
+    pub compact: bool,
+    /// Allows creating iterators.
+    pub iterate: bool,
+}
+
+impl Default for Permissions {
+    fn default() -> Self {
+        Self {
+            put: true,
+            delete: true,
+            compact: true,
+            iterate: true,
+        }
+    }
+}
+
+/// Database which returns a "forbidden" error for the operations its
+/// [`Permissions`] disallow. Batches can both put and delete, so creating
+/// one requires both permissions.
+#[derive(Clone)]
+pub struct Database {
+    db: BoxedDatabase,
+    /// Same database as "db", if it can be compacted.
+    compacter: Option<Arc<dyn Compacter + Send + Sync>>,
+    permissions: Permissions,
+}
+
+impl Database {
+    pub fn new(db: BoxedDatabase, permissions: Permissions) -> BoxedDatabase {
+        Box::new(Self {
+            db,
+            compacter: None,
+            permissions,
+        })
+    }
+
+    /// Same as [`Database::new`] but keeps the [`Compacter`] of the
+    /// database, so that the handle compacts it when allowed.
+    pub fn new_compactable<D>(db: D, permissions: Permissions) -> Self
+    where
+        D: crate::subnet::rpc::database::Database + Compacter + Clone + Send + Sync + 'static,
+    {
+        Self {
+            db: Box::new(db.clone()),
+            compacter: Some(Arc::new(db)),
+            permissions,
+        }
+    }
+
+    fn check(&self, allowed: bool, op: &str) -> io::Result<()> {
+        if allowed {
+            return Ok(());
+        }
+        Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{}: {}", ERR_FORBIDDEN, op),
+        ))
+    }
+}
+
+/// Returns true if the error is from a forbidden operation.
+pub fn is_forbidden(error: &io::Error) -> bool {
+    error.kind() == io::ErrorKind::PermissionDenied && error.to_string().starts_with(ERR_FORBIDDEN)
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::KeyValueReaderWriterDeleter for Database {
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn has(&self, key: &[u8]) -> io::Result<bool> {
+        self.db.has(key).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn get(&self, key: &[u8]) -> io::Result<Vec<u8>> {
+        self.db.get(key).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn put(&mut self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        self.check(self.permissions.put, "put")?;
+        self.db.put(key, value).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn delete(&mut self, key: &[u8]) -> io::Result<()> {
+        self.check(self.permissions.delete, "delete")?;
+        self.db.delete(key).await
+    }
+}
+
+#[tonic::async_trait]
+impl Compacter for Database {
+    /// Compacts the database if it was created with
+    /// [`Database::new_compactable`], and does nothing otherwise.
+    async fn compact(&self, start: &[u8], limit: &[u8]) -> io::Result<()> {
+        self.check(self.permissions.compact, "compact")?;
+        match &self.compacter {
+            Some(compacter) => compacter.compact(start, limit).await,
+            None => Ok(()),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::Closer for Database {
+    /// Implements the [`crate::subnet::rpc::database::Closer`] trait.
+    async fn close(&self) -> io::Result<()> {
+        self.db.close().await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::health::Checkable for Database {
+    /// Implements the [`crate::subnet::rpc::health::Checkable`] trait.
+    async fn health_check(&self) -> io::Result<Vec<u8>> {
+        self.db.health_check().await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::iterator::Iteratee for Database {
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator(&self) -> io::Result<BoxedIterator> {
+        self.check(self.permissions.iterate, "iterate")?;
+        self.db.new_iterator().await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_start(&self, start: &[u8]) -> io::Result<BoxedIterator> {
+        self.check(self.permissions.iterate, "iterate")?;
+        self.db.new_iterator_with_start(start).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_prefix(&self, prefix: &[u8]) -> io::Result<BoxedIterator> {
+        self.check(self.permissions.iterate, "iterate")?;
+        self.db.new_iterator_with_prefix(prefix).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_start_and_prefix(
+        &self,
+        start: &[u8],
+        prefix: &[u8],
+    ) -> io::Result<BoxedIterator> {
+        self.check(self.permissions.iterate, "iterate")?;
+        self.db
+            .new_iterator_with_start_and_prefix(start, prefix)
+            .await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::batch::Batcher for Database {
+    /// Implements the [`crate::subnet::rpc::database::batch::Batcher`] trait.
+    async fn new_batch(&self) -> io::Result<BoxedBatch> {
+        self.check(self.permissions.put && self.permissions.delete, "batch")?;
+        self.db.new_batch().await
+    }
+}
+
+impl crate::subnet::rpc::database::Database for Database {}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::guardeddb::test_delete_forbidden --exact --show-output
+#[tokio::test]
+async fn test_delete_forbidden() {
+    use crate::subnet::rpc::database::memdb;
+
+    let base = memdb::Database::new();
+    let mut db = Database::new(
+        base.clone(),
+        Permissions {
+            delete: false,
+            ..Default::default()
+        },
+    );
+
+    db.put(b"foo", b"bar").await.unwrap();
+    assert_eq!(db.get(b"foo").await.unwrap(), b"bar");
+
+    let err = db.delete(b"foo").await.unwrap_err();
+    assert!(is_forbidden(&err));
+    assert_eq!(err.to_string(), "forbidden: delete");
+    assert!(base.has(b"foo").await.unwrap());
+
+    // batches could delete too
+    assert!(is_forbidden(&db.new_batch().await.err().unwrap()));
+
+    let mut iter = db.new_iterator().await.unwrap();
+    assert!(iter.next().await.unwrap());
+    assert_eq!(iter.key().await.unwrap(), b"foo");
+
+    let db = Database::new(
+        base,
+        Permissions {
+            iterate: false,
+            ..Default::default()
+        },
+    );
+    assert!(is_forbidden(
+        &db.new_iterator_with_prefix(b"f").await.err().unwrap()
+    ));
+    assert!(db.new_batch().await.is_ok());
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::guardeddb::test_compact_forbidden --exact --show-output
+#[tokio::test]
+async fn test_compact_forbidden() {
+    use crate::subnet::rpc::database::{memdb, Closer, KeyValueReaderWriterDeleter};
+
+    let base = memdb::Database::new_with_options(memdb::Options::default());
+    let db = Database::new_compactable(
+        base.clone(),
+        Permissions {
+            compact: false,
+            ..Default::default()
+        },
+    );
+    let err = db.compact(&[], &[]).await.unwrap_err();
+    assert!(is_forbidden(&err));
+    assert_eq!(err.to_string(), "forbidden: compact");
+    assert_eq!(
+        db.get(b"foo").await.unwrap_err().kind(),
+        io::ErrorKind::NotFound
+    );
+
+    // allowed compactions reach the database
+    let db = Database::new_compactable(base.clone(), Permissions::default());
+    db.compact(&[], &[]).await.unwrap();
+    base.close().await.unwrap();
+    assert!(!is_forbidden(&db.compact(&[], &[]).await.unwrap_err()));
+}
@@ -0,0 +1,261 @@
+//! Database wrapper which runs hooks around every call, for tests.
+//!
+//! Passes each call through to the underlying database, so that a test can
+//! count, delay or fail the calls of one method without implementing every
+//! database trait for its own mock.
+use std::{collections::HashMap, io, sync::Arc, time::Duration};
+
+use super::{batch::BoxedBatch, iterator::BoxedIterator, BoxedDatabase};
+
+/// Database method passed to the hooks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Method {
+    Has,
+    Get,
+    Put,
+    Delete,
+    Close,
+    HealthCheck,
+    /// Any of the "new_iterator*" methods.
+    NewIterator,
+    NewBatch,
+    Compact,
+}
+
+/// Runs before the call with its method and key (the start key for
+/// iterators and compactions). An error fails the call without reaching
+/// the underlying database.
+pub type BeforeHook = Arc<dyn Fn(Method, &[u8]) -> io::Result<()> + Send + Sync>;
+
+/// Runs once the underlying database returns, whatever the result. Skipped
+/// if the call is cancelled (e.g., by a timeout) before then.
+pub type AfterHook = Arc<dyn Fn(Method) + Send + Sync>;
+
+#[derive(Clone)]
+pub struct Database {
+    db: BoxedDatabase,
+    before: Option<BeforeHook>,
+    after: Option<AfterHook>,
+    delays: HashMap<Method, Duration>,
+}
+
+impl Database {
+    pub fn new(db: BoxedDatabase) -> Self {
+        Self {
+            db,
+            before: None,
+            after: None,
+            delays: HashMap::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_before(
+        mut self,
+        hook: impl Fn(Method, &[u8]) -> io::Result<()> + Send + Sync + 'static,
+    ) -> Self {
+        self.before = Some(Arc::new(hook));
+        self
+    }
+
+    #[must_use]
+    pub fn with_after(mut self, hook: impl Fn(Method) + Send + Sync + 'static) -> Self {
+        self.after = Some(Arc::new(hook));
+        self
+    }
+
+    /// Sleeps for "delay" before each call of the method, after the
+    /// [`BeforeHook`] passed.
+    #[must_use]
+    pub fn with_delay(mut self, method: Method, delay: Duration) -> Self {
+        self.delays.insert(method, delay);
+        self
+    }
+
+    async fn enter(&self, method: Method, key: &[u8]) -> io::Result<()> {
+        if let Some(before) = &self.before {
+            before(method, key)?;
+        }
+        if let Some(delay) = self.delays.get(&method) {
+            tokio::time::sleep(*delay).await;
+        }
+        Ok(())
+    }
+
+    fn exit(&self, method: Method) {
+        if let Some(after) = &self.after {
+            after(method);
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::KeyValueReaderWriterDeleter for Database {
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn has(&self, key: &[u8]) -> io::Result<bool> {
+        self.enter(Method::Has, key).await?;
+        let resp = self.db.has(key).await;
+        self.exit(Method::Has);
+        resp
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn get(&self, key: &[u8]) -> io::Result<Vec<u8>> {
+        self.enter(Method::Get, key).await?;
+        let resp = self.db.get(key).await;
+        self.exit(Method::Get);
+        resp
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn put(&mut self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        self.enter(Method::Put, key).await?;
+        let resp = self.db.put(key, value).await;
+        self.exit(Method::Put);
+        resp
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn delete(&mut self, key: &[u8]) -> io::Result<()> {
+        self.enter(Method::Delete, key).await?;
+        let resp = self.db.delete(key).await;
+        self.exit(Method::Delete);
+        resp
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::Closer for Database {
+    /// Implements the [`crate::subnet::rpc::database::Closer`] trait.
+    async fn close(&self) -> io::Result<()> {
+        self.enter(Method::Close, &[]).await?;
+        let resp = self.db.close().await;
+        self.exit(Method::Close);
+        resp
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::Compacter for Database {
+    /// Runs the hooks only, as a [`BoxedDatabase`] cannot be compacted.
+    async fn compact(&self, start: &[u8], _limit: &[u8]) -> io::Result<()> {
+        self.enter(Method::Compact, start).await?;
+        self.exit(Method::Compact);
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::health::Checkable for Database {
+    /// Implements the [`crate::subnet::rpc::health::Checkable`] trait.
+    async fn health_check(&self) -> io::Result<Vec<u8>> {
+        self.enter(Method::HealthCheck, &[]).await?;
+        let resp = self.db.health_check().await;
+        self.exit(Method::HealthCheck);
+        resp
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::iterator::Iteratee for Database {
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator(&self) -> io::Result<BoxedIterator> {
+        self.new_iterator_with_start_and_prefix(&[], &[]).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_start(&self, start: &[u8]) -> io::Result<BoxedIterator> {
+        self.new_iterator_with_start_and_prefix(start, &[]).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_prefix(&self, prefix: &[u8]) -> io::Result<BoxedIterator> {
+        self.new_iterator_with_start_and_prefix(&[], prefix).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_start_and_prefix(
+        &self,
+        start: &[u8],
+        prefix: &[u8],
+    ) -> io::Result<BoxedIterator> {
+        self.enter(Method::NewIterator, start).await?;
+        let resp = self
+            .db
+            .new_iterator_with_start_and_prefix(start, prefix)
+            .await;
+        self.exit(Method::NewIterator);
+        resp
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::batch::Batcher for Database {
+    /// Implements the [`crate::subnet::rpc::database::batch::Batcher`] trait.
+    async fn new_batch(&self) -> io::Result<BoxedBatch> {
+        self.enter(Method::NewBatch, &[]).await?;
+        let resp = self.db.new_batch().await;
+        self.exit(Method::NewBatch);
+        resp
+    }
+}
+
+impl crate::subnet::rpc::database::Database for Database {}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::hookdb::test_hooks --exact --show-output
+#[tokio::test]
+async fn test_hooks() {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Mutex,
+        },
+        time::Instant,
+    };
+
+    use crate::subnet::rpc::database::{memdb, Compacter, KeyValueReaderWriterDeleter};
+
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let returned = Arc::new(AtomicUsize::new(0));
+    let mut db = Database::new(memdb::Database::new())
+        .with_before({
+            let calls = Arc::clone(&calls);
+            move |method, key| {
+                calls.lock().unwrap().push((method, key.to_vec()));
+                if method == Method::Delete {
+                    return Err(io::Error::new(io::ErrorKind::Other, "injected"));
+                }
+                Ok(())
+            }
+        })
+        .with_after({
+            let returned = Arc::clone(&returned);
+            move |_| {
+                returned.fetch_add(1, Ordering::SeqCst);
+            }
+        })
+        .with_delay(Method::Get, Duration::from_millis(20));
+
+    db.put(b"foo", b"bar").await.unwrap();
+    let start = Instant::now();
+    assert_eq!(db.get(b"foo").await.unwrap(), b"bar");
+    assert!(start.elapsed() >= Duration::from_millis(20));
+
+    // a failing hook keeps the call from the underlying database
+    let err = db.delete(b"foo").await.unwrap_err();
+    assert_eq!(err.to_string(), "injected");
+    assert!(db.has(b"foo").await.unwrap());
+
+    db.compact(b"a", b"z").await.unwrap();
+    assert_eq!(
+        *calls.lock().unwrap(),
+        vec![
+            (Method::Put, b"foo".to_vec()),
+            (Method::Get, b"foo".to_vec()),
+            (Method::Delete, b"foo".to_vec()),
+            (Method::Has, b"foo".to_vec()),
+            (Method::Compact, b"a".to_vec()),
+        ]
+    );
+    assert_eq!(returned.load(Ordering::SeqCst), 4);
+}
@@ -53,3 +53,126 @@ pub trait Iteratee {
         prefix: &[u8],
     ) -> Result<BoxedIterator>;
 }
+
+/// Order in which a prefix scan yields its keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Order {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+/// Creates an iterator over the keys with the prefix, in the order. Works
+/// with any database (e.g., memdb or rpcdb): as their iterators only
+/// move forward, a descending scan reads the whole prefix up front and
+/// yields it backwards.
+pub async fn new_iterator_with_prefix_and_order(
+    db: &super::BoxedDatabase,
+    prefix: &[u8],
+    order: Order,
+) -> Result<BoxedIterator> {
+    let mut iter = db.new_iterator_with_prefix(prefix).await?;
+    if order == Order::Ascending {
+        return Ok(iter);
+    }
+
+    let mut entries = Vec::new();
+    while iter.next().await? {
+        entries.push((iter.key().await?.to_vec(), iter.value().await?.to_vec()));
+    }
+    iter.error().await?;
+    iter.release().await;
+
+    entries.reverse();
+    Ok(Box::new(Buffered { entries, next: 0 }))
+}
+
+/// Iterator over entries read ahead of time.
+struct Buffered {
+    entries: Vec<(Vec<u8>, Vec<u8>)>,
+    /// Index of the entry after the current one.
+    next: usize,
+}
+
+impl Buffered {
+    fn current(&self) -> Option<&(Vec<u8>, Vec<u8>)> {
+        self.next.checked_sub(1).and_then(|i| self.entries.get(i))
+    }
+}
+
+#[tonic::async_trait]
+impl Iterator for Buffered {
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iterator`] trait.
+    async fn next(&mut self) -> Result<bool> {
+        if self.next < self.entries.len() {
+            self.next += 1;
+            return Ok(true);
+        }
+        self.next = self.entries.len() + 1;
+        Ok(false)
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iterator`] trait.
+    async fn error(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iterator`] trait.
+    async fn key(&self) -> Result<&[u8]> {
+        Ok(self.current().map_or(&[], |(k, _)| k))
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iterator`] trait.
+    async fn value(&self) -> Result<&[u8]> {
+        Ok(self.current().map_or(&[], |(_, v)| v))
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iterator`] trait.
+    async fn release(&mut self) {
+        self.entries.clear();
+        self.next = 0;
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::iterator::test_prefix_order --exact --show-output
+#[tokio::test]
+async fn test_prefix_order() {
+    use super::memdb;
+
+    let mut db = memdb::Database::new();
+    for height in [3u64, 1, 300, 2] {
+        let mut key = b"height/".to_vec();
+        key.extend_from_slice(&height.to_be_bytes());
+        db.put(&key, &height.to_be_bytes()).await.unwrap();
+    }
+    db.put(b"other", b"skipped").await.unwrap();
+
+    let scan = |order: Order| {
+        let db = db.clone();
+        async move {
+            let mut iter = new_iterator_with_prefix_and_order(&db, b"height/", order)
+                .await
+                .unwrap();
+            let mut heights = Vec::new();
+            while iter.next().await.unwrap() {
+                assert!(iter.key().await.unwrap().starts_with(b"height/"));
+                heights.push(u64::from_be_bytes(
+                    iter.value().await.unwrap().try_into().unwrap(),
+                ));
+            }
+            iter.error().await.unwrap();
+            assert!(iter.key().await.unwrap().is_empty());
+            assert!(!iter.next().await.unwrap());
+            iter.release().await;
+            heights
+        }
+    };
+
+    assert_eq!(scan(Order::default()).await, vec![1, 2, 3, 300]);
+    assert_eq!(scan(Order::Descending).await, vec![300, 3, 2, 1]);
+
+    let mut iter = new_iterator_with_prefix_and_order(&db, b"missing/", Order::Descending)
+        .await
+        .unwrap();
+    assert!(!iter.next().await.unwrap());
+}
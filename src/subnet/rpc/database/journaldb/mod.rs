@@ -0,0 +1,289 @@
+//! Database wrapper which journals every mutation for crash-consistency tests.
+//!
+//! The journal can be truncated to simulate a crash after the first "n"
+//! operations, then replayed against a fresh database to check what a VM
+//! recovers from any prefix of its write history.
+use std::{
+    io::{self, Error, ErrorKind},
+    sync::Arc,
+};
+
+use super::{batch::BoxedBatch, iterator::BoxedIterator, BoxedDatabase};
+use tokio::sync::Mutex;
+
+/// Mutation recorded in the journal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Op {
+    Put {
+        key: Vec<u8>,
+        value: Vec<u8>,
+    },
+    Delete {
+        key: Vec<u8>,
+    },
+    /// Operations of a batch, written atomically: a crash either keeps or
+    /// drops all of them.
+    Batch {
+        ops: Vec<Op>,
+    },
+}
+
+/// Database which appends each successful "put", "delete" and batch write
+/// to a journal. The journal stays locked while the mutation is applied, so
+/// that its order is the order the underlying database applied them in.
+#[derive(Clone)]
+pub struct Database {
+    db: BoxedDatabase,
+    journal: Arc<Mutex<Vec<Op>>>,
+}
+
+impl Database {
+    pub fn new(db: BoxedDatabase) -> Self {
+        Self {
+            db,
+            journal: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Returns the journaled operations, oldest first.
+    pub async fn journal(&self) -> Vec<Op> {
+        self.journal.lock().await.clone()
+    }
+
+    /// Keeps only the first "n" operations of the journal, as if the
+    /// process crashed right after them. A batch counts as one operation.
+    pub async fn truncate_at(&self, n: usize) {
+        self.journal.lock().await.truncate(n);
+    }
+
+    /// Applies the journal in order to the database (e.g., a fresh memdb),
+    /// each journaled batch through one batch write.
+    pub async fn replay(&self, mut db: BoxedDatabase) -> io::Result<()> {
+        for op in self.journal().await.iter() {
+            match op {
+                Op::Put { key, value } => db.put(key, value).await?,
+                Op::Delete { key } => db.delete(key).await?,
+                Op::Batch { ops } => {
+                    let mut batch = db.new_batch().await?;
+                    for op in ops.iter() {
+                        match op {
+                            Op::Put { key, value } => batch.put(key, value).await?,
+                            Op::Delete { key } => batch.delete(key).await?,
+                            Op::Batch { .. } => {
+                                return Err(Error::new(
+                                    ErrorKind::InvalidData,
+                                    "nested batch in the journal",
+                                ))
+                            }
+                        }
+                    }
+                    batch.write().await?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::KeyValueReaderWriterDeleter for Database {
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn has(&self, key: &[u8]) -> io::Result<bool> {
+        self.db.has(key).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn get(&self, key: &[u8]) -> io::Result<Vec<u8>> {
+        self.db.get(key).await
+    }
+
+    /// Journals the "put" once the underlying database accepts it.
+    async fn put(&mut self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        let mut journal = self.journal.lock().await;
+        self.db.put(key, value).await?;
+        journal.push(Op::Put {
+            key: key.to_vec(),
+            value: value.to_vec(),
+        });
+        Ok(())
+    }
+
+    /// Journals the "delete" once the underlying database accepts it.
+    async fn delete(&mut self, key: &[u8]) -> io::Result<()> {
+        let mut journal = self.journal.lock().await;
+        self.db.delete(key).await?;
+        journal.push(Op::Delete { key: key.to_vec() });
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::Closer for Database {
+    /// Implements the [`crate::subnet::rpc::database::Closer`] trait.
+    async fn close(&self) -> io::Result<()> {
+        self.db.close().await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::health::Checkable for Database {
+    /// Implements the [`crate::subnet::rpc::health::Checkable`] trait.
+    async fn health_check(&self) -> io::Result<Vec<u8>> {
+        self.db.health_check().await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::iterator::Iteratee for Database {
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator(&self) -> io::Result<BoxedIterator> {
+        self.db.new_iterator().await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_start(&self, start: &[u8]) -> io::Result<BoxedIterator> {
+        self.db.new_iterator_with_start(start).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_prefix(&self, prefix: &[u8]) -> io::Result<BoxedIterator> {
+        self.db.new_iterator_with_prefix(prefix).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_start_and_prefix(
+        &self,
+        start: &[u8],
+        prefix: &[u8],
+    ) -> io::Result<BoxedIterator> {
+        self.db
+            .new_iterator_with_start_and_prefix(start, prefix)
+            .await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::batch::Batcher for Database {
+    /// Returns a batch whose operations are journaled when it is written.
+    async fn new_batch(&self) -> io::Result<BoxedBatch> {
+        Ok(Box::new(Batch {
+            batch: self.db.new_batch().await?,
+            ops: Vec::new(),
+            journal: Arc::clone(&self.journal),
+        }))
+    }
+}
+
+impl crate::subnet::rpc::database::Database for Database {}
+
+/// Batch which journals its queued operations on "write".
+#[derive(Clone)]
+struct Batch {
+    batch: BoxedBatch,
+    ops: Vec<Op>,
+    journal: Arc<Mutex<Vec<Op>>>,
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::batch::Batch for Batch {
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn put(&mut self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        self.batch.put(key, value).await?;
+        self.ops.push(Op::Put {
+            key: key.to_vec(),
+            value: value.to_vec(),
+        });
+        Ok(())
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn delete(&mut self, key: &[u8]) -> io::Result<()> {
+        self.batch.delete(key).await?;
+        self.ops.push(Op::Delete { key: key.to_vec() });
+        Ok(())
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn size(&self) -> io::Result<usize> {
+        self.batch.size().await
+    }
+
+    /// Journals the queued operations as one entry once the underlying
+    /// batch is written.
+    async fn write(&self) -> io::Result<()> {
+        let mut journal = self.journal.lock().await;
+        self.batch.write().await?;
+        journal.push(Op::Batch {
+            ops: self.ops.clone(),
+        });
+        Ok(())
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn reset(&mut self) {
+        self.ops.clear();
+        self.batch.reset().await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn replay(&self, k: Arc<tokio::sync::Mutex<BoxedDatabase>>) -> io::Result<()> {
+        self.batch.replay(k).await
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::journaldb::test_truncate_and_replay --exact --show-output
+#[tokio::test]
+async fn test_truncate_and_replay() {
+    use crate::subnet::rpc::database::{batch::Batcher, memdb, KeyValueReaderWriterDeleter};
+
+    let mut db = Database::new(memdb::Database::new());
+    for i in 0..8u8 {
+        db.put(&[i], &[i]).await.unwrap();
+    }
+    db.delete(&[0]).await.unwrap();
+    let mut batch = db.new_batch().await.unwrap();
+    batch.put(&[9], &[9]).await.unwrap();
+    batch.put(&[10], &[10]).await.unwrap();
+    batch.write().await.unwrap();
+
+    let journal = db.journal().await;
+    assert_eq!(journal.len(), 10);
+    assert_eq!(journal[8], Op::Delete { key: vec![0] });
+    assert_eq!(
+        journal[9],
+        Op::Batch {
+            ops: vec![
+                Op::Put {
+                    key: vec![9],
+                    value: vec![9],
+                },
+                Op::Put {
+                    key: vec![10],
+                    value: vec![10],
+                },
+            ],
+        }
+    );
+
+    // the batch is replayed as a whole
+    let recovered = memdb::Database::new();
+    db.replay(recovered.clone()).await.unwrap();
+    assert!(!recovered.has(&[0]).await.unwrap());
+    assert_eq!(recovered.get(&[9]).await.unwrap(), vec![9]);
+    assert_eq!(recovered.get(&[10]).await.unwrap(), vec![10]);
+
+    // crash after the first 5 operations
+    db.truncate_at(5).await;
+    let recovered = memdb::Database::new();
+    db.replay(recovered.clone()).await.unwrap();
+    for i in 0..5u8 {
+        assert_eq!(recovered.get(&[i]).await.unwrap(), vec![i]);
+    }
+    for i in 5..11u8 {
+        assert!(!recovered.has(&[i]).await.unwrap());
+    }
+
+    // the live database is left as is
+    assert!(!db.has(&[0]).await.unwrap());
+    assert_eq!(db.get(&[9]).await.unwrap(), vec![9]);
+}
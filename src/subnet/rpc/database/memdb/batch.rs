@@ -1,6 +1,6 @@
 //! Database Batch management implementation for memdb.
 use std::{
-    collections::HashMap,
+    collections::BTreeMap,
     io,
     sync::{
         atomic::{AtomicBool, Ordering},
@@ -25,13 +25,13 @@ pub struct Batch {
     writes: Arc<Mutex<Vec<KeyValue>>>,
     size: usize,
 
-    db_state: Arc<RwLock<HashMap<Vec<u8>, Vec<u8>>>>,
+    db_state: Arc<RwLock<BTreeMap<Vec<u8>, Vec<u8>>>>,
     db_closed: Arc<AtomicBool>,
 }
 
 impl Batch {
     pub fn new(
-        db_state: Arc<RwLock<HashMap<Vec<u8>, Vec<u8>>>>,
+        db_state: Arc<RwLock<BTreeMap<Vec<u8>, Vec<u8>>>>,
         db_closed: Arc<AtomicBool>,
     ) -> Self {
         Self {
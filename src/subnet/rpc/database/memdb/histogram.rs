@@ -0,0 +1,63 @@
+//! Key and value size histogram recorded by memdb on "put".
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+/// Shared handle to the size histogram of a memdb.
+#[derive(Debug, Clone, Default)]
+pub struct Histogram {
+    inner: Arc<Mutex<SizeHistogram>>,
+}
+
+/// Counts of observed sizes, bucketed by the smallest power of two that is
+/// greater than or equal to the size in bytes (empty entries go to bucket 0).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SizeHistogram {
+    pub keys: BTreeMap<usize, u64>,
+    pub values: BTreeMap<usize, u64>,
+}
+
+impl Histogram {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the key and value sizes of a single "put".
+    pub fn record(&self, key_len: usize, value_len: usize) {
+        let mut inner = self.inner.lock().unwrap();
+        *inner.keys.entry(bucket(key_len)).or_insert(0) += 1;
+        *inner.values.entry(bucket(value_len)).or_insert(0) += 1;
+    }
+
+    /// Returns a snapshot of the histogram.
+    pub fn size_histogram(&self) -> SizeHistogram {
+        self.inner.lock().unwrap().clone()
+    }
+}
+
+/// Returns the histogram bucket for the size.
+pub fn bucket(size: usize) -> usize {
+    if size == 0 {
+        return 0;
+    }
+    size.next_power_of_two()
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet_memdb_histogram -- subnet::rpc::database::memdb::histogram::test_size_histogram --exact --show-output
+#[tokio::test]
+async fn test_size_histogram() {
+    use crate::subnet::rpc::database::{memdb::Database, KeyValueReaderWriterDeleter};
+
+    let mut db = Database::new_with_options(Default::default());
+    let histogram = db.size_histogram();
+    db.put(b"a", b"").await.unwrap();
+    db.put(b"bb", &[0; 3]).await.unwrap();
+    db.put(b"ccc", &[0; 4]).await.unwrap();
+    db.put(b"dddd", &[0; 1000]).await.unwrap();
+    db.put(b"dddd", &[0; 1024]).await.unwrap();
+
+    let h = histogram.size_histogram();
+    assert_eq!(h.keys, BTreeMap::from([(1, 1), (2, 1), (4, 3)]));
+    assert_eq!(h.values, BTreeMap::from([(0, 1), (4, 2), (1024, 2)]));
+}
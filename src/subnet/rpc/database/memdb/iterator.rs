@@ -1,13 +1,16 @@
 //! Database Iterator management implementation for memdb.
 use std::{
+    collections::BTreeMap,
     io::{Error, ErrorKind, Result},
+    ops::Bound,
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicUsize, Ordering},
         Arc,
     },
 };
 
 use crate::subnet::rpc::database::{self, iterator::BoxedIterator};
+use tokio::sync::RwLock;
 
 /// Iterator iterates over a membd database's key/value pairs.
 ///
@@ -89,3 +92,201 @@ impl database::iterator::Iterator for Iterator {
         self.values = vec![];
     }
 }
+
+/// Returns the key/value pairs of the state at or after "start" whose keys
+/// have the prefix, in key order.
+pub fn range<'a>(
+    state: &'a BTreeMap<Vec<u8>, Vec<u8>>,
+    start: &[u8],
+    prefix: &'a [u8],
+) -> impl std::iter::Iterator<Item = (&'a Vec<u8>, &'a Vec<u8>)> + 'a {
+    // keys with the prefix are contiguous, from the prefix itself on
+    let lower = std::cmp::max(start, prefix);
+    state
+        .range::<[u8], _>((Bound::Included(lower), Bound::Unbounded))
+        .take_while(move |(k, _)| k.starts_with(prefix))
+}
+
+/// LiveIterator iterates over the current key/value pairs of a memdb database
+/// without copying them up front. Each step seeks past the current key in the
+/// live state, so entries put after creation may be yielded.
+pub struct LiveIterator {
+    state: Arc<RwLock<BTreeMap<Vec<u8>, Vec<u8>>>>,
+    start: Vec<u8>,
+    prefix: Vec<u8>,
+    key: Option<Vec<u8>>,
+    value: Option<Vec<u8>>,
+    exhausted: bool,
+    error: Option<Error>,
+    closed: Arc<AtomicBool>,
+}
+
+impl LiveIterator {
+    pub fn new(
+        state: Arc<RwLock<BTreeMap<Vec<u8>, Vec<u8>>>>,
+        start: &[u8],
+        prefix: &[u8],
+        closed: Arc<AtomicBool>,
+    ) -> BoxedIterator {
+        Box::new(Self {
+            state,
+            start: start.to_vec(),
+            prefix: prefix.to_vec(),
+            key: None,
+            value: None,
+            exhausted: false,
+            error: None,
+            closed,
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl database::iterator::Iterator for LiveIterator {
+    /// Implements the [`crate::subnet::rpc::database::Iterator`] trait.
+    async fn next(&mut self) -> Result<bool> {
+        // Short-circuit and set an error if the underlying database has been closed
+        if self.closed.load(Ordering::Relaxed) {
+            self.release().await;
+            self.error = Some(Error::new(ErrorKind::Other, "database closed"));
+            return Ok(false);
+        }
+        if self.exhausted {
+            return Ok(false);
+        }
+
+        // smallest matching key strictly after the current one
+        let db = self.state.read().await;
+        let next = match &self.key {
+            Some(current) => db
+                .range::<[u8], _>((Bound::Excluded(current.as_slice()), Bound::Unbounded))
+                .next()
+                .filter(|(k, _)| k.starts_with(&self.prefix)),
+            None => range(&db, &self.start, &self.prefix).next(),
+        };
+
+        match next {
+            Some((k, v)) => {
+                self.key = Some(k.to_owned());
+                self.value = Some(v.to_owned());
+                Ok(true)
+            }
+            None => {
+                drop(db);
+                self.release().await;
+                Ok(false)
+            }
+        }
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::Iterator`] trait.
+    async fn error(&mut self) -> Result<()> {
+        if let Some(err) = &self.error {
+            return Err(Error::new(err.kind(), err.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::Iterator`] trait.
+    async fn key(&self) -> Result<&[u8]> {
+        Ok(self.key.as_deref().unwrap_or(&[]))
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::Iterator`] trait.
+    async fn value(&self) -> Result<&[u8]> {
+        Ok(self.value.as_deref().unwrap_or(&[]))
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::Iterator`] trait.
+    async fn release(&mut self) {
+        self.key = None;
+        self.value = None;
+        self.exhausted = true;
+    }
+}
+
+pub const ERR_TOO_MANY_ITERATORS: &str = "too many iterators";
+
+/// Counts the concurrently-open iterators of a database against a cap.
+#[derive(Clone, Debug)]
+pub struct Slots {
+    max: usize,
+    open: Arc<AtomicUsize>,
+}
+
+impl Slots {
+    pub fn new(max: usize) -> Self {
+        Self {
+            max,
+            open: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Takes a slot, or returns None if "max" iterators are already open.
+    pub fn acquire(&self) -> Option<Slot> {
+        self.open
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |open| {
+                (open < self.max).then_some(open + 1)
+            })
+            .ok()
+            .map(|_| Slot {
+                open: Arc::clone(&self.open),
+            })
+    }
+}
+
+/// Slot held by an open iterator, freed on drop.
+#[derive(Debug)]
+pub struct Slot {
+    open: Arc<AtomicUsize>,
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.open.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Iterator that holds a [`Slot`] until it is released or dropped.
+pub struct SlottedIterator {
+    inner: BoxedIterator,
+    slot: Option<Slot>,
+}
+
+impl SlottedIterator {
+    pub fn new(inner: BoxedIterator, slot: Slot) -> BoxedIterator {
+        Box::new(Self {
+            inner,
+            slot: Some(slot),
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl database::iterator::Iterator for SlottedIterator {
+    /// Implements the [`crate::subnet::rpc::database::Iterator`] trait.
+    async fn next(&mut self) -> Result<bool> {
+        self.inner.next().await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::Iterator`] trait.
+    async fn error(&mut self) -> Result<()> {
+        self.inner.error().await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::Iterator`] trait.
+    async fn key(&self) -> Result<&[u8]> {
+        self.inner.key().await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::Iterator`] trait.
+    async fn value(&self) -> Result<&[u8]> {
+        self.inner.value().await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::Iterator`] trait.
+    async fn release(&mut self) {
+        self.inner.release().await;
+        self.slot = None;
+    }
+}
@@ -11,14 +11,18 @@
 pub mod batch;
 pub mod iterator;
 
+#[cfg(feature = "subnet_memdb_histogram")]
+#[cfg_attr(docsrs, doc(cfg(feature = "subnet_memdb_histogram")))]
+pub mod histogram;
+
 use std::{
-    collections::HashMap,
+    collections::BTreeMap,
     io,
     sync::atomic::{AtomicBool, Ordering},
     sync::Arc,
 };
 
-use crate::subnet::rpc::errors::Error;
+use crate::subnet::rpc::{errors::Error, health::HealthResult};
 
 use super::{batch::BoxedBatch, iterator::BoxedIterator, BoxedDatabase};
 use tokio::sync::RwLock;
@@ -27,18 +31,69 @@ use tokio::sync::RwLock;
 /// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/database/memdb#Database>
 #[derive(Clone)]
 pub struct Database {
-    /// Hashmap guarded by mutex which stores the memdb state.
-    state: Arc<RwLock<HashMap<Vec<u8>, Vec<u8>>>>,
+    /// Ordered map guarded by mutex which stores the memdb state.
+    state: Arc<RwLock<BTreeMap<Vec<u8>, Vec<u8>>>>,
     /// True if the database is closed.
     closed: Arc<AtomicBool>,
+    /// If true, iterators copy the matching key range at creation.
+    snapshot_iterators: bool,
+    /// Optional cap on the number of concurrently-open iterators.
+    iterator_slots: Option<iterator::Slots>,
+    /// Records key and value sizes seen on "put".
+    #[cfg(feature = "subnet_memdb_histogram")]
+    histogram: histogram::Histogram,
+}
+
+/// Configures the database created by [`Database::new_with_options`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// If true (default), iterators capture a snapshot of the key range at
+    /// creation; otherwise they walk the live state.
+    ///
+    /// A snapshot copies every matching key and value when the iterator is
+    /// created, so concurrent puts and deletes never affect an in-flight
+    /// scan, at the cost of memory proportional to the size of the range.
+    /// Live iterators hold only the current entry, and seek past it in the
+    /// ordered state on each step, but may yield keys put after creation.
+    pub snapshot_iterators: bool,
+    /// If set, at most this many iterators may be open at once, mirroring
+    /// the resource limits of real backends. Creating one more fails with
+    /// "too many iterators" until an iterator is released or dropped.
+    pub max_iterators: Option<usize>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            snapshot_iterators: true,
+            max_iterators: None,
+        }
+    }
 }
 
 impl Database {
     pub fn new() -> BoxedDatabase {
-        Box::new(Self {
-            state: Arc::new(RwLock::new(HashMap::new())),
+        Box::new(Self::new_with_options(Options::default()))
+    }
+
+    /// Creates a database configured by the options, returning the
+    /// database itself rather than a [`BoxedDatabase`].
+    pub fn new_with_options(opts: Options) -> Self {
+        Self {
+            state: Arc::new(RwLock::new(BTreeMap::new())),
             closed: Arc::new(AtomicBool::new(false)),
-        })
+            snapshot_iterators: opts.snapshot_iterators,
+            iterator_slots: opts.max_iterators.map(iterator::Slots::new),
+            #[cfg(feature = "subnet_memdb_histogram")]
+            histogram: histogram::Histogram::new(),
+        }
+    }
+
+    /// Returns the handle to query the key and value size histogram.
+    #[cfg(feature = "subnet_memdb_histogram")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subnet_memdb_histogram")))]
+    pub fn size_histogram(&self) -> histogram::Histogram {
+        self.histogram.clone()
     }
 }
 
@@ -72,6 +127,9 @@ impl super::KeyValueReaderWriterDeleter for Database {
             return Err(Error::DatabaseClosed.to_err());
         }
 
+        #[cfg(feature = "subnet_memdb_histogram")]
+        self.histogram.record(key.len(), value.len());
+
         let mut db = self.state.write().await;
         db.insert(key.to_vec(), value.to_vec());
         Ok(())
@@ -102,6 +160,17 @@ impl super::Closer for Database {
     }
 }
 
+#[tonic::async_trait]
+impl super::Compacter for Database {
+    /// Nothing to compact in memory; errors only if the database is closed.
+    async fn compact(&self, _start: &[u8], _limit: &[u8]) -> io::Result<()> {
+        if self.closed.load(Ordering::Relaxed) {
+            return Err(Error::DatabaseClosed.to_err());
+        }
+        Ok(())
+    }
+}
+
 #[tonic::async_trait]
 impl crate::subnet::rpc::health::Checkable for Database {
     /// Checks if the database has been closed.
@@ -109,7 +178,7 @@ impl crate::subnet::rpc::health::Checkable for Database {
         if self.closed.load(Ordering::Relaxed) {
             return Err(Error::DatabaseClosed.to_err());
         }
-        Ok(vec![])
+        HealthResult::healthy(None).to_vec()
     }
 }
 
@@ -140,28 +209,40 @@ impl super::iterator::Iteratee for Database {
             return Err(Error::DatabaseClosed.to_err());
         }
 
-        let db = self.state.read().await;
-        let mut keys: Vec<Vec<u8>> = Vec::with_capacity(db.len());
-        for (k, _v) in db.iter() {
-            if k.starts_with(prefix) && k >= &start.to_vec() {
-                keys.push(k.to_owned());
-            }
-        }
-        // keys need to be in sorted order
-        keys.sort();
+        let slot = match &self.iterator_slots {
+            Some(slots) => match slots.acquire() {
+                Some(slot) => Some(slot),
+                None => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::Other,
+                        iterator::ERR_TOO_MANY_ITERATORS,
+                    ))
+                }
+            },
+            None => None,
+        };
 
-        let mut values: Vec<Vec<u8>> = Vec::with_capacity(keys.len());
-        for key in keys.iter() {
-            if let Some(v) = db.get(key) {
-                values.push(v.to_owned());
-            }
-        }
+        let iter = if !self.snapshot_iterators {
+            iterator::LiveIterator::new(
+                Arc::clone(&self.state),
+                start,
+                prefix,
+                Arc::clone(&self.closed),
+            )
+        } else {
+            // copies the matching range so later writes do not affect the scan
+            let db = self.state.read().await;
+            let (keys, values) = iterator::range(&db, start, prefix)
+                .map(|(k, v)| (k.to_owned(), v.to_owned()))
+                .unzip();
 
-        Ok(iterator::Iterator::new(
-            keys,
-            values,
-            Arc::clone(&self.closed),
-        ))
+            iterator::Iterator::new(keys, values, Arc::clone(&self.closed))
+        };
+
+        match slot {
+            Some(slot) => Ok(iterator::SlottedIterator::new(iter, slot)),
+            None => Ok(iter),
+        }
     }
 }
 
@@ -180,6 +261,8 @@ impl crate::subnet::rpc::database::Database for Database {}
 
 #[tokio::test]
 async fn test_memdb() {
+    use super::{Closer, Compacter};
+
     let mut db = Database::new();
     let _ = db.put("foo".as_bytes(), "bar".as_bytes()).await;
     let resp = db.get("notfound".as_bytes()).await;
@@ -212,10 +295,108 @@ async fn test_memdb() {
     assert!(!resp.is_err());
     assert_eq!(resp.unwrap(), false);
 
+    let db = Database::new_with_options(Options::default());
+    db.compact(b"", b"").await.unwrap();
+    db.close().await.unwrap();
+    let resp = db.compact(b"", b"").await;
+    assert_eq!(resp.unwrap_err().to_string(), "database closed");
+
     let db = Database::new();
     let resp = db.health_check().await;
     assert!(!resp.is_err());
+    let health: HealthResult = serde_json::from_slice(&resp.unwrap()).unwrap();
+    assert!(health.healthy);
     let _ = db.close().await;
     let resp = db.health_check().await;
     assert_eq!(resp.err().unwrap().to_string(), "database closed");
 }
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::memdb::test_snapshot_iterator --exact --show-output
+#[tokio::test]
+async fn test_snapshot_iterator() {
+    use super::{iterator::Iteratee, KeyValueReaderWriterDeleter};
+
+    for snapshot in [true, false] {
+        let mut db = Database::new_with_options(Options {
+            snapshot_iterators: snapshot,
+            ..Options::default()
+        });
+        db.put(b"a1", b"1").await.unwrap();
+        db.put(b"a3", b"3").await.unwrap();
+        db.put(b"b1", b"x").await.unwrap();
+
+        let mut iter = db.new_iterator_with_prefix(b"a").await.unwrap();
+        assert!(iter.next().await.unwrap());
+        assert_eq!(iter.key().await.unwrap(), b"a1");
+
+        // matching keys inserted while the scan is in-flight
+        db.put(b"a2", b"2").await.unwrap();
+        db.put(b"a4", b"4").await.unwrap();
+
+        let mut keys = vec![];
+        while iter.next().await.unwrap() {
+            keys.push(iter.key().await.unwrap().to_vec());
+        }
+        iter.error().await.unwrap();
+        if snapshot {
+            assert_eq!(keys, vec![b"a3".to_vec()]);
+        } else {
+            assert_eq!(keys, vec![b"a2".to_vec(), b"a3".to_vec(), b"a4".to_vec()]);
+        }
+
+        // the start bound within and before the prefix range
+        for (start, expected) in [(&b"a3"[..], 2), (&b""[..], 4), (&b"b"[..], 0)] {
+            let mut iter = db
+                .new_iterator_with_start_and_prefix(start, b"a")
+                .await
+                .unwrap();
+            let mut n = 0;
+            while iter.next().await.unwrap() {
+                assert!(iter.key().await.unwrap().starts_with(b"a"));
+                assert!(iter.key().await.unwrap() >= start);
+                n += 1;
+            }
+            assert_eq!(n, expected);
+        }
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::memdb::test_max_iterators --exact --show-output
+#[tokio::test]
+async fn test_max_iterators() {
+    use super::{iterator::Iteratee, KeyValueReaderWriterDeleter};
+
+    let mut db = Database::new_with_options(Options {
+        max_iterators: Some(2),
+        ..Options::default()
+    });
+    db.put(b"a", b"1").await.unwrap();
+
+    let mut first = db.new_iterator().await.unwrap();
+    let second = db.new_iterator_with_prefix(b"a").await.unwrap();
+    let err = db.new_iterator_with_start(b"a").await.err().unwrap();
+    assert_eq!(err.to_string(), iterator::ERR_TOO_MANY_ITERATORS);
+
+    // slotted iterators behave as usual
+    assert!(first.next().await.unwrap());
+    assert_eq!(first.key().await.unwrap(), b"a");
+
+    // dropping frees a slot
+    drop(second);
+    let mut third = db.new_iterator().await.unwrap();
+    assert!(db.new_iterator().await.is_err());
+
+    // so does releasing, even more than once
+    third.release().await;
+    third.release().await;
+    let _fourth = db.new_iterator().await.unwrap();
+    assert!(db.new_iterator().await.is_err());
+
+    // the cap applies to live iterators too
+    let db = Database::new_with_options(Options {
+        snapshot_iterators: false,
+        max_iterators: Some(1),
+    });
+    let _live = db.new_iterator().await.unwrap();
+    assert!(db.new_iterator().await.is_err());
+}
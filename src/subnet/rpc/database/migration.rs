@@ -0,0 +1,78 @@
+//! Schema migrations of the keys in a database.
+use std::io::Result;
+
+use super::{versiondb, BoxedDatabase, Commitable, KeyValueReaderWriterDeleter};
+
+/// Moves every key under "old_prefix" to the same key under "new_prefix",
+/// returning the number of keys moved. The moves are staged in a
+/// [`versiondb::Database`] and committed in a single batch, so that the
+/// database never exposes a partially renamed prefix.
+///
+/// Keys already under "new_prefix" are overwritten by the moved ones.
+pub async fn rename_prefix(db: BoxedDatabase, old_prefix: &[u8], new_prefix: &[u8]) -> Result<u64> {
+    if old_prefix == new_prefix {
+        return Ok(0);
+    }
+
+    let mut entries = Vec::new();
+    let mut iter = db.new_iterator_with_prefix(old_prefix).await?;
+    while iter.next().await? {
+        entries.push((iter.key().await?.to_vec(), iter.value().await?.to_vec()));
+    }
+    iter.error().await?;
+    iter.release().await;
+
+    let batch = db.new_batch().await?;
+    let mut vdb = versiondb::Database::new(db, batch);
+
+    // deletes first, in case a moved key lands on another old key
+    for (key, _) in entries.iter() {
+        vdb.delete(key).await?;
+    }
+    for (key, value) in entries.iter() {
+        let mut new_key = new_prefix.to_vec();
+        new_key.extend_from_slice(&key[old_prefix.len()..]);
+        vdb.put(&new_key, value).await?;
+    }
+    vdb.commit().await?;
+
+    Ok(entries.len() as u64)
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::migration::test_rename_prefix --exact --show-output
+#[tokio::test]
+async fn test_rename_prefix() {
+    use super::memdb;
+
+    let mut db = memdb::Database::new();
+    for (key, value) in [
+        (&b"v1/a"[..], &b"1"[..]),
+        (b"v1/b", b"2"),
+        (b"v1/c/d", b"3"),
+        (b"v2/b", b"stale"),
+        (b"other", b"kept"),
+    ] {
+        db.put(key, value).await.unwrap();
+    }
+
+    assert_eq!(rename_prefix(db.clone(), b"v1/", b"v2/").await.unwrap(), 3);
+    assert_eq!(db.get(b"v2/a").await.unwrap(), b"1");
+    assert_eq!(db.get(b"v2/b").await.unwrap(), b"2");
+    assert_eq!(db.get(b"v2/c/d").await.unwrap(), b"3");
+    for key in [&b"v1/a"[..], b"v1/b", b"v1/c/d"] {
+        assert!(!db.has(key).await.unwrap());
+    }
+    assert_eq!(db.get(b"other").await.unwrap(), b"kept");
+
+    // nothing left to move
+    assert_eq!(rename_prefix(db.clone(), b"v1/", b"v2/").await.unwrap(), 0);
+
+    // the new prefix may nest under the old one
+    assert_eq!(
+        rename_prefix(db.clone(), b"v2/", b"v2/v3/").await.unwrap(),
+        3
+    );
+    assert_eq!(db.get(b"v2/v3/a").await.unwrap(), b"1");
+    assert_eq!(db.get(b"v2/v3/c/d").await.unwrap(), b"3");
+    assert!(!db.has(b"v2/a").await.unwrap());
+}
@@ -0,0 +1,332 @@
+//! Database wrapper which mirrors every write to a secondary database.
+//!
+//! Useful during migrations where a new store is populated by dual writes
+//! while the old store keeps serving reads, optionally checking that both
+//! stores agree on each read.
+#[cfg(not(feature = "subnet_metrics"))]
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::{io, sync::Arc};
+
+use super::{batch::BoxedBatch, iterator::BoxedIterator, BoxedDatabase};
+use crate::subnet::rpc::{errors, utils};
+
+/// Whether "get" also reads the secondary database to compare the values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VerifyReads {
+    /// Only reads the primary database (default).
+    #[default]
+    Disabled,
+    /// Counts the divergent reads and returns the primary value.
+    Count,
+    /// Counts the divergent reads and fails them.
+    Fail,
+}
+
+/// Database which writes to both databases and reads from the primary.
+#[derive(Clone)]
+pub struct Database {
+    primary: BoxedDatabase,
+    secondary: BoxedDatabase,
+    verify_reads: VerifyReads,
+    /// Number of reads where the secondary disagreed with the primary.
+    divergence_total: Counter,
+}
+
+/// Name of the counter of divergent reads.
+pub const DIVERGENCE_TOTAL: &str = "mirror_divergence_total";
+
+#[cfg(feature = "subnet_metrics")]
+type Counter = prometheus::IntCounter;
+
+#[cfg(feature = "subnet_metrics")]
+fn new_counter() -> Counter {
+    prometheus::IntCounter::new(
+        DIVERGENCE_TOTAL,
+        "number of reads where the secondary database disagreed with the primary",
+    )
+    .expect("valid counter options")
+}
+
+/// Counter of the divergent reads, when not exported to Prometheus.
+#[cfg(not(feature = "subnet_metrics"))]
+#[derive(Clone, Default)]
+struct Counter(Arc<AtomicU64>);
+
+#[cfg(not(feature = "subnet_metrics"))]
+impl Counter {
+    fn inc(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(not(feature = "subnet_metrics"))]
+fn new_counter() -> Counter {
+    Counter::default()
+}
+
+impl Database {
+    pub fn new(primary: BoxedDatabase, secondary: BoxedDatabase) -> Self {
+        Self::new_with_verify_reads(primary, secondary, VerifyReads::Disabled)
+    }
+
+    /// Creates a database which, unless "verify_reads" is disabled, reads
+    /// the secondary database on each "get" and flags any divergence.
+    pub fn new_with_verify_reads(
+        primary: BoxedDatabase,
+        secondary: BoxedDatabase,
+        verify_reads: VerifyReads,
+    ) -> Self {
+        Self {
+            primary,
+            secondary,
+            verify_reads,
+            divergence_total: new_counter(),
+        }
+    }
+
+    /// Returns the number of divergent reads so far.
+    pub fn divergence_total(&self) -> u64 {
+        self.divergence_total.get()
+    }
+
+    /// Registers the [`DIVERGENCE_TOTAL`] counter of divergent reads.
+    #[cfg(feature = "subnet_metrics")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subnet_metrics")))]
+    pub fn register(&self, registry: &prometheus::Registry) -> io::Result<()> {
+        registry
+            .register(Box::new(self.divergence_total.clone()))
+            .map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("failed to register {}: {}", DIVERGENCE_TOTAL, e),
+                )
+            })
+    }
+}
+
+/// Maps "not found" to "None", to compare the reads of both databases.
+fn found(resp: io::Result<Vec<u8>>) -> io::Result<Option<Vec<u8>>> {
+    match resp {
+        Ok(value) => Ok(Some(value)),
+        Err(e) if errors::is_not_found(&e) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::KeyValueReaderWriterDeleter for Database {
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn has(&self, key: &[u8]) -> io::Result<bool> {
+        self.primary.has(key).await
+    }
+
+    /// Returns the value from the primary database, comparing it with the
+    /// secondary database if "verify_reads" is enabled. A failing secondary
+    /// read is logged but not counted as a divergence.
+    async fn get(&self, key: &[u8]) -> io::Result<Vec<u8>> {
+        let resp = self.primary.get(key).await;
+        if self.verify_reads == VerifyReads::Disabled {
+            return resp;
+        }
+        let primary = found(resp)?;
+
+        match found(self.secondary.get(key).await) {
+            Ok(secondary) if secondary != primary => {
+                self.divergence_total.inc();
+                log::warn!("mirror divergence on key {:?}", key);
+                if self.verify_reads == VerifyReads::Fail {
+                    return Err(io::Error::new(
+                        io::ErrorKind::Other,
+                        format!("mirror divergence on key {:?}", key),
+                    ));
+                }
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("failed to verify read against secondary ({})", e),
+        }
+
+        primary.ok_or_else(|| errors::Error::NotFound.to_err())
+    }
+
+    /// Writes to the primary database, then to the secondary database.
+    async fn put(&mut self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        self.primary.put(key, value).await?;
+        self.secondary.put(key, value).await
+    }
+
+    /// Deletes from the primary database, then from the secondary database.
+    async fn delete(&mut self, key: &[u8]) -> io::Result<()> {
+        self.primary.delete(key).await?;
+        self.secondary.delete(key).await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::Closer for Database {
+    /// Closes both databases, returning the first error observed.
+    async fn close(&self) -> io::Result<()> {
+        let mut errs = utils::Errors::new();
+        for db in [&self.primary, &self.secondary] {
+            if let Err(e) = db.close().await {
+                errs.add(&e);
+            }
+        }
+        errs.err()
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::health::Checkable for Database {
+    /// Checks the health of both databases, returning the first error observed.
+    async fn health_check(&self) -> io::Result<Vec<u8>> {
+        self.secondary.health_check().await?;
+        self.primary.health_check().await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::iterator::Iteratee for Database {
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator(&self) -> io::Result<BoxedIterator> {
+        self.new_iterator_with_start_and_prefix(&[], &[]).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_start(&self, start: &[u8]) -> io::Result<BoxedIterator> {
+        self.new_iterator_with_start_and_prefix(start, &[]).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_prefix(&self, prefix: &[u8]) -> io::Result<BoxedIterator> {
+        self.new_iterator_with_start_and_prefix(&[], prefix).await
+    }
+
+    /// Iterates over the primary database only.
+    async fn new_iterator_with_start_and_prefix(
+        &self,
+        start: &[u8],
+        prefix: &[u8],
+    ) -> io::Result<BoxedIterator> {
+        self.primary
+            .new_iterator_with_start_and_prefix(start, prefix)
+            .await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::batch::Batcher for Database {
+    /// Returns a batch which is written to both databases.
+    async fn new_batch(&self) -> io::Result<BoxedBatch> {
+        Ok(Box::new(Batch {
+            primary: self.primary.new_batch().await?,
+            secondary: self.secondary.new_batch().await?,
+        }))
+    }
+}
+
+impl crate::subnet::rpc::database::Database for Database {}
+
+/// Batch which queues each operation on the batches of both databases.
+#[derive(Clone)]
+struct Batch {
+    primary: BoxedBatch,
+    secondary: BoxedBatch,
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::batch::Batch for Batch {
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn put(&mut self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        self.primary.put(key, value).await?;
+        self.secondary.put(key, value).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn delete(&mut self, key: &[u8]) -> io::Result<()> {
+        self.primary.delete(key).await?;
+        self.secondary.delete(key).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn size(&self) -> io::Result<usize> {
+        self.primary.size().await
+    }
+
+    /// Writes the primary batch, then the secondary batch.
+    async fn write(&self) -> io::Result<()> {
+        self.primary.write().await?;
+        self.secondary.write().await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn reset(&mut self) {
+        self.primary.reset().await;
+        self.secondary.reset().await
+    }
+
+    /// Replays the primary batch only.
+    async fn replay(&self, k: Arc<tokio::sync::Mutex<BoxedDatabase>>) -> io::Result<()> {
+        self.primary.replay(k).await
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::mirrordb::test_verify_reads --exact --show-output
+#[tokio::test]
+async fn test_verify_reads() {
+    use crate::subnet::rpc::database::{batch::Batcher, memdb, KeyValueReaderWriterDeleter};
+
+    let primary = memdb::Database::new();
+    let mut secondary = memdb::Database::new();
+    let mut db =
+        Database::new_with_verify_reads(primary.clone(), secondary.clone(), VerifyReads::Count);
+
+    // writes, including batches, reach both databases
+    db.put(b"foo", b"bar").await.unwrap();
+    let mut batch = db.new_batch().await.unwrap();
+    batch.put(b"baz", b"qux").await.unwrap();
+    batch.write().await.unwrap();
+    for key in [b"foo", b"baz"] {
+        assert_eq!(
+            primary.get(key).await.unwrap(),
+            secondary.get(key).await.unwrap()
+        );
+    }
+    assert_eq!(db.get(b"foo").await.unwrap(), b"bar");
+    assert!(errors::is_not_found(&db.get(b"missing").await.unwrap_err()));
+    assert_eq!(db.divergence_total(), 0);
+
+    // diverge behind the mirror's back
+    secondary.put(b"foo", b"stale").await.unwrap();
+    secondary.delete(b"baz").await.unwrap();
+    assert_eq!(db.get(b"foo").await.unwrap(), b"bar");
+    assert_eq!(db.get(b"baz").await.unwrap(), b"qux");
+    assert_eq!(db.divergence_total(), 2);
+
+    #[cfg(feature = "subnet_metrics")]
+    {
+        let registry = prometheus::Registry::new();
+        db.register(&registry).unwrap();
+        let families = registry.gather();
+        assert_eq!(families.len(), 1);
+        assert_eq!(families[0].get_name(), DIVERGENCE_TOTAL);
+        assert_eq!(families[0].get_metric()[0].get_counter().get_value(), 2.0);
+        assert!(db.register(&registry).is_err());
+    }
+
+    // failing mode rejects the divergent read, missing keys still agree
+    let strict = Database::new_with_verify_reads(primary, secondary, VerifyReads::Fail);
+    let err = strict.get(b"foo").await.unwrap_err();
+    assert!(err.to_string().contains("mirror divergence"));
+    assert_eq!(strict.divergence_total(), 1);
+    assert!(strict.get(b"missing").await.is_err());
+    assert_eq!(strict.divergence_total(), 1);
+
+    // disabled by default
+    let db = Database::new(memdb::Database::new(), memdb::Database::new());
+    assert!(db.get(b"foo").await.is_err());
+    assert_eq!(db.divergence_total(), 0);
+}
@@ -1,10 +1,27 @@
+pub mod autocompactdb;
 pub mod batch;
+pub mod chunkdb;
 pub mod corruptabledb;
+pub mod delaydb;
+pub mod diskspacedb;
+pub mod dump;
+pub mod fallbackdb;
+pub mod fencedb;
+pub mod guardeddb;
+#[cfg(any(test, feature = "subnet_test_support"))]
+#[doc(hidden)]
+pub mod hookdb;
 pub mod iterator;
+pub mod journaldb;
 pub mod manager;
 pub mod memdb;
+pub mod migration;
+pub mod mirrordb;
 pub mod nodb;
 pub mod rpcdb;
+pub mod scoped;
+pub mod singleflightdb;
+pub mod ttldb;
 pub mod versiondb;
 
 use std::io::Result;
@@ -20,6 +37,14 @@ pub trait Closer {
     async fn close(&self) -> Result<()>;
 }
 
+/// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/database#Compacter>
+#[tonic::async_trait]
+pub trait Compacter {
+    /// Compacts the key range ["start", "limit"), where an empty "start"
+    /// starts from the first key and an empty "limit" runs past the last.
+    async fn compact(&self, start: &[u8], limit: &[u8]) -> Result<()>;
+}
+
 #[tonic::async_trait]
 pub trait Database:
     batch::Batcher + CloneBox + KeyValueReaderWriterDeleter + Closer + Checkable + iterator::Iteratee
@@ -41,7 +66,7 @@ pub trait KeyValueReaderWriterDeleter {
 // Trait that specifies that something may be
 // committed.
 #[tonic::async_trait]
-trait Commitable {
+pub trait Commitable {
     /// Writes all the operations of this database to the underlying database.
     async fn commit(&mut self) -> Result<()>;
     /// Abort all changes to the underlying database.
@@ -17,7 +17,7 @@ use std::{
 };
 
 use bytes::Bytes;
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::{Mutex, RwLock, Semaphore};
 use tonic::transport::Channel;
 
 pub const BASE_ELEMENT_SIZE: usize = 8;
@@ -36,14 +36,42 @@ pub struct Batch {
     db: DatabaseClient<Channel>,
     writes: Arc<RwLock<Vec<KeyValue>>>,
     size: usize,
+    /// Values cached by the client, evicted for the written keys once the
+    /// batch is written.
+    cache: super::Cache,
+    /// Bounds the requests in flight over the channel, if set.
+    in_flight: Option<Arc<Semaphore>>,
+    /// Fails the writes fast while the server keeps failing, if set.
+    breaker: Option<super::breaker::CircuitBreaker>,
 }
 
 impl Batch {
     pub fn new(db: DatabaseClient<Channel>) -> Self {
+        Self::new_with_client_state(
+            db,
+            Arc::new(std::sync::RwLock::new(super::cache::ValueCache::new(
+                super::cache::DEFAULT_MAX_ENTRIES,
+            ))),
+            None,
+            None,
+        )
+    }
+
+    /// Shares the cache, the in-flight limit and the circuit breaker of the
+    /// client.
+    pub(crate) fn new_with_client_state(
+        db: DatabaseClient<Channel>,
+        cache: super::Cache,
+        in_flight: Option<Arc<Semaphore>>,
+        breaker: Option<super::breaker::CircuitBreaker>,
+    ) -> Self {
         Self {
             db,
             writes: Arc::new(RwLock::new(Vec::new())),
             size: 0,
+            cache,
+            in_flight,
+            breaker,
         }
     }
 }
@@ -110,18 +138,24 @@ impl database::batch::Batch for Batch {
             }
         }
 
-        let resp = db
-            .write_batch(req)
+        let _permit = super::acquire(&self.in_flight).await;
+        let resp = super::breaker::guard(&self.breaker, "write_batch", db.write_batch(req))
             .await
             .map_err(|e| {
                 Error::new(
                     ErrorKind::Other,
                     format!("batch write request failed: {:?}", e),
                 )
-            })?
-            .into_inner();
+            });
 
-        errors::from_i32(resp.err)
+        // evicted once the write completes, as for the client's own writes
+        {
+            let mut cache = self.cache.write().unwrap();
+            for key in key_set.iter() {
+                cache.remove(key);
+            }
+        }
+        errors::from_i32(resp?.into_inner().err)
     }
 
     /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
@@ -0,0 +1,189 @@
+//! Circuit breaker for the rpcdb client.
+use std::{
+    collections::HashMap,
+    future::Future,
+    io,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tonic::Status;
+
+/// Message of the errors returned while the circuit is open.
+pub const CIRCUIT_OPEN: &str = "circuit open";
+
+/// State of the circuit of an operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    /// Calls go through.
+    Closed,
+    /// Calls fail fast until the cooldown elapses.
+    Open,
+    /// One call is let through to probe whether the server recovered.
+    HalfOpen,
+}
+
+#[derive(Debug)]
+enum Circuit {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    HalfOpen { since: Instant },
+}
+
+/// Fails the calls of an operation (e.g., "get") fast once its requests
+/// failed "failure_threshold" times in a row, rather than waiting on a
+/// server that keeps failing. After "cooldown", a single call probes the
+/// server: the circuit closes if it succeeds, and opens again otherwise.
+///
+/// Each operation has its own circuit, shared by the clones of the client
+/// and its batches. Only failed requests count (e.g., the server is
+/// unreachable), not database errors such as "not found".
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    circuits: Arc<Mutex<HashMap<&'static str, Circuit>>>,
+}
+
+impl CircuitBreaker {
+    /// Creates a breaker which opens after "failure_threshold" consecutive
+    /// failures (at least one), for "cooldown".
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            circuits: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Returns the state of the circuit of the operation.
+    pub fn state(&self, op: &str) -> State {
+        match self.circuits.lock().unwrap().get(op) {
+            None | Some(Circuit::Closed { .. }) => State::Closed,
+            Some(Circuit::Open { until }) if Instant::now() < *until => State::Open,
+            Some(Circuit::Open { .. }) | Some(Circuit::HalfOpen { .. }) => State::HalfOpen,
+        }
+    }
+
+    /// Returns why the call must fail fast, if it must. Once the cooldown has
+    /// elapsed, lets one probe through; the probe is given up (e.g., if its
+    /// call was dropped) after another cooldown.
+    fn allow(&self, op: &'static str) -> Result<(), String> {
+        let now = Instant::now();
+        let mut circuits = self.circuits.lock().unwrap();
+        let circuit = circuits
+            .entry(op)
+            .or_insert(Circuit::Closed { failures: 0 });
+        match circuit {
+            Circuit::Closed { .. } => Ok(()),
+            Circuit::Open { until } if now < *until => Err(format!(
+                "{} for {} (retry in {:?})",
+                CIRCUIT_OPEN,
+                op,
+                *until - now
+            )),
+            Circuit::HalfOpen { since } if now < *since + self.cooldown => {
+                Err(format!("{} for {} (probing)", CIRCUIT_OPEN, op))
+            }
+            _ => {
+                log::info!("circuit half-open for {}, probing", op);
+                *circuit = Circuit::HalfOpen { since: now };
+                Ok(())
+            }
+        }
+    }
+
+    fn record(&self, op: &'static str, success: bool) {
+        let mut circuits = self.circuits.lock().unwrap();
+        let circuit = circuits
+            .entry(op)
+            .or_insert(Circuit::Closed { failures: 0 });
+        *circuit = match (&circuit, success) {
+            (Circuit::Closed { .. }, true) => Circuit::Closed { failures: 0 },
+            (_, true) => {
+                log::info!("circuit closed for {}", op);
+                Circuit::Closed { failures: 0 }
+            }
+            (Circuit::Closed { failures }, false) if failures + 1 < self.failure_threshold => {
+                Circuit::Closed {
+                    failures: failures + 1,
+                }
+            }
+            (_, false) => {
+                log::warn!("circuit open for {} for {:?}", op, self.cooldown);
+                Circuit::Open {
+                    until: Instant::now() + self.cooldown,
+                }
+            }
+        };
+    }
+}
+
+/// Runs the request of the operation through the breaker, if any.
+pub(crate) async fn guard<T, F>(
+    breaker: &Option<CircuitBreaker>,
+    op: &'static str,
+    request: F,
+) -> Result<T, Status>
+where
+    F: Future<Output = Result<T, Status>>,
+{
+    let breaker = match breaker {
+        Some(breaker) => breaker,
+        None => return request.await,
+    };
+    breaker.allow(op).map_err(Status::unavailable)?;
+    let resp = request.await;
+    breaker.record(op, resp.is_ok());
+    resp
+}
+
+/// Returns true if the call failed fast on an open circuit.
+pub fn is_circuit_open(error: &io::Error) -> bool {
+    error.to_string().starts_with(CIRCUIT_OPEN)
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::rpcdb::client::breaker::test_circuit_breaker --exact --show-output
+#[tokio::test]
+async fn test_circuit_breaker() {
+    let breaker = Some(CircuitBreaker::new(3, Duration::from_millis(50)));
+    let failing = || async { Err::<(), _>(Status::unavailable("connection refused")) };
+    let succeeding = || async { Ok::<(), Status>(()) };
+
+    // a success resets the count of consecutive failures
+    for _ in 0..2 {
+        assert!(guard(&breaker, "get", failing()).await.is_err());
+    }
+    guard(&breaker, "get", succeeding()).await.unwrap();
+    for _ in 0..2 {
+        assert!(guard(&breaker, "get", failing()).await.is_err());
+    }
+    let cb = breaker.as_ref().unwrap();
+    assert_eq!(cb.state("get"), State::Closed);
+
+    assert!(guard(&breaker, "get", failing()).await.is_err());
+    assert_eq!(cb.state("get"), State::Open);
+    let err = guard(&breaker, "get", succeeding()).await.unwrap_err();
+    assert!(is_circuit_open(&crate::subnet::rpc::errors::from_status(
+        err
+    )));
+
+    // other operations have their own circuit
+    guard(&breaker, "put", succeeding()).await.unwrap();
+    assert_eq!(cb.state("put"), State::Closed);
+
+    // a failed probe opens the circuit again
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert_eq!(cb.state("get"), State::HalfOpen);
+    let status = guard(&breaker, "get", failing()).await.unwrap_err();
+    assert_eq!(status.message(), "connection refused");
+    assert_eq!(cb.state("get"), State::Open);
+
+    // a successful probe closes it
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    guard(&breaker, "get", succeeding()).await.unwrap();
+    assert_eq!(cb.state("get"), State::Closed);
+    guard(&breaker, "get", succeeding()).await.unwrap();
+
+    assert!(guard(&None, "get", failing()).await.is_err());
+}
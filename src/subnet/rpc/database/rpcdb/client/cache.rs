@@ -0,0 +1,109 @@
+//! Bounded cache of the values read by the client.
+use std::collections::{BTreeMap, HashMap};
+
+/// Default number of values the client caches.
+pub const DEFAULT_MAX_ENTRIES: usize = 1024;
+
+/// Values keyed by the database key, with at most "max_entries" values.
+/// Once full, the oldest inserted value is evicted first.
+pub struct ValueCache {
+    max_entries: usize,
+    /// Key to its insertion sequence number and value.
+    values: HashMap<Vec<u8>, (u64, Vec<u8>)>,
+    /// Insertion sequence number to key, oldest first.
+    order: BTreeMap<u64, Vec<u8>>,
+    next_seq: u64,
+}
+
+impl ValueCache {
+    /// Creates a cache of at most "max_entries" values. A limit of zero
+    /// disables caching.
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            max_entries,
+            values: HashMap::new(),
+            order: BTreeMap::new(),
+            next_seq: 0,
+        }
+    }
+
+    pub fn max_entries(&self) -> usize {
+        self.max_entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn contains_key(&self, key: &[u8]) -> bool {
+        self.values.contains_key(key)
+    }
+
+    pub fn get(&self, key: &[u8]) -> Option<&Vec<u8>> {
+        self.values.get(key).map(|(_, value)| value)
+    }
+
+    /// Caches the value, evicting the oldest values past the limit.
+    pub fn insert(&mut self, key: Vec<u8>, value: Vec<u8>) {
+        if self.max_entries == 0 {
+            return;
+        }
+        self.remove(&key);
+
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.order.insert(seq, key.clone());
+        self.values.insert(key, (seq, value));
+
+        while self.values.len() > self.max_entries {
+            let (_, oldest) = self.order.pop_first().expect("order tracks every value");
+            self.values.remove(&oldest);
+        }
+    }
+
+    /// Replaces the value only if the key is already cached.
+    pub fn update(&mut self, key: &[u8], value: &[u8]) {
+        if let Some((_, cached)) = self.values.get_mut(key) {
+            cached.clear();
+            cached.extend_from_slice(value);
+        }
+    }
+
+    pub fn remove(&mut self, key: &[u8]) {
+        if let Some((seq, _)) = self.values.remove(key) {
+            self.order.remove(&seq);
+        }
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::rpcdb::client::cache::test_value_cache --exact --show-output
+#[test]
+fn test_value_cache() {
+    let mut cache = ValueCache::new(2);
+    cache.insert(b"a".to_vec(), b"1".to_vec());
+    cache.insert(b"b".to_vec(), b"2".to_vec());
+
+    // re-inserting moves the key to the back
+    cache.insert(b"a".to_vec(), b"1'".to_vec());
+    cache.insert(b"c".to_vec(), b"3".to_vec());
+    assert_eq!(cache.len(), 2);
+    assert!(!cache.contains_key(b"b"));
+    assert_eq!(cache.get(b"a").unwrap(), b"1'");
+
+    cache.update(b"c", b"3'");
+    cache.update(b"d", b"4");
+    assert_eq!(cache.get(b"c").unwrap(), b"3'");
+    assert!(!cache.contains_key(b"d"));
+
+    cache.remove(b"a");
+    cache.remove(b"c");
+    assert!(cache.is_empty());
+
+    let mut disabled = ValueCache::new(0);
+    disabled.insert(b"a".to_vec(), b"1".to_vec());
+    assert!(disabled.is_empty());
+}
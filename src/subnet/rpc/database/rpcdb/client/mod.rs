@@ -1,12 +1,14 @@
 //! RPC Database Client
 pub mod batch;
+pub mod breaker;
+pub mod cache;
 pub mod iterator;
 
 use std::{
     io,
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc,
+        Arc, RwLock,
     },
 };
 
@@ -16,7 +18,7 @@ use crate::{
             google::protobuf::Empty,
             rpcdb::{
                 database_client::DatabaseClient as RpcDbDatabaseClient, CloseRequest,
-                DeleteRequest, GetRequest, PutRequest,
+                CompactRequest, DeleteRequest, GetRequest, PutRequest,
             },
         },
         rpcdb::{HasRequest, NewIteratorWithStartAndPrefixRequest},
@@ -28,8 +30,24 @@ use crate::{
 };
 
 use prost::bytes::Bytes;
+use tokio::sync::{Semaphore, SemaphorePermit};
 use tonic::transport::Channel;
 
+/// Values cached by the client, keyed by the database key.
+pub(crate) type Cache = Arc<RwLock<cache::ValueCache>>;
+
+/// How fresh a read through [`DatabaseClient::get_with_consistency`] must be.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConsistencyLevel {
+    /// Always reads from the server (default).
+    #[default]
+    Strong,
+    /// Serves the value from the local cache if present, and caches the
+    /// value read from the server otherwise. The cached value may be stale
+    /// if another client wrote the key since.
+    CachedOk,
+}
+
 /// DatabaseClient is an implementation of [`crate::subnet::rpc::database::Database`] that talks over RPC.
 ///
 /// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/database/rpcdb#DatabaseClient>
@@ -38,14 +56,120 @@ pub struct DatabaseClient {
     inner: RpcDbDatabaseClient<Channel>,
     /// True if the underlying database is closed.
     closed: Arc<AtomicBool>,
+    /// Values of the keys read with [`ConsistencyLevel::CachedOk`], evicted
+    /// once the writes made through this client complete.
+    cache: Cache,
+    /// Bounds the requests in flight over the channel, if set.
+    in_flight: Option<Arc<Semaphore>>,
+    /// Fails the calls fast while the server keeps failing, if set.
+    breaker: Option<breaker::CircuitBreaker>,
 }
 
 impl DatabaseClient {
     pub fn new(client_conn: Channel) -> BoxedDatabase {
-        Box::new(Self {
+        Box::new(Self::new_client(client_conn))
+    }
+
+    /// Returns the client itself rather than a [`BoxedDatabase`], to read
+    /// with [`DatabaseClient::get_with_consistency`].
+    pub fn new_client(client_conn: Channel) -> Self {
+        Self {
             inner: RpcDbDatabaseClient::new(client_conn),
             closed: Arc::new(AtomicBool::new(false)),
-        })
+            cache: Arc::new(RwLock::new(cache::ValueCache::new(
+                cache::DEFAULT_MAX_ENTRIES,
+            ))),
+            in_flight: None,
+            breaker: None,
+        }
+    }
+
+    /// Keeps at most "max_in_flight" requests outstanding, shared by all
+    /// the clones of the client and its batches. Calls past the limit wait
+    /// for a slot. A limit of zero is treated as one.
+    ///
+    /// Only creating an iterator takes a slot; its "next" and "release"
+    /// calls are not bounded.
+    #[must_use]
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.in_flight = Some(Arc::new(Semaphore::new(max_in_flight.max(1))));
+        self
+    }
+
+    /// Fails the calls of an operation fast while its requests keep
+    /// failing, as configured by the breaker. Shared by the clones of the
+    /// client and its batches.
+    ///
+    /// As with the in-flight limit, the "next" and "release" calls of an
+    /// iterator go through regardless.
+    #[must_use]
+    pub fn with_circuit_breaker(mut self, breaker: breaker::CircuitBreaker) -> Self {
+        self.breaker = Some(breaker);
+        self
+    }
+
+    /// Caches at most "max_entries" values read with
+    /// [`ConsistencyLevel::CachedOk`] (default [`cache::DEFAULT_MAX_ENTRIES`]),
+    /// evicting the oldest first. Zero disables the cache. Shared by the
+    /// clones of the client and its batches.
+    #[must_use]
+    pub fn with_max_cached_values(mut self, max_entries: usize) -> Self {
+        self.cache = Arc::new(RwLock::new(cache::ValueCache::new(max_entries)));
+        self
+    }
+
+    /// Returns the value of the key, from the local cache if "level" allows.
+    pub async fn get_with_consistency(
+        &self,
+        key: &[u8],
+        level: ConsistencyLevel,
+    ) -> io::Result<Vec<u8>> {
+        if self.closed.load(Ordering::Relaxed) {
+            return Err(errors::Error::DatabaseClosed.to_err());
+        }
+
+        if level == ConsistencyLevel::CachedOk {
+            if let Some(value) = self.cache.read().unwrap().get(key) {
+                return Ok(value.clone());
+            }
+        }
+
+        let value = self.get_from_server(key).await?;
+
+        let mut cache = self.cache.write().unwrap();
+        if level == ConsistencyLevel::CachedOk || cache.contains_key(key) {
+            cache.insert(key.to_vec(), value.clone());
+        }
+        Ok(value)
+    }
+
+    async fn get_from_server(&self, key: &[u8]) -> io::Result<Vec<u8>> {
+        let _permit = acquire(&self.in_flight).await;
+        let mut db = self.inner.clone();
+        let resp = breaker::guard(
+            &self.breaker,
+            "get",
+            db.get(GetRequest {
+                key: Bytes::from(key.to_owned()),
+            }),
+        )
+        .await
+        .map_err(|s| {
+            log::error!("get request failed: {:?}", s);
+            errors::from_status(s)
+        })?;
+
+        log::debug!("get response: {:?}", resp);
+
+        let resp = resp.into_inner();
+        if let Err(e) = errors::from_i32(resp.err) {
+            if errors::is_not_found(&e) {
+                self.cache.write().unwrap().remove(key);
+            }
+            return Err(e);
+        }
+
+        Ok(resp.value.to_vec())
     }
 }
 
@@ -53,73 +177,78 @@ impl DatabaseClient {
 impl database::KeyValueReaderWriterDeleter for DatabaseClient {
     /// Attempts to return if the database has a key with the provided value.
     async fn has(&self, key: &[u8]) -> io::Result<bool> {
+        let _permit = acquire(&self.in_flight).await;
         let mut db = self.inner.clone();
-        let resp = db
-            .has(HasRequest {
+        let resp = breaker::guard(
+            &self.breaker,
+            "has",
+            db.has(HasRequest {
                 key: Bytes::from(key.to_owned()),
-            })
-            .await
-            .map_err(|s| {
-                log::error!("has request failed: {:?}", s);
-                errors::from_status(s)
-            })?
-            .into_inner();
+            }),
+        )
+        .await
+        .map_err(|s| {
+            log::error!("has request failed: {:?}", s);
+            errors::from_status(s)
+        })?
+        .into_inner();
 
         Ok(resp.has)
     }
 
     /// Attempts to return the value that was mapped to the key that was provided.
+    /// Always reads from the server (i.e., [`ConsistencyLevel::Strong`]).
     async fn get(&self, key: &[u8]) -> io::Result<Vec<u8>> {
-        let mut db = self.inner.clone();
-        let resp = db
-            .get(GetRequest {
-                key: Bytes::from(key.to_owned()),
-            })
-            .await
-            .map_err(|s| {
-                log::error!("get request failed: {:?}", s);
-                errors::from_status(s)
-            })?;
+        let value = self.get_from_server(key).await?;
 
-        log::debug!("get response: {:?}", resp);
-
-        let resp = resp.into_inner();
-        errors::from_i32(resp.err)?;
-
-        Ok(resp.value.to_vec())
+        self.cache.write().unwrap().update(key, &value);
+        Ok(value)
     }
 
     /// Attempts to set the value this key maps to.
     async fn put(&mut self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        let _permit = acquire(&self.in_flight).await;
         let mut db = self.inner.clone();
-        let resp = db
-            .put(PutRequest {
+        let resp = breaker::guard(
+            &self.breaker,
+            "put",
+            db.put(PutRequest {
                 key: Bytes::from(key.to_owned()),
                 value: Bytes::from(value.to_owned()),
-            })
-            .await
-            .map_err(|s| {
-                log::error!("put request failed: {:?}", s);
-                errors::from_status(s)
-            })?;
+            }),
+        )
+        .await
+        .map_err(|s| {
+            log::error!("put request failed: {:?}", s);
+            errors::from_status(s)
+        });
 
-        errors::from_i32(resp.into_inner().err)
+        // evicted once the write completes, so a cached read racing the
+        // write cannot leave the old value behind; a failed write may still
+        // have been applied by the server
+        self.cache.write().unwrap().remove(key);
+        errors::from_i32(resp?.into_inner().err)
     }
 
     /// Attempts to remove any mapping from the key.
     async fn delete(&mut self, key: &[u8]) -> io::Result<()> {
+        let _permit = acquire(&self.in_flight).await;
         let mut client = self.inner.clone();
-        let resp = client
-            .delete(DeleteRequest {
+        let resp = breaker::guard(
+            &self.breaker,
+            "delete",
+            client.delete(DeleteRequest {
                 key: Bytes::from(key.to_owned()),
-            })
-            .await
-            .map_err(|s| {
-                log::error!("delete request failed: {:?}", s);
-                errors::from_status(s)
-            })?;
+            }),
+        )
+        .await
+        .map_err(|s| {
+            log::error!("delete request failed: {:?}", s);
+            errors::from_status(s)
+        });
 
-        errors::from_i32(resp.into_inner().err)
+        self.cache.write().unwrap().remove(key);
+        errors::from_i32(resp?.into_inner().err)
     }
 }
 
@@ -127,11 +256,38 @@ impl database::KeyValueReaderWriterDeleter for DatabaseClient {
 impl database::Closer for DatabaseClient {
     /// Attempts to close the database.
     async fn close(&self) -> io::Result<()> {
+        let _permit = acquire(&self.in_flight).await;
         let mut db = self.inner.clone();
         self.closed.store(true, Ordering::Relaxed);
 
-        let resp = db.close(CloseRequest {}).await.map_err(|s| {
-            log::error!("close request failed: {:?}", s);
+        let resp = breaker::guard(&self.breaker, "close", db.close(CloseRequest {}))
+            .await
+            .map_err(|s| {
+                log::error!("close request failed: {:?}", s);
+                errors::from_status(s)
+            })?;
+
+        errors::from_i32(resp.into_inner().err)
+    }
+}
+
+#[tonic::async_trait]
+impl database::Compacter for DatabaseClient {
+    /// Asks the server to compact the key range.
+    async fn compact(&self, start: &[u8], limit: &[u8]) -> io::Result<()> {
+        let _permit = acquire(&self.in_flight).await;
+        let mut db = self.inner.clone();
+        let resp = breaker::guard(
+            &self.breaker,
+            "compact",
+            db.compact(CompactRequest {
+                start: Bytes::from(start.to_owned()),
+                limit: Bytes::from(limit.to_owned()),
+            }),
+        )
+        .await
+        .map_err(|s| {
+            log::error!("compact request failed: {:?}", s);
             errors::from_status(s)
         })?;
 
@@ -143,11 +299,14 @@ impl database::Closer for DatabaseClient {
 impl crate::subnet::rpc::health::Checkable for DatabaseClient {
     /// Attempts to perform a health check against the underlying database.
     async fn health_check(&self) -> io::Result<Vec<u8>> {
+        let _permit = acquire(&self.in_flight).await;
         let mut db = self.inner.clone();
-        let resp = db.health_check(Empty {}).await.map_err(|s| {
-            log::error!("health check failed: {:?}", s);
-            errors::from_status(s)
-        })?;
+        let resp = breaker::guard(&self.breaker, "health_check", db.health_check(Empty {}))
+            .await
+            .map_err(|s| {
+                log::error!("health check failed: {:?}", s);
+                errors::from_status(s)
+            })?;
 
         Ok(resp.into_inner().details.to_vec())
     }
@@ -176,13 +335,17 @@ impl database::iterator::Iteratee for DatabaseClient {
         start: &[u8],
         prefix: &[u8],
     ) -> io::Result<BoxedIterator> {
+        let _permit = acquire(&self.in_flight).await;
         let mut db = self.inner.clone();
-        match db
-            .new_iterator_with_start_and_prefix(NewIteratorWithStartAndPrefixRequest {
+        match breaker::guard(
+            &self.breaker,
+            "new_iterator",
+            db.new_iterator_with_start_and_prefix(NewIteratorWithStartAndPrefixRequest {
                 start: Bytes::from(start.to_owned()),
                 prefix: Bytes::from(prefix.to_owned()),
-            })
-            .await
+            }),
+        )
+        .await
         {
             Ok(resp) => Ok(iterator::Iterator::new(
                 self.inner.clone(),
@@ -200,8 +363,22 @@ impl database::iterator::Iteratee for DatabaseClient {
 impl database::batch::Batcher for DatabaseClient {
     /// Implements the [`crate::subnet::rpc::database::batch::Batcher`] trait.
     async fn new_batch(&self) -> io::Result<BoxedBatch> {
-        Ok(Box::new(batch::Batch::new(self.inner.clone())))
+        Ok(Box::new(batch::Batch::new_with_client_state(
+            self.inner.clone(),
+            Arc::clone(&self.cache),
+            self.in_flight.clone(),
+            self.breaker.clone(),
+        )))
     }
 }
 
 impl database::Database for DatabaseClient {}
+
+/// Waits for a slot if the requests in flight are bounded.
+pub(crate) async fn acquire(in_flight: &Option<Arc<Semaphore>>) -> Option<SemaphorePermit<'_>> {
+    match in_flight {
+        // never closed, so acquiring cannot fail
+        Some(semaphore) => Some(semaphore.acquire().await.unwrap()),
+        None => None,
+    }
+}
@@ -0,0 +1,135 @@
+//! Scoped keyspace whose keys are deleted from the database on drop.
+use std::io::Result;
+
+use super::BoxedDatabase;
+
+/// Prefixes every key written through it and, on [`Drop`], deletes all keys
+/// under the prefix from the wrapped database in a background task. Useful to
+/// keep tests and ephemeral subsystems from polluting a shared database.
+///
+/// The cleanup needs a Tokio runtime; if dropped outside of one, the keys are
+/// left in place.
+pub struct ScopedPrefix {
+    db: BoxedDatabase,
+    prefix: Vec<u8>,
+    leaked: bool,
+}
+
+impl ScopedPrefix {
+    pub fn new(db: BoxedDatabase, prefix: &[u8]) -> Self {
+        Self {
+            db,
+            prefix: prefix.to_vec(),
+            leaked: false,
+        }
+    }
+
+    pub fn prefix(&self) -> &[u8] {
+        &self.prefix
+    }
+
+    /// Opts out of the cleanup on drop, keeping the keys in the database.
+    pub fn leak(mut self) {
+        self.leaked = true;
+    }
+
+    pub async fn has(&self, key: &[u8]) -> Result<bool> {
+        self.db.has(&self.prefixed(key)).await
+    }
+
+    pub async fn get(&self, key: &[u8]) -> Result<Vec<u8>> {
+        self.db.get(&self.prefixed(key)).await
+    }
+
+    pub async fn put(&mut self, key: &[u8], value: &[u8]) -> Result<()> {
+        let key = self.prefixed(key);
+        self.db.put(&key, value).await
+    }
+
+    pub async fn delete(&mut self, key: &[u8]) -> Result<()> {
+        let key = self.prefixed(key);
+        self.db.delete(&key).await
+    }
+
+    fn prefixed(&self, key: &[u8]) -> Vec<u8> {
+        let mut k = self.prefix.clone();
+        k.extend_from_slice(key);
+        k
+    }
+}
+
+impl Drop for ScopedPrefix {
+    fn drop(&mut self) {
+        if self.leaked {
+            return;
+        }
+
+        let handle = match tokio::runtime::Handle::try_current() {
+            Ok(handle) => handle,
+            Err(_) => {
+                log::warn!("no runtime to delete scoped keys, leaking prefix");
+                return;
+            }
+        };
+
+        let db = self.db.clone();
+        let prefix = std::mem::take(&mut self.prefix);
+        handle.spawn(async move {
+            if let Err(e) = delete_prefix(db, &prefix).await {
+                log::warn!("failed to delete scoped keys: {}", e);
+            }
+        });
+    }
+}
+
+/// Deletes all keys under the prefix.
+async fn delete_prefix(mut db: BoxedDatabase, prefix: &[u8]) -> Result<()> {
+    let mut keys = Vec::new();
+    let mut iter = db.new_iterator_with_prefix(prefix).await?;
+    while iter.next().await? {
+        keys.push(iter.key().await?.to_vec());
+    }
+    iter.error().await?;
+    iter.release().await;
+
+    for key in keys.iter() {
+        db.delete(key).await?;
+    }
+    Ok(())
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::scoped::test_scoped_prefix --exact --show-output
+#[tokio::test]
+async fn test_scoped_prefix() {
+    use std::time::Duration;
+
+    use super::memdb;
+
+    let mut db = memdb::Database::new();
+    db.put(b"other", b"kept").await.unwrap();
+
+    let mut scope = ScopedPrefix::new(db.clone(), b"test/");
+    scope.put(b"a", b"1").await.unwrap();
+    scope.put(b"b", b"2").await.unwrap();
+    assert_eq!(scope.get(b"a").await.unwrap(), b"1");
+    assert!(db.has(b"test/b").await.unwrap());
+    drop(scope);
+
+    let mut gone = false;
+    for _ in 0..100 {
+        if !db.has(b"test/a").await.unwrap() && !db.has(b"test/b").await.unwrap() {
+            gone = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(gone);
+    assert!(db.has(b"other").await.unwrap());
+
+    // leaked scopes keep their keys
+    let mut scope = ScopedPrefix::new(db.clone(), b"leaked/");
+    scope.put(b"a", b"1").await.unwrap();
+    scope.leak();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(db.has(b"leaked/a").await.unwrap());
+}
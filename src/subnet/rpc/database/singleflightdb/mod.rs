@@ -0,0 +1,267 @@
+//! Database wrapper which coalesces concurrent reads of the same key.
+//!
+//! Useful in front of a remote database (e.g., rpcdb) when many tasks read
+//! overlapping keys at once: only one request per key is in flight and its
+//! result is shared with every caller that asked in the meantime.
+//!
+//! A "put" or "delete" through this database stops later reads of the key
+//! from joining a read issued before the write. Writes made through a batch
+//! or another handle are not tracked: a read may still join one issued
+//! before such a write and return the older value.
+//! ref. <https://pkg.go.dev/golang.org/x/sync/singleflight>
+use std::{
+    collections::HashMap,
+    io,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use super::{batch::BoxedBatch, iterator::BoxedIterator, BoxedDatabase};
+use tokio::sync::watch;
+
+/// Result of an in-flight "get", shareable across callers.
+type Outcome = Option<Result<Vec<u8>, (io::ErrorKind, String)>>;
+
+/// Reads in flight, keyed by the key being read, with the sequence number
+/// of the read that issued the request.
+type InFlight = Arc<Mutex<HashMap<Vec<u8>, (u64, watch::Receiver<Outcome>)>>>;
+
+/// Database which deduplicates concurrent "get" calls on the same key.
+/// All other calls pass through to the underlying database.
+#[derive(Clone)]
+pub struct Database {
+    db: BoxedDatabase,
+    /// Receivers of the result of each key being read.
+    in_flight: InFlight,
+    /// Sequence number of the next issued read.
+    next_seq: Arc<AtomicU64>,
+}
+
+impl Database {
+    pub fn new(db: BoxedDatabase) -> BoxedDatabase {
+        Box::new(Self {
+            db,
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+            next_seq: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    /// Stops later reads of the key from joining the read in flight.
+    fn forget(&self, key: &[u8]) {
+        self.in_flight.lock().unwrap().remove(key);
+    }
+}
+
+/// Removes the in-flight entry of the issuing read once it completes or is
+/// cancelled, unless a write has already replaced it.
+struct InFlightGuard {
+    in_flight: InFlight,
+    key: Vec<u8>,
+    seq: u64,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if matches!(in_flight.get(&self.key), Some((seq, _)) if *seq == self.seq) {
+            in_flight.remove(&self.key);
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::KeyValueReaderWriterDeleter for Database {
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn has(&self, key: &[u8]) -> io::Result<bool> {
+        self.db.has(key).await
+    }
+
+    /// Joins the read of the key already in flight, if any, or issues it
+    /// and shares its result with the callers that join meanwhile.
+    async fn get(&self, key: &[u8]) -> io::Result<Vec<u8>> {
+        let joined = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            match in_flight.get(key) {
+                Some((_, rx)) => Err(rx.clone()),
+                None => {
+                    let (tx, rx) = watch::channel(None);
+                    let seq = self.next_seq.fetch_add(1, Ordering::Relaxed);
+                    in_flight.insert(key.to_vec(), (seq, rx));
+                    Ok((
+                        tx,
+                        InFlightGuard {
+                            in_flight: Arc::clone(&self.in_flight),
+                            key: key.to_vec(),
+                            seq,
+                        },
+                    ))
+                }
+            }
+        };
+
+        let mut rx = match joined {
+            Ok((tx, guard)) => {
+                let resp = self.db.get(key).await;
+                drop(guard);
+
+                let outcome = match &resp {
+                    Ok(value) => Ok(value.clone()),
+                    Err(e) => Err((e.kind(), e.to_string())),
+                };
+                let _ = tx.send(Some(outcome));
+                return resp;
+            }
+            Err(rx) => rx,
+        };
+
+        loop {
+            if let Some(outcome) = rx.borrow().clone() {
+                return outcome.map_err(|(kind, msg)| io::Error::new(kind, msg));
+            }
+            if rx.changed().await.is_err() {
+                // the issuing caller was cancelled, read on our own
+                return self.db.get(key).await;
+            }
+        }
+    }
+
+    /// Writes the key, after which reads of the key no longer join a read
+    /// issued before the write.
+    async fn put(&mut self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        let resp = self.db.put(key, value).await;
+        self.forget(key);
+        resp
+    }
+
+    /// Deletes the key, after which reads of the key no longer join a read
+    /// issued before the delete.
+    async fn delete(&mut self, key: &[u8]) -> io::Result<()> {
+        let resp = self.db.delete(key).await;
+        self.forget(key);
+        resp
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::Closer for Database {
+    /// Implements the [`crate::subnet::rpc::database::Closer`] trait.
+    async fn close(&self) -> io::Result<()> {
+        self.db.close().await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::health::Checkable for Database {
+    /// Implements the [`crate::subnet::rpc::health::Checkable`] trait.
+    async fn health_check(&self) -> io::Result<Vec<u8>> {
+        self.db.health_check().await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::iterator::Iteratee for Database {
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator(&self) -> io::Result<BoxedIterator> {
+        self.db.new_iterator().await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_start(&self, start: &[u8]) -> io::Result<BoxedIterator> {
+        self.db.new_iterator_with_start(start).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_prefix(&self, prefix: &[u8]) -> io::Result<BoxedIterator> {
+        self.db.new_iterator_with_prefix(prefix).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_start_and_prefix(
+        &self,
+        start: &[u8],
+        prefix: &[u8],
+    ) -> io::Result<BoxedIterator> {
+        self.db
+            .new_iterator_with_start_and_prefix(start, prefix)
+            .await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::batch::Batcher for Database {
+    /// Implements the [`crate::subnet::rpc::database::batch::Batcher`] trait.
+    async fn new_batch(&self) -> io::Result<BoxedBatch> {
+        self.db.new_batch().await
+    }
+}
+
+impl crate::subnet::rpc::database::Database for Database {}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::singleflightdb::test_coalesced_get --exact --show-output
+#[tokio::test]
+async fn test_coalesced_get() {
+    use std::{sync::atomic::AtomicUsize, time::Duration};
+
+    use crate::subnet::rpc::{
+        database::{hookdb, memdb},
+        errors,
+    };
+
+    let gets = Arc::new(AtomicUsize::new(0));
+    let mut inner = memdb::Database::new();
+    inner.put(b"foo", b"bar").await.unwrap();
+    // counts the "get" calls reaching the inner database
+    let db = Database::new(Box::new(
+        hookdb::Database::new(inner)
+            .with_before({
+                let gets = Arc::clone(&gets);
+                move |method, _| {
+                    if method == hookdb::Method::Get {
+                        gets.fetch_add(1, Ordering::SeqCst);
+                    }
+                    Ok(())
+                }
+            })
+            .with_delay(hookdb::Method::Get, Duration::from_millis(50)),
+    ));
+
+    let handles: Vec<_> = (0..50)
+        .map(|_| {
+            let db = db.clone();
+            tokio::spawn(async move { db.get(b"foo").await })
+        })
+        .collect();
+    for handle in handles {
+        assert_eq!(handle.await.unwrap().unwrap(), b"bar");
+    }
+    assert_eq!(gets.load(Ordering::SeqCst), 1);
+
+    // errors are shared too, and later reads issue a new request
+    let (a, b) = tokio::join!(db.get(b"missing"), db.get(b"missing"));
+    assert!(errors::is_not_found(&a.unwrap_err()));
+    assert!(errors::is_not_found(&b.unwrap_err()));
+    assert_eq!(gets.load(Ordering::SeqCst), 2);
+
+    assert_eq!(db.get(b"foo").await.unwrap(), b"bar");
+    assert_eq!(gets.load(Ordering::SeqCst), 3);
+
+    // a cancelled read does not leave its entry behind
+    let cancelled = tokio::time::timeout(Duration::from_millis(10), db.get(b"foo")).await;
+    assert!(cancelled.is_err());
+    assert_eq!(db.get(b"foo").await.unwrap(), b"bar");
+    assert_eq!(gets.load(Ordering::SeqCst), 5);
+
+    // a read after a write does not join the read issued before it
+    let mut writer = db.clone();
+    let stale = {
+        let db = db.clone();
+        tokio::spawn(async move { db.get(b"foo").await })
+    };
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    writer.put(b"foo", b"baz").await.unwrap();
+    assert_eq!(db.get(b"foo").await.unwrap(), b"baz");
+    assert_eq!(gets.load(Ordering::SeqCst), 7);
+    stale.await.unwrap().unwrap();
+}
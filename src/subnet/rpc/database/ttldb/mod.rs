@@ -0,0 +1,456 @@
+//! Database wrapper whose keys expire after a time-to-live.
+//!
+//! Each value is stored with its expiry as Unix milliseconds, so that expired
+//! keys read as absent even across restarts. Expired keys stay on disk until
+//! a sweep deletes them, see [`Database::spawn_sweeper`]. Writes through the
+//! database (or its clones) never race with the sweep, so that a key
+//! rewritten while it is swept keeps its new value.
+use std::{
+    io,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use tokio::sync::RwLock;
+
+use super::{batch::BoxedBatch, iterator::BoxedIterator, BoxedDatabase};
+use crate::subnet::rpc::errors::Error;
+
+/// Length of the expiry prefixed to every stored value.
+const EXPIRY_LEN: usize = 8;
+
+type Clock = Arc<dyn Fn() -> SystemTime + Send + Sync>;
+
+/// Database which treats keys older than their time-to-live as not found
+/// on "has", "get" and iteration.
+#[derive(Clone)]
+pub struct Database {
+    db: BoxedDatabase,
+    ttl: Duration,
+    clock: Clock,
+    /// Held shared by writes and exclusively by the sweep while it deletes
+    /// a key.
+    sweep_lock: Arc<RwLock<()>>,
+}
+
+impl Database {
+    /// Creates a database where every "put" expires after "ttl".
+    pub fn new(db: BoxedDatabase, ttl: Duration) -> Self {
+        Self::with_clock(db, ttl, Arc::new(SystemTime::now))
+    }
+
+    fn with_clock(db: BoxedDatabase, ttl: Duration, clock: Clock) -> Self {
+        Self {
+            db,
+            ttl,
+            clock,
+            sweep_lock: Arc::new(RwLock::new(())),
+        }
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Writes the key with its own time-to-live instead of the default.
+    pub async fn put_with_ttl(
+        &mut self,
+        key: &[u8],
+        value: &[u8],
+        ttl: Duration,
+    ) -> io::Result<()> {
+        let value = encode(expiry(&self.clock, ttl), value);
+        let _guard = self.sweep_lock.read().await;
+        self.db.put(key, &value).await
+    }
+
+    /// Deletes the expired keys from the underlying database, returning how
+    /// many were deleted. Each key is read again before it is deleted, and
+    /// kept if it was rewritten with a later expiry since the scan.
+    pub async fn sweep(&self) -> io::Result<usize> {
+        let now = unix_millis((self.clock)());
+
+        let mut expired = Vec::new();
+        let mut iter = self.db.new_iterator().await?;
+        while iter.next().await? {
+            let (expiry, _) = decode(iter.value().await?)?;
+            if expiry <= now {
+                expired.push(iter.key().await?.to_vec());
+            }
+        }
+        iter.error().await?;
+        iter.release().await;
+
+        let mut db = self.db.clone();
+        let mut deleted = 0;
+        for key in expired.iter() {
+            let _guard = self.sweep_lock.write().await;
+            let stored = match db.get(key).await {
+                Ok(stored) => stored,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+            let (expiry, _) = decode(&stored)?;
+            if expiry > now {
+                continue;
+            }
+            db.delete(key).await?;
+            deleted += 1;
+        }
+        Ok(deleted)
+    }
+
+    /// Spawns a task which sweeps the expired keys every "interval", until
+    /// the returned handle is aborted.
+    pub fn spawn_sweeper(&self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let db = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match db.sweep().await {
+                    Ok(0) => {}
+                    Ok(n) => log::debug!("swept {} expired keys", n),
+                    Err(e) => log::warn!("failed to sweep expired keys: {}", e),
+                }
+            }
+        })
+    }
+
+    fn wrap_iterator(&self, iterator: BoxedIterator) -> BoxedIterator {
+        Box::new(Iterator {
+            iterator,
+            now: unix_millis((self.clock)()),
+            key: Vec::new(),
+            value: Vec::new(),
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::KeyValueReaderWriterDeleter for Database {
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn has(&self, key: &[u8]) -> io::Result<bool> {
+        match self.get(key).await {
+            Ok(_) => Ok(true),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns not found if the key has expired.
+    async fn get(&self, key: &[u8]) -> io::Result<Vec<u8>> {
+        let stored = self.db.get(key).await?;
+        let (expiry, value) = decode(&stored)?;
+        if expiry <= unix_millis((self.clock)()) {
+            return Err(Error::NotFound.to_err());
+        }
+        Ok(value.to_vec())
+    }
+
+    /// Writes the key to expire after the default time-to-live.
+    async fn put(&mut self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        let ttl = self.ttl;
+        self.put_with_ttl(key, value, ttl).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::KeyValueReaderWriterDeleter`] trait.
+    async fn delete(&mut self, key: &[u8]) -> io::Result<()> {
+        let _guard = self.sweep_lock.read().await;
+        self.db.delete(key).await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::Closer for Database {
+    /// Implements the [`crate::subnet::rpc::database::Closer`] trait.
+    async fn close(&self) -> io::Result<()> {
+        self.db.close().await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::health::Checkable for Database {
+    /// Implements the [`crate::subnet::rpc::health::Checkable`] trait.
+    async fn health_check(&self) -> io::Result<Vec<u8>> {
+        self.db.health_check().await
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::iterator::Iteratee for Database {
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator(&self) -> io::Result<BoxedIterator> {
+        Ok(self.wrap_iterator(self.db.new_iterator().await?))
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_start(&self, start: &[u8]) -> io::Result<BoxedIterator> {
+        Ok(self.wrap_iterator(self.db.new_iterator_with_start(start).await?))
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_prefix(&self, prefix: &[u8]) -> io::Result<BoxedIterator> {
+        Ok(self.wrap_iterator(self.db.new_iterator_with_prefix(prefix).await?))
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iteratee`] trait.
+    async fn new_iterator_with_start_and_prefix(
+        &self,
+        start: &[u8],
+        prefix: &[u8],
+    ) -> io::Result<BoxedIterator> {
+        Ok(self.wrap_iterator(
+            self.db
+                .new_iterator_with_start_and_prefix(start, prefix)
+                .await?,
+        ))
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::batch::Batcher for Database {
+    /// Returns a batch whose "put"s expire after the default time-to-live.
+    async fn new_batch(&self) -> io::Result<BoxedBatch> {
+        Ok(Box::new(Batch {
+            batch: self.db.new_batch().await?,
+            ttl: self.ttl,
+            clock: Arc::clone(&self.clock),
+            sweep_lock: Arc::clone(&self.sweep_lock),
+        }))
+    }
+}
+
+impl crate::subnet::rpc::database::Database for Database {}
+
+/// Iterator which skips the expired keys and strips the expiry from values.
+/// Keys expiring during the iteration are still yielded.
+struct Iterator {
+    iterator: BoxedIterator,
+    now: u64,
+    key: Vec<u8>,
+    value: Vec<u8>,
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::iterator::Iterator for Iterator {
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iterator`] trait.
+    async fn next(&mut self) -> io::Result<bool> {
+        while self.iterator.next().await? {
+            let (expiry, value) = decode(self.iterator.value().await?)?;
+            if expiry > self.now {
+                self.value = value.to_vec();
+                self.key = self.iterator.key().await?.to_vec();
+                return Ok(true);
+            }
+        }
+        self.key.clear();
+        self.value.clear();
+        Ok(false)
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iterator`] trait.
+    async fn error(&mut self) -> io::Result<()> {
+        self.iterator.error().await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iterator`] trait.
+    async fn key(&self) -> io::Result<&[u8]> {
+        Ok(&self.key)
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iterator`] trait.
+    async fn value(&self) -> io::Result<&[u8]> {
+        Ok(&self.value)
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::iterator::Iterator`] trait.
+    async fn release(&mut self) {
+        self.key.clear();
+        self.value.clear();
+        self.iterator.release().await
+    }
+}
+
+/// Batch which stores the expiry along with each value.
+#[derive(Clone)]
+struct Batch {
+    batch: BoxedBatch,
+    ttl: Duration,
+    clock: Clock,
+    sweep_lock: Arc<RwLock<()>>,
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::database::batch::Batch for Batch {
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn put(&mut self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        let value = encode(expiry(&self.clock, self.ttl), value);
+        self.batch.put(key, &value).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn delete(&mut self, key: &[u8]) -> io::Result<()> {
+        self.batch.delete(key).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn size(&self) -> io::Result<usize> {
+        self.batch.size().await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn write(&self) -> io::Result<()> {
+        let _guard = self.sweep_lock.read().await;
+        self.batch.write().await
+    }
+
+    /// Implements the [`crate::subnet::rpc::database::batch::Batch`] trait.
+    async fn reset(&mut self) {
+        self.batch.reset().await
+    }
+
+    /// Replays the values with their expiry, as stored in the underlying database.
+    async fn replay(&self, k: Arc<tokio::sync::Mutex<BoxedDatabase>>) -> io::Result<()> {
+        self.batch.replay(k).await
+    }
+}
+
+fn unix_millis(t: SystemTime) -> u64 {
+    t.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+fn expiry(clock: &Clock, ttl: Duration) -> u64 {
+    unix_millis(clock()).saturating_add(ttl.as_millis() as u64)
+}
+
+fn encode(expiry: u64, value: &[u8]) -> Vec<u8> {
+    let mut d = Vec::with_capacity(EXPIRY_LEN + value.len());
+    d.extend_from_slice(&expiry.to_be_bytes());
+    d.extend_from_slice(value);
+    d
+}
+
+fn decode(d: &[u8]) -> io::Result<(u64, &[u8])> {
+    if d.len() < EXPIRY_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("value too short for its expiry ({} bytes)", d.len()),
+        ));
+    }
+    let (expiry, value) = d.split_at(EXPIRY_LEN);
+    Ok((u64::from_be_bytes(expiry.try_into().unwrap()), value))
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::ttldb::test_expiry --exact --show-output
+#[tokio::test]
+async fn test_expiry() {
+    use crate::subnet::rpc::{
+        database::{batch::Batcher, iterator::Iteratee, memdb, KeyValueReaderWriterDeleter},
+        errors,
+    };
+
+    // mock clock advanced manually by the test
+    let start = SystemTime::now();
+    let offset = Arc::new(std::sync::Mutex::new(Duration::ZERO));
+    let clock_offset = offset.clone();
+    let clock = Arc::new(move || start + *clock_offset.lock().unwrap());
+
+    let inner = memdb::Database::new();
+    let mut db = Database::with_clock(inner.clone(), Duration::from_secs(60), clock);
+    db.put(b"foo", b"bar").await.unwrap();
+    db.put_with_ttl(b"long", b"lived", Duration::from_secs(600))
+        .await
+        .unwrap();
+    let mut batch = db.new_batch().await.unwrap();
+    batch.put(b"batched", b"value").await.unwrap();
+    batch.write().await.unwrap();
+
+    *offset.lock().unwrap() = Duration::from_secs(59);
+    assert_eq!(db.get(b"foo").await.unwrap(), b"bar");
+    assert_eq!(db.get(b"batched").await.unwrap(), b"value");
+
+    // past the default TTL
+    *offset.lock().unwrap() = Duration::from_secs(61);
+    assert!(errors::is_not_found(&db.get(b"foo").await.unwrap_err()));
+    assert!(!db.has(b"foo").await.unwrap());
+    assert!(!db.has(b"batched").await.unwrap());
+    assert_eq!(db.get(b"long").await.unwrap(), b"lived");
+
+    let mut iter = db.new_iterator().await.unwrap();
+    assert!(iter.next().await.unwrap());
+    assert_eq!(iter.key().await.unwrap(), b"long");
+    assert_eq!(iter.value().await.unwrap(), b"lived");
+    assert!(!iter.next().await.unwrap());
+    iter.release().await;
+
+    // expired keys stay on disk until swept
+    assert!(inner.has(b"foo").await.unwrap());
+    assert_eq!(db.sweep().await.unwrap(), 2);
+    assert!(!inner.has(b"foo").await.unwrap());
+    assert!(!inner.has(b"batched").await.unwrap());
+    assert!(inner.has(b"long").await.unwrap());
+
+    // rewriting refreshes the expiry
+    db.put(b"foo", b"baz").await.unwrap();
+    *offset.lock().unwrap() = Duration::from_secs(120);
+    assert_eq!(db.get(b"foo").await.unwrap(), b"baz");
+
+    // the sweeper runs in the background
+    *offset.lock().unwrap() = Duration::from_secs(700);
+    let sweeper = db.spawn_sweeper(Duration::from_millis(10));
+    let mut swept = false;
+    for _ in 0..100 {
+        if !inner.has(b"foo").await.unwrap() && !inner.has(b"long").await.unwrap() {
+            swept = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    sweeper.abort();
+    assert!(swept);
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::ttldb::test_sweep_rewritten_key --exact --show-output
+#[tokio::test]
+async fn test_sweep_rewritten_key() {
+    use crate::subnet::rpc::database::{hookdb, memdb, KeyValueReaderWriterDeleter};
+
+    let start = SystemTime::now();
+    let offset = Arc::new(std::sync::Mutex::new(Duration::ZERO));
+    let clock_offset = offset.clone();
+    let clock = Arc::new(move || start + *clock_offset.lock().unwrap());
+
+    // signals the first delete of the sweep, which is then delayed so that
+    // the key is rewritten between the scan and the delete
+    let deleting = Arc::new(tokio::sync::Notify::new());
+    let inner = memdb::Database::new();
+    let hooked = hookdb::Database::new(inner.clone())
+        .with_before({
+            let deleting = Arc::clone(&deleting);
+            move |method, _| {
+                if method == hookdb::Method::Delete {
+                    deleting.notify_one();
+                }
+                Ok(())
+            }
+        })
+        .with_delay(hookdb::Method::Delete, Duration::from_millis(50));
+    let mut db = Database::with_clock(Box::new(hooked), Duration::from_secs(60), clock);
+    db.put(b"foo", b"bar").await.unwrap();
+
+    *offset.lock().unwrap() = Duration::from_secs(61);
+    let sweep = {
+        let db = db.clone();
+        tokio::spawn(async move { db.sweep().await })
+    };
+    deleting.notified().await;
+    db.put(b"foo", b"baz").await.unwrap();
+    assert_eq!(sweep.await.unwrap().unwrap(), 1);
+
+    // the rewrite waits for the delete instead of being deleted by it
+    assert_eq!(db.get(b"foo").await.unwrap(), b"baz");
+    assert_eq!(db.sweep().await.unwrap(), 0);
+}
@@ -23,6 +23,10 @@ pub struct Iterator {
     closed: Arc<AtomicBool>,
     key: Vec<u8>,
     value: Vec<u8>,
+    /// True if deleted keys are yielded instead of skipped.
+    include_tombstones: bool,
+    /// True if the current key is deleted.
+    tombstone: bool,
     initialized: Arc<AtomicBool>,
     exhausted: Arc<AtomicBool>,
 }
@@ -40,7 +44,22 @@ impl Iterator {
         closed: Arc<AtomicBool>,
         iterator: BoxedIterator,
     ) -> BoxedIterator {
-        Box::new(Self {
+        Box::new(Self::new_with_options(
+            keys, values, closed, iterator, false,
+        ))
+    }
+
+    /// Creates an iterator which, if "include_tombstones" is true, yields the
+    /// deleted keys with an empty value and [`Iterator::is_tombstone`] set,
+    /// rather than skipping them.
+    pub fn new_with_options(
+        keys: Vec<Vec<u8>>,
+        values: Vec<ValueDelete>,
+        closed: Arc<AtomicBool>,
+        iterator: BoxedIterator,
+        include_tombstones: bool,
+    ) -> Self {
+        Self {
             keys,
             values,
             error: None,
@@ -50,7 +69,26 @@ impl Iterator {
             iterator,
             key: vec![],
             value: vec![],
-        })
+            include_tombstones,
+            tombstone: false,
+        }
+    }
+
+    /// Returns true if the current key is deleted, which only happens if
+    /// the iterator includes tombstones.
+    pub fn is_tombstone(&self) -> bool {
+        self.tombstone
+    }
+
+    /// Moves to the in-memory entry if it is to be yielded.
+    fn yield_mem(&mut self, key: Vec<u8>, value: ValueDelete) -> bool {
+        if value.delete && !self.include_tombstones {
+            return false;
+        }
+        self.key = key;
+        self.value = if value.delete { vec![] } else { value.value };
+        self.tombstone = value.delete;
+        true
     }
 }
 
@@ -77,6 +115,7 @@ impl database::iterator::Iterator for Iterator {
             if self.exhausted.load(Ordering::Relaxed) && self.keys.is_empty() {
                 self.key.clear();
                 self.value.clear();
+                self.tombstone = false;
 
                 return Ok(false);
             }
@@ -90,17 +129,16 @@ impl database::iterator::Iterator for Iterator {
                 self.values[0].value.clear();
                 self.values = self.values[1..].to_vec();
 
-                if !next_value.delete {
-                    self.key = next_key;
-                    self.value = next_value.value;
-
+                if self.yield_mem(next_key, next_value) {
                     return Ok(true);
                 }
+                continue;
             }
 
             if self.keys.is_empty() {
                 self.key = self.iterator.key().await?.to_vec();
                 self.value = self.iterator.value().await?.to_vec();
+                self.tombstone = false;
                 let exhausted = !self.iterator.next().await?;
                 self.exhausted.store(exhausted, Ordering::Relaxed);
 
@@ -117,17 +155,16 @@ impl database::iterator::Iterator for Iterator {
                 self.values[0].value.clear();
                 self.values = self.values[1..].to_vec();
 
-                if !mem_value.delete {
-                    self.key = mem_key;
-                    self.value = mem_value.value.clone();
-
+                if self.yield_mem(mem_key, mem_value) {
                     return Ok(true);
                 }
+                continue;
             }
 
             if db_key.lt(&mem_key) {
                 self.key = db_key.to_vec();
                 self.value = self.iterator.value().await?.to_vec();
+                self.tombstone = false;
                 let exhausted = !self.iterator.next().await?;
                 self.exhausted.store(exhausted, Ordering::Relaxed);
 
@@ -142,9 +179,7 @@ impl database::iterator::Iterator for Iterator {
             let exhausted = !self.iterator.next().await?;
             self.exhausted.store(exhausted, Ordering::Relaxed);
 
-            if !mem_value.delete {
-                self.key = mem_key.to_owned();
-                self.value = mem_value.value.clone();
+            if self.yield_mem(mem_key, mem_value) {
                 return Ok(true);
             }
         }
@@ -171,6 +206,7 @@ impl database::iterator::Iterator for Iterator {
 
     /// Implements the [`crate::subnet::rpc::database::Iterator`] trait.
     async fn release(&mut self) {
+        self.tombstone = false;
         self.key.clear();
         self.value.clear();
         self.keys.clear();
@@ -42,6 +42,123 @@ impl Database {
             closed: Arc::new(AtomicBool::new(false)),
         }
     }
+
+    /// Returns the merged iterator over the pending changes and the underlying
+    /// database. If "include_tombstones" is true, the keys deleted since the
+    /// last commit are yielded with an empty value and
+    /// [`iterator::Iterator::is_tombstone`] set, which helps debugging
+    /// deletions. The [`database::iterator::Iteratee`] methods never include
+    /// them.
+    pub async fn new_iterator_with_tombstones(
+        &self,
+        start: &[u8],
+        prefix: &[u8],
+        include_tombstones: bool,
+    ) -> io::Result<iterator::Iterator> {
+        if self.closed.load(Ordering::Relaxed) {
+            return Err(Error::DatabaseClosed.to_err());
+        }
+
+        let mem = self.mem.write().await;
+        let mut keys: Vec<Vec<u8>> = Vec::with_capacity(mem.len());
+        for (k, _v) in mem.iter() {
+            if k.starts_with(prefix) && k >= &start.to_vec() {
+                keys.push(k.to_owned());
+            }
+        }
+
+        // keys need to be in sorted order
+        keys.sort();
+
+        let mut values: Vec<iterator::ValueDelete> = Vec::with_capacity(keys.len());
+        for key in keys.iter() {
+            if let Some(v) = mem.get(key) {
+                values.push(v.to_owned());
+            }
+        }
+
+        Ok(iterator::Iterator::new_with_options(
+            keys,
+            values,
+            Arc::clone(&self.closed),
+            self.db
+                .new_iterator_with_start_and_prefix(start, prefix)
+                .await?,
+            include_tombstones,
+        ))
+    }
+
+    /// Writes the pending changes to the underlying database in batches of
+    /// at most "chunk_size" keys, in key order, calling "progress" with the
+    /// number of keys written so far and the total after each batch.
+    ///
+    /// Batches already written are not rolled back on failure: they are
+    /// dropped from the pending changes and the error reports how many keys
+    /// made it, so that calling commit again writes only the remainder.
+    pub async fn commit_with_progress<F>(
+        &mut self,
+        chunk_size: usize,
+        progress: F,
+    ) -> io::Result<()>
+    where
+        F: Fn(usize, usize) + Send,
+    {
+        if self.closed.load(Ordering::Relaxed) {
+            return Err(Error::DatabaseClosed.to_err());
+        }
+        if chunk_size == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "chunk size must be positive",
+            ));
+        }
+
+        let mem = Arc::clone(&self.mem);
+        let mut mem = mem.write().await;
+        let mut keys: Vec<Vec<u8>> = mem.keys().cloned().collect();
+        keys.sort();
+
+        let total = keys.len();
+        let mut committed = 0;
+        for chunk in keys.chunks(chunk_size) {
+            if let Err(e) = self.write_chunk(&mem, chunk).await {
+                return Err(io::Error::new(
+                    e.kind(),
+                    format!(
+                        "partial commit: wrote {} of {} keys before failing ({})",
+                        committed, total, e
+                    ),
+                ));
+            }
+            for key in chunk.iter() {
+                mem.remove(key);
+            }
+
+            committed += chunk.len();
+            progress(committed, total);
+        }
+
+        Ok(())
+    }
+
+    async fn write_chunk(
+        &mut self,
+        mem: &HashMap<Vec<u8>, iterator::ValueDelete>,
+        keys: &[Vec<u8>],
+    ) -> io::Result<()> {
+        self.batch.reset().await;
+        for key in keys.iter() {
+            let value = &mem[key];
+            if value.delete {
+                self.batch.delete(key).await?;
+            } else {
+                self.batch.put(key, &value.value).await?;
+            }
+        }
+        self.batch.write().await?;
+        self.batch.reset().await;
+        Ok(())
+    }
 }
 
 #[tonic::async_trait]
@@ -162,34 +279,8 @@ impl database::iterator::Iteratee for Database {
         start: &[u8],
         prefix: &[u8],
     ) -> io::Result<BoxedIterator> {
-        if self.closed.load(Ordering::Relaxed) {
-            return Err(Error::DatabaseClosed.to_err());
-        }
-
-        let mem = self.mem.write().await;
-        let mut keys: Vec<Vec<u8>> = Vec::with_capacity(mem.len());
-        for (k, _v) in mem.iter() {
-            if k.starts_with(prefix) && k >= &start.to_vec() {
-                keys.push(k.to_owned());
-            }
-        }
-
-        // keys need to be in sorted order
-        keys.sort();
-
-        let mut values: Vec<iterator::ValueDelete> = Vec::with_capacity(keys.len());
-        for key in keys.iter() {
-            if let Some(v) = mem.get(key) {
-                values.push(v.to_owned());
-            }
-        }
-
-        Ok(iterator::Iterator::new(
-            keys,
-            values,
-            Arc::clone(&self.closed),
-            self.db
-                .new_iterator_with_start_and_prefix(start, prefix)
+        Ok(Box::new(
+            self.new_iterator_with_tombstones(start, prefix, false)
                 .await?,
         ))
     }
@@ -326,3 +417,95 @@ async fn iterate_test() {
     assert!(iterator.value().await.unwrap().is_empty());
     assert!(iterator.error().await.is_ok());
 }
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::versiondb::test_commit_with_progress --exact --show-output
+#[tokio::test]
+async fn test_commit_with_progress() {
+    use std::sync::Mutex;
+
+    use crate::subnet::rpc::database::{memdb, KeyValueReaderWriterDeleter};
+
+    let mut base_db = memdb::Database::new();
+    base_db.put(b"stale", b"old").await.unwrap();
+
+    let batch = base_db.new_batch().await.unwrap();
+    let mut db = Database::new(base_db.clone(), batch);
+    for i in 0..10u8 {
+        db.put(&[b'k', i], &[i]).await.unwrap();
+    }
+    db.delete(b"stale").await.unwrap();
+
+    let calls = Mutex::new(Vec::new());
+    db.commit_with_progress(3, |committed, total| {
+        calls.lock().unwrap().push((committed, total))
+    })
+    .await
+    .unwrap();
+
+    let calls = calls.into_inner().unwrap();
+    assert_eq!(calls, vec![(3, 11), (6, 11), (9, 11), (11, 11)]);
+    assert!(calls.windows(2).all(|w| w[0].0 < w[1].0));
+
+    for i in 0..10u8 {
+        assert_eq!(base_db.get(&[b'k', i]).await.unwrap(), vec![i]);
+    }
+    assert!(!base_db.has(b"stale").await.unwrap());
+    assert!(db.mem.read().await.is_empty());
+
+    assert!(db.commit_with_progress(0, |_, _| {}).await.is_err());
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::database::versiondb::test_iterator_with_tombstones --exact --show-output
+#[tokio::test]
+async fn test_iterator_with_tombstones() {
+    use crate::subnet::rpc::database::{
+        iterator::{Iteratee, Iterator},
+        memdb, Commitable, KeyValueReaderWriterDeleter,
+    };
+
+    let base_db = memdb::Database::new();
+    let batch = base_db.new_batch().await.unwrap();
+    let mut db = Database::new(base_db, batch);
+
+    db.put(b"a", b"1").await.unwrap();
+    db.put(b"b", b"2").await.unwrap();
+    db.commit().await.unwrap();
+
+    // "b" shadows a committed key, "c" never reached the underlying database
+    db.delete(b"b").await.unwrap();
+    db.put(b"c", b"3").await.unwrap();
+    db.delete(b"c").await.unwrap();
+    db.put(b"d", b"4").await.unwrap();
+
+    // hidden by default
+    let mut iterator = db.new_iterator().await.unwrap();
+    let mut keys = Vec::new();
+    while iterator.next().await.unwrap() {
+        keys.push(iterator.key().await.unwrap().to_vec());
+    }
+    assert_eq!(keys, vec![b"a".to_vec(), b"d".to_vec()]);
+
+    let mut iterator = db
+        .new_iterator_with_tombstones(&[], &[], true)
+        .await
+        .unwrap();
+    let mut entries = Vec::new();
+    while iterator.next().await.unwrap() {
+        entries.push((
+            iterator.key().await.unwrap().to_vec(),
+            iterator.value().await.unwrap().to_vec(),
+            iterator.is_tombstone(),
+        ));
+    }
+    assert_eq!(
+        entries,
+        vec![
+            (b"a".to_vec(), b"1".to_vec(), false),
+            (b"b".to_vec(), vec![], true),
+            (b"c".to_vec(), vec![], true),
+            (b"d".to_vec(), b"4".to_vec(), false),
+        ]
+    );
+    assert!(!iterator.is_tombstone());
+    assert!(iterator.error().await.is_ok());
+}
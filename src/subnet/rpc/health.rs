@@ -1,5 +1,58 @@
 //! Provides health checking.
-use std::io::Result;
+use std::{
+    io::{self, Result},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+/// Result of a health check, as the JSON details avalanchego reports.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthResult {
+    pub healthy: bool,
+    /// Check-specific details, "null" if none.
+    pub details: Option<serde_json::Value>,
+    /// Why the check failed, "null" when healthy.
+    pub error: Option<String>,
+    #[serde_as(as = "crate::codec::serde::rfc_3339::DateTimeUtc")]
+    pub timestamp: DateTime<Utc>,
+}
+
+impl HealthResult {
+    /// Reports healthy as of now.
+    pub fn healthy(details: Option<serde_json::Value>) -> Self {
+        Self {
+            healthy: true,
+            details,
+            error: None,
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// Reports unhealthy as of now, because of the error.
+    pub fn unhealthy(error: &io::Error) -> Self {
+        Self {
+            healthy: false,
+            details: None,
+            error: Some(error.to_string()),
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// Returns the JSON-encoded bytes, as returned by [`Checkable::health_check`].
+    pub fn to_vec(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(self).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                format!("failed serde_json::to_vec '{}'", e),
+            )
+        })
+    }
+}
 
 /// Checkable can have its health checked
 ///
@@ -7,4 +60,187 @@ use std::io::Result;
 #[tonic::async_trait]
 pub trait Checkable {
     async fn health_check(&self) -> Result<Vec<u8>>;
+
+    /// Returns an error if the process is wedged and should be restarted
+    /// (e.g., a Kubernetes liveness probe). Defaults to always alive.
+    async fn liveness(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Returns an error if it is alive but cannot serve requests yet, or
+    /// anymore (e.g., a Kubernetes readiness probe). Defaults to the
+    /// health check.
+    async fn readiness(&self) -> Result<()> {
+        self.health_check().await.map(|_| ())
+    }
+}
+
+/// Called with the new result when the probed health flips between healthy
+/// and unhealthy.
+pub type TransitionCallback = Arc<dyn Fn(&HealthResult) + Send + Sync>;
+
+/// Periodically checks the health of a [`Checkable`] (e.g., a layered
+/// database) in the background, so that failures are detected before a
+/// caller asks. Clones share the latest result.
+#[derive(Clone)]
+pub struct Prober {
+    checkable: Arc<dyn Checkable + Send + Sync>,
+    interval: Duration,
+    on_transition: Option<TransitionCallback>,
+    latest: Arc<Mutex<Option<HealthResult>>>,
+}
+
+impl Prober {
+    pub fn new(checkable: Arc<dyn Checkable + Send + Sync>, interval: Duration) -> Self {
+        Self {
+            checkable,
+            interval,
+            on_transition: None,
+            latest: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Sets the callback fired when the health flips, but not on the
+    /// first probe.
+    #[must_use]
+    pub fn with_on_transition(mut self, on_transition: TransitionCallback) -> Self {
+        self.on_transition = Some(on_transition);
+        self
+    }
+
+    /// Returns the result of the latest probe, or "None" before the first.
+    pub fn latest(&self) -> Option<HealthResult> {
+        self.latest.lock().unwrap().clone()
+    }
+
+    /// Checks the health once, caches and returns the result. A failed
+    /// check is unhealthy, and a check returning other than a JSON
+    /// [`HealthResult`] is healthy with no details.
+    pub async fn probe(&self) -> HealthResult {
+        let result = match self.checkable.health_check().await {
+            Ok(check) => {
+                serde_json::from_slice(&check).unwrap_or_else(|_| HealthResult::healthy(None))
+            }
+            Err(e) => HealthResult::unhealthy(&e),
+        };
+
+        let previous = self.latest.lock().unwrap().replace(result.clone());
+        if let Some(previous) = previous {
+            if previous.healthy != result.healthy {
+                log::info!(
+                    "health changed from {} to {}",
+                    previous.healthy,
+                    result.healthy
+                );
+                if let Some(on_transition) = &self.on_transition {
+                    on_transition(&result);
+                }
+            }
+        }
+        result
+    }
+
+    /// Probes every interval, starting now, until the returned task is
+    /// aborted.
+    pub fn start(&self) -> tokio::task::JoinHandle<()> {
+        let prober = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(prober.interval);
+            loop {
+                ticker.tick().await;
+                prober.probe().await;
+            }
+        })
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::health::test_health_result --exact --show-output
+#[test]
+fn test_health_result() {
+    use chrono::TimeZone;
+
+    let timestamp = Utc.with_ymd_and_hms(2023, 5, 1, 12, 0, 0).unwrap();
+    let healthy = HealthResult {
+        timestamp,
+        ..HealthResult::healthy(Some(serde_json::json!({ "keys": 3 })))
+    };
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(&healthy.to_vec().unwrap()).unwrap(),
+        serde_json::json!({
+            "healthy": true,
+            "details": { "keys": 3 },
+            "error": null,
+            "timestamp": "2023-05-01T12:00:00.000Z",
+        })
+    );
+
+    let unhealthy = HealthResult {
+        timestamp,
+        ..HealthResult::unhealthy(&io::Error::new(io::ErrorKind::Other, "database closed"))
+    };
+    let bytes = unhealthy.to_vec().unwrap();
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(&bytes).unwrap(),
+        serde_json::json!({
+            "healthy": false,
+            "details": null,
+            "error": "database closed",
+            "timestamp": "2023-05-01T12:00:00.000Z",
+        })
+    );
+    assert_eq!(
+        serde_json::from_slice::<HealthResult>(&bytes).unwrap(),
+        unhealthy
+    );
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::health::test_prober --exact --show-output
+#[tokio::test]
+async fn test_prober() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Healthy for the first "healthy_checks" checks, then failing.
+    struct Flipping {
+        checks: AtomicUsize,
+        healthy_checks: usize,
+    }
+
+    #[tonic::async_trait]
+    impl Checkable for Flipping {
+        async fn health_check(&self) -> Result<Vec<u8>> {
+            if self.checks.fetch_add(1, Ordering::SeqCst) < self.healthy_checks {
+                return HealthResult::healthy(None).to_vec();
+            }
+            Err(io::Error::new(io::ErrorKind::Other, "database closed"))
+        }
+    }
+
+    let checker = Arc::new(Flipping {
+        checks: AtomicUsize::new(0),
+        healthy_checks: 3,
+    });
+    let transitions = Arc::new(Mutex::new(Vec::new()));
+    let prober = Prober::new(checker.clone(), Duration::from_millis(5)).with_on_transition({
+        let transitions = Arc::clone(&transitions);
+        Arc::new(move |result: &HealthResult| transitions.lock().unwrap().push(result.clone()))
+    });
+    assert_eq!(prober.latest(), None);
+
+    let handle = prober.start();
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while checker.checks.load(Ordering::SeqCst) < 10 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .unwrap();
+    handle.abort();
+
+    let transitions = transitions.lock().unwrap().clone();
+    assert_eq!(transitions.len(), 1);
+    assert!(!transitions[0].healthy);
+    assert_eq!(transitions[0].error.as_deref(), Some("database closed"));
+
+    let latest = prober.latest().unwrap();
+    assert!(!latest.healthy);
 }
@@ -0,0 +1,202 @@
+//! Bounded transaction mempool for subnet VMs.
+//!
+//! The mempool reports its [`Pressure`] from how full it is, so the VM can
+//! build a block early (e.g., by sending "PendingTxs" to the engine) before
+//! new transactions are rejected.
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{self, Error, ErrorKind},
+    sync::{Arc, Mutex},
+};
+
+use crate::ids;
+use tokio::sync::watch;
+
+/// Default fill ratio at and above which the pressure is high.
+pub const DEFAULT_HIGH_RATIO: f64 = 0.75;
+
+/// How urgently the mempool needs to be drained into a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Pressure {
+    #[default]
+    Low,
+    /// At or above the high fill ratio.
+    High,
+}
+
+/// FIFO of transactions keyed by ID, with at most "capacity" transactions.
+#[derive(Clone)]
+pub struct Mempool<T> {
+    txs: Arc<Mutex<Txs<T>>>,
+    capacity: usize,
+    high_ratio: f64,
+    pressure: Arc<watch::Sender<Pressure>>,
+}
+
+struct Txs<T> {
+    order: VecDeque<ids::Id>,
+    by_id: HashMap<ids::Id, T>,
+}
+
+impl<T: Clone> Mempool<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self::new_with_high_ratio(capacity, DEFAULT_HIGH_RATIO)
+            .expect("default high ratio is valid")
+    }
+
+    /// Creates a mempool whose pressure is high once it holds at least
+    /// "high_ratio" (in (0, 1]) of its capacity.
+    pub fn new_with_high_ratio(capacity: usize, high_ratio: f64) -> io::Result<Self> {
+        if capacity == 0 {
+            return Err(Error::new(ErrorKind::InvalidInput, "zero mempool capacity"));
+        }
+        if !(high_ratio > 0.0 && high_ratio <= 1.0) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("high ratio {} not in (0, 1]", high_ratio),
+            ));
+        }
+        let (pressure, _) = watch::channel(Pressure::Low);
+        Ok(Self {
+            txs: Arc::new(Mutex::new(Txs {
+                order: VecDeque::new(),
+                by_id: HashMap::new(),
+            })),
+            capacity,
+            high_ratio,
+            pressure: Arc::new(pressure),
+        })
+    }
+
+    /// Returns the current pressure, for the VM to decide to build a block.
+    pub fn pressure(&self) -> Pressure {
+        let len = self.txs.lock().unwrap().order.len();
+        self.pressure_at(len)
+    }
+
+    /// Returns a receiver which is notified each time the pressure changes,
+    /// i.e., when the mempool crosses the high ratio in either direction.
+    pub fn subscribe(&self) -> watch::Receiver<Pressure> {
+        self.pressure.subscribe()
+    }
+
+    /// Adds the transaction. Fails if it is already in the mempool or if the
+    /// mempool is full.
+    pub fn add(&self, id: ids::Id, tx: T) -> io::Result<()> {
+        let mut txs = self.txs.lock().unwrap();
+        if txs.by_id.contains_key(&id) {
+            return Err(Error::new(
+                ErrorKind::AlreadyExists,
+                format!("tx {} already in mempool", id),
+            ));
+        }
+        if txs.order.len() >= self.capacity {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!("mempool is full ({} txs)", self.capacity),
+            ));
+        }
+        txs.order.push_back(id);
+        txs.by_id.insert(id, tx);
+        self.update_pressure(txs.order.len());
+        Ok(())
+    }
+
+    /// Removes and returns the oldest transaction.
+    pub fn pop(&self) -> Option<(ids::Id, T)> {
+        let mut txs = self.txs.lock().unwrap();
+        let id = txs.order.pop_front()?;
+        let tx = txs.by_id.remove(&id)?;
+        self.update_pressure(txs.order.len());
+        Some((id, tx))
+    }
+
+    /// Removes the transaction (e.g., once included in an accepted block).
+    pub fn remove(&self, id: &ids::Id) -> Option<T> {
+        let mut txs = self.txs.lock().unwrap();
+        let tx = txs.by_id.remove(id)?;
+        txs.order.retain(|queued| queued != id);
+        self.update_pressure(txs.order.len());
+        Some(tx)
+    }
+
+    /// Returns the transaction without removing it.
+    pub fn get(&self, id: &ids::Id) -> Option<T> {
+        self.txs.lock().unwrap().by_id.get(id).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.txs.lock().unwrap().order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn pressure_at(&self, len: usize) -> Pressure {
+        if len as f64 >= self.capacity as f64 * self.high_ratio {
+            Pressure::High
+        } else {
+            Pressure::Low
+        }
+    }
+
+    /// Notifies the subscribers only if the pressure changed.
+    fn update_pressure(&self, len: usize) {
+        let pressure = self.pressure_at(len);
+        self.pressure.send_if_modified(|current| {
+            if *current == pressure {
+                return false;
+            }
+            *current = pressure;
+            true
+        });
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::mempool::test_pressure --exact --show-output
+#[tokio::test]
+async fn test_pressure() {
+    use std::time::Duration;
+
+    let mempool = Mempool::new_with_high_ratio(4, 0.5).unwrap();
+    let mut rx = mempool.subscribe();
+    let id = |i: u8| ids::Id::from_slice(&[i; 32]);
+
+    mempool.add(id(1), "tx-1").unwrap();
+    assert_eq!(mempool.pressure(), Pressure::Low);
+    assert!(!rx.has_changed().unwrap());
+
+    // crossing the high ratio notifies the subscriber
+    mempool.add(id(2), "tx-2").unwrap();
+    tokio::time::timeout(Duration::from_secs(1), rx.changed())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(*rx.borrow_and_update(), Pressure::High);
+    assert_eq!(mempool.pressure(), Pressure::High);
+
+    // staying high does not notify again
+    mempool.add(id(3), "tx-3").unwrap();
+    mempool.add(id(4), "tx-4").unwrap();
+    assert!(!rx.has_changed().unwrap());
+    assert!(mempool.add(id(5), "tx-5").is_err());
+    assert_eq!(
+        mempool.add(id(1), "tx-1").unwrap_err().kind(),
+        ErrorKind::AlreadyExists
+    );
+
+    // draining below the ratio notifies the low pressure
+    assert_eq!(mempool.pop(), Some((id(1), "tx-1")));
+    assert_eq!(mempool.remove(&id(3)), Some("tx-3"));
+    assert!(!rx.has_changed().unwrap());
+    assert_eq!(mempool.pop(), Some((id(2), "tx-2")));
+    assert!(rx.has_changed().unwrap());
+    assert_eq!(*rx.borrow_and_update(), Pressure::Low);
+    assert_eq!(mempool.len(), 1);
+    assert_eq!(mempool.get(&id(4)), Some("tx-4"));
+
+    assert!(Mempool::<()>::new_with_high_ratio(0, 0.5).is_err());
+    assert!(Mempool::<()>::new_with_high_ratio(4, 0.0).is_err());
+    assert!(Mempool::<()>::new_with_high_ratio(4, 1.5).is_err());
+}
@@ -1,12 +1,16 @@
+pub mod bootstrap;
 pub mod consensus;
 pub mod context;
 pub mod database;
 pub mod errors;
 pub mod health;
 pub mod http;
+pub mod mempool;
+pub mod plugin;
 pub mod runtime;
 pub mod snow;
 pub mod snowman;
+pub mod uptime;
 pub mod utils;
 pub mod vm;
 
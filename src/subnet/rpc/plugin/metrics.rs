@@ -0,0 +1,178 @@
+//! Aggregation of the Prometheus registries a plugin VM serves to avalanchego.
+//!
+//! avalanchego scrapes a plugin only through the "Gather" call of the VM
+//! service, so the VM's own metrics and those of its database layers must be
+//! merged into a single set of families.
+//! ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/api/metrics#MultiGatherer>
+use std::{
+    collections::BTreeMap,
+    io::{self, Error, ErrorKind},
+    sync::{Arc, RwLock},
+};
+
+use crate::{proto::pb::io::prometheus::client::MetricFamily, subnet::rpc::metrics};
+use prometheus::proto;
+
+/// Gathers the metric families of the VM registry and of every registry
+/// registered afterwards (e.g., one per database layer).
+#[derive(Clone)]
+pub struct Gatherer {
+    /// Registries by namespace, the VM registry being "".
+    registries: Arc<RwLock<BTreeMap<String, prometheus::Registry>>>,
+}
+
+impl Gatherer {
+    pub fn new(vm_registry: prometheus::Registry) -> Self {
+        let mut registries = BTreeMap::new();
+        registries.insert(String::new(), vm_registry);
+        Self {
+            registries: Arc::new(RwLock::new(registries)),
+        }
+    }
+
+    /// Returns the VM registry. Registries share their metrics when cloned,
+    /// so metrics registered to it are gathered.
+    pub fn vm_registry(&self) -> prometheus::Registry {
+        self.registries.read().unwrap()[""].clone()
+    }
+
+    /// Adds the registry, prefixing its family names with "{namespace}_".
+    /// Fails if the namespace is empty or already registered.
+    pub fn register(&self, namespace: &str, registry: prometheus::Registry) -> io::Result<()> {
+        if namespace.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "empty namespace is reserved for the VM registry",
+            ));
+        }
+        let mut registries = self.registries.write().unwrap();
+        if registries.contains_key(namespace) {
+            return Err(Error::new(
+                ErrorKind::AlreadyExists,
+                format!("namespace '{}' already registered", namespace),
+            ));
+        }
+        registries.insert(namespace.to_owned(), registry);
+        Ok(())
+    }
+
+    /// Returns the merged families of all registries, sorted by name.
+    /// Families of the same name are merged into one, which fails if their
+    /// types differ.
+    pub fn gather(&self) -> io::Result<Vec<proto::MetricFamily>> {
+        let mut merged: BTreeMap<String, proto::MetricFamily> = BTreeMap::new();
+        for (namespace, registry) in self.registries.read().unwrap().iter() {
+            for mut mf in registry.gather() {
+                let name = if namespace.is_empty() {
+                    mf.get_name().to_owned()
+                } else {
+                    format!("{}_{}", namespace, mf.get_name())
+                };
+                mf.set_name(name.clone());
+
+                match merged.get_mut(&name) {
+                    Some(existing) => {
+                        if existing.get_field_type() != mf.get_field_type() {
+                            return Err(Error::new(
+                                ErrorKind::InvalidData,
+                                format!(
+                                    "metric family '{}' gathered as both {:?} and {:?}",
+                                    name,
+                                    existing.get_field_type(),
+                                    mf.get_field_type()
+                                ),
+                            ));
+                        }
+                        for m in mf.take_metric().into_iter() {
+                            existing.mut_metric().push(m);
+                        }
+                    }
+                    None => {
+                        merged.insert(name, mf);
+                    }
+                }
+            }
+        }
+        Ok(merged.into_values().collect())
+    }
+
+    /// Returns the merged families in the form of the "Gather" response.
+    pub fn gather_families(&self) -> io::Result<Vec<MetricFamily>> {
+        Ok(metrics::MetricsFamilies::from(&self.gather()?).mfs)
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet_metrics -- subnet::rpc::plugin::metrics::test_gatherer --exact --show-output
+#[test]
+fn test_gatherer() {
+    use prometheus::{IntCounter, IntGauge};
+
+    let vm_registry = prometheus::Registry::new();
+    let blocks = IntCounter::new("blocks_accepted", "accepted blocks").unwrap();
+    vm_registry.register(Box::new(blocks.clone())).unwrap();
+    blocks.inc_by(3);
+
+    let db_registry = prometheus::Registry::new();
+    let size = IntGauge::new("size", "database size").unwrap();
+    db_registry.register(Box::new(size.clone())).unwrap();
+    size.set(42);
+
+    let gatherer = Gatherer::new(vm_registry);
+    gatherer.register("memdb", db_registry).unwrap();
+    assert!(gatherer
+        .register("memdb", prometheus::Registry::new())
+        .is_err());
+    assert!(gatherer.register("", prometheus::Registry::new()).is_err());
+
+    let families = gatherer.gather_families().unwrap();
+    let names: Vec<&str> = families
+        .iter()
+        .map(|mf| mf.name.as_deref().unwrap())
+        .collect();
+    assert_eq!(names, vec!["blocks_accepted", "memdb_size"]);
+    assert_eq!(
+        families[0].metric[0].counter.as_ref().unwrap().value,
+        Some(3.0)
+    );
+    assert_eq!(
+        families[1].metric[0].gauge.as_ref().unwrap().value,
+        Some(42.0)
+    );
+
+    // metrics registered to the VM registry afterwards are gathered
+    gatherer
+        .vm_registry()
+        .register(Box::new(IntGauge::new("height", "last height").unwrap()))
+        .unwrap();
+    assert_eq!(gatherer.gather().unwrap().len(), 3);
+
+    // same-named families are merged into one
+    let vm_registry = prometheus::Registry::new();
+    let vm_size = IntGauge::with_opts(
+        prometheus::Opts::new("memdb_size", "database size").const_label("db", "vm"),
+    )
+    .unwrap();
+    vm_registry.register(Box::new(vm_size)).unwrap();
+    let db_registry = prometheus::Registry::new();
+    db_registry
+        .register(Box::new(IntGauge::new("size", "database size").unwrap()))
+        .unwrap();
+    let gatherer = Gatherer::new(vm_registry);
+    gatherer.register("memdb", db_registry).unwrap();
+    let families = gatherer.gather().unwrap();
+    assert_eq!(families.len(), 1);
+    assert_eq!(families[0].get_metric().len(), 2);
+
+    // unless their types differ
+    let vm_registry = prometheus::Registry::new();
+    vm_registry
+        .register(Box::new(IntCounter::new("memdb_size", "counter").unwrap()))
+        .unwrap();
+    let db_registry = prometheus::Registry::new();
+    db_registry
+        .register(Box::new(IntGauge::new("size", "database size").unwrap()))
+        .unwrap();
+    let gatherer = Gatherer::new(vm_registry);
+    gatherer.register("memdb", db_registry).unwrap();
+    assert!(gatherer.gather().is_err());
+}
@@ -0,0 +1,340 @@
+#[cfg(feature = "subnet_metrics")]
+#[cfg_attr(docsrs, doc(cfg(feature = "subnet_metrics")))]
+pub mod metrics;
+pub mod shutdown;
+
+use std::{
+    io::{Error, ErrorKind, Result, Write},
+    net::SocketAddr,
+};
+
+use crate::{
+    proto::{
+        pb::{
+            self,
+            google::protobuf::Empty,
+            rpcdb::database_server::DatabaseServer,
+            vm::{
+                self,
+                vm_server::{Vm, VmServer},
+            },
+        },
+        PROTOCOL_VERSION,
+    },
+    subnet::rpc::{
+        database::{rpcdb, BoxedDatabase},
+        utils,
+    },
+};
+use tokio::sync::broadcast::{self, Receiver};
+use tonic::{
+    transport::server::{NamedService, Router},
+    Request, Response, Status,
+};
+use tonic_health::server::health_reporter;
+
+/// Vm service served by [`serve`], e.g., a boxed
+/// [`crate::subnet::rpc::vm::server::Server`].
+pub type BoxedVm = Box<dyn Vm>;
+
+/// Calls the macro with every RPC of the [`Vm`] service, as
+/// "method(request) -> response;".
+macro_rules! with_vm_rpcs {
+    ($callback:ident) => {
+        $callback! {
+            initialize(vm::InitializeRequest) -> vm::InitializeResponse;
+            set_state(vm::SetStateRequest) -> vm::SetStateResponse;
+            shutdown(Empty) -> Empty;
+            create_handlers(Empty) -> vm::CreateHandlersResponse;
+            create_static_handlers(Empty) -> vm::CreateStaticHandlersResponse;
+            connected(vm::ConnectedRequest) -> Empty;
+            disconnected(vm::DisconnectedRequest) -> Empty;
+            build_block(vm::BuildBlockRequest) -> vm::BuildBlockResponse;
+            parse_block(vm::ParseBlockRequest) -> vm::ParseBlockResponse;
+            get_block(vm::GetBlockRequest) -> vm::GetBlockResponse;
+            set_preference(vm::SetPreferenceRequest) -> Empty;
+            health(Empty) -> vm::HealthResponse;
+            version(Empty) -> vm::VersionResponse;
+            app_request(vm::AppRequestMsg) -> Empty;
+            app_request_failed(vm::AppRequestFailedMsg) -> Empty;
+            app_response(vm::AppResponseMsg) -> Empty;
+            app_gossip(vm::AppGossipMsg) -> Empty;
+            gather(Empty) -> vm::GatherResponse;
+            cross_chain_app_request(vm::CrossChainAppRequestMsg) -> Empty;
+            cross_chain_app_request_failed(vm::CrossChainAppRequestFailedMsg) -> Empty;
+            cross_chain_app_response(vm::CrossChainAppResponseMsg) -> Empty;
+            get_ancestors(vm::GetAncestorsRequest) -> vm::GetAncestorsResponse;
+            batched_parse_block(vm::BatchedParseBlockRequest) -> vm::BatchedParseBlockResponse;
+            verify_height_index(Empty) -> vm::VerifyHeightIndexResponse;
+            get_block_id_at_height(vm::GetBlockIdAtHeightRequest) -> vm::GetBlockIdAtHeightResponse;
+            state_sync_enabled(Empty) -> vm::StateSyncEnabledResponse;
+            get_ongoing_sync_state_summary(Empty) -> vm::GetOngoingSyncStateSummaryResponse;
+            get_last_state_summary(Empty) -> vm::GetLastStateSummaryResponse;
+            parse_state_summary(vm::ParseStateSummaryRequest) -> vm::ParseStateSummaryResponse;
+            get_state_summary(vm::GetStateSummaryRequest) -> vm::GetStateSummaryResponse;
+            block_verify(vm::BlockVerifyRequest) -> vm::BlockVerifyResponse;
+            block_accept(vm::BlockAcceptRequest) -> Empty;
+            block_reject(vm::BlockRejectRequest) -> Empty;
+            state_summary_accept(vm::StateSummaryAcceptRequest) -> vm::StateSummaryAcceptResponse;
+        }
+    };
+}
+
+macro_rules! forward_vm_rpcs {
+    ($($method:ident($req:ty) -> $resp:ty;)*) => {
+        #[tonic::async_trait]
+        impl Vm for BoxedVm {
+            $(
+                async fn $method(
+                    &self,
+                    request: Request<$req>,
+                ) -> std::result::Result<Response<$resp>, Status> {
+                    (**self).$method(request).await
+                }
+            )*
+        }
+    };
+}
+
+with_vm_rpcs!(forward_vm_rpcs);
+
+/// ref. <https://github.com/ava-labs/avalanchego/blob/v1.9.5/version/constants.go#L15-L17>
+struct HandshakeConfig {
+    protocol_version: u32,
+}
+
+impl HandshakeConfig {
+    pub fn new() -> Self {
+        Self {
+            protocol_version: PROTOCOL_VERSION,
+        }
+    }
+}
+
+struct Plugin;
+
+impl NamedService for Plugin {
+    const NAME: &'static str = "plugin";
+}
+
+/// Returns the go-plugin handshake line for the server listening on the address.
+/// ref. <https://github.com/hashicorp/go-plugin/blob/master/docs/guide-plugin-write-non-go.md#4-output-handshake-information>
+pub fn handshake_message(addr: &SocketAddr) -> String {
+    let handshake_config = HandshakeConfig::new();
+    format!("1|{}|tcp|{}|grpc|", handshake_config.protocol_version, addr)
+}
+
+/// Starts the go-plugin gRPC server for the VM on a local address, serving
+/// the database over rpcdb, until the process receives SIGTERM.
+///
+/// Nodes launching the VM as a go-plugin read the handshake message from
+/// stdout. Nodes running the rpcchainvm runtime instead pass the runtime
+/// engine address in the environment, which [`crate::subnet::rpc::vm::serve`]
+/// dials. Both serve the same health, reflection and Vm services.
+pub async fn serve(vm: BoxedVm, db: BoxedDatabase) -> Result<()> {
+    // TODO: Add support for abstract unix sockets once supported by tonic.
+    // ref. https://github.com/hyperium/tonic/issues/966
+    // avalanchego currently only supports plugins listening on IP address.
+    let addr = utils::new_socket_addr();
+
+    // never sent, so that only SIGTERM stops the server
+    let (_stop_tx, stop_ch) = broadcast::channel(1);
+
+    // handshake message must be printed to stdout
+    // TODO: remove this once go-plugin is deprecated in avalanchego
+    serve_with_address(vm, db, addr, stop_ch, std::io::stdout()).await
+}
+
+/// Serves the VM, the database over rpcdb, and the health services on the
+/// address, writing the handshake message to "out", until the stop signal
+/// is broadcasted or the process receives SIGTERM.
+pub async fn serve_with_address<W: Write>(
+    vm: BoxedVm,
+    db: BoxedDatabase,
+    addr: SocketAddr,
+    stop_ch: Receiver<()>,
+    out: W,
+) -> Result<()> {
+    let router = vm_router::<Plugin, _>(vm)
+        .await?
+        .add_service(DatabaseServer::new(rpcdb::server::Server::new(db)));
+    serve_router(router, addr, stop_ch, out).await
+}
+
+/// Returns the router serving the Vm service, the reflection service and
+/// the health service, with "H" reported as serving.
+pub(crate) async fn vm_router<H, V>(vm: V) -> Result<Router>
+where
+    H: NamedService,
+    V: Vm,
+{
+    // "go-plugin requires the gRPC Health Checking Service to be registered on your server"
+    // ref. https://github.com/hashicorp/go-plugin/blob/master/docs/guide-plugin-write-non-go.md
+    // ref. https://github.com/hyperium/tonic/blob/v0.7.1/examples/src/health/server.rs
+    let (mut health_reporter, health_svc) = health_reporter();
+    health_reporter.set_serving::<H>().await;
+
+    // ref. https://github.com/hyperium/tonic/blob/v0.7.2/examples/src/reflection/server.rs
+    // ref. https://docs.rs/prost-types/latest/prost_types/struct.FileDescriptorSet.html
+    let reflection_service = tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(pb::rpcdb::FILE_DESCRIPTOR_SET)
+        .register_encoded_file_descriptor_set(pb::vm::FILE_DESCRIPTOR_SET)
+        .register_encoded_file_descriptor_set(pb::google::protobuf::FILE_DESCRIPTOR_SET)
+        .register_encoded_file_descriptor_set(pb::io::prometheus::client::FILE_DESCRIPTOR_SET)
+        .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
+        .build()
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed to create gRPC reflection service: {:?}", e),
+            )
+        })?;
+
+    Ok(utils::grpc::default_server()
+        .add_service(health_svc)
+        .add_service(reflection_service)
+        .add_service(VmServer::new(vm)))
+}
+
+/// Writes the handshake message to "out" and serves the routes until shutdown.
+async fn serve_router<W: Write>(
+    router: Router,
+    addr: SocketAddr,
+    stop_ch: Receiver<()>,
+    mut out: W,
+) -> Result<()> {
+    log::info!("plugin listening on address {:?}", addr);
+
+    writeln!(out, "{}", handshake_message(&addr))?;
+    out.flush()?;
+
+    router
+        .serve_with_shutdown(addr, shutdown_signal(stop_ch))
+        .await
+        .map_err(|e| Error::new(ErrorKind::Other, format!("grpc server failed: {:?}", e)))?;
+    log::info!("grpc server shutdown complete: {}", addr);
+
+    Ok(())
+}
+
+/// Resolves on the broadcasted stop signal or SIGTERM, whichever comes first.
+async fn shutdown_signal(mut stop_ch: Receiver<()>) {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    _ = stop_ch.recv() => {}
+                    _ = sigterm.recv() => log::info!("received SIGTERM, shutting down"),
+                }
+            }
+            Err(e) => {
+                log::warn!("failed to register SIGTERM handler: {}", e);
+                let _ = stop_ch.recv().await;
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = stop_ch.recv().await;
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::plugin::test_serve_with_address --exact --show-output
+#[tokio::test]
+async fn test_serve_with_address() {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use crate::{proto::pb::vm::vm_client::VmClient, subnet::rpc::database::memdb};
+    use tonic_health::pb::{health_client::HealthClient, HealthCheckRequest};
+
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Records the RPCs it receives, answering each with an empty response.
+    #[derive(Clone, Default)]
+    struct TestVm(Arc<Mutex<Vec<&'static str>>>);
+
+    macro_rules! record_vm_rpcs {
+        ($($method:ident($req:ty) -> $resp:ty;)*) => {
+            #[tonic::async_trait]
+            impl Vm for TestVm {
+                $(
+                    async fn $method(
+                        &self,
+                        _request: Request<$req>,
+                    ) -> std::result::Result<Response<$resp>, Status> {
+                        self.0.lock().unwrap().push(stringify!($method));
+                        Ok(Response::new(<$resp>::default()))
+                    }
+                )*
+            }
+        };
+    }
+
+    with_vm_rpcs!(record_vm_rpcs);
+
+    let (tx, rx) = broadcast::channel(1);
+    let addr = utils::new_socket_addr();
+
+    let mut db = memdb::Database::new();
+    db.put(b"foo", b"bar").await.unwrap();
+
+    let vm = TestVm::default();
+    let out = SharedBuf::default();
+    let handle = tokio::spawn(serve_with_address(
+        Box::new(vm.clone()),
+        db,
+        addr,
+        rx,
+        out.clone(),
+    ));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let line = String::from_utf8(out.0.lock().unwrap().clone()).unwrap();
+    assert_eq!(line, format!("1|{}|tcp|{}|grpc|\n", PROTOCOL_VERSION, addr));
+    let fields: Vec<&str> = line.trim_end().split('|').collect();
+    assert_eq!(fields.len(), 6);
+    assert_eq!(fields[3].parse::<SocketAddr>().unwrap(), addr);
+
+    let client_conn =
+        tonic::transport::Channel::builder(format!("http://{}", addr).parse().unwrap())
+            .connect()
+            .await
+            .unwrap();
+
+    let mut health = HealthClient::new(client_conn.clone());
+    let resp = health
+        .check(HealthCheckRequest {
+            service: Plugin::NAME.to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(resp.into_inner().status, 1); // SERVING
+
+    let mut client = VmClient::new(client_conn.clone());
+    client.version(Empty {}).await.unwrap();
+    assert_eq!(*vm.0.lock().unwrap(), vec!["version"]);
+
+    let db = rpcdb::client::DatabaseClient::new(client_conn);
+    assert_eq!(db.get(b"foo").await.unwrap(), b"bar");
+
+    tx.send(()).unwrap();
+    handle.await.unwrap().unwrap();
+}
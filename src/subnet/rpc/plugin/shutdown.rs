@@ -0,0 +1,133 @@
+//! Ordered shutdown of the VM subsystems.
+use std::{
+    future::Future,
+    io::{Error, ErrorKind, Result},
+    time::Duration,
+};
+
+use futures::future::BoxFuture;
+
+type CloseFn = Box<dyn FnOnce() -> BoxFuture<'static, Result<()>> + Send>;
+
+/// Closer registered with the shutdown sequence.
+struct Closer {
+    name: String,
+    timeout: Duration,
+    close: CloseFn,
+}
+
+/// Runs the registered closers in the reverse order of registration, like
+/// deferred calls: register the database first and the mempool last to
+/// drain the mempool, then flush the state, then close the database.
+///
+/// Every closer runs even if an earlier one fails or times out, and all the
+/// failures are reported together.
+#[derive(Default)]
+pub struct ShutdownSequence {
+    closers: Vec<Closer>,
+}
+
+impl ShutdownSequence {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the closer, which fails if it does not complete within
+    /// "timeout".
+    pub fn register<F, Fut>(&mut self, name: &str, timeout: Duration, close: F)
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.closers.push(Closer {
+            name: name.to_string(),
+            timeout,
+            close: Box::new(move || Box::pin(close())),
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.closers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.closers.is_empty()
+    }
+
+    /// Runs all the closers, last registered first. Returns an error listing
+    /// every closer that failed or timed out, in the order they ran.
+    pub async fn run(self) -> Result<()> {
+        let mut failures = Vec::new();
+        for closer in self.closers.into_iter().rev() {
+            log::info!("shutting down '{}'", closer.name);
+            let res = match tokio::time::timeout(closer.timeout, (closer.close)()).await {
+                Ok(res) => res,
+                Err(_) => Err(Error::new(
+                    ErrorKind::TimedOut,
+                    format!("timed out after {:?}", closer.timeout),
+                )),
+            };
+            if let Err(e) = res {
+                log::warn!("failed to shut down '{}': {}", closer.name, e);
+                failures.push(format!("'{}' ({})", closer.name, e));
+            }
+        }
+
+        if failures.is_empty() {
+            return Ok(());
+        }
+        Err(Error::new(
+            ErrorKind::Other,
+            format!("shutdown failed: {}", failures.join(", ")),
+        ))
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::plugin::shutdown::test_shutdown_sequence --exact --show-output
+#[tokio::test]
+async fn test_shutdown_sequence() {
+    use std::sync::{Arc, Mutex};
+
+    let ran = Arc::new(Mutex::new(Vec::new()));
+    let mut seq = ShutdownSequence::new();
+    assert!(seq.is_empty());
+
+    let db_ran = ran.clone();
+    seq.register("db", Duration::from_secs(1), move || async move {
+        db_ran.lock().unwrap().push("db");
+        Ok(())
+    });
+    let state_ran = ran.clone();
+    seq.register("state", Duration::from_secs(1), move || async move {
+        state_ran.lock().unwrap().push("state");
+        Err(Error::new(ErrorKind::Other, "flush failed"))
+    });
+    let mempool_ran = ran.clone();
+    seq.register("mempool", Duration::from_secs(1), move || async move {
+        mempool_ran.lock().unwrap().push("mempool");
+        Ok(())
+    });
+    assert_eq!(seq.len(), 3);
+
+    let err = seq.run().await.unwrap_err();
+    assert_eq!(*ran.lock().unwrap(), vec!["mempool", "state", "db"]);
+    assert_eq!(err.to_string(), "shutdown failed: 'state' (flush failed)");
+
+    // timeouts are reported without holding up the rest
+    let ran = Arc::new(Mutex::new(Vec::new()));
+    let mut seq = ShutdownSequence::new();
+    let db_ran = ran.clone();
+    seq.register("db", Duration::from_secs(1), move || async move {
+        db_ran.lock().unwrap().push("db");
+        Ok(())
+    });
+    seq.register("stuck", Duration::from_millis(10), || async {
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        Ok(())
+    });
+    let err = seq.run().await.unwrap_err();
+    assert_eq!(*ran.lock().unwrap(), vec!["db"]);
+    assert!(err.to_string().contains("'stuck' (timed out after 10ms)"));
+
+    assert!(ShutdownSequence::new().run().await.is_ok());
+}
@@ -1,7 +1,16 @@
-use std::io::Result;
+use std::{
+    collections::HashMap,
+    io::{Error, ErrorKind, Result},
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
 
-use crate::ids;
+use crate::{ids, subnet::rpc::snow::engine::common::appsender::AppSender};
 use chrono::{DateTime, Utc};
+use tokio::sync::oneshot;
 
 /// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/snow/engine/common#NetworkAppHandler>
 #[tonic::async_trait]
@@ -49,3 +58,245 @@ pub trait CrossChainAppHandler {
 // Defines how a consensus engine reacts to app specific messages.
 // Functions only return fatal errors.
 pub trait AppHandler: NetworkAppHandler + CrossChainAppHandler {}
+
+/// Correlates outbound cross-chain app requests with their responses by
+/// destination chain and request ID, failing requests that are not answered
+/// within the timeout. Feed it from the [`CrossChainAppHandler`] callbacks.
+#[derive(Clone)]
+pub struct CrossChainRequests {
+    timeout: Duration,
+    next_request_id: Arc<AtomicU32>,
+    pending: Arc<Mutex<HashMap<(ids::Id, u32), PendingResponse>>>,
+}
+
+type PendingResponse = oneshot::Sender<Result<Vec<u8>>>;
+
+impl CrossChainRequests {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            next_request_id: Arc::new(AtomicU32::new(0)),
+            pending: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Sends the request to the chain and waits for its response.
+    pub async fn request(
+        &self,
+        sender: &(dyn AppSender + Send + Sync),
+        chain_id: ids::Id,
+        request: Vec<u8>,
+    ) -> Result<Vec<u8>> {
+        let request_id = self.next_request_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        self.pending
+            .lock()
+            .unwrap()
+            .insert((chain_id, request_id), tx);
+
+        if let Err(e) = sender
+            .send_cross_chain_app_request(chain_id, request_id, request)
+            .await
+        {
+            self.pending.lock().unwrap().remove(&(chain_id, request_id));
+            return Err(e);
+        }
+
+        match tokio::time::timeout(self.timeout, rx).await {
+            Ok(Ok(resp)) => resp,
+            Ok(Err(_)) => Err(Error::new(
+                ErrorKind::Other,
+                format!("cross-chain request {request_id} to {chain_id} dropped"),
+            )),
+            Err(_) => {
+                self.pending.lock().unwrap().remove(&(chain_id, request_id));
+                Err(Error::new(
+                    ErrorKind::TimedOut,
+                    format!(
+                        "cross-chain request {request_id} to {chain_id} timed out after {:?}",
+                        self.timeout
+                    ),
+                ))
+            }
+        }
+    }
+
+    /// Completes the pending request with the response from the chain.
+    /// Returns false if no such request is pending (e.g., it timed out).
+    pub fn respond(&self, chain_id: &ids::Id, request_id: u32, response: &[u8]) -> bool {
+        self.complete(chain_id, request_id, Ok(response.to_vec()))
+    }
+
+    /// Fails the pending request to the chain.
+    /// Returns false if no such request is pending.
+    pub fn fail(&self, chain_id: &ids::Id, request_id: u32) -> bool {
+        self.complete(
+            chain_id,
+            request_id,
+            Err(Error::new(
+                ErrorKind::Other,
+                format!("cross-chain request {request_id} to {chain_id} failed"),
+            )),
+        )
+    }
+
+    fn complete(&self, chain_id: &ids::Id, request_id: u32, resp: Result<Vec<u8>>) -> bool {
+        let tx = self
+            .pending
+            .lock()
+            .unwrap()
+            .remove(&(*chain_id, request_id));
+        match tx {
+            Some(tx) => tx.send(resp).is_ok(),
+            None => false,
+        }
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::snow::engine::common::engine::test_cross_chain_round_trip --exact --show-output
+#[tokio::test]
+async fn test_cross_chain_round_trip() {
+    /// Requesting VM, which forwards responses to its pending requests.
+    struct RequesterVm {
+        requests: CrossChainRequests,
+    }
+
+    #[tonic::async_trait]
+    impl CrossChainAppHandler for RequesterVm {
+        async fn cross_chain_app_request(
+            &self,
+            _chain_id: &ids::Id,
+            _request_id: u32,
+            _deadline: DateTime<Utc>,
+            _request: &[u8],
+        ) -> Result<()> {
+            Ok(())
+        }
+        async fn cross_chain_app_request_failed(
+            &self,
+            chain_id: &ids::Id,
+            request_id: u32,
+        ) -> Result<()> {
+            self.requests.fail(chain_id, request_id);
+            Ok(())
+        }
+        async fn cross_chain_app_response(
+            &self,
+            chain_id: &ids::Id,
+            request_id: u32,
+            response: &[u8],
+        ) -> Result<()> {
+            self.requests.respond(chain_id, request_id, response);
+            Ok(())
+        }
+    }
+
+    /// Routes requests to the destination chain, which echoes them reversed,
+    /// drops them, or fails them.
+    #[derive(Clone)]
+    struct Router {
+        requester: Arc<RequesterVm>,
+        echo_chain: ids::Id,
+        failing_chain: ids::Id,
+    }
+
+    #[tonic::async_trait]
+    impl AppSender for Router {
+        async fn send_app_request(
+            &self,
+            _node_ids: ids::node::Set,
+            _request_id: u32,
+            _request: Vec<u8>,
+        ) -> Result<()> {
+            Ok(())
+        }
+        async fn send_app_response(
+            &self,
+            _node_id: ids::node::Id,
+            _request_id: u32,
+            _response: Vec<u8>,
+        ) -> Result<()> {
+            Ok(())
+        }
+        async fn send_app_gossip(&self, _msg: Vec<u8>) -> Result<()> {
+            Ok(())
+        }
+        async fn send_app_gossip_specific(
+            &self,
+            _node_ids: ids::node::Set,
+            _msg: Vec<u8>,
+        ) -> Result<()> {
+            Ok(())
+        }
+        async fn send_cross_chain_app_request(
+            &self,
+            chain_id: ids::Id,
+            request_id: u32,
+            mut request: Vec<u8>,
+        ) -> Result<()> {
+            let requester = Arc::clone(&self.requester);
+            if chain_id == self.echo_chain {
+                tokio::spawn(async move {
+                    request.reverse();
+                    requester
+                        .cross_chain_app_response(&chain_id, request_id, &request)
+                        .await
+                });
+            } else if chain_id == self.failing_chain {
+                tokio::spawn(async move {
+                    requester
+                        .cross_chain_app_request_failed(&chain_id, request_id)
+                        .await
+                });
+            }
+            Ok(())
+        }
+        async fn send_cross_chain_app_response(
+            &self,
+            _chain_id: ids::Id,
+            _request_id: u32,
+            _response: Vec<u8>,
+        ) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    let requests = CrossChainRequests::new(Duration::from_millis(100));
+    let router = Router {
+        requester: Arc::new(RequesterVm {
+            requests: requests.clone(),
+        }),
+        echo_chain: ids::Id::from_slice(&[1; 32]),
+        failing_chain: ids::Id::from_slice(&[2; 32]),
+    };
+
+    let resp = requests
+        .request(&router, router.echo_chain, vec![1, 2, 3])
+        .await
+        .unwrap();
+    assert_eq!(resp, vec![3, 2, 1]);
+
+    // responses are correlated per request
+    let (a, b) = tokio::join!(
+        requests.request(&router, router.echo_chain, vec![4, 5]),
+        requests.request(&router, router.echo_chain, vec![6, 7]),
+    );
+    assert_eq!(a.unwrap(), vec![5, 4]);
+    assert_eq!(b.unwrap(), vec![7, 6]);
+
+    let err = requests
+        .request(&router, router.failing_chain, vec![1])
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Other);
+
+    let silent_chain = ids::Id::from_slice(&[3; 32]);
+    let err = requests
+        .request(&router, silent_chain, vec![1])
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TimedOut);
+
+    // late responses are ignored
+    assert!(!requests.respond(&silent_chain, 4, &[1]));
+}
@@ -43,7 +43,9 @@ pub trait CommonVm: AppHandler + Connector + Checkable {
     ) -> Result<()>;
     async fn set_state(&self, state: State) -> Result<()>;
     async fn shutdown(&self) -> Result<()>;
-    async fn version(&self) -> Result<String>;
+    /// Returns the semantic version of the VM, reported to avalanchego
+    /// through the "Version" RPC of the plugin.
+    async fn version(&self) -> Result<semver::Version>;
     async fn create_static_handlers(
         &mut self,
     ) -> Result<HashMap<String, HttpHandler<Self::StaticHandler>>>;
@@ -58,6 +60,14 @@ pub trait CommonVm: AppHandler + Connector + Checkable {
 pub trait Connector {
     async fn connected(&self, id: &ids::node::Id) -> Result<()>;
     async fn disconnected(&self, id: &ids::node::Id) -> Result<()>;
+
+    /// Called by the VM server when the node connects to the peer, with the
+    /// peer's application version (e.g., "avalanche/1.10.0"). Defaults to
+    /// [`Connector::connected`], ignoring the version.
+    async fn connected_with_version(&self, id: &ids::node::Id, version: &str) -> Result<()> {
+        log::debug!("peer {} connected with version {}", id, version);
+        self.connected(id).await
+    }
 }
 
 /// TODO: Currently not implemented
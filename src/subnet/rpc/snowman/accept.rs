@@ -0,0 +1,141 @@
+//! Atomic commit of the state changes of an accepted block.
+use std::io::{Error, ErrorKind, Result};
+
+use crate::{
+    ids::Id,
+    subnet::rpc::{
+        consensus::snowman::Block,
+        database::{versiondb, BoxedDatabase, Commitable, KeyValueReaderWriterDeleter},
+        errors,
+    },
+};
+
+/// Key of the ID of the last accepted block.
+pub const LAST_ACCEPTED_KEY: &[u8] = b"last_accepted";
+
+/// Commits the state changes of the accepted block, pending in the
+/// versiondb, together with the last accepted pointer. Both land in the
+/// same batch write to the underlying database, so that a crash cannot
+/// leave the state of one block with the pointer of another.
+pub async fn commit_block<B: Block + ?Sized>(
+    block: &B,
+    batch: &mut versiondb::Database,
+) -> Result<()> {
+    let id = block.id().await;
+    batch.put(LAST_ACCEPTED_KEY, id.as_ref()).await?;
+    batch.commit().await
+}
+
+/// Returns the ID of the last accepted block, or "None" if no block was
+/// committed yet.
+pub async fn get_last_accepted(db: &BoxedDatabase) -> Result<Option<Id>> {
+    let value = match db.get(LAST_ACCEPTED_KEY).await {
+        Ok(value) => value,
+        Err(e) if errors::is_not_found(&e) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    if value.len() != 32 {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("invalid last accepted ID length {}", value.len()),
+        ));
+    }
+    Ok(Some(Id::from_slice(&value)))
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::snowman::accept::test_commit_block --exact --show-output
+#[tokio::test]
+async fn test_commit_block() {
+    use crate::subnet::rpc::{
+        consensus::snowman::Decidable,
+        database::{batch::Batcher, journaldb, memdb},
+        snowman::block::TimestampBlock,
+    };
+
+    // the state of each block is its height, under the "height" key
+    let journal = journaldb::Database::new(memdb::Database::new());
+    let mut db = versiondb::Database::new(
+        Box::new(journal.clone()),
+        journal.new_batch().await.unwrap(),
+    );
+    assert_eq!(
+        get_last_accepted(&(Box::new(db.clone()) as BoxedDatabase))
+            .await
+            .unwrap(),
+        None
+    );
+
+    let genesis = TimestampBlock::new(Id::empty(), 0, 0, &[]);
+    let block1 = genesis.child(1, b"block1");
+    let block2 = block1.child(2, b"block2");
+    let mut commits = vec![0];
+    for block in [&genesis, &block1, &block2] {
+        db.put(b"height", &block.height().await.to_be_bytes())
+            .await
+            .unwrap();
+        commit_block(block, &mut db).await.unwrap();
+        commits.push(journal.journal().await.len());
+    }
+    assert_eq!(
+        get_last_accepted(&(Box::new(db.clone()) as BoxedDatabase))
+            .await
+            .unwrap(),
+        Some(block2.id().await)
+    );
+
+    // each commit is journaled as one batch of both the state and the
+    // pointer
+    let journal_ops = journal.journal().await;
+    for (i, block) in [&genesis, &block1, &block2].into_iter().enumerate() {
+        assert_eq!(commits[i + 1], commits[i] + 1);
+        match &journal_ops[commits[i]] {
+            journaldb::Op::Batch { ops } => {
+                assert!(ops.contains(&journaldb::Op::Put {
+                    key: b"height".to_vec(),
+                    value: block.height().await.to_be_bytes().to_vec(),
+                }));
+                assert!(ops.contains(&journaldb::Op::Put {
+                    key: LAST_ACCEPTED_KEY.to_vec(),
+                    value: block.id().await.to_vec(),
+                }));
+            }
+            op => panic!("unexpected journal entry {:?}", op),
+        }
+    }
+
+    // a crash in between the commits recovers the state and the pointer of
+    // the same block, latest first since truncating drops the tail; a crash
+    // inside a commit batch drops it as a whole, so neither the state nor the
+    // pointer of its block is visible
+    let heights = [None, Some(0u64), Some(1), Some(2)];
+    for (n, height) in commits.iter().zip(heights).rev() {
+        journal.truncate_at(*n).await;
+        let recovered = memdb::Database::new();
+        journal.replay(recovered.clone()).await.unwrap();
+
+        let last_accepted = get_last_accepted(&recovered).await.unwrap();
+        match height {
+            None => {
+                assert_eq!(last_accepted, None);
+                assert!(!recovered.has(b"height").await.unwrap());
+            }
+            Some(height) => {
+                let expected = [&genesis, &block1, &block2][height as usize];
+                assert_eq!(last_accepted, Some(expected.id().await));
+                assert_eq!(
+                    recovered.get(b"height").await.unwrap(),
+                    height.to_be_bytes()
+                );
+
+                // the next block's commit batch never landed
+                if let Some(next) = [&genesis, &block1, &block2].get(height as usize + 1) {
+                    assert_ne!(last_accepted, Some(next.id().await));
+                    assert_ne!(
+                        recovered.get(b"height").await.unwrap(),
+                        next.height().await.to_be_bytes()
+                    );
+                }
+            }
+        }
+    }
+}
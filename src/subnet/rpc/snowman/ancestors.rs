@@ -0,0 +1,115 @@
+//! Capped batch of ancestor blocks for "GetAncestors".
+use bytes::Bytes;
+
+use crate::packer;
+
+/// Ancestor blocks gathered for a "GetAncestors" response, from the
+/// requested block back towards genesis, within both the count and the
+/// byte caps of the request. Each block counts its bytes plus the 4-byte
+/// length prefix it is encoded with.
+///
+/// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/snow/engine/snowman/block#GetAncestors>
+#[derive(Debug, Clone)]
+pub struct AncestorsBatch {
+    max_count: usize,
+    max_bytes: usize,
+    blocks: Vec<Bytes>,
+    bytes_len: usize,
+}
+
+impl AncestorsBatch {
+    pub fn new(max_count: usize, max_bytes: usize) -> Self {
+        Self {
+            max_count,
+            max_bytes,
+            blocks: Vec::new(),
+            bytes_len: 0,
+        }
+    }
+
+    /// Adds the next ancestor if it fits both caps, and returns false
+    /// otherwise, after which no more blocks should be fetched. The first
+    /// block is always added, even beyond the caps, so that a response is
+    /// never empty.
+    pub fn push(&mut self, block: &[u8]) -> bool {
+        if !self.blocks.is_empty() {
+            if self.is_full() {
+                log::debug!("get_ancestors reached maximum count: {}", self.max_count);
+                return false;
+            }
+            if self.bytes_len + packer::prefixed_len(block.len()) > self.max_bytes {
+                log::debug!(
+                    "get_ancestors reached maximum response size: {}",
+                    self.bytes_len + packer::prefixed_len(block.len())
+                );
+                return false;
+            }
+        }
+        self.bytes_len += packer::prefixed_len(block.len());
+        self.blocks.push(Bytes::copy_from_slice(block));
+        true
+    }
+
+    /// Returns true once the count cap is reached.
+    pub fn is_full(&self) -> bool {
+        self.blocks.len() >= self.max_count
+    }
+
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Returns the encoded size of the gathered blocks.
+    pub fn bytes_len(&self) -> usize {
+        self.bytes_len
+    }
+
+    pub fn into_blocks(self) -> Vec<Bytes> {
+        self.blocks
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::snowman::ancestors::test_ancestors_batch --exact --show-output
+#[test]
+fn test_ancestors_batch() {
+    let blocks: Vec<Vec<u8>> = (0..5u8).map(|i| vec![i; 10]).collect();
+
+    // count cap first
+    let mut batch = AncestorsBatch::new(3, 1024);
+    let added = blocks.iter().take_while(|b| batch.push(b)).count();
+    assert_eq!(added, 3);
+    assert!(batch.is_full());
+    assert_eq!(batch.bytes_len(), 3 * packer::prefixed_len(10));
+    assert_eq!(
+        batch.into_blocks(),
+        vec![
+            Bytes::from(vec![0; 10]),
+            Bytes::from(vec![1; 10]),
+            Bytes::from(vec![2; 10]),
+        ]
+    );
+
+    // byte cap first, which counts the length prefixes
+    let mut batch = AncestorsBatch::new(10, 2 * packer::prefixed_len(10) + 5);
+    let added = blocks.iter().take_while(|b| batch.push(b)).count();
+    assert_eq!(added, 2);
+    assert!(!batch.is_full());
+    assert_eq!(batch.len(), 2);
+
+    let mut batch = AncestorsBatch::new(10, 2 * packer::prefixed_len(10));
+    assert_eq!(blocks.iter().take_while(|b| batch.push(b)).count(), 2);
+
+    // the first block is returned alone even if it exceeds the byte cap
+    let mut batch = AncestorsBatch::new(10, 8);
+    assert!(batch.push(&[7; 100]));
+    assert!(!batch.push(&[8; 1]));
+    assert_eq!(batch.into_blocks(), vec![Bytes::from(vec![7; 100])]);
+
+    let batch = AncestorsBatch::new(10, 8);
+    assert!(batch.is_empty());
+    assert!(batch.into_blocks().is_empty());
+}
@@ -4,6 +4,7 @@ use bytes::Bytes;
 
 use crate::{
     ids::Id,
+    packer,
     subnet::rpc::{consensus::snowman, snow::engine::common::vm::CommonVm},
 };
 
@@ -19,12 +20,39 @@ pub trait ChainVm: CommonVm + BatchedChainVm + Getter + Parser {
     /// Issues a transaction to the chain
     async fn issue_tx(&self) -> Result<<Self as ChainVm>::Block>;
 
-    /// Notify the Vm of the currently preferred block.
+    /// Notify the Vm of the currently preferred block. VMs keeping a
+    /// [`crate::subnet::rpc::snowman::chain_state::ChainState`] can serve it
+    /// with [`crate::subnet::rpc::snowman::chain_state::ChainState::set_preference`].
     async fn set_preference(&self, id: Id) -> Result<()>;
 
     /// Returns the ID of the last accepted block.
     /// If no blocks have been accepted, this should return the genesis block
     async fn last_accepted(&self) -> Result<Id>;
+
+    /// Returns the accepted frontier queried while bootstrapping, which on
+    /// a linear chain is the last accepted block.
+    ///
+    /// ref. "avalanchego/snow/engine/snowman/getter.GetAcceptedFrontier"
+    async fn get_accepted_frontier(&self) -> Result<Id> {
+        self.last_accepted().await
+    }
+
+    /// Returns the ID of the block accepted at the height, or a "not found"
+    /// error if no accepted block is indexed there. VMs keeping a
+    /// [`crate::subnet::rpc::snowman::height_index::HeightIndex`] can serve
+    /// it from the index.
+    ///
+    /// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/snow/engine/snowman/block#HeightIndexedChainVM>
+    async fn get_block_id_at_height(&self, _height: u64) -> Result<Id> {
+        Err(crate::subnet::rpc::errors::Error::HeightIndexedVMNotImplemented.to_err())
+    }
+
+    /// Returns the VM as a [`StateSyncableVm`] if it supports state sync,
+    /// for the VM server to serve the state sync RPCs. Defaults to "None",
+    /// in which case state sync is reported as disabled.
+    fn state_syncable(&self) -> Option<&(dyn StateSyncableVm + Send + Sync)> {
+        None
+    }
 }
 
 /// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/snow/engine/snowman/block#Getter>
@@ -33,6 +61,45 @@ pub trait Getter {
     type Block: snowman::Block;
 
     async fn get_block(&self, id: Id) -> Result<<Self as Getter>::Block>;
+
+    /// Returns the blocks in the order of the IDs, with "None" for the IDs
+    /// whose block is not found (e.g., to serve many blocks at once while
+    /// bootstrapping). Other errors are returned as is.
+    async fn get_blocks(&self, ids: &[Id]) -> Result<Vec<Option<<Self as Getter>::Block>>>
+    where
+        Self: Sync,
+    {
+        let mut blocks = Vec::with_capacity(ids.len());
+        for id in ids {
+            match self.get_block(*id).await {
+                Ok(block) => blocks.push(Some(block)),
+                Err(e) if crate::subnet::rpc::errors::is_not_found(&e) => blocks.push(None),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(blocks)
+    }
+
+    /// Returns the IDs of the blocks known as accepted, in the order of
+    /// the given IDs. Unknown, processing and rejected blocks are left out.
+    ///
+    /// ref. "avalanchego/snow/engine/snowman/getter.GetAccepted"
+    async fn filter_accepted(&self, ids: &[Id]) -> Result<Vec<Id>>
+    where
+        Self: Sync,
+    {
+        use crate::{choices::status::Status, subnet::rpc::consensus::snowman::Decidable};
+
+        let mut accepted = Vec::new();
+        for (id, block) in ids.iter().zip(self.get_blocks(ids).await?) {
+            if let Some(block) = block {
+                if block.status().await == Status::Accepted {
+                    accepted.push(*id);
+                }
+            }
+        }
+        Ok(accepted)
+    }
 }
 
 /// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/snow/engine/snowman/block#Parser>
@@ -118,3 +185,700 @@ pub trait BatchedChainVm {
     /// Attempts to batch parse_block requests.
     async fn batched_parse_block(&self, blocks: &[Vec<u8>]) -> Result<Vec<Self::Block>>;
 }
+
+/// Describes how a VM handles an accepted state summary.
+///
+/// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/snow/engine/snowman/block#StateSyncMode>
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StateSyncMode {
+    /// The VM skipped syncing to the summary; the engine bootstraps instead.
+    Skipped = 1,
+    /// The VM syncs in the background and the engine waits until it is done.
+    Static = 2,
+    /// The VM syncs while the engine keeps processing new blocks.
+    Dynamic = 3,
+}
+
+/// Represents all the information needed to complete state sync to a height.
+///
+/// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/snow/engine/snowman/block#StateSummary>
+#[tonic::async_trait]
+pub trait StateSummary: Send + Sync {
+    /// Returns the unique ID of this summary.
+    async fn id(&self) -> Id;
+
+    /// Returns the block height this summary syncs the state to.
+    async fn height(&self) -> u64;
+
+    /// Returns the binary representation of this summary.
+    async fn bytes(&self) -> &[u8];
+
+    /// Triggers the VM to start state syncing to this summary.
+    async fn accept(&mut self) -> Result<StateSyncMode>;
+}
+
+pub type BoxedStateSummary = Box<dyn StateSummary>;
+
+/// Defines the trait a [`ChainVm`] can optionally implement to support
+/// state sync.
+///
+/// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/snow/engine/snowman/block#StateSyncableVM>
+#[tonic::async_trait]
+pub trait StateSyncableVm {
+    /// Returns true if state sync is enabled for this VM.
+    async fn state_sync_enabled(&self) -> Result<bool>;
+
+    /// Returns the summary of an in-progress state sync, so that it can be
+    /// resumed after a restart. Returns a not found error if there is none.
+    async fn get_ongoing_sync_state_summary(&self) -> Result<BoxedStateSummary>;
+
+    /// Returns the latest summary this VM can serve to peers.
+    async fn get_last_state_summary(&self) -> Result<BoxedStateSummary>;
+
+    /// Parses the summary from its bytes.
+    async fn parse_state_summary(&self, bytes: &[u8]) -> Result<BoxedStateSummary>;
+
+    /// Returns the summary at the block height, or a not found error.
+    async fn get_state_summary(&self, height: u64) -> Result<BoxedStateSummary>;
+}
+
+/// Default bound on how far in the future a [`TimestampBlock`] may be.
+pub const DEFAULT_MAX_FUTURE_SKEW: Duration = Duration::from_secs(10);
+
+/// Height and timestamp of the parent a [`TimestampBlock`] is verified against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParentInfo {
+    pub height: u64,
+    pub timestamp: u64,
+}
+
+/// Reference block whose only rule is a monotonic timestamp (e.g., for
+/// spacesvm-style VMs): a child extends its parent by one height, and is
+/// proposed no earlier than its parent and no later than "now" plus the
+/// allowed skew. The block is encoded as parent ID (32 bytes), height and
+/// Unix timestamp in seconds (8 bytes each, big endian), then the payload.
+///
+/// ref. <https://github.com/ava-labs/spacesvm/blob/master/chain/block.go>
+#[derive(Debug, Clone)]
+pub struct TimestampBlock {
+    id: Id,
+    parent: Id,
+    height: u64,
+    timestamp: u64,
+    data: Vec<u8>,
+    bytes: Vec<u8>,
+    status: crate::choices::status::Status,
+    /// Set before verification, by the VM that knows the parent.
+    parent_info: Option<ParentInfo>,
+    max_future_skew: Duration,
+}
+
+impl TimestampBlock {
+    pub fn new(parent: Id, height: u64, timestamp: u64, data: &[u8]) -> Self {
+        let mut bytes = Vec::with_capacity(48 + data.len());
+        bytes.extend_from_slice(parent.as_ref());
+        bytes.extend_from_slice(&height.to_be_bytes());
+        bytes.extend_from_slice(&timestamp.to_be_bytes());
+        bytes.extend_from_slice(data);
+        Self {
+            id: Id::sha256(&bytes),
+            parent,
+            height,
+            timestamp,
+            data: data.to_vec(),
+            bytes,
+            status: crate::choices::status::Status::Processing,
+            parent_info: None,
+            max_future_skew: DEFAULT_MAX_FUTURE_SKEW,
+        }
+    }
+
+    /// Parses the block from its encoding.
+    pub fn from_slice(d: &[u8]) -> Result<Self> {
+        if d.len() < 48 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("block too short ({} < 48 bytes)", d.len()),
+            ));
+        }
+        let parent = Id::from_slice(&d[..32]);
+        let height = u64::from_be_bytes(d[32..40].try_into().unwrap());
+        let timestamp = u64::from_be_bytes(d[40..48].try_into().unwrap());
+        Ok(Self::new(parent, height, timestamp, &d[48..]))
+    }
+
+    /// Builds the child of the block at the next height, ready to verify.
+    pub fn child(&self, timestamp: u64, data: &[u8]) -> Self {
+        Self::new(self.id, self.height + 1, timestamp, data).with_parent(ParentInfo {
+            height: self.height,
+            timestamp: self.timestamp,
+        })
+    }
+
+    /// Sets the parent to verify against.
+    #[must_use]
+    pub fn with_parent(mut self, parent_info: ParentInfo) -> Self {
+        self.parent_info = Some(parent_info);
+        self
+    }
+
+    /// Sets how far in the future the timestamp may be.
+    #[must_use]
+    pub fn with_max_future_skew(mut self, max_future_skew: Duration) -> Self {
+        self.max_future_skew = max_future_skew;
+        self
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Checks the block against its parent, as of "now" (Unix seconds).
+    pub fn verify_at(&self, now: u64) -> Result<()> {
+        let parent = self.parent_info.ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("parent {} of block {} is unknown", self.parent, self.id),
+            )
+        })?;
+        if parent.height.checked_add(1) != Some(self.height) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "block height {} does not extend parent height {}",
+                    self.height, parent.height
+                ),
+            ));
+        }
+        if self.timestamp < parent.timestamp {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "block timestamp {} is before parent timestamp {}",
+                    self.timestamp, parent.timestamp
+                ),
+            ));
+        }
+        let max_timestamp = now.saturating_add(self.max_future_skew.as_secs());
+        if self.timestamp > max_timestamp {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "block timestamp {} is too far in the future (> {})",
+                    self.timestamp, max_timestamp
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::consensus::snowman::Decidable for TimestampBlock {
+    /// Implements the [`crate::subnet::rpc::consensus::snowman::Decidable`] trait.
+    async fn id(&self) -> Id {
+        self.id
+    }
+
+    /// Implements the [`crate::subnet::rpc::consensus::snowman::Decidable`] trait.
+    async fn status(&self) -> crate::choices::status::Status {
+        self.status.clone()
+    }
+
+    /// Implements the [`crate::subnet::rpc::consensus::snowman::Decidable`] trait.
+    async fn accept(&mut self) -> Result<()> {
+        self.status = crate::choices::status::Status::Accepted;
+        Ok(())
+    }
+
+    /// Implements the [`crate::subnet::rpc::consensus::snowman::Decidable`] trait.
+    async fn reject(&mut self) -> Result<()> {
+        self.status = crate::choices::status::Status::Rejected;
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl snowman::Block for TimestampBlock {
+    /// Implements the [`crate::subnet::rpc::consensus::snowman::Block`] trait.
+    async fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Implements the [`crate::subnet::rpc::consensus::snowman::Block`] trait.
+    async fn height(&self) -> u64 {
+        self.height
+    }
+
+    /// Implements the [`crate::subnet::rpc::consensus::snowman::Block`] trait.
+    async fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    /// Implements the [`crate::subnet::rpc::consensus::snowman::Block`] trait.
+    async fn parent(&self) -> Id {
+        self.parent
+    }
+
+    /// Verifies the block against its parent as of the current time.
+    async fn verify(&mut self) -> Result<()> {
+        self.verify_at(chrono::Utc::now().timestamp().max(0) as u64)
+    }
+}
+
+/// Block decoded by [`decode_block`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockWithTxs {
+    /// SHA256 of the encoded block.
+    pub id: Id,
+    pub parent_id: Id,
+    pub height: u64,
+    pub timestamp: u64,
+    /// Bytes of each signed transaction, in block order.
+    pub txs: Vec<Vec<u8>>,
+}
+
+/// Encodes a block embedding the signed transactions (as their bytes):
+/// the codec version, parent ID, height, Unix timestamp in seconds, then
+/// the number of txs and each tx prefixed by its length. The block ID is
+/// the SHA256 of the returned bytes.
+/// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/utils/wrappers#Packer.Pack2DByteSlice>
+pub fn encode_block(parent_id: &Id, height: u64, timestamp: u64, txs: &[Vec<u8>]) -> Vec<u8> {
+    let txs_len: usize = txs.iter().map(|tx| tx.len()).sum();
+    let mut b = Vec::with_capacity(2 + 32 + 8 + 8 + packer::bytes_overhead(txs.len()) + txs_len);
+    b.extend_from_slice(&crate::codec::VERSION.to_be_bytes());
+    b.extend_from_slice(parent_id.as_ref());
+    b.extend_from_slice(&height.to_be_bytes());
+    b.extend_from_slice(&timestamp.to_be_bytes());
+    b.extend_from_slice(&(txs.len() as u32).to_be_bytes());
+    for tx in txs.iter() {
+        b.extend_from_slice(&(tx.len() as u32).to_be_bytes());
+        b.extend_from_slice(tx);
+    }
+    b
+}
+
+/// Parses the block encoded by [`encode_block`], rejecting an unknown codec
+/// version, truncated input or trailing bytes.
+pub fn decode_block(d: &[u8]) -> Result<BlockWithTxs> {
+    let invalid = |e: crate::errors::Error| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("failed to decode block ({})", e),
+        )
+    };
+
+    let packer = crate::packer::Packer::load_bytes_for_unpack(d.len() + 1024, d);
+    let version = packer.unpack_u16().map_err(invalid)?;
+    if version != crate::codec::VERSION {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("unsupported block codec version {}", version),
+        ));
+    }
+    let parent_id = Id::from_slice(&packer.unpack_bytes(32).map_err(invalid)?);
+    let height = packer.unpack_u64().map_err(invalid)?;
+    let timestamp = packer.unpack_u64().map_err(invalid)?;
+    let txs = packer.unpack_2d_bytes_with_header().map_err(invalid)?;
+    if packer.get_offset() != d.len() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("block has {} trailing bytes", d.len() - packer.get_offset()),
+        ));
+    }
+
+    Ok(BlockWithTxs {
+        id: Id::sha256(d),
+        parent_id,
+        height,
+        timestamp,
+        txs,
+    })
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- subnet::rpc::snowman::block::test_timestamp_block --exact --show-output
+#[tokio::test]
+async fn test_timestamp_block() {
+    use crate::subnet::rpc::consensus::snowman::{Block, Decidable};
+
+    let now = chrono::Utc::now().timestamp() as u64;
+    let genesis = TimestampBlock::new(Id::empty(), 0, now - 100, b"genesis");
+
+    // valid child, which survives a round trip through its bytes
+    let mut child = genesis.child(now - 50, b"hello");
+    assert_eq!(child.height().await, 1);
+    assert_eq!(child.parent().await, genesis.id().await);
+    child.verify().await.unwrap();
+    let parsed = TimestampBlock::from_slice(child.bytes().await).unwrap();
+    assert_eq!(parsed.id().await, child.id().await);
+    assert_eq!(parsed.data(), b"hello");
+    assert_eq!(parsed.timestamp().await, now - 50);
+
+    // parsed blocks need their parent to verify
+    let mut parsed = parsed;
+    assert_eq!(
+        parsed.verify().await.unwrap_err().kind(),
+        std::io::ErrorKind::NotFound
+    );
+    let mut parsed = parsed.with_parent(ParentInfo {
+        height: 0,
+        timestamp: now - 100,
+    });
+    parsed.verify().await.unwrap();
+
+    // same timestamp as the parent is fine, earlier is not
+    assert!(genesis.child(now - 100, b"").verify_at(now).is_ok());
+    let err = genesis.child(now - 101, b"").verify_at(now).unwrap_err();
+    assert!(err.to_string().contains("before parent timestamp"));
+
+    // too far in the future
+    assert!(genesis.child(now + 10, b"").verify_at(now).is_ok());
+    let err = genesis.child(now + 11, b"").verify_at(now).unwrap_err();
+    assert!(err.to_string().contains("too far in the future"));
+    assert!(genesis
+        .child(now + 11, b"")
+        .with_max_future_skew(Duration::from_secs(60))
+        .verify_at(now)
+        .is_ok());
+
+    // height must extend the parent
+    let skipped = TimestampBlock::new(genesis.id().await, 2, now, b"").with_parent(ParentInfo {
+        height: 0,
+        timestamp: now - 100,
+    });
+    assert!(skipped.verify_at(now).is_err());
+    assert!(TimestampBlock::from_slice(&[0; 47]).is_err());
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- subnet::rpc::snowman::block::test_build_parse_get_block --exact --show-output
+#[tokio::test]
+async fn test_build_parse_get_block() {
+    use std::{collections::HashMap, io, sync::Arc};
+
+    use crate::{
+        choices::status::Status,
+        subnet::rpc::{consensus::snowman::Decidable, snowman::chain_state::ChainState},
+    };
+    use tokio::sync::RwLock;
+
+    #[derive(Clone)]
+    struct TestBlock {
+        id: Id,
+        parent: Id,
+        height: u64,
+        timestamp: u64,
+        bytes: Vec<u8>,
+        status: Status,
+    }
+
+    impl TestBlock {
+        fn new(parent: Id, height: u64, timestamp: u64) -> Self {
+            let mut bytes = parent.to_vec();
+            bytes.extend_from_slice(&height.to_be_bytes());
+            bytes.extend_from_slice(&timestamp.to_be_bytes());
+            Self {
+                id: Id::sha256(&bytes),
+                parent,
+                height,
+                timestamp,
+                bytes,
+                status: Status::Processing,
+            }
+        }
+
+        fn from_slice(d: &[u8]) -> Result<Self> {
+            if d.len() != 48 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid block length {}", d.len()),
+                ));
+            }
+            let parent = Id::from_slice(&d[..32]);
+            let height = u64::from_be_bytes(d[32..40].try_into().unwrap());
+            let timestamp = u64::from_be_bytes(d[40..48].try_into().unwrap());
+            Ok(Self::new(parent, height, timestamp))
+        }
+    }
+
+    #[tonic::async_trait]
+    impl Decidable for TestBlock {
+        async fn id(&self) -> Id {
+            self.id
+        }
+        async fn status(&self) -> Status {
+            self.status.clone()
+        }
+        async fn accept(&mut self) -> Result<()> {
+            self.status = Status::Accepted;
+            Ok(())
+        }
+        async fn reject(&mut self) -> Result<()> {
+            self.status = Status::Rejected;
+            Ok(())
+        }
+    }
+
+    #[tonic::async_trait]
+    impl snowman::Block for TestBlock {
+        async fn bytes(&self) -> &[u8] {
+            &self.bytes
+        }
+        async fn height(&self) -> u64 {
+            self.height
+        }
+        async fn timestamp(&self) -> u64 {
+            self.timestamp
+        }
+        async fn parent(&self) -> Id {
+            self.parent
+        }
+        async fn verify(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Keeps the processing blocks and the preferred tip in the chain state,
+    /// and the decided blocks in "stored".
+    struct TestVm {
+        state: ChainState<TestBlock>,
+        stored: Arc<RwLock<HashMap<Id, TestBlock>>>,
+    }
+
+    impl TestVm {
+        async fn build_block(&self) -> Result<TestBlock> {
+            let preferred = self.state.preferred().await;
+            let parent_height = match self.get_block(preferred).await {
+                Ok(parent) => parent.height,
+                Err(_) => 0,
+            };
+            let blk = TestBlock::new(preferred, parent_height + 1, 1);
+            Ok(self.state.build_block(blk).await)
+        }
+
+        async fn decide(&self, blk: TestBlock) {
+            self.state.decided(&blk.id).await;
+            self.stored.write().await.insert(blk.id, blk);
+        }
+    }
+
+    #[tonic::async_trait]
+    impl Getter for TestVm {
+        type Block = TestBlock;
+
+        async fn get_block(&self, id: Id) -> Result<TestBlock> {
+            let stored = Arc::clone(&self.stored);
+            self.state
+                .get_block(id, |id| async move {
+                    stored
+                        .read()
+                        .await
+                        .get(&id)
+                        .cloned()
+                        .ok_or_else(|| crate::subnet::rpc::errors::Error::NotFound.to_err())
+                })
+                .await
+        }
+    }
+
+    #[tonic::async_trait]
+    impl Parser for TestVm {
+        type Block = TestBlock;
+
+        async fn parse_block(&self, bytes: &[u8]) -> Result<TestBlock> {
+            let stored = self.stored.read().await;
+            self.state
+                .parse_block(bytes, |bytes| {
+                    let blk = TestBlock::from_slice(bytes)?;
+                    Ok(stored.get(&blk.id).cloned().unwrap_or(blk))
+                })
+                .await
+        }
+    }
+
+    let vm = TestVm {
+        state: ChainState::new(),
+        stored: Arc::new(RwLock::new(HashMap::new())),
+    };
+
+    let built = vm.build_block().await.unwrap();
+    let bytes = snowman::Block::bytes(&built).await.to_vec();
+
+    let parsed = vm.parse_block(&bytes).await.unwrap();
+    assert_eq!(parsed.id().await, built.id().await);
+    assert_eq!(snowman::Block::height(&parsed).await, 1);
+
+    let fetched = vm.get_block(built.id().await).await.unwrap();
+    assert_eq!(fetched.id().await, built.id().await);
+    assert_eq!(vm.state.len().await, 1);
+
+    // the next block builds on the preferred one
+    vm.state
+        .set_preference(built.id, Id::empty())
+        .await
+        .unwrap();
+    let child = vm.build_block().await.unwrap();
+    assert_eq!(child.parent, built.id);
+    assert_eq!(snowman::Block::height(&child).await, 2);
+    vm.state.decided(&child.id).await;
+    assert_eq!(vm.state.len().await, 1);
+
+    assert!(vm.get_block(Id::empty()).await.is_err());
+    assert!(vm.parse_block(&bytes[1..]).await.is_err());
+
+    // batched lookups keep the order of the IDs
+    let unknown = Id::sha256(b"unknown");
+    let blocks = vm
+        .get_blocks(&[unknown, built.id().await, Id::empty(), built.id().await])
+        .await
+        .unwrap();
+    assert_eq!(blocks.len(), 4);
+    assert!(blocks[0].is_none());
+    assert_eq!(blocks[1].as_ref().unwrap().id().await, built.id().await);
+    assert!(blocks[2].is_none());
+    assert_eq!(blocks[3].as_ref().unwrap().id().await, built.id().await);
+    assert!(vm.get_blocks(&[]).await.unwrap().is_empty());
+
+    // only accepted blocks are reported as such
+    let mut accepted = built.clone();
+    accepted.accept().await.unwrap();
+    vm.decide(accepted).await;
+    assert!(vm.state.is_empty().await);
+    let processing = vm
+        .parse_block(&TestBlock::new(built.id, 2, 2).bytes)
+        .await
+        .unwrap();
+    assert_eq!(vm.state.len().await, 1);
+    assert_eq!(
+        vm.filter_accepted(&[processing.id, unknown, built.id])
+            .await
+            .unwrap(),
+        vec![built.id]
+    );
+    assert!(vm
+        .filter_accepted(&[processing.id])
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- subnet::rpc::snowman::block::test_state_syncable_vm --exact --show-output
+#[tokio::test]
+async fn test_state_syncable_vm() {
+    use crate::subnet::rpc::errors;
+
+    struct TestSummary {
+        height: u64,
+        bytes: Vec<u8>,
+    }
+
+    impl TestSummary {
+        fn new(height: u64) -> BoxedStateSummary {
+            Box::new(Self {
+                height,
+                bytes: height.to_be_bytes().to_vec(),
+            })
+        }
+    }
+
+    #[tonic::async_trait]
+    impl StateSummary for TestSummary {
+        async fn id(&self) -> Id {
+            Id::sha256(&self.bytes)
+        }
+        async fn height(&self) -> u64 {
+            self.height
+        }
+        async fn bytes(&self) -> &[u8] {
+            &self.bytes
+        }
+        async fn accept(&mut self) -> Result<StateSyncMode> {
+            Ok(StateSyncMode::Static)
+        }
+    }
+
+    struct TestVm {
+        last_accepted_height: u64,
+    }
+
+    #[tonic::async_trait]
+    impl StateSyncableVm for TestVm {
+        async fn state_sync_enabled(&self) -> Result<bool> {
+            Ok(true)
+        }
+        async fn get_ongoing_sync_state_summary(&self) -> Result<BoxedStateSummary> {
+            Err(errors::Error::NotFound.to_err())
+        }
+        async fn get_last_state_summary(&self) -> Result<BoxedStateSummary> {
+            self.get_state_summary(self.last_accepted_height).await
+        }
+        async fn parse_state_summary(&self, bytes: &[u8]) -> Result<BoxedStateSummary> {
+            let height: [u8; 8] = bytes.try_into().map_err(|_| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("invalid summary length {}", bytes.len()),
+                )
+            })?;
+            Ok(TestSummary::new(u64::from_be_bytes(height)))
+        }
+        async fn get_state_summary(&self, height: u64) -> Result<BoxedStateSummary> {
+            if height > self.last_accepted_height {
+                return Err(errors::Error::NotFound.to_err());
+            }
+            Ok(TestSummary::new(height))
+        }
+    }
+
+    let vm = TestVm {
+        last_accepted_height: 100,
+    };
+    assert!(vm.state_sync_enabled().await.unwrap());
+    assert!(errors::is_not_found(
+        &vm.get_ongoing_sync_state_summary().await.err().unwrap()
+    ));
+
+    let mut summary = vm.get_state_summary(42).await.unwrap();
+    assert_eq!(summary.height().await, 42);
+    assert_eq!(summary.accept().await.unwrap(), StateSyncMode::Static);
+
+    let parsed = vm.parse_state_summary(summary.bytes().await).await.unwrap();
+    assert_eq!(parsed.id().await, summary.id().await);
+    assert_eq!(
+        vm.get_last_state_summary().await.unwrap().height().await,
+        100
+    );
+
+    assert!(vm.get_state_summary(101).await.is_err());
+    assert!(vm.parse_state_summary(&[0; 4]).await.is_err());
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- subnet::rpc::snowman::block::test_encode_decode_block --exact --show-output
+#[test]
+fn test_encode_decode_block() {
+    let parent_id = Id::sha256(b"parent");
+    let txs = vec![b"first signed tx".to_vec(), vec![0xff; 300]];
+    let bytes = encode_block(&parent_id, 7, 1_683_000_000, &txs);
+    assert_eq!(&bytes[..2], &[0, 0]);
+    assert_eq!(bytes.len(), 2 + 32 + 8 + 8 + 4 + (4 + 15) + (4 + 300));
+
+    let block = decode_block(&bytes).unwrap();
+    assert_eq!(block.id, Id::sha256(&bytes));
+    assert_eq!(block.parent_id, parent_id);
+    assert_eq!(block.height, 7);
+    assert_eq!(block.timestamp, 1_683_000_000);
+    assert_eq!(block.txs, txs);
+
+    let empty = decode_block(&encode_block(&parent_id, 8, 0, &[])).unwrap();
+    assert!(empty.txs.is_empty());
+    assert_ne!(empty.id, block.id);
+
+    assert!(decode_block(&bytes[..bytes.len() - 1]).is_err());
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(decode_block(&trailing).is_err());
+    let mut versioned = bytes;
+    versioned[1] = 1;
+    assert!(decode_block(&versioned)
+        .unwrap_err()
+        .to_string()
+        .contains("unsupported block codec version 1"));
+}
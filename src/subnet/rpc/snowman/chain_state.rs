@@ -0,0 +1,219 @@
+//! Cache of the blocks a VM has built or parsed but not yet decided, and
+//! the block consensus prefers among them.
+use std::{
+    collections::HashMap,
+    future::Future,
+    io::{Error, ErrorKind, Result},
+    sync::Arc,
+};
+
+use crate::{choices::status::Status, ids::Id, subnet::rpc::consensus::snowman::Block};
+use tokio::sync::RwLock;
+
+/// Serves the build, parse and get block hooks of a [`crate::subnet::rpc::snowman::block::ChainVm`]
+/// from one set of processing blocks, so that consensus sees the same block
+/// (and status) whichever hook returned it. Decided blocks are evicted and
+/// served from the VM's storage instead.
+/// Also tracks the preferred tip set by "SetPreference", so that the VM
+/// builds its next block on it.
+/// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/vms/components/chain#State>
+#[derive(Clone)]
+pub struct ChainState<B> {
+    processing: Arc<RwLock<HashMap<Id, B>>>,
+    preferred: Arc<RwLock<Id>>,
+}
+
+impl<B> Default for ChainState<B> {
+    fn default() -> Self {
+        Self {
+            processing: Arc::new(RwLock::new(HashMap::new())),
+            preferred: Arc::new(RwLock::new(Id::empty())),
+        }
+    }
+}
+
+impl<B> ChainState<B>
+where
+    B: Block + Clone + Send + Sync,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Caches the block the VM just built as processing, and returns it.
+    pub async fn build_block(&self, block: B) -> B {
+        let id = block.id().await;
+        self.processing.write().await.insert(id, block.clone());
+        block
+    }
+
+    /// Parses the block with "parse", then returns the processing block of
+    /// the same ID if any, so that re-parsing does not fork its state.
+    /// Newly parsed processing blocks are cached.
+    pub async fn parse_block<P>(&self, bytes: &[u8], parse: P) -> Result<B>
+    where
+        P: FnOnce(&[u8]) -> Result<B>,
+    {
+        let block = parse(bytes)?;
+        let id = block.id().await;
+
+        let mut processing = self.processing.write().await;
+        if let Some(cached) = processing.get(&id) {
+            return Ok(cached.clone());
+        }
+        if block.status().await == Status::Processing {
+            processing.insert(id, block.clone());
+        }
+        Ok(block)
+    }
+
+    /// Returns the processing block of the ID, or fetches the block from
+    /// the VM's storage with "fetch" (e.g., a decided block).
+    pub async fn get_block<F, Fut>(&self, id: Id, fetch: F) -> Result<B>
+    where
+        F: FnOnce(Id) -> Fut,
+        Fut: Future<Output = Result<B>>,
+    {
+        if let Some(cached) = self.processing.read().await.get(&id) {
+            return Ok(cached.clone());
+        }
+        fetch(id).await
+    }
+
+    /// Evicts the block once decided, when the VM's storage becomes the
+    /// source of its status.
+    pub async fn decided(&self, id: &Id) {
+        self.processing.write().await.remove(id);
+    }
+
+    /// Returns the ID of the preferred block, or the empty ID until the
+    /// first [`ChainState::set_preference`].
+    pub async fn preferred(&self) -> Id {
+        *self.preferred.read().await
+    }
+
+    /// Prefers the block, which must be processing, or be the last accepted
+    /// block (e.g., when nothing is processing). Unknown and decided blocks
+    /// are rejected, and keep the preference as is.
+    /// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/snow/engine/snowman/block#ChainVM>
+    pub async fn set_preference(&self, id: Id, last_accepted: Id) -> Result<()> {
+        if id != last_accepted {
+            let processing = self.processing.read().await;
+            let block = processing.get(&id).ok_or_else(|| {
+                Error::new(
+                    ErrorKind::NotFound,
+                    format!("cannot prefer unknown block {}", id),
+                )
+            })?;
+            let status = block.status().await;
+            if status != Status::Processing {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("cannot prefer block {} with status {}", id, status),
+                ));
+            }
+        }
+
+        *self.preferred.write().await = id;
+        Ok(())
+    }
+
+    /// Returns the number of processing blocks.
+    pub async fn len(&self) -> usize {
+        self.processing.read().await.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.processing.read().await.is_empty()
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::snowman::chain_state::test_set_preference --exact --show-output
+#[tokio::test]
+async fn test_set_preference() {
+    use crate::subnet::rpc::consensus::snowman::Decidable;
+
+    #[derive(Clone)]
+    struct TestBlock {
+        id: Id,
+        status: Status,
+    }
+
+    #[tonic::async_trait]
+    impl Decidable for TestBlock {
+        async fn id(&self) -> Id {
+            self.id
+        }
+        async fn status(&self) -> Status {
+            self.status.clone()
+        }
+        async fn accept(&mut self) -> Result<()> {
+            self.status = Status::Accepted;
+            Ok(())
+        }
+        async fn reject(&mut self) -> Result<()> {
+            self.status = Status::Rejected;
+            Ok(())
+        }
+    }
+
+    #[tonic::async_trait]
+    impl Block for TestBlock {
+        async fn bytes(&self) -> &[u8] {
+            self.id.as_ref()
+        }
+        async fn height(&self) -> u64 {
+            0
+        }
+        async fn timestamp(&self) -> u64 {
+            0
+        }
+        async fn parent(&self) -> Id {
+            Id::empty()
+        }
+        async fn verify(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    let genesis = Id::sha256(b"genesis");
+    let verified = Id::sha256(b"verified");
+    let state = ChainState::new();
+    state
+        .build_block(TestBlock {
+            id: verified,
+            status: Status::Processing,
+        })
+        .await;
+    assert_eq!(state.preferred().await, Id::empty());
+
+    state.set_preference(verified, genesis).await.unwrap();
+    assert_eq!(state.preferred().await, verified);
+
+    let err = state
+        .set_preference(Id::sha256(b"unknown"), genesis)
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    assert!(err.to_string().contains("unknown block"));
+    assert_eq!(state.preferred().await, verified);
+
+    // a block rejected while still cached cannot be preferred
+    let rejected = Id::sha256(b"rejected");
+    state
+        .build_block(TestBlock {
+            id: rejected,
+            status: Status::Rejected,
+        })
+        .await;
+    let err = state.set_preference(rejected, genesis).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    // decided blocks are no longer processing
+    state.decided(&verified).await;
+    assert!(state.set_preference(verified, genesis).await.is_err());
+
+    // back to the last accepted block
+    state.set_preference(genesis, genesis).await.unwrap();
+    assert_eq!(state.preferred().await, genesis);
+}
@@ -0,0 +1,74 @@
+//! Persisted index of accepted block IDs by height.
+use std::io::{Error, ErrorKind, Result};
+
+use crate::{ids::Id, subnet::rpc::database::BoxedDatabase};
+
+/// Key prefix for the block ID at each height.
+const PREFIX: &[u8] = b"height";
+
+/// Maps the height of each accepted block to its ID, so that a VM can serve
+/// "GetBlockIDAtHeight" to avalanchego.
+/// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/snow/engine/snowman/block#HeightIndexedChainVM>
+#[derive(Clone)]
+pub struct HeightIndex {
+    db: BoxedDatabase,
+}
+
+impl HeightIndex {
+    pub fn new(db: BoxedDatabase) -> Self {
+        Self { db }
+    }
+
+    /// Records the ID of the block accepted at the height.
+    pub async fn put(&mut self, height: u64, id: &Id) -> Result<()> {
+        self.db.put(&key(height), id.as_ref()).await
+    }
+
+    /// Returns the ID of the block accepted at the height, or a
+    /// "not found" error if no block is indexed there.
+    pub async fn get(&self, height: u64) -> Result<Id> {
+        let value = self.db.get(&key(height)).await?;
+        if value.len() != 32 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "invalid block ID length {} at height {}",
+                    value.len(),
+                    height
+                ),
+            ));
+        }
+        Ok(Id::from_slice(&value))
+    }
+
+    /// Removes the block ID at the height, if any.
+    pub async fn delete(&mut self, height: u64) -> Result<()> {
+        self.db.delete(&key(height)).await
+    }
+}
+
+/// Big-endian so that the keys sort by height.
+fn key(height: u64) -> Vec<u8> {
+    let mut k = PREFIX.to_vec();
+    k.extend_from_slice(&height.to_be_bytes());
+    k
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::snowman::height_index::test_height_index --exact --show-output
+#[tokio::test]
+async fn test_height_index() {
+    use crate::subnet::rpc::{database::memdb, errors};
+
+    let mut index = HeightIndex::new(memdb::Database::new());
+    let genesis = Id::sha256(b"genesis");
+    let block1 = Id::sha256(b"block1");
+    index.put(0, &genesis).await.unwrap();
+    index.put(1, &block1).await.unwrap();
+
+    assert_eq!(index.get(0).await.unwrap(), genesis);
+    assert_eq!(index.get(1).await.unwrap(), block1);
+    assert!(errors::is_not_found(&index.get(2).await.unwrap_err()));
+
+    index.delete(1).await.unwrap();
+    assert!(errors::is_not_found(&index.get(1).await.unwrap_err()));
+}
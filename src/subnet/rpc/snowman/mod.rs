@@ -1 +1,5 @@
+pub mod accept;
+pub mod ancestors;
 pub mod block;
+pub mod chain_state;
+pub mod height_index;
@@ -0,0 +1,264 @@
+//! Local uptime tracking of validators for subnet VMs.
+//!
+//! Uptime is an exponentially decaying, time-weighted fraction of the time a
+//! node was connected, so that recent behavior matters more than the distant
+//! past. The state is persisted to the database on every update so restarts
+//! do not reset it.
+use std::{
+    io::{self, Error, ErrorKind},
+    sync::Arc,
+    time::Duration,
+};
+
+use crate::{
+    ids::node,
+    subnet::rpc::{database::BoxedDatabase, errors},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+/// Key prefix for the persisted uptime state of each node.
+const PREFIX: &[u8] = b"uptime";
+
+/// Persisted uptime state of a node.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+struct State {
+    connected: bool,
+    /// Unix timestamp in milliseconds of the last update.
+    last_updated_ms: i64,
+    /// Decayed weight of the time spent connected.
+    weighted_up: f64,
+    /// Decayed weight of the total time tracked.
+    weighted_total: f64,
+}
+
+impl State {
+    fn new(now_ms: i64) -> Self {
+        Self {
+            connected: false,
+            last_updated_ms: now_ms,
+            weighted_up: 0.0,
+            weighted_total: 0.0,
+        }
+    }
+
+    /// Decays the previous weights and accounts for the time elapsed since
+    /// the last update, in the current connection state.
+    fn advance(&mut self, now_ms: i64, half_life: Duration) {
+        let elapsed_ms = now_ms.saturating_sub(self.last_updated_ms).max(0);
+        let half_life_ms = half_life.as_millis().max(1) as f64;
+        let decay = 0.5_f64.powf(elapsed_ms as f64 / half_life_ms);
+
+        self.weighted_total = self.weighted_total * decay + (1.0 - decay);
+        self.weighted_up *= decay;
+        if self.connected {
+            self.weighted_up += 1.0 - decay;
+        }
+        self.last_updated_ms = self.last_updated_ms.max(now_ms);
+    }
+
+    fn uptime(&self) -> f64 {
+        if self.weighted_total <= 0.0 {
+            return 0.0;
+        }
+        (self.weighted_up / self.weighted_total).clamp(0.0, 1.0)
+    }
+}
+
+/// Tracks the decaying uptime of nodes and persists it to the database.
+#[derive(Clone)]
+pub struct Tracker {
+    db: Arc<RwLock<BoxedDatabase>>,
+    /// Duration after which the weight of a past interval is halved.
+    half_life: Duration,
+}
+
+impl Tracker {
+    pub fn new(db: BoxedDatabase, half_life: Duration) -> Self {
+        Self {
+            db: Arc::new(RwLock::new(db)),
+            half_life,
+        }
+    }
+
+    /// Marks the node as connected from now on.
+    pub async fn connect(&self, node_id: &node::Id) -> io::Result<()> {
+        self.connect_at(node_id, Utc::now()).await
+    }
+
+    /// Marks the node as connected from the given time on.
+    pub async fn connect_at(&self, node_id: &node::Id, now: DateTime<Utc>) -> io::Result<()> {
+        self.set_connected(node_id, now, true).await
+    }
+
+    /// Marks the node as disconnected from now on.
+    pub async fn disconnect(&self, node_id: &node::Id) -> io::Result<()> {
+        self.disconnect_at(node_id, Utc::now()).await
+    }
+
+    /// Marks the node as disconnected from the given time on.
+    pub async fn disconnect_at(&self, node_id: &node::Id, now: DateTime<Utc>) -> io::Result<()> {
+        self.set_connected(node_id, now, false).await
+    }
+
+    /// Returns the decaying connected fraction of the node in [0, 1] as of now.
+    /// Returns 0 for a node that has never been tracked.
+    pub async fn uptime(&self, node_id: &node::Id) -> io::Result<f64> {
+        self.uptime_at(node_id, Utc::now()).await
+    }
+
+    /// Returns the decaying connected fraction of the node in [0, 1] as of
+    /// the given time.
+    pub async fn uptime_at(&self, node_id: &node::Id, now: DateTime<Utc>) -> io::Result<f64> {
+        let db = self.db.read().await;
+        match load(&db, node_id).await? {
+            Some(mut state) => {
+                state.advance(now.timestamp_millis(), self.half_life);
+                Ok(state.uptime())
+            }
+            None => Ok(0.0),
+        }
+    }
+
+    async fn set_connected(
+        &self,
+        node_id: &node::Id,
+        now: DateTime<Utc>,
+        connected: bool,
+    ) -> io::Result<()> {
+        let now_ms = now.timestamp_millis();
+
+        let mut db = self.db.write().await;
+        let mut state = load(&db, node_id)
+            .await?
+            .unwrap_or_else(|| State::new(now_ms));
+        state.advance(now_ms, self.half_life);
+        state.connected = connected;
+
+        let value = serde_json::to_vec(&state)
+            .map_err(|e| Error::new(ErrorKind::Other, format!("failed to encode state {}", e)))?;
+        db.put(&key(node_id), &value).await
+    }
+}
+
+fn key(node_id: &node::Id) -> Vec<u8> {
+    let mut k = PREFIX.to_vec();
+    k.extend_from_slice(node_id.as_ref());
+    k
+}
+
+async fn load(db: &BoxedDatabase, node_id: &node::Id) -> io::Result<Option<State>> {
+    match db.get(&key(node_id)).await {
+        Ok(value) => {
+            let state = serde_json::from_slice(&value).map_err(|e| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("failed to decode state {}", e),
+                )
+            })?;
+            Ok(Some(state))
+        }
+        Err(e) if errors::is_not_found(&e) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Tracks the uptime of peers as they connect and disconnect.
+#[tonic::async_trait]
+impl crate::subnet::rpc::snow::engine::common::vm::Connector for Tracker {
+    /// Implements the [`crate::subnet::rpc::snow::engine::common::vm::Connector`] trait.
+    async fn connected(&self, node_id: &node::Id) -> io::Result<()> {
+        self.connect(node_id).await
+    }
+
+    /// Implements the [`crate::subnet::rpc::snow::engine::common::vm::Connector`] trait.
+    async fn disconnected(&self, node_id: &node::Id) -> io::Result<()> {
+        self.disconnect(node_id).await
+    }
+}
+
+#[cfg(test)]
+fn at(secs: i64) -> DateTime<Utc> {
+    use chrono::TimeZone;
+    Utc.timestamp_opt(secs, 0).unwrap()
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::uptime::test_uptime_intervals --exact --show-output
+#[tokio::test]
+async fn test_uptime_intervals() {
+    use crate::subnet::rpc::database::memdb;
+
+    // a very long half-life makes the uptime a plain connected fraction
+    let tracker = Tracker::new(memdb::Database::new(), Duration::from_secs(1_000_000_000));
+    let node_id = node::Id::from_slice(&[1; 20]);
+
+    assert_eq!(tracker.uptime_at(&node_id, at(0)).await.unwrap(), 0.0);
+
+    tracker.connect_at(&node_id, at(0)).await.unwrap();
+    assert!((tracker.uptime_at(&node_id, at(10)).await.unwrap() - 1.0).abs() < 1e-6);
+
+    tracker.disconnect_at(&node_id, at(30)).await.unwrap();
+    assert!((tracker.uptime_at(&node_id, at(40)).await.unwrap() - 0.75).abs() < 1e-6);
+
+    tracker.connect_at(&node_id, at(40)).await.unwrap();
+    assert!((tracker.uptime_at(&node_id, at(60)).await.unwrap() - (50.0 / 60.0)).abs() < 1e-6);
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::uptime::test_uptime_decay --exact --show-output
+#[tokio::test]
+async fn test_uptime_decay() {
+    use crate::subnet::rpc::database::memdb;
+
+    let tracker = Tracker::new(memdb::Database::new(), Duration::from_secs(10));
+    let node_id = node::Id::from_slice(&[2; 20]);
+
+    // connected for many half-lives, then disconnected for exactly one
+    tracker.connect_at(&node_id, at(0)).await.unwrap();
+    tracker.disconnect_at(&node_id, at(1000)).await.unwrap();
+    assert!((tracker.uptime_at(&node_id, at(1010)).await.unwrap() - 0.5).abs() < 1e-6);
+
+    // recent downtime dominates
+    assert!(tracker.uptime_at(&node_id, at(1100)).await.unwrap() < 0.01);
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::uptime::test_uptime_persisted --exact --show-output
+#[tokio::test]
+async fn test_uptime_persisted() {
+    use crate::subnet::rpc::database::memdb;
+
+    let db = memdb::Database::new();
+    let half_life = Duration::from_secs(1_000_000_000);
+    let node_id = node::Id::from_slice(&[3; 20]);
+
+    let tracker = Tracker::new(db.clone_box(), half_life);
+    tracker.connect_at(&node_id, at(0)).await.unwrap();
+    tracker.disconnect_at(&node_id, at(20)).await.unwrap();
+    drop(tracker);
+
+    // restart over the same database
+    let tracker = Tracker::new(db, half_life);
+    assert!((tracker.uptime_at(&node_id, at(40)).await.unwrap() - 0.5).abs() < 1e-6);
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::uptime::test_connector_uptime --exact --show-output
+#[tokio::test]
+async fn test_connector_uptime() {
+    use crate::subnet::rpc::{database::memdb, snow::engine::common::vm::Connector};
+
+    let tracker = Tracker::new(memdb::Database::new(), Duration::from_secs(1_000_000_000));
+    let connector: &(dyn Connector + Send + Sync) = &tracker;
+    let node_id = node::Id::from_slice(&[1; 20]);
+
+    connector
+        .connected_with_version(&node_id, "avalanche/1.10.0")
+        .await
+        .unwrap();
+    let later = Utc::now() + chrono::Duration::seconds(10);
+    assert!(tracker.uptime_at(&node_id, later).await.unwrap() > 0.99);
+
+    // disconnected right away, so the node was down for nearly all the time
+    connector.disconnected(&node_id).await.unwrap();
+    let later = Utc::now() + chrono::Duration::seconds(10);
+    assert!(tracker.uptime_at(&node_id, later).await.unwrap() < 0.01);
+}
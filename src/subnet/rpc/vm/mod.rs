@@ -9,19 +9,12 @@ use std::{
 };
 
 use crate::{
-    proto::{
-        pb::{
-            self,
-            vm::vm_server::{Vm as VmImpl, VmServer},
-        },
-        PROTOCOL_VERSION,
-    },
-    subnet::rpc::{runtime, utils},
+    proto::{pb::vm::vm_server::Vm as VmImpl, PROTOCOL_VERSION},
+    subnet::rpc::{plugin, runtime, utils},
 };
 use jsonrpc_core::futures::FutureExt;
 use tokio::sync::broadcast::Receiver;
 use tonic::transport::server::NamedService;
-use tonic_health::server::health_reporter;
 
 use super::runtime::Initializer;
 
@@ -83,33 +76,16 @@ where
     serve_with_address(vm, vm_server_addr, stop_ch).await
 }
 
+/// Serves the Vm service on the address until the stop signal is broadcasted.
+/// The health, reflection and Vm services are shared with the go-plugin
+/// server of [`crate::subnet::rpc::plugin::serve`], which also serves a
+/// database and prints the handshake message.
 pub async fn serve_with_address<V>(vm: V, addr: SocketAddr, mut stop_ch: Receiver<()>) -> Result<()>
 where
     V: VmImpl,
 {
-    let (mut health_reporter, health_svc) = health_reporter();
-    health_reporter.set_serving::<HealthServer>().await;
-
-    // ref. https://github.com/hyperium/tonic/blob/v0.7.2/examples/src/reflection/server.rs
-    // ref. https://docs.rs/prost-types/latest/prost_types/struct.FileDescriptorSet.html
-    let reflection_service = tonic_reflection::server::Builder::configure()
-        .register_encoded_file_descriptor_set(pb::rpcdb::FILE_DESCRIPTOR_SET)
-        .register_encoded_file_descriptor_set(pb::vm::FILE_DESCRIPTOR_SET)
-        .register_encoded_file_descriptor_set(pb::google::protobuf::FILE_DESCRIPTOR_SET)
-        .register_encoded_file_descriptor_set(pb::io::prometheus::client::FILE_DESCRIPTOR_SET)
-        .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
-        .build()
-        .map_err(|e| {
-            Error::new(
-                ErrorKind::Other,
-                format!("failed to create gRPC reflection service: {:?}", e),
-            )
-        })?;
-
-    utils::grpc::default_server()
-        .add_service(health_svc)
-        .add_service(reflection_service)
-        .add_service(VmServer::new(vm))
+    plugin::vm_router::<HealthServer, V>(vm)
+        .await?
         .serve_with_shutdown(addr, stop_ch.recv().map(|_| ()))
         .await
         .map_err(|e| Error::new(ErrorKind::Other, format!("grpc server failed: {:?}", e)))?;
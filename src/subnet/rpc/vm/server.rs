@@ -6,7 +6,6 @@ use std::{
 
 use crate::{
     ids,
-    packer::U32_LEN,
     proto::pb::{
         self,
         aliasreader::alias_reader_client::AliasReaderClient,
@@ -31,7 +30,7 @@ use crate::{
             validators::client::ValidatorStateClient,
             State,
         },
-        snowman::block::ChainVm,
+        snowman::{ancestors::AncestorsBatch, block::ChainVm},
         utils::{
             self,
             grpc::{self, timestamp_from_time},
@@ -51,8 +50,9 @@ pub struct Server<V> {
 
     #[cfg(feature = "subnet_metrics")]
     #[cfg_attr(docsrs, doc(cfg(feature = "subnet_metrics")))]
-    /// Subnet Prometheus process metrics.
-    pub process_metrics: Arc<RwLock<prometheus::Registry>>,
+    /// Gathers the process metrics and the registries added to it (e.g.,
+    /// by the VM's database layers) for the "Gather" call.
+    pub metrics: crate::subnet::rpc::plugin::metrics::Gatherer,
 
     /// Stop channel broadcast producer.
     pub stop_ch: broadcast::Sender<()>,
@@ -64,11 +64,35 @@ impl<V: ChainVm> Server<V> {
             vm: Arc::new(RwLock::new(vm)),
             #[cfg(feature = "subnet_metrics")]
             #[cfg_attr(docsrs, doc(cfg(feature = "subnet_metrics")))]
-            process_metrics: Arc::new(RwLock::new(prometheus::default_registry().to_owned())),
+            metrics: crate::subnet::rpc::plugin::metrics::Gatherer::new(
+                prometheus::default_registry().to_owned(),
+            ),
             stop_ch,
         }
     }
 
+    /// Serves the "Gather" call from the gatherer, rather than from the
+    /// process metrics alone.
+    #[cfg(feature = "subnet_metrics")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subnet_metrics")))]
+    #[must_use]
+    pub fn with_gatherer(
+        mut self,
+        gatherer: crate::subnet::rpc::plugin::metrics::Gatherer,
+    ) -> Self {
+        self.metrics = gatherer;
+        self
+    }
+
+    /// Returns the process metrics registry, now the VM registry of
+    /// [`Server::metrics`]. Metrics registered to it are still gathered.
+    #[cfg(feature = "subnet_metrics")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subnet_metrics")))]
+    #[deprecated(note = "use `metrics`, the gatherer serving the \"Gather\" call")]
+    pub fn process_metrics(&self) -> Arc<RwLock<prometheus::Registry>> {
+        Arc::new(RwLock::new(self.metrics.vm_registry()))
+    }
+
     /// Attempts to get the ancestors of a block from the underlying Vm.
     pub async fn vm_ancestors(
         &self,
@@ -519,7 +543,7 @@ where
             .await
             .map_err(|e| tonic::Status::unknown(e.to_string()))?;
 
-        Ok(Response::new(vm::VersionResponse { version }))
+        Ok(Response::new(version_response(&version)))
     }
 
     async fn connected(
@@ -532,7 +556,7 @@ where
         let inner_vm = self.vm.read().await;
         let node_id = ids::node::Id::from_slice(&req.node_id);
         inner_vm
-            .connected(&node_id)
+            .connected_with_version(&node_id, &req.version)
             .await
             .map_err(|e| tonic::Status::unknown(e.to_string()))?;
 
@@ -759,15 +783,11 @@ where
             }
         };
 
-        let mut ancestors = Vec::with_capacity(max_blocks_num);
-        let block_bytes = block.bytes().await;
-
-        // length, in bytes, of all elements of ancestors
-        let mut ancestors_bytes_len = block_bytes.len() + U32_LEN;
-        ancestors.push(Bytes::from(block_bytes.to_owned()));
+        let mut ancestors = AncestorsBatch::new(max_blocks_num, max_blocks_size);
+        ancestors.push(block.bytes().await);
 
-        while ancestors.len() < max_blocks_num {
-            if start.elapsed() < max_blocks_retrival_time {
+        while !ancestors.is_full() {
+            if start.elapsed() >= max_blocks_retrival_time {
                 log::debug!("get_ancestors exceeded max block retrival time");
                 break;
             }
@@ -786,23 +806,14 @@ where
                 }
             };
 
-            let block_bytes = block.bytes().await;
-
-            // Ensure response size isn't too large. Include U32_LEN because
-            // the size of the message is included with each container, and the size
-            // is repr. by 4 bytes.
-            ancestors_bytes_len += block_bytes.len() + U32_LEN;
-
-            if ancestors_bytes_len > max_blocks_size {
-                log::debug!("get_ancestors reached maximum response size: {ancestors_bytes_len}");
+            // stops at whichever of the count and size caps is hit first
+            if !ancestors.push(block.bytes().await) {
                 break;
             }
-
-            ancestors.push(Bytes::from(block_bytes.to_owned()));
         }
 
         Ok(Response::new(vm::GetAncestorsResponse {
-            blks_bytes: ancestors,
+            blks_bytes: ancestors.into_blocks(),
         }))
     }
 
@@ -851,10 +862,10 @@ where
         log::debug!("gather called");
 
         // ref. <https://prometheus.io/docs/instrumenting/writing_clientlibs/#process-metrics>
-        let metric_families = crate::subnet::rpc::metrics::MetricsFamilies::from(
-            &self.process_metrics.read().await.gather(),
-        )
-        .mfs;
+        let metric_families = self
+            .metrics
+            .gather_families()
+            .map_err(|e| tonic::Status::unknown(e.to_string()))?;
 
         Ok(Response::new(vm::GatherResponse { metric_families }))
     }
@@ -919,7 +930,21 @@ where
     ) -> std::result::Result<Response<vm::StateSyncEnabledResponse>, tonic::Status> {
         log::debug!("state_sync_enabled called");
 
-        Err(tonic::Status::unimplemented("state_sync_enabled"))
+        let inner_vm = self.vm.read().await;
+        let enabled = match inner_vm.state_syncable() {
+            Some(syncable) => syncable.state_sync_enabled().await,
+            None => Ok(false),
+        };
+        match enabled {
+            Ok(enabled) => Ok(Response::new(vm::StateSyncEnabledResponse {
+                enabled,
+                err: 0,
+            })),
+            Err(e) => Ok(Response::new(vm::StateSyncEnabledResponse {
+                enabled: false,
+                err: state_sync_error_code(&e).map_err(tonic::Status::unknown)?,
+            })),
+        }
     }
 
     async fn get_ongoing_sync_state_summary(
@@ -928,27 +953,73 @@ where
     ) -> std::result::Result<Response<vm::GetOngoingSyncStateSummaryResponse>, tonic::Status> {
         log::debug!("get_ongoing_sync_state_summary called");
 
-        Err(tonic::Status::unimplemented(
-            "get_ongoing_sync_state_summary",
-        ))
+        let inner_vm = self.vm.read().await;
+        let summary = match inner_vm.state_syncable() {
+            Some(syncable) => syncable.get_ongoing_sync_state_summary().await,
+            None => Err(errors::Error::StateSyncableVMNotImplemented.to_err()),
+        };
+        match summary {
+            Ok(summary) => Ok(Response::new(vm::GetOngoingSyncStateSummaryResponse {
+                id: Bytes::from(summary.id().await.to_vec()),
+                height: summary.height().await,
+                bytes: Bytes::from(summary.bytes().await.to_vec()),
+                err: 0,
+            })),
+            Err(e) => Ok(Response::new(vm::GetOngoingSyncStateSummaryResponse {
+                err: state_sync_error_code(&e).map_err(tonic::Status::unknown)?,
+                ..Default::default()
+            })),
+        }
     }
 
     async fn parse_state_summary(
         &self,
-        _req: Request<vm::ParseStateSummaryRequest>,
+        req: Request<vm::ParseStateSummaryRequest>,
     ) -> std::result::Result<tonic::Response<vm::ParseStateSummaryResponse>, tonic::Status> {
         log::debug!("parse_state_summary called");
 
-        Err(tonic::Status::unimplemented("parse_state_summary"))
+        let req = req.into_inner();
+        let inner_vm = self.vm.read().await;
+        let summary = match inner_vm.state_syncable() {
+            Some(syncable) => syncable.parse_state_summary(&req.bytes).await,
+            None => Err(errors::Error::StateSyncableVMNotImplemented.to_err()),
+        };
+        match summary {
+            Ok(summary) => Ok(Response::new(vm::ParseStateSummaryResponse {
+                id: Bytes::from(summary.id().await.to_vec()),
+                height: summary.height().await,
+                err: 0,
+            })),
+            Err(e) => Ok(Response::new(vm::ParseStateSummaryResponse {
+                err: state_sync_error_code(&e).map_err(tonic::Status::unknown)?,
+                ..Default::default()
+            })),
+        }
     }
 
     async fn get_state_summary(
         &self,
-        _req: Request<vm::GetStateSummaryRequest>,
+        req: Request<vm::GetStateSummaryRequest>,
     ) -> std::result::Result<Response<vm::GetStateSummaryResponse>, tonic::Status> {
         log::debug!("get_state_summary called");
 
-        Err(tonic::Status::unimplemented("get_state_summary"))
+        let req = req.into_inner();
+        let inner_vm = self.vm.read().await;
+        let summary = match inner_vm.state_syncable() {
+            Some(syncable) => syncable.get_state_summary(req.height).await,
+            None => Err(errors::Error::StateSyncableVMNotImplemented.to_err()),
+        };
+        match summary {
+            Ok(summary) => Ok(Response::new(vm::GetStateSummaryResponse {
+                id: Bytes::from(summary.id().await.to_vec()),
+                bytes: Bytes::from(summary.bytes().await.to_vec()),
+                err: 0,
+            })),
+            Err(e) => Ok(Response::new(vm::GetStateSummaryResponse {
+                err: state_sync_error_code(&e).map_err(tonic::Status::unknown)?,
+                ..Default::default()
+            })),
+        }
     }
 
     async fn get_last_state_summary(
@@ -957,16 +1028,50 @@ where
     ) -> std::result::Result<Response<vm::GetLastStateSummaryResponse>, tonic::Status> {
         log::debug!("get_last_state_summary called");
 
-        Err(tonic::Status::unimplemented("get_last_state_summary"))
+        let inner_vm = self.vm.read().await;
+        let summary = match inner_vm.state_syncable() {
+            Some(syncable) => syncable.get_last_state_summary().await,
+            None => Err(errors::Error::StateSyncableVMNotImplemented.to_err()),
+        };
+        match summary {
+            Ok(summary) => Ok(Response::new(vm::GetLastStateSummaryResponse {
+                id: Bytes::from(summary.id().await.to_vec()),
+                height: summary.height().await,
+                bytes: Bytes::from(summary.bytes().await.to_vec()),
+                err: 0,
+            })),
+            Err(e) => Ok(Response::new(vm::GetLastStateSummaryResponse {
+                err: state_sync_error_code(&e).map_err(tonic::Status::unknown)?,
+                ..Default::default()
+            })),
+        }
     }
 
     async fn state_summary_accept(
         &self,
-        _req: Request<vm::StateSummaryAcceptRequest>,
+        req: Request<vm::StateSummaryAcceptRequest>,
     ) -> std::result::Result<tonic::Response<vm::StateSummaryAcceptResponse>, tonic::Status> {
         log::debug!("state_summary_accept called");
 
-        Err(tonic::Status::unimplemented("state_summary_accept"))
+        let req = req.into_inner();
+        let inner_vm = self.vm.read().await;
+        let mode = match inner_vm.state_syncable() {
+            Some(syncable) => match syncable.parse_state_summary(&req.bytes).await {
+                Ok(mut summary) => summary.accept().await,
+                Err(e) => Err(e),
+            },
+            None => Err(errors::Error::StateSyncableVMNotImplemented.to_err()),
+        };
+        match mode {
+            Ok(mode) => Ok(Response::new(vm::StateSummaryAcceptResponse {
+                mode: mode as i32,
+                err: 0,
+            })),
+            Err(e) => Ok(Response::new(vm::StateSummaryAcceptResponse {
+                mode: vm::state_summary_accept_response::Mode::Unspecified as i32,
+                err: state_sync_error_code(&e).map_err(tonic::Status::unknown)?,
+            })),
+        }
     }
 
     async fn verify_height_index(
@@ -979,10 +1084,84 @@ where
 
     async fn get_block_id_at_height(
         &self,
-        _req: Request<vm::GetBlockIdAtHeightRequest>,
+        req: Request<vm::GetBlockIdAtHeightRequest>,
     ) -> std::result::Result<Response<vm::GetBlockIdAtHeightResponse>, tonic::Status> {
         log::debug!("get_block_id_at_height called");
 
-        Err(tonic::Status::unimplemented("get_block_id_at_height"))
+        let req = req.into_inner();
+        let inner_vm = self.vm.read().await;
+        match inner_vm.get_block_id_at_height(req.height).await {
+            Ok(id) => Ok(Response::new(vm::GetBlockIdAtHeightResponse {
+                blk_id: Bytes::from(id.to_vec()),
+                err: 0, // return 0 indicating no error
+            })),
+            Err(e) => {
+                let err = if errors::is_not_found(&e) {
+                    errors::Error::NotFound.to_i32()
+                } else if e.to_string() == errors::Error::HeightIndexedVMNotImplemented.as_str() {
+                    errors::Error::HeightIndexedVMNotImplemented.to_i32()
+                } else if e.to_string() == errors::Error::IndexIncomplete.as_str() {
+                    errors::Error::IndexIncomplete.to_i32()
+                } else {
+                    return Err(tonic::Status::unknown(e.to_string()));
+                };
+                Ok(Response::new(vm::GetBlockIdAtHeightResponse {
+                    blk_id: Bytes::new(),
+                    err,
+                }))
+            }
+        }
+    }
+}
+
+/// Returns the version RPC response for the VM version, as the semver
+/// string avalanchego parses.
+fn version_response(version: &Version) -> vm::VersionResponse {
+    vm::VersionResponse {
+        version: version.to_string(),
+    }
+}
+
+/// Returns the error code of a state sync RPC response for the error, as
+/// avalanchego expects it, or the error message if it has no code.
+fn state_sync_error_code(e: &std::io::Error) -> std::result::Result<i32, String> {
+    if errors::is_not_found(e) {
+        Ok(errors::Error::NotFound.to_i32())
+    } else if e.to_string() == errors::Error::StateSyncableVMNotImplemented.as_str() {
+        Ok(errors::Error::StateSyncableVMNotImplemented.to_i32())
+    } else {
+        Err(e.to_string())
     }
 }
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::vm::server::test_version --exact --show-output
+#[test]
+fn test_version() {
+    let version = version_response(&Version::new(1, 2, 3)).version;
+    assert_eq!(version, "1.2.3");
+    assert_eq!(Version::parse(&version).unwrap(), Version::new(1, 2, 3));
+
+    let pre = Version::parse("0.5.0-rc.1+build.7").unwrap();
+    assert_eq!(
+        Version::parse(&version_response(&pre).version).unwrap(),
+        pre
+    );
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- subnet::rpc::vm::server::test_state_sync_error_code --exact --show-output
+#[test]
+fn test_state_sync_error_code() {
+    // a VM without state sync reports it not implemented
+    assert_eq!(
+        state_sync_error_code(&errors::Error::StateSyncableVMNotImplemented.to_err()),
+        Ok(errors::Error::StateSyncableVMNotImplemented.to_i32())
+    );
+    assert_eq!(
+        state_sync_error_code(&errors::Error::NotFound.to_err()),
+        Ok(errors::Error::NotFound.to_i32())
+    );
+    assert_eq!(
+        state_sync_error_code(&std::io::Error::new(std::io::ErrorKind::Other, "boom")),
+        Err("boom".to_string())
+    );
+}
@@ -5,6 +5,7 @@ use std::{
     path::Path,
 };
 
+use crate::{hash, ids};
 use serde::{Deserialize, Serialize};
 
 /// ref. <https://pkg.go.dev/github.com/ava-labs/subnet-evm/core#Genesis>
@@ -154,6 +155,14 @@ impl Genesis {
             .map_err(|e| Error::new(ErrorKind::Other, format!("failed encode JSON {}", e)))
     }
 
+    /// Returns the SHA256 of the encoded genesis, as it is passed to the
+    /// "CreateChainTx" and stored by avalanchego for the blockchain.
+    /// Allocations are keyed in a sorted map, so the encoding is canonical.
+    pub fn hash(&self) -> io::Result<ids::Id> {
+        let d = self.to_bytes()?;
+        Ok(ids::Id::from_slice(&hash::sha256(&d)))
+    }
+
     /// Saves the current genesis to disk
     /// and overwrites the file.
     pub fn sync(&self, file_path: &str) -> io::Result<()> {
@@ -569,3 +578,27 @@ fn test_parse() {
     let d = d.encode_json().unwrap();
     log::info!("{}", d);
 }
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet_evm -- subnet_evm::genesis::test_hash --exact --show-output
+#[test]
+fn test_hash() {
+    let genesis = Genesis::default();
+    let h = genesis.hash().unwrap();
+    assert_eq!(h, genesis.hash().unwrap());
+    assert_eq!(h, Genesis::default().hash().unwrap());
+    assert_eq!(
+        h,
+        ids::Id::from_slice(&hash::sha256(genesis.to_bytes().unwrap()))
+    );
+
+    let mut changed = genesis.clone();
+    changed
+        .alloc
+        .as_mut()
+        .unwrap()
+        .values_mut()
+        .next()
+        .unwrap()
+        .balance += primitive_types::U256::one();
+    assert_ne!(changed.hash().unwrap(), h);
+}
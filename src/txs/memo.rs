@@ -0,0 +1,82 @@
+//! Transaction memo, as entered by users and tooling.
+use std::str::FromStr;
+
+use crate::errors::{Error, Result};
+
+/// Maximum memo size in bytes.
+/// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/vms/components/avax#pkg-constants>
+pub const MAX_LEN: usize = 256;
+
+/// Memo bytes of a base transaction, at most [`MAX_LEN`] bytes.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Memo(Vec<u8>);
+
+impl Memo {
+    pub fn new(d: &[u8]) -> Result<Self> {
+        if d.len() > MAX_LEN {
+            return Err(Error::Other {
+                message: format!("memo has {} bytes, exceeds the limit {}", d.len(), MAX_LEN),
+                retryable: false,
+            });
+        }
+        Ok(Self(d.to_vec()))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Returns the memo as a "0x"-prefixed hex string, which
+    /// [`Memo::from_str`] parses back to the same bytes.
+    pub fn to_hex(&self) -> String {
+        format!("0x{}", hex::encode(&self.0))
+    }
+}
+
+impl From<Memo> for Vec<u8> {
+    fn from(memo: Memo) -> Self {
+        memo.0
+    }
+}
+
+/// Parses the memo from a "0x"-prefixed hex string, or else from the UTF-8
+/// bytes of the string as is. The prefix alone decides: a prefixed string
+/// that is not valid hex is rejected rather than read as text, and text
+/// starting with "0x" must be hex-encoded to be kept as is.
+impl FromStr for Memo {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        match s.strip_prefix("0x") {
+            Some(h) => {
+                let d = hex::decode(h).map_err(|e| Error::Other {
+                    message: format!("invalid hex memo '{}' ({})", s, e),
+                    retryable: false,
+                })?;
+                Self::new(&d)
+            }
+            None => Self::new(s.as_bytes()),
+        }
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- txs::memo::test_memo --exact --show-output
+#[test]
+fn test_memo() {
+    let memo = Memo::from_str("0x00010203").unwrap();
+    assert_eq!(memo.as_bytes(), &[0x00, 0x01, 0x02, 0x03]);
+    assert_eq!(memo.to_hex(), "0x00010203");
+    assert_eq!(Memo::from_str(&memo.to_hex()).unwrap(), memo);
+
+    let memo = Memo::from_str("hello avalanche").unwrap();
+    assert_eq!(memo.as_bytes(), b"hello avalanche");
+    assert_eq!(memo.to_hex(), "0x68656c6c6f206176616c616e636865");
+    assert_eq!(Vec::from(memo), b"hello avalanche".to_vec());
+
+    // the prefix decides, so invalid hex is not read as text
+    assert!(Memo::from_str("0xhello").is_err());
+    assert!(Memo::from_str("").unwrap().as_bytes().is_empty());
+
+    assert!(Memo::from_str(&"a".repeat(MAX_LEN)).is_ok());
+    assert!(Memo::from_str(&"a".repeat(MAX_LEN + 1)).is_err());
+    assert!(Memo::from_str(&format!("0x{}", "ab".repeat(MAX_LEN + 1))).is_err());
+}
@@ -1,7 +1,12 @@
+pub mod memo;
 pub mod raw;
 pub mod transferable;
+mod unsigned;
 pub mod utxo;
 
+pub use memo::Memo;
+pub use utxo::Id as UtxoId;
+
 use super::{
     codec::{self, serde::hex_0x_bytes::Hex0xBytes},
     errors::{Error, Result},
@@ -324,6 +329,506 @@ impl Tx {
 
         Ok(packer)
     }
+
+    /// Unpacks the "BaseTx" fields at the current offset of the packer,
+    /// the inverse of [`Tx::pack`] without the codec version and type ID.
+    /// Empty outputs, inputs and memo are unpacked as "None".
+    pub fn unpack_from(packer: &packer::Packer) -> Result<Self> {
+        let network_id = packer.unpack_u32()?;
+        let blockchain_id = ids::Id::from_slice(&packer.unpack_bytes(ids::LEN)?);
+
+        let outputs_len = packer.unpack_u32()?;
+        let mut transferable_outputs = Vec::new();
+        for _ in 0..outputs_len {
+            transferable_outputs.push(transferable::Output::unpack_from(packer)?);
+        }
+
+        let inputs_len = packer.unpack_u32()?;
+        let mut transferable_inputs = Vec::new();
+        for _ in 0..inputs_len {
+            transferable_inputs.push(transferable::Input::unpack_from(packer)?);
+        }
+
+        let memo_len = packer.unpack_u32()?;
+        let memo = packer.unpack_bytes(memo_len as usize)?;
+
+        Ok(Self {
+            metadata: None,
+            network_id,
+            blockchain_id,
+            transferable_inputs: (!transferable_inputs.is_empty()).then_some(transferable_inputs),
+            transferable_outputs: (!transferable_outputs.is_empty())
+                .then_some(transferable_outputs),
+            memo: (!memo.is_empty()).then_some(memo),
+        })
+    }
+}
+
+/// Splits the signed transaction bytes into the unsigned transaction bytes
+/// (with the codec version and type ID) and its "secp256k1fx.Credential"s.
+///
+/// The unsigned transaction is decoded by its codec type ID to find where
+/// the credentials start; the credentials must then consume the rest of
+/// the bytes exactly.
+/// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/vms/platformvm/txs#Tx>
+pub fn split(signed_bytes: &[u8]) -> Result<(Vec<u8>, Vec<key::secp256k1::txs::Credential>)> {
+    let pos = unsigned::unsigned_tx_len(signed_bytes)?;
+    let creds = unpack_credentials(&signed_bytes[pos..])?;
+    Ok((signed_bytes[..pos].to_vec(), creds))
+}
+
+/// Combines the unsigned transaction bytes with the credentials,
+/// the inverse of [`split`].
+pub fn join(unsigned_bytes: &[u8], creds: &[key::secp256k1::txs::Credential]) -> Result<Vec<u8>> {
+    let packer = packer::Packer::load_bytes_for_pack(1 << 31, unsigned_bytes);
+    packer.pack_u32(creds.len() as u32)?;
+
+    let cred_type_id = key::secp256k1::txs::Credential::type_id();
+    for cred in creds.iter() {
+        packer.pack_u32(cred_type_id)?;
+        packer.pack_u32(cred.signatures.len() as u32)?;
+        for sig in cred.signatures.iter() {
+            packer.pack_bytes(sig)?;
+        }
+    }
+    Ok(packer.take_bytes().to_vec())
+}
+
+/// Parses the credential section, erroring unless the bytes hold exactly
+/// the declared number of well-formed credentials.
+fn unpack_credentials(d: &[u8]) -> Result<Vec<key::secp256k1::txs::Credential>> {
+    let packer = packer::Packer::load_bytes_for_unpack(d.len() + 1024, d);
+    let cred_type_id = key::secp256k1::txs::Credential::type_id();
+
+    let creds_len = packer.unpack_u32()?;
+    let mut creds = Vec::new();
+    for _ in 0..creds_len {
+        let type_id = packer.unpack_u32()?;
+        if type_id != cred_type_id {
+            return Err(Error::Other {
+                message: format!(
+                    "unexpected credential type ID {} (expected {})",
+                    type_id, cred_type_id
+                ),
+                retryable: false,
+            });
+        }
+
+        let sigs_len = packer.unpack_u32()?;
+        let mut signatures = Vec::new();
+        for _ in 0..sigs_len {
+            signatures.push(packer.unpack_bytes(key::secp256k1::signature::LEN)?);
+        }
+        creds.push(key::secp256k1::txs::Credential::new(signatures));
+    }
+
+    if packer.get_offset() != d.len() {
+        return Err(Error::Other {
+            message: format!(
+                "{} trailing bytes after {} credentials",
+                d.len() - packer.get_offset(),
+                creds_len
+            ),
+            retryable: false,
+        });
+    }
+    Ok(creds)
+}
+
+/// Verifies that the transaction neither creates nor destroys value: for
+/// each asset, the inputs must add up to the outputs, plus "fee" for
+/// "fee_asset_id". Only the base transaction inputs and outputs are
+/// counted, so the staked outputs and imported inputs of P-chain and atomic
+/// transactions must be verified along with their own fields.
+pub fn verify_conservation(tx: &Tx, fee_asset_id: &ids::Id, fee: u64) -> std::io::Result<()> {
+    use std::{
+        collections::BTreeMap,
+        io::{Error, ErrorKind},
+    };
+
+    let overflow = |asset_id: &ids::Id| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("amounts overflow u64 for asset {}", asset_id),
+        )
+    };
+
+    // asset ID to the total of its inputs and outputs
+    let mut totals: BTreeMap<ids::Id, (u64, u64)> = BTreeMap::new();
+    totals.insert(*fee_asset_id, (0, 0));
+    for input in tx.transferable_inputs.iter().flatten() {
+        let amount = input_amount(input);
+        let total = totals.entry(input.asset_id).or_insert((0, 0));
+        total.0 = total
+            .0
+            .checked_add(amount)
+            .ok_or_else(|| overflow(&input.asset_id))?;
+    }
+    for output in tx.transferable_outputs.iter().flatten() {
+        let amount = output_amount(output);
+        let total = totals.entry(output.asset_id).or_insert((0, 0));
+        total.1 = total
+            .1
+            .checked_add(amount)
+            .ok_or_else(|| overflow(&output.asset_id))?;
+    }
+
+    for (asset_id, (inputs, outputs)) in totals.iter() {
+        let burned = if asset_id == fee_asset_id { fee } else { 0 };
+        let expected = outputs
+            .checked_add(burned)
+            .ok_or_else(|| overflow(asset_id))?;
+        if *inputs != expected {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "asset {} is not conserved: inputs {} != outputs {} + burned {}",
+                    asset_id, inputs, outputs, burned
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Returns the amount of the input, looking into stakeable locked inputs.
+fn input_amount(input: &transferable::Input) -> u64 {
+    if let Some(transfer_input) = &input.transfer_input {
+        transfer_input.amount
+    } else if let Some(stakeable_lock_in) = &input.stakeable_lock_in {
+        stakeable_lock_in.transfer_input.amount
+    } else {
+        0
+    }
+}
+
+/// Returns the amount of the output, looking into stakeable locked outputs.
+fn output_amount(output: &transferable::Output) -> u64 {
+    if let Some(transfer_output) = &output.transfer_output {
+        transfer_output.amount
+    } else if let Some(stakeable_lock_out) = &output.stakeable_lock_out {
+        stakeable_lock_out.transfer_output.amount
+    } else {
+        0
+    }
+}
+
+/// Verifies that the transaction pays at least "required_fee", i.e., that
+/// its "fee_asset_id" inputs exceed its outputs by that much, so that an
+/// underpaid transaction is caught before it is issued. As in
+/// [`verify_conservation`], only the base transaction is counted.
+pub fn check_fee(tx: &Tx, fee_asset_id: &ids::Id, required_fee: u64) -> std::io::Result<()> {
+    use std::io::{Error, ErrorKind};
+
+    let overflow = || {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("amounts overflow u64 for asset {}", fee_asset_id),
+        )
+    };
+    let mut inputs: u64 = 0;
+    for input in tx.transferable_inputs.iter().flatten() {
+        if input.asset_id == *fee_asset_id {
+            inputs = inputs
+                .checked_add(input_amount(input))
+                .ok_or_else(overflow)?;
+        }
+    }
+    let mut outputs: u64 = 0;
+    for output in tx.transferable_outputs.iter().flatten() {
+        if output.asset_id == *fee_asset_id {
+            outputs = outputs
+                .checked_add(output_amount(output))
+                .ok_or_else(overflow)?;
+        }
+    }
+
+    // outputs above the inputs pay nothing
+    let fee = inputs.saturating_sub(outputs);
+    if fee < required_fee {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "tx pays fee {} below required fee {} (short by {})",
+                fee,
+                required_fee,
+                required_fee - fee
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Returns a transfer input of "amount" of the asset, for tests.
+#[cfg(test)]
+pub(crate) fn test_input(asset_id: ids::Id, amount: u64) -> transferable::Input {
+    transferable::Input {
+        asset_id,
+        transfer_input: Some(key::secp256k1::txs::transfer::Input {
+            amount,
+            sig_indices: vec![0],
+        }),
+        ..Default::default()
+    }
+}
+
+/// Returns a transfer output of "amount" of the asset to the owners, for tests.
+#[cfg(test)]
+pub(crate) fn test_output(
+    asset_id: ids::Id,
+    amount: u64,
+    owners: &key::secp256k1::txs::OutputOwners,
+) -> transferable::Output {
+    transferable::Output {
+        asset_id,
+        transfer_output: Some(key::secp256k1::txs::transfer::Output::new(
+            amount,
+            owners.clone(),
+        )),
+        ..Default::default()
+    }
+}
+
+/// Returns a UTXO at "output_index" holding a transfer output of "amount" of
+/// the asset to the owners, or no output if "amount" is "None" (e.g., as
+/// mint outputs are decoded), for the wallet tests.
+#[cfg(all(test, feature = "wallet"))]
+pub(crate) fn test_utxo(
+    output_index: u32,
+    asset_id: ids::Id,
+    amount: Option<u64>,
+    owners: &key::secp256k1::txs::OutputOwners,
+) -> utxo::Utxo {
+    utxo::Utxo {
+        utxo_id: utxo::Id::new(&[3; 32], output_index, false).unwrap(),
+        asset_id,
+        transfer_output: amount
+            .map(|amount| key::secp256k1::txs::transfer::Output::new(amount, owners.clone())),
+        ..Default::default()
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- txs::test_verify_conservation --exact --show-output
+#[test]
+fn test_verify_conservation() {
+    let avax = ids::Id::from_slice(&[1; 32]);
+    let other = ids::Id::from_slice(&[2; 32]);
+    let owners = key::secp256k1::txs::OutputOwners::new(0, 1, &[ids::short::Id::empty()]);
+
+    let mut tx = Tx {
+        transferable_inputs: Some(vec![
+            test_input(avax, 700),
+            test_input(avax, 400),
+            test_input(other, 50),
+        ]),
+        transferable_outputs: Some(vec![
+            test_output(avax, 1_000, &owners),
+            test_output(other, 50, &owners),
+        ]),
+        ..Default::default()
+    };
+    assert!(verify_conservation(&tx, &avax, 100).is_ok());
+
+    // the fee must be burned exactly
+    assert!(verify_conservation(&tx, &avax, 99).is_err());
+
+    // inflated output of the non-fee asset
+    tx.transferable_outputs = Some(vec![
+        test_output(avax, 1_000, &owners),
+        test_output(other, 51, &owners),
+    ]);
+    let err = verify_conservation(&tx, &avax, 100).unwrap_err();
+    assert!(err.to_string().contains(&other.to_string()));
+    assert!(!err.to_string().contains(&avax.to_string()));
+
+    // a fee with no inputs to pay it
+    assert!(verify_conservation(&Tx::default(), &avax, 1).is_err());
+    assert!(verify_conservation(&Tx::default(), &avax, 0).is_ok());
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- txs::test_check_fee --exact --show-output
+#[test]
+fn test_check_fee() {
+    let avax = ids::Id::from_slice(&[1; 32]);
+    let other = ids::Id::from_slice(&[2; 32]);
+    let owners = key::secp256k1::txs::OutputOwners::new(0, 1, &[ids::short::Id::empty()]);
+
+    // implied fee of 1_100 - 1_000 = 100, other assets do not count
+    let tx = Tx {
+        transferable_inputs: Some(vec![
+            test_input(avax, 700),
+            test_input(avax, 400),
+            test_input(other, 500),
+        ]),
+        transferable_outputs: Some(vec![
+            test_output(avax, 1_000, &owners),
+            test_output(other, 50, &owners),
+        ]),
+        ..Default::default()
+    };
+    assert!(check_fee(&tx, &avax, 100).is_ok());
+    assert!(check_fee(&tx, &avax, 0).is_ok());
+
+    let err = check_fee(&tx, &avax, 1_000_000).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("short by 999900"));
+
+    // more outputs than inputs pays nothing
+    let tx = Tx {
+        transferable_inputs: Some(vec![test_input(avax, 10)]),
+        transferable_outputs: Some(vec![test_output(avax, 20, &owners)]),
+        ..Default::default()
+    };
+    assert!(check_fee(&tx, &avax, 1)
+        .unwrap_err()
+        .to_string()
+        .contains("short by 1"));
+    assert!(check_fee(&Tx::default(), &avax, 0).is_ok());
+}
+
+/// Verifies that the transaction was built for the expected network, so that
+/// a transaction signed for one network (e.g., fuji) cannot be replayed on
+/// another (e.g., mainnet).
+pub fn verify_network(tx: &Tx, expected_network_id: u32) -> std::io::Result<()> {
+    if tx.network_id != expected_network_id {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!(
+                "tx network ID {} does not match expected network ID {}",
+                tx.network_id, expected_network_id
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- txs::test_verify_network --exact --show-output
+#[test]
+fn test_verify_network() {
+    let fuji_tx = Tx {
+        network_id: 5,
+        ..Default::default()
+    };
+    assert!(verify_network(&fuji_tx, 5).is_ok());
+
+    let err = verify_network(&fuji_tx, 1).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(err.to_string().contains("network ID 5"));
+    assert!(err.to_string().contains("expected network ID 1"));
+}
+
+/// Reorders the credentials, given in the same order as "inputs", to match
+/// the canonical (sorted) order of the inputs the transaction is serialized
+/// with. Errors if the number of credentials differs from the inputs.
+pub fn order_credentials(
+    inputs: &[transferable::Input],
+    creds: Vec<key::secp256k1::txs::Credential>,
+) -> std::io::Result<Vec<key::secp256k1::txs::Credential>> {
+    if inputs.len() != creds.len() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "{} credentials for {} inputs, must be the same",
+                creds.len(),
+                inputs.len()
+            ),
+        ));
+    }
+
+    // stable, so credentials of equal inputs keep their relative order
+    let mut paired: Vec<(&transferable::Input, key::secp256k1::txs::Credential)> =
+        inputs.iter().zip(creds).collect();
+    paired.sort_by(|a, b| a.0.cmp(b.0));
+    Ok(paired.into_iter().map(|(_, cred)| cred).collect())
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- txs::test_order_credentials --exact --show-output
+#[test]
+fn test_order_credentials() {
+    let input = |tx_id: u8, output_index: u32| transferable::Input {
+        utxo_id: utxo::Id::new(&[tx_id; 32], output_index, false).unwrap(),
+        ..Default::default()
+    };
+    let cred = |sig: u8| key::secp256k1::txs::Credential::new(vec![vec![sig; 65]]);
+
+    let inputs = vec![input(3, 0), input(1, 1), input(1, 0), input(2, 0)];
+    let creds = vec![cred(30), cred(11), cred(10), cred(20)];
+    let ordered = order_credentials(&inputs, creds).unwrap();
+    assert_eq!(ordered, vec![cred(10), cred(11), cred(20), cred(30)]);
+
+    // same order as the sorted inputs
+    let mut sorted = inputs.clone();
+    sorted.sort();
+    assert_eq!(sorted[0].utxo_id.tx_id, ids::Id::from_slice(&[1; 32]));
+    assert_eq!(sorted[0].utxo_id.output_index, 0);
+
+    let err = order_credentials(&inputs, vec![cred(1)]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("1 credentials for 4 inputs"));
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- txs::test_split --exact --show-output
+#[test]
+fn test_split() {
+    // signed "platformvm.AddValidatorTx" from "test_add_validator_tx_serialization_with_one_signer"
+    let signed_bytes = crate::formatting::decode_hex_with_checksum(
+        "00000000000c000f424000000000000000000000000000000000000000000000000000000000000000000000000188eec2e099c6a528e689618e8721e04ae85ea574c7a15a7968644d14d54780140000000702c6874d687fc0000000000000000000000000010000000165844a05405f3662c1928142c6c2a783ef871de900000001783b22c6a8d6834c8930aeac3db60263c12e98160ef7221b4d5e622e870f92d90000000088eec2e099c6a528e689618e8721e04ae85ea574c7a15a7968644d14d54780140000000502c6891f11c9e0000000000100000000000000009cd7b3e47904f67cc48eb5b9afdb03e6d18acf6c00000000623d72670000000063c91062000001d1a94a20000000000188eec2e099c6a528e689618e8721e04ae85ea574c7a15a7968644d14d547801400000007000001d1a94a20000000000000000000000000010000000165844a05405f3662c1928142c6c2a783ef871de90000000b0000000000000000000000010000000165844a05405f3662c1928142c6c2a783ef871de900004e2000000001000000090000000183a863c89002ab70a12c37802284b703c1653a93a0a25e0451f0daa07916a32471b165bb4b1bd1b6edc6b494bc6aac63c24fccfd9a547b5f03a60252d45c2480005ffd4ae5".as_bytes(),
+    )
+    .unwrap();
+
+    let (unsigned_bytes, creds) = split(&signed_bytes).unwrap();
+    assert_eq!(creds.len(), 1);
+    assert_eq!(creds[0].signatures.len(), 1);
+    assert_eq!(
+        unsigned_bytes.len() + 4 + 4 + 4 + key::secp256k1::signature::LEN,
+        signed_bytes.len()
+    );
+
+    // the signature must be over the unsigned bytes
+    let test_key = key::secp256k1::private_key::Key::from_cb58(
+        "PrivateKey-2kqWNDaqUKQyE4ZsV5GLCGeizE6sHAJVyjnfjXoXrtcZpK9M67",
+    )
+    .unwrap();
+    let sig = key::secp256k1::signature::Sig::from_bytes(&creds[0].signatures[0]).unwrap();
+    let (recovered, _) = sig
+        .recover_public_key(&hash::sha256(&unsigned_bytes))
+        .unwrap();
+    assert_eq!(recovered, test_key.to_public_key());
+
+    assert_eq!(join(&unsigned_bytes, &creds).unwrap(), signed_bytes);
+
+    // credential count does not match the credentials
+    let mut mismatched = signed_bytes.clone();
+    let count_pos = unsigned_bytes.len();
+    mismatched[count_pos..count_pos + 4].copy_from_slice(&2_u32.to_be_bytes());
+    assert!(split(&mismatched).is_err());
+
+    // "avm.BaseTx" with no outputs, inputs or memo ends in zero bytes
+    // that could be mistaken for an empty credential section
+    let packer = packer::Packer::new(1024, 0);
+    packer.pack_u16(codec::VERSION).unwrap();
+    packer
+        .pack_u32(codec::X_TYPES["avm.BaseTx"] as u32)
+        .unwrap();
+    packer.pack_u32(10).unwrap(); // network ID
+    packer.pack_bytes(ids::Id::empty().as_ref()).unwrap();
+    packer.pack_u32(0).unwrap(); // outputs
+    packer.pack_u32(0).unwrap(); // inputs
+    packer.pack_u32(0).unwrap(); // memo
+    let unsigned_bytes = packer.take_bytes().to_vec();
+    assert!(unsigned_bytes.ends_with(&[0; 12]));
+
+    for creds in [vec![], creds] {
+        let signed_bytes = join(&unsigned_bytes, &creds).unwrap();
+        let (split_unsigned_bytes, split_creds) = split(&signed_bytes).unwrap();
+        assert_eq!(split_unsigned_bytes, unsigned_bytes);
+        assert_eq!(split_creds, creds);
+    }
+
+    // trailing bytes after the credentials
+    let mut trailing = join(&unsigned_bytes, &[]).unwrap();
+    trailing.extend_from_slice(&[0; 4]);
+    assert!(split(&trailing).is_err());
 }
 
 /// RUST_LOG=debug cargo test --package avalanche-types --lib -- txs::test_base_tx_serialization --exact --show-output
@@ -1,6 +1,10 @@
 use std::cmp::Ordering;
 
-use crate::{ids, key, platformvm, txs};
+use crate::{
+    errors::{Error, Result},
+    ids::{self, short},
+    key, packer, platformvm, txs,
+};
 use serde::{Deserialize, Serialize};
 
 /// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/vms/components/avax#TransferableOutput>
@@ -44,6 +48,74 @@ impl Output {
             stakeable_lock_out: None,
         }
     }
+
+    /// Unpacks the output at the current offset of the packer,
+    /// the inverse of the output encoding in [`txs::Tx::pack`].
+    pub fn unpack_from(packer: &packer::Packer) -> Result<Self> {
+        let asset_id = ids::Id::from_slice(&packer.unpack_bytes(ids::LEN)?);
+
+        let type_id = packer.unpack_u32()?;
+        let mut output = Self {
+            asset_id,
+            ..Self::default()
+        };
+        match type_id {
+            t if t == key::secp256k1::txs::transfer::Output::type_id() => {
+                output.transfer_output = Some(unpack_transfer_output(packer)?);
+            }
+            t if t == platformvm::txs::StakeableLockOut::type_id() => {
+                let locktime = packer.unpack_u64()?;
+                let inner_type_id = packer.unpack_u32()?;
+                if inner_type_id != key::secp256k1::txs::transfer::Output::type_id() {
+                    return Err(Error::Other {
+                        message: format!(
+                            "unexpected type ID {} for StakeableLockOut.TransferableOut",
+                            inner_type_id
+                        ),
+                        retryable: false,
+                    });
+                }
+                output.stakeable_lock_out = Some(platformvm::txs::StakeableLockOut {
+                    locktime,
+                    transfer_output: unpack_transfer_output(packer)?,
+                });
+            }
+            _ => {
+                return Err(Error::Other {
+                    message: format!("unexpected type ID {} for TransferableOutput", type_id),
+                    retryable: false,
+                })
+            }
+        }
+        Ok(output)
+    }
+}
+
+fn unpack_transfer_output(
+    packer: &packer::Packer,
+) -> Result<key::secp256k1::txs::transfer::Output> {
+    let amount = packer.unpack_u64()?;
+    let output_owners = unpack_output_owners(packer)?;
+    Ok(key::secp256k1::txs::transfer::Output::new(
+        amount,
+        output_owners,
+    ))
+}
+
+/// Unpacks the "secp256k1fx.OutputOwners" fields without the type ID.
+pub(crate) fn unpack_output_owners(
+    packer: &packer::Packer,
+) -> Result<key::secp256k1::txs::OutputOwners> {
+    let locktime = packer.unpack_u64()?;
+    let threshold = packer.unpack_u32()?;
+    let addresses_len = packer.unpack_u32()?;
+    let mut addresses = Vec::new();
+    for _ in 0..addresses_len {
+        addresses.push(short::Id::from_slice(&packer.unpack_bytes(short::LEN)?));
+    }
+    Ok(key::secp256k1::txs::OutputOwners::new(
+        locktime, threshold, &addresses,
+    ))
 }
 
 /// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/vms/components/avax#SortTransferableOutputs>
@@ -441,6 +513,54 @@ impl Input {
             stakeable_lock_in: None,
         }
     }
+
+    /// Unpacks the input at the current offset of the packer,
+    /// the inverse of the input encoding in [`txs::Tx::pack`].
+    pub fn unpack_from(packer: &packer::Packer) -> Result<Self> {
+        let tx_id = packer.unpack_bytes(ids::LEN)?;
+        let output_index = packer.unpack_u32()?;
+        let utxo_id = txs::utxo::Id::new(&tx_id, output_index, false)?;
+        let asset_id = ids::Id::from_slice(&packer.unpack_bytes(ids::LEN)?);
+
+        let type_id = packer.unpack_u32()?;
+        let mut input = Self {
+            utxo_id,
+            asset_id,
+            ..Self::default()
+        };
+        match type_id {
+            t if t == key::secp256k1::txs::transfer::Input::type_id() => {
+                input.transfer_input = Some(unpack_transfer_input(packer)?);
+            }
+            t if t == platformvm::txs::StakeableLockIn::type_id() => {
+                let locktime = packer.unpack_u64()?;
+                input.stakeable_lock_in = Some(platformvm::txs::StakeableLockIn {
+                    locktime,
+                    transfer_input: unpack_transfer_input(packer)?,
+                });
+            }
+            _ => {
+                return Err(Error::Other {
+                    message: format!("unexpected type ID {} for TransferableInput", type_id),
+                    retryable: false,
+                })
+            }
+        }
+        Ok(input)
+    }
+}
+
+fn unpack_transfer_input(packer: &packer::Packer) -> Result<key::secp256k1::txs::transfer::Input> {
+    let amount = packer.unpack_u64()?;
+    let sig_indices_len = packer.unpack_u32()?;
+    let mut sig_indices = Vec::new();
+    for _ in 0..sig_indices_len {
+        sig_indices.push(packer.unpack_u32()?);
+    }
+    Ok(key::secp256k1::txs::transfer::Input::new(
+        amount,
+        sig_indices,
+    ))
 }
 
 /// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/vms/components/avax#SortTransferableInputs>
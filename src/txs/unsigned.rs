@@ -0,0 +1,310 @@
+//! Walks the codec encoding of unsigned transactions, to find where the
+//! unsigned bytes of a signed transaction end.
+use crate::{
+    codec,
+    errors::{Error, Result},
+    ids,
+    key::bls,
+    packer::Packer,
+};
+
+/// Chain whose codec registered the type IDs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Chain {
+    X,
+    P,
+}
+
+impl Chain {
+    fn type_id(&self, type_name: &str) -> u32 {
+        let types = match self {
+            Chain::X => &*codec::X_TYPES,
+            Chain::P => &*codec::P_TYPES,
+        };
+        types[type_name] as u32
+    }
+}
+
+/// Returns the length of the unsigned transaction (codec version, type ID
+/// and fields) at the start of the bytes, decoding it by its type ID. The
+/// X-chain and P-chain transaction type IDs do not overlap, so either is
+/// accepted.
+/// ref. <https://github.com/ava-labs/avalanchego/blob/v1.9.11/vms/avm/txs/codec.go>
+/// ref. <https://github.com/ava-labs/avalanchego/blob/v1.9.11/vms/platformvm/txs/codec.go>
+pub(crate) fn unsigned_tx_len(d: &[u8]) -> Result<usize> {
+    let packer = Packer::load_bytes_for_unpack(d.len() + 1024, d);
+
+    let version = packer.unpack_u16()?;
+    if version != codec::VERSION {
+        return Err(invalid(format!("unknown codec version {}", version)));
+    }
+
+    let type_id = packer.unpack_u32()?;
+    let (x, p) = (Chain::X, Chain::P);
+    match type_id {
+        t if t == x.type_id("avm.BaseTx") => base_tx(&packer, x)?,
+        t if t == x.type_id("avm.CreateAssetTx") => {
+            base_tx(&packer, x)?;
+            packer.unpack_str()?; // name
+            packer.unpack_str()?; // symbol
+            packer.unpack_byte()?; // denomination
+            for _ in 0..packer.unpack_u32()? {
+                packer.unpack_u32()?; // fx index
+                for _ in 0..packer.unpack_u32()? {
+                    output(&packer, x)?;
+                }
+            }
+        }
+        t if t == x.type_id("avm.ImportTx") => {
+            base_tx(&packer, x)?;
+            id(&packer)?; // source chain
+            inputs(&packer, x)?;
+        }
+        t if t == x.type_id("avm.ExportTx") => {
+            base_tx(&packer, x)?;
+            id(&packer)?; // destination chain
+            outputs(&packer, x)?;
+        }
+        t if t == p.type_id("platformvm.AddValidatorTx") => {
+            base_tx(&packer, p)?;
+            validator(&packer)?;
+            outputs(&packer, p)?; // stake
+            owner(&packer)?; // rewards
+            packer.unpack_u32()?; // shares
+        }
+        t if t == p.type_id("platformvm.AddSubnetValidatorTx") => {
+            base_tx(&packer, p)?;
+            validator(&packer)?;
+            id(&packer)?; // subnet
+            subnet_auth(&packer)?;
+        }
+        t if t == p.type_id("platformvm.AddDelegatorTx") => {
+            base_tx(&packer, p)?;
+            validator(&packer)?;
+            outputs(&packer, p)?; // stake
+            owner(&packer)?; // rewards
+        }
+        t if t == p.type_id("platformvm.CreateChainTx") => {
+            base_tx(&packer, p)?;
+            id(&packer)?; // subnet
+            packer.unpack_str()?; // chain name
+            id(&packer)?; // vm
+            for _ in 0..packer.unpack_u32()? {
+                id(&packer)?; // fx
+            }
+            packer.unpack_bytes_with_header()?; // genesis
+            subnet_auth(&packer)?;
+        }
+        t if t == p.type_id("platformvm.CreateSubnetTx") => {
+            base_tx(&packer, p)?;
+            owner(&packer)?;
+        }
+        t if t == p.type_id("platformvm.ImportTx") => {
+            base_tx(&packer, p)?;
+            id(&packer)?; // source chain
+            inputs(&packer, p)?;
+        }
+        t if t == p.type_id("platformvm.ExportTx") => {
+            base_tx(&packer, p)?;
+            id(&packer)?; // destination chain
+            outputs(&packer, p)?;
+        }
+        t if t == p.type_id("platformvm.AdvanceTimeTx") => {
+            packer.unpack_u64()?; // time
+        }
+        t if t == p.type_id("platformvm.RewardValidatorTx") => {
+            id(&packer)?; // staker tx
+        }
+        t if t == p.type_id("platformvm.RemoveSubnetValidatorTx") => {
+            base_tx(&packer, p)?;
+            packer.unpack_bytes(ids::node::LEN)?;
+            id(&packer)?; // subnet
+            subnet_auth(&packer)?;
+        }
+        t if t == p.type_id("platformvm.TransformSubnetTx") => {
+            base_tx(&packer, p)?;
+            id(&packer)?; // subnet
+            id(&packer)?; // asset
+            for _ in 0..6 {
+                // initial and maximum supply, minimum and maximum
+                // consumption rate, minimum and maximum validator stake
+                packer.unpack_u64()?;
+            }
+            packer.unpack_u32()?; // min stake duration
+            packer.unpack_u32()?; // max stake duration
+            packer.unpack_u32()?; // min delegation fee
+            packer.unpack_u64()?; // min delegator stake
+            packer.unpack_byte()?; // max validator weight factor
+            packer.unpack_u32()?; // uptime requirement
+            subnet_auth(&packer)?;
+        }
+        t if t == p.type_id("platformvm.AddPermissionlessValidatorTx") => {
+            base_tx(&packer, p)?;
+            validator(&packer)?;
+            id(&packer)?; // subnet
+            signer(&packer)?;
+            outputs(&packer, p)?; // stake
+            owner(&packer)?; // validator rewards
+            owner(&packer)?; // delegator rewards
+            packer.unpack_u32()?; // delegation shares
+        }
+        t if t == p.type_id("platformvm.AddPermissionlessDelegatorTx") => {
+            base_tx(&packer, p)?;
+            validator(&packer)?;
+            id(&packer)?; // subnet
+            outputs(&packer, p)?; // stake
+            owner(&packer)?; // rewards
+        }
+        _ => return Err(invalid(format!("unsupported tx type ID {}", type_id))),
+    }
+
+    Ok(packer.get_offset())
+}
+
+fn invalid(message: String) -> Error {
+    Error::Other {
+        message,
+        retryable: false,
+    }
+}
+
+fn id(packer: &Packer) -> Result<()> {
+    packer.unpack_bytes(ids::LEN).map(|_| ())
+}
+
+/// "avax.BaseTx" without the codec version and type ID.
+fn base_tx(packer: &Packer, chain: Chain) -> Result<()> {
+    packer.unpack_u32()?; // network ID
+    id(packer)?; // blockchain ID
+    outputs(packer, chain)?;
+    inputs(packer, chain)?;
+    packer.unpack_bytes_with_header()?; // memo
+    Ok(())
+}
+
+fn outputs(packer: &Packer, chain: Chain) -> Result<()> {
+    for _ in 0..packer.unpack_u32()? {
+        id(packer)?; // asset
+        output(packer, chain)?;
+    }
+    Ok(())
+}
+
+fn inputs(packer: &Packer, chain: Chain) -> Result<()> {
+    for _ in 0..packer.unpack_u32()? {
+        id(packer)?; // tx
+        packer.unpack_u32()?; // output index
+        id(packer)?; // asset
+        input(packer, chain)?;
+    }
+    Ok(())
+}
+
+/// Output with its type ID, e.g., in "avax.TransferableOutput".
+fn output(packer: &Packer, chain: Chain) -> Result<()> {
+    let type_id = packer.unpack_u32()?;
+    match (chain, type_id) {
+        (_, t) if t == chain.type_id("secp256k1fx.TransferOutput") => {
+            packer.unpack_u64()?; // amount
+            output_owners(packer)
+        }
+        (_, t) if t == chain.type_id("secp256k1fx.MintOutput") => output_owners(packer),
+        (Chain::P, t) if t == chain.type_id("platformvm.StakeableLockOut") => {
+            packer.unpack_u64()?; // locktime
+            output(packer, chain)
+        }
+        (Chain::X, t) if t == chain.type_id("nftfx.MintOutput") => {
+            packer.unpack_u32()?; // group ID
+            output_owners(packer)
+        }
+        (Chain::X, t) if t == chain.type_id("nftfx.TransferOutput") => {
+            packer.unpack_u32()?; // group ID
+            packer.unpack_bytes_with_header()?; // payload
+            output_owners(packer)
+        }
+        (Chain::X, t)
+            if t == chain.type_id("propertyfx.MintOutput")
+                || t == chain.type_id("propertyfx.OwnedOutput") =>
+        {
+            output_owners(packer)
+        }
+        _ => Err(invalid(format!("unsupported output type ID {}", type_id))),
+    }
+}
+
+/// Input with its type ID, e.g., in "avax.TransferableInput".
+fn input(packer: &Packer, chain: Chain) -> Result<()> {
+    let type_id = packer.unpack_u32()?;
+    match (chain, type_id) {
+        (_, t) if t == chain.type_id("secp256k1fx.TransferInput") => {
+            packer.unpack_u64()?; // amount
+            sig_indices(packer)
+        }
+        (Chain::P, t) if t == chain.type_id("platformvm.StakeableLockIn") => {
+            packer.unpack_u64()?; // locktime
+            input(packer, chain)
+        }
+        _ => Err(invalid(format!("unsupported input type ID {}", type_id))),
+    }
+}
+
+/// "secp256k1fx.OutputOwners" without its type ID.
+fn output_owners(packer: &Packer) -> Result<()> {
+    packer.unpack_u64()?; // locktime
+    packer.unpack_u32()?; // threshold
+    for _ in 0..packer.unpack_u32()? {
+        packer.unpack_bytes(ids::short::LEN)?;
+    }
+    Ok(())
+}
+
+fn sig_indices(packer: &Packer) -> Result<()> {
+    for _ in 0..packer.unpack_u32()? {
+        packer.unpack_u32()?;
+    }
+    Ok(())
+}
+
+/// "fx.Owner" (i.e., "secp256k1fx.OutputOwners") with its type ID.
+fn owner(packer: &Packer) -> Result<()> {
+    let type_id = packer.unpack_u32()?;
+    if type_id != Chain::P.type_id("secp256k1fx.OutputOwners") {
+        return Err(invalid(format!("unsupported owner type ID {}", type_id)));
+    }
+    output_owners(packer)
+}
+
+/// "verify.Verifiable" (i.e., "secp256k1fx.Input") with its type ID.
+fn subnet_auth(packer: &Packer) -> Result<()> {
+    let type_id = packer.unpack_u32()?;
+    if type_id != Chain::P.type_id("secp256k1fx.Input") {
+        return Err(invalid(format!(
+            "unsupported subnet auth type ID {}",
+            type_id
+        )));
+    }
+    sig_indices(packer)
+}
+
+/// "txs.Validator": node ID, start and end times, and weight.
+fn validator(packer: &Packer) -> Result<()> {
+    packer.unpack_bytes(ids::node::LEN)?;
+    packer.unpack_u64()?;
+    packer.unpack_u64()?;
+    packer.unpack_u64()?;
+    Ok(())
+}
+
+/// "signer.Signer" with its type ID.
+fn signer(packer: &Packer) -> Result<()> {
+    let type_id = packer.unpack_u32()?;
+    match type_id {
+        t if t == Chain::P.type_id("signer.Empty") => Ok(()),
+        t if t == Chain::P.type_id("signer.ProofOfPossession") => {
+            packer.unpack_bytes(bls::public_key::LEN)?;
+            packer.unpack_bytes(bls::signature::LEN)?;
+            Ok(())
+        }
+        _ => Err(invalid(format!("unsupported signer type ID {}", type_id))),
+    }
+}
@@ -1,4 +1,4 @@
-use std::cmp::Ordering;
+use std::{cmp::Ordering, fmt, str::FromStr};
 
 use crate::{
     codec,
@@ -52,6 +52,85 @@ impl Id {
     }
 }
 
+impl Id {
+    /// Packs the UTXO ID as the tx ID followed by the output index.
+    /// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/vms/components/avax#UTXOID>
+    pub fn pack(&self) -> Result<packer::Packer> {
+        let packer = packer::Packer::new(ids::LEN + packer::U32_LEN, 0);
+        packer.pack_bytes(self.tx_id.as_ref())?;
+        packer.pack_u32(self.output_index)?;
+        Ok(packer)
+    }
+
+    /// Parses the UTXO ID from the packed tx ID and output index.
+    pub fn unpack(d: &[u8]) -> Result<Self> {
+        if d.len() != ids::LEN + packer::U32_LEN {
+            return Err(Error::Other {
+                message: format!("invalid UTXO ID length {}", d.len()),
+                retryable: false,
+            });
+        }
+        let packer = packer::Packer::load_bytes_for_unpack(d.len() + 1024, d);
+        let tx_id_bytes = packer.unpack_bytes(ids::LEN)?;
+        let output_index = packer.unpack_u32()?;
+        Self::new(&tx_id_bytes, output_index, false)
+    }
+}
+
+/// Renders the UTXO ID as "[TX ID]:[OUTPUT INDEX]".
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.tx_id, self.output_index)
+    }
+}
+
+/// Parses the "[TX ID]:[OUTPUT INDEX]" form of the UTXO ID.
+impl FromStr for Id {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        let (tx_id, output_index) = s.split_once(':').ok_or_else(|| Error::Other {
+            message: format!("invalid UTXO ID '{}' (expected 'txID:outputIndex')", s),
+            retryable: false,
+        })?;
+        let tx_id = ids::Id::from_str(tx_id).map_err(|e| Error::Other {
+            message: format!("invalid tx ID in UTXO ID '{}' ({})", s, e),
+            retryable: false,
+        })?;
+        let output_index = output_index.parse::<u32>().map_err(|e| Error::Other {
+            message: format!("invalid output index in UTXO ID '{}' ({})", s, e),
+            retryable: false,
+        })?;
+        Self::new(tx_id.as_ref(), output_index, false)
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- txs::utxo::test_utxo_id_encoding --exact --show-output
+#[test]
+fn test_utxo_id_encoding() {
+    let tx_id = ids::Id::from_str("G3BuH6ytQ2averrLxJJugjWZHTRubzCrUZEXoheG5JMqL5ccY").unwrap();
+    let utxo_id = Id::new(tx_id.as_ref(), 3, false).unwrap();
+
+    let packed = utxo_id.pack().unwrap().take_bytes();
+    assert_eq!(packed.len(), ids::LEN + 4);
+    assert_eq!(&packed[..ids::LEN], tx_id.as_ref());
+    assert_eq!(&packed[ids::LEN..], &[0x00, 0x00, 0x00, 0x03]);
+
+    let unpacked = Id::unpack(&packed).unwrap();
+    assert_eq!(unpacked, utxo_id);
+    assert_eq!(unpacked.id, utxo_id.id);
+
+    let s = utxo_id.to_string();
+    assert_eq!(s, "G3BuH6ytQ2averrLxJJugjWZHTRubzCrUZEXoheG5JMqL5ccY:3");
+    let parsed = Id::from_str(&s).unwrap();
+    assert_eq!(parsed, utxo_id);
+    assert_eq!(parsed.id, utxo_id.id);
+
+    assert!(Id::from_str("G3BuH6ytQ2averrLxJJugjWZHTRubzCrUZEXoheG5JMqL5ccY").is_err());
+    assert!(Id::from_str("G3BuH6ytQ2averrLxJJugjWZHTRubzCrUZEXoheG5JMqL5ccY:x").is_err());
+    assert!(Id::from_str("invalid:3").is_err());
+    assert!(Id::unpack(&packed[1..]).is_err());
+}
+
 impl Ord for Id {
     fn cmp(&self, other: &Id) -> Ordering {
         self.tx_id
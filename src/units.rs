@@ -1,3 +1,5 @@
+use std::io::{self, Error, ErrorKind};
+
 use primitive_types::U256;
 
 pub const KIB: u64 = 1024; // 1 kibibyte
@@ -138,3 +140,100 @@ fn test_cast_avax_to_evm_navax() {
         U256::from_dec_str("500000000000000000000").unwrap()
     );
 }
+
+/// Number of decimal places of the X/P chain AVAX unit.
+const AVAX_DECIMALS: usize = 9;
+
+/// Parses a decimal AVAX amount with an optional "AVAX" suffix
+/// (e.g., "1.5 AVAX", "0.000000001") into nano-AVAX for X and P chain.
+/// Rejects negative values, more than 9 decimal places, and overflows.
+pub fn parse_avax(s: &str) -> io::Result<u64> {
+    let invalid = |msg: String| Error::new(ErrorKind::InvalidInput, msg);
+
+    let trimmed = s.trim();
+    let amount = match trimmed.len().checked_sub(4) {
+        Some(n) if trimmed.is_char_boundary(n) && trimmed[n..].eq_ignore_ascii_case("AVAX") => {
+            trimmed[..n].trim_end()
+        }
+        _ => trimmed,
+    };
+    if amount.starts_with('-') {
+        return Err(invalid(format!("negative amount '{}'", s)));
+    }
+
+    let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return Err(invalid(format!("invalid amount '{}'", s)));
+    }
+    if !whole
+        .bytes()
+        .chain(fraction.bytes())
+        .all(|b| b.is_ascii_digit())
+    {
+        return Err(invalid(format!("invalid amount '{}'", s)));
+    }
+    if fraction.len() > AVAX_DECIMALS {
+        return Err(invalid(format!(
+            "amount '{}' has more than {} decimal places",
+            s, AVAX_DECIMALS
+        )));
+    }
+
+    let overflow = || invalid(format!("amount '{}' overflows u64 nano-AVAX", s));
+    let whole = if whole.is_empty() {
+        0
+    } else {
+        whole.parse::<u64>().map_err(|_| overflow())?
+    };
+    let fraction = format!("{:0<width$}", fraction, width = AVAX_DECIMALS)
+        .parse::<u64>()
+        .unwrap();
+    whole
+        .checked_mul(AVAX)
+        .and_then(|n| n.checked_add(fraction))
+        .ok_or_else(overflow)
+}
+
+/// Formats the nano-AVAX for X and P chain as a decimal AVAX amount
+/// (e.g., "1.5 AVAX"), the inverse of [`parse_avax`].
+pub fn format_avax(nanos: u64) -> String {
+    let (whole, fraction) = (nanos / AVAX, nanos % AVAX);
+    if fraction == 0 {
+        return format!("{} AVAX", whole);
+    }
+    let fraction = format!("{:0width$}", fraction, width = AVAX_DECIMALS);
+    format!("{}.{} AVAX", whole, fraction.trim_end_matches('0'))
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- units::test_parse_format_avax --exact --show-output
+#[test]
+fn test_parse_format_avax() {
+    assert_eq!(parse_avax("1.5").unwrap(), 1_500_000_000);
+    assert_eq!(parse_avax("1.5 AVAX").unwrap(), 1_500_000_000);
+    assert_eq!(parse_avax(" 1.5avax ").unwrap(), 1_500_000_000);
+    assert_eq!(parse_avax("0.000000001").unwrap(), 1);
+    assert_eq!(parse_avax(".25").unwrap(), 250_000_000);
+    assert_eq!(parse_avax("2.").unwrap(), 2 * AVAX);
+    assert_eq!(parse_avax("0").unwrap(), 0);
+    assert_eq!(parse_avax("18446744073.709551615").unwrap(), u64::MAX);
+
+    let err = parse_avax("0.0000000001").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("more than 9 decimal places"));
+    assert!(parse_avax("-1")
+        .unwrap_err()
+        .to_string()
+        .contains("negative"));
+    assert!(parse_avax("18446744073.709551616").is_err());
+    for invalid in ["", "AVAX", ".", "1.2.3", "1,5", "+1", "1e9", "abc"] {
+        assert!(parse_avax(invalid).is_err(), "{}", invalid);
+    }
+
+    assert_eq!(format_avax(1_500_000_000), "1.5 AVAX");
+    assert_eq!(format_avax(1), "0.000000001 AVAX");
+    assert_eq!(format_avax(0), "0 AVAX");
+    assert_eq!(format_avax(2 * AVAX), "2 AVAX");
+    for nanos in [0, 1, 1_500_000_000, 123_456_789_012, u64::MAX] {
+        assert_eq!(parse_avax(&format_avax(nanos)).unwrap(), nanos);
+    }
+}
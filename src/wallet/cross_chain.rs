@@ -0,0 +1,244 @@
+//! Atomic transfers of AVAX between the X-chain and the P-chain.
+//!
+//! Moving funds across chains takes two transactions: an export on the source
+//! chain, which puts the UTXOs in the shared (atomic) memory, and an import on
+//! the destination chain, which consumes them.
+//! ref. <https://docs.avax.network/build/avalanchego-apis/p-chain#platformimportavax>
+use std::future::Future;
+
+use crate::{
+    errors::{Error, Result},
+    ids, key, wallet,
+};
+use tokio::time::{sleep, Duration, Instant};
+
+/// Wait between import attempts, until the exported UTXOs are visible to
+/// the destination chain.
+pub const IMPORT_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Maximum duration to retry the import for after the export is accepted.
+pub const IMPORT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Primary network chain holding the funds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Chain {
+    X,
+    P,
+}
+
+/// Exports "amount" of AVAX from "from_chain" and imports it into "to_chain",
+/// both to the wallet's own address. Waits for the export to be accepted and
+/// for its UTXOs to reach the shared memory before importing, and returns the
+/// export and import transaction Ids.
+pub async fn cross_chain_transfer<T>(
+    wallet: &wallet::Wallet<T>,
+    from_chain: Chain,
+    to_chain: Chain,
+    amount: u64,
+) -> Result<(ids::Id, ids::Id)>
+where
+    T: key::secp256k1::ReadOnly + key::secp256k1::SignOnly + Clone,
+{
+    log::info!("transferring {amount} AVAX from {from_chain:?} to {to_chain:?}");
+    match (from_chain, to_chain) {
+        (Chain::X, Chain::P) => {
+            let export = wallet
+                .x()
+                .export()
+                .destination_blockchain_id(wallet.blockchain_id_p)
+                .amount(amount)
+                .check_acceptance(true);
+            let import = wallet
+                .p()
+                .import()
+                .source_blockchain_id(wallet.blockchain_id_x)
+                .check_acceptance(true);
+            export_then_import(
+                || async move { export.issue().await },
+                || {
+                    let import = import.clone();
+                    async move { import.issue().await }
+                },
+                IMPORT_RETRY_INTERVAL,
+                IMPORT_TIMEOUT,
+            )
+            .await
+        }
+        (Chain::P, Chain::X) => {
+            let export = wallet
+                .p()
+                .export()
+                .destination_blockchain_id(wallet.blockchain_id_x)
+                .amount(amount)
+                .check_acceptance(true);
+            let import = wallet
+                .x()
+                .import()
+                .source_blockchain_id(wallet.blockchain_id_p)
+                .check_acceptance(true);
+            export_then_import(
+                || async move { export.issue().await },
+                || {
+                    let import = import.clone();
+                    async move { import.issue().await }
+                },
+                IMPORT_RETRY_INTERVAL,
+                IMPORT_TIMEOUT,
+            )
+            .await
+        }
+        _ => Err(Error::Other {
+            message: format!("cannot transfer from {from_chain:?} to itself"),
+            retryable: false,
+        }),
+    }
+}
+
+/// Issues the export, then retries the import while the exported UTXOs are
+/// not spendable yet on the destination chain. Any other import failure is
+/// returned as is, since the import may have been issued already.
+async fn export_then_import<E, EF, I, IF>(
+    export: E,
+    import: I,
+    retry_interval: Duration,
+    timeout: Duration,
+) -> Result<(ids::Id, ids::Id)>
+where
+    E: FnOnce() -> EF,
+    EF: Future<Output = Result<ids::Id>>,
+    I: Fn() -> IF,
+    IF: Future<Output = Result<ids::Id>>,
+{
+    let export_tx_id = export().await?;
+    log::info!("export {export_tx_id} accepted, importing");
+
+    let start = Instant::now();
+    loop {
+        match import().await {
+            Ok(import_tx_id) => return Ok((export_tx_id, import_tx_id)),
+            Err(e) if e.contains("no spendable funds") => {
+                let elapsed = start.elapsed();
+                if elapsed >= timeout {
+                    return Err(Error::API {
+                        message: format!(
+                            "exported UTXOs of {export_tx_id} not importable in {elapsed:?}"
+                        ),
+                        retryable: true,
+                    });
+                }
+                log::warn!("export {export_tx_id} not importable yet (elapsed {elapsed:?})");
+                sleep(retry_interval).await;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features wallet -- wallet::cross_chain::test_export_then_import --exact --show-output
+#[tokio::test]
+async fn test_export_then_import() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    };
+
+    let export_tx_id = ids::Id::from_slice(&[1; 32]);
+    let import_tx_id = ids::Id::from_slice(&[2; 32]);
+    let not_yet = || Error::Other {
+        message: "no spendable funds were found".to_string(),
+        retryable: false,
+    };
+
+    // the import only sees the exported UTXOs on its third attempt
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let export = {
+        let calls = Arc::clone(&calls);
+        move || async move {
+            calls.lock().unwrap().push("export");
+            Ok(export_tx_id)
+        }
+    };
+    let import = {
+        let calls = Arc::clone(&calls);
+        let attempts = Arc::clone(&attempts);
+        move || {
+            let calls = Arc::clone(&calls);
+            let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+            async move {
+                calls.lock().unwrap().push("import");
+                if attempt < 2 {
+                    return Err(not_yet());
+                }
+                Ok(import_tx_id)
+            }
+        }
+    };
+    let tx_ids = export_then_import(
+        export,
+        import,
+        Duration::from_millis(10),
+        Duration::from_secs(5),
+    )
+    .await
+    .unwrap();
+    assert_eq!(tx_ids, (export_tx_id, import_tx_id));
+    assert_eq!(
+        *calls.lock().unwrap(),
+        vec!["export", "import", "import", "import"]
+    );
+
+    // failed export never imports
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let err = export_then_import(
+        || async {
+            Err(Error::API {
+                message: "insufficient funds".to_string(),
+                retryable: false,
+            })
+        },
+        || {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            async { Ok(import_tx_id) }
+        },
+        Duration::from_millis(10),
+        Duration::from_secs(5),
+    )
+    .await
+    .unwrap_err();
+    assert!(err.contains("insufficient funds"));
+    assert_eq!(attempts.load(Ordering::SeqCst), 0);
+
+    // other import failures are not retried
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let err = export_then_import(
+        || async { Ok(export_tx_id) },
+        || {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            async {
+                Err(Error::API {
+                    message: "failed to check acceptance in time".to_string(),
+                    retryable: true,
+                })
+            }
+        },
+        Duration::from_millis(10),
+        Duration::from_secs(5),
+    )
+    .await
+    .unwrap_err();
+    assert!(err.contains("acceptance"));
+    assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+    // gives up once the UTXOs stay missing past the timeout
+    let err = export_then_import(
+        || async { Ok(export_tx_id) },
+        || async { Err(not_yet()) },
+        Duration::from_millis(10),
+        Duration::from_millis(50),
+    )
+    .await
+    .unwrap_err();
+    assert!(err.contains("not importable"));
+    assert!(err.retryable());
+}
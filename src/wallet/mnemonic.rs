@@ -0,0 +1,100 @@
+//! Addresses of the HD wallet derived from a mnemonic.
+use std::io::{self, Error, ErrorKind};
+
+use crate::key::secp256k1::{
+    mnemonic::{DerivationPath, Mnemonic, AVAX_ACCOUNT_DERIV_PATH},
+    private_key::Key,
+};
+
+/// Addresses of the key at an HD index of "m/44'/9000'/0'/0/{index}".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivedAddresses {
+    pub index: u32,
+    pub x_address: String,
+    pub p_address: String,
+    /// Hex-encoded EIP-55 address with the prefix "0x".
+    pub c_address: String,
+}
+
+/// Derives the first "count" external addresses of the mnemonic
+/// (m/44'/9000'/0'/0/n), the same paths the avalanche wallet uses.
+pub fn addresses_from_mnemonic(
+    mnemonic: &Mnemonic,
+    network_id: u32,
+    count: usize,
+) -> io::Result<Vec<DerivedAddresses>> {
+    let mut addresses = Vec::with_capacity(count);
+    for index in 0..count {
+        let index = u32::try_from(index).map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("HD index {} overflows u32", index),
+            )
+        })?;
+        let deriv: DerivationPath = format!("{}/0/{}", AVAX_ACCOUNT_DERIV_PATH, index)
+            .parse()
+            .map_err(|e| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("failed to parse derive path ({})", e),
+                )
+            })?;
+
+        let pubkey = Key::from_mnemonic_with_path(mnemonic, &deriv)
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, e.to_string()))?
+            .to_public_key();
+        let to_hrp_address = |chain_id_alias: &str| {
+            pubkey
+                .to_hrp_address(network_id, chain_id_alias)
+                .map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))
+        };
+        addresses.push(DerivedAddresses {
+            index,
+            x_address: to_hrp_address("X")?,
+            p_address: to_hrp_address("P")?,
+            c_address: pubkey.to_eth_address(),
+        });
+    }
+    Ok(addresses)
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features mnemonic -- wallet::mnemonic::test_addresses_from_mnemonic --exact --show-output
+#[test]
+fn test_addresses_from_mnemonic() {
+    use bip32::Language;
+
+    let phrase = "vehicle arrive more spread busy regret onion fame argue nice grocery humble vocal slot quit toss learn artwork theory fault tip belt cloth disorder";
+    let mnemonic = Mnemonic::new(phrase, Language::English).unwrap();
+
+    let addresses = addresses_from_mnemonic(&mnemonic, 1, 3).unwrap();
+    assert_eq!(addresses.len(), 3);
+    assert_eq!(
+        addresses[0],
+        DerivedAddresses {
+            index: 0,
+            x_address: "X-avax1mkv5uq80k074kht7qaa6n7r2jpnhww96wq82f8".to_string(),
+            p_address: "P-avax1mkv5uq80k074kht7qaa6n7r2jpnhww96wq82f8".to_string(),
+            c_address: "0x5e66F8F745abbB21BD7197728F6057590fcF153e".to_string(),
+        }
+    );
+
+    // same keys as loading each path from the phrase
+    for derived in addresses.iter() {
+        let key = Key::from_mnemonic_phrase(
+            phrase.to_string(),
+            format!("m/44'/9000'/0'/0/{}", derived.index),
+        )
+        .unwrap();
+        let info = key.to_info(1).unwrap();
+        assert_eq!(derived.x_address, info.addresses[&1].x);
+        assert_eq!(derived.p_address, info.addresses[&1].p);
+        assert_eq!(derived.c_address, info.eth_address);
+    }
+    assert_ne!(addresses[0].x_address, addresses[1].x_address);
+
+    let fuji = addresses_from_mnemonic(&mnemonic, 5, 1).unwrap();
+    assert!(fuji[0].x_address.starts_with("X-fuji1"));
+    assert_eq!(fuji[0].c_address, addresses[0].c_address);
+
+    assert!(addresses_from_mnemonic(&mnemonic, 1, 0).unwrap().is_empty());
+}
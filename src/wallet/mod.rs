@@ -1,3 +1,7 @@
+pub mod cross_chain;
+#[cfg(feature = "mnemonic")]
+#[cfg_attr(docsrs, doc(cfg(feature = "mnemonic")))]
+pub mod mnemonic;
 pub mod p;
 pub mod x;
 
@@ -6,17 +10,23 @@ pub mod x;
 pub mod evm;
 
 use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
     fmt,
     sync::{Arc, Mutex},
 };
 
 use crate::{
-    errors::Result,
+    errors::{Error, Result},
     ids::{self, short},
     jsonrpc::client::{info as api_info, x as api_x},
-    key, utils,
+    key, txs, utils,
 };
 
+pub use cross_chain::cross_chain_transfer;
+#[cfg(feature = "mnemonic")]
+#[cfg_attr(docsrs, doc(cfg(feature = "mnemonic")))]
+pub use mnemonic::{addresses_from_mnemonic, DerivedAddresses};
+
 #[derive(Debug, Clone)]
 pub struct Wallet<T: key::secp256k1::ReadOnly + key::secp256k1::SignOnly + Clone> {
     pub key_type: key::secp256k1::KeyType,
@@ -284,3 +294,437 @@ where
 
 /// ref. <https://docs.avax.network/learn/platform-overview/transaction-fees/#fee-schedule>
 pub const ADD_PRIMARY_NETWORK_VALIDATOR_FEE: u64 = 0;
+
+/// Number of bytes that spending a single-signature secp256k1 UTXO adds to a
+/// transaction: the transferable input (UTXO ID 36, asset ID 32, type ID 4,
+/// amount 8, one signature index 8) and its 65-byte credential signature.
+pub const SPEND_INPUT_SIZE: u64 = 36 + 32 + 4 + 8 + 8 + 65;
+
+/// Returns true if spending an output of "amount" would cost at least as much
+/// in fees (at "fee_per_byte") as the output is worth.
+pub fn is_dust(amount: u64, fee_per_byte: u64) -> bool {
+    amount <= SPEND_INPUT_SIZE.saturating_mul(fee_per_byte)
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- wallet::test_is_dust --exact --show-output
+#[test]
+fn test_is_dust() {
+    assert_eq!(SPEND_INPUT_SIZE, 153);
+
+    assert!(is_dust(100, 1));
+    assert!(is_dust(153, 1));
+    assert!(!is_dust(154, 1));
+    assert!(!is_dust(1_000_000_000, 1_000));
+
+    // free spends only make empty outputs dust
+    assert!(is_dust(0, 0));
+    assert!(!is_dust(1, 0));
+    assert!(is_dust(u64::MAX, u64::MAX));
+}
+
+/// Builds the change output that returns the unspent value of the selected
+/// inputs back to "change_owner". Returns "None" if there is no change left
+/// after paying "amount" and "fee", and an error if the inputs do not cover
+/// both.
+pub fn build_change_output(
+    selected_total: u64,
+    amount: u64,
+    fee: u64,
+    change_owner: &key::secp256k1::txs::OutputOwners,
+    asset_id: ids::Id,
+) -> Result<Option<txs::transferable::Output>> {
+    let need = amount.checked_add(fee).ok_or_else(|| Error::Other {
+        message: format!("amount {} plus fee {} overflows", amount, fee),
+        retryable: false,
+    })?;
+    let change = selected_total
+        .checked_sub(need)
+        .ok_or_else(|| Error::Other {
+            message: format!(
+                "insufficient funds: need {} but short by {}",
+                need,
+                need - selected_total
+            ),
+            retryable: false,
+        })?;
+    if change == 0 {
+        return Ok(None);
+    }
+    Ok(Some(txs::transferable::Output {
+        asset_id,
+        transfer_output: Some(key::secp256k1::txs::transfer::Output::new(
+            change,
+            change_owner.clone(),
+        )),
+        ..Default::default()
+    }))
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- wallet::test_build_change_output --exact --show-output
+#[test]
+fn test_build_change_output() {
+    let owner = key::secp256k1::txs::OutputOwners::new(0, 1, &[short::Id::from_slice(&[1; 20])]);
+    let asset_id = ids::Id::from_slice(&[2; 32]);
+
+    // exact spend
+    assert!(build_change_output(1_100, 1_000, 100, &owner, asset_id)
+        .unwrap()
+        .is_none());
+
+    // insufficient inputs
+    let err = build_change_output(1_000, 1_000, 100, &owner, asset_id).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("insufficient funds: need 1100 but short by 100"));
+    let err = build_change_output(1_000, u64::MAX, 100, &owner, asset_id).unwrap_err();
+    assert!(err.to_string().contains("overflows"));
+
+    let out = build_change_output(1_500, 1_000, 100, &owner, asset_id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(out.asset_id, asset_id);
+    let transfer_output = out.transfer_output.unwrap();
+    assert_eq!(transfer_output.amount, 400);
+    assert_eq!(transfer_output.output_owners, owner);
+    assert!(out.stakeable_lock_out.is_none());
+}
+
+/// Returns, for each of "utxo_ids", whether it is still present in the
+/// current UTXO set of the owner (i.e., not yet spent).
+pub fn verify_utxos_unspent(
+    current_utxos: &[txs::utxo::Utxo],
+    utxo_ids: &[txs::utxo::Id],
+) -> Vec<bool> {
+    let current: BTreeSet<&txs::utxo::Id> = current_utxos.iter().map(|u| &u.utxo_id).collect();
+    utxo_ids.iter().map(|id| current.contains(id)).collect()
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- wallet::test_verify_utxos_unspent --exact --show-output
+#[test]
+fn test_verify_utxos_unspent() {
+    let unspent = txs::utxo::Id::new(&[1; 32], 0, false).unwrap();
+    let spent = txs::utxo::Id::new(&[1; 32], 1, false).unwrap();
+
+    let current_utxos = vec![
+        txs::utxo::Utxo {
+            utxo_id: unspent.clone(),
+            ..Default::default()
+        },
+        txs::utxo::Utxo {
+            utxo_id: txs::utxo::Id::new(&[2; 32], 0, false).unwrap(),
+            ..Default::default()
+        },
+    ];
+
+    assert_eq!(
+        verify_utxos_unspent(&current_utxos, &[unspent.clone(), spent.clone()]),
+        vec![true, false]
+    );
+    assert_eq!(
+        verify_utxos_unspent(&[], &[unspent, spent]),
+        vec![false, false]
+    );
+}
+
+/// Maps the ID of each UTXO to the owners of its output, so that a signer
+/// can pick the keys matching each input. The owners of a stakeable locked
+/// output are the ones of its inner transfer output. UTXOs whose output is
+/// neither (e.g., mint outputs) are skipped.
+pub fn utxo_owners(
+    utxos: &[txs::utxo::Utxo],
+) -> HashMap<ids::Id, key::secp256k1::txs::OutputOwners> {
+    let mut owners = HashMap::with_capacity(utxos.len());
+    for utxo in utxos.iter() {
+        let output_owners = match output_owners(utxo) {
+            Some(output_owners) => output_owners,
+            None => {
+                log::debug!("skipping UTXO {} without transfer output", utxo.utxo_id);
+                continue;
+            }
+        };
+
+        // derives the ID rather than using "utxo_id.id", which is not decoded from JSON
+        let utxo_id = match utxo
+            .utxo_id
+            .tx_id
+            .prefix(&[utxo.utxo_id.output_index as u64])
+        {
+            Ok(id) => id,
+            Err(e) => {
+                log::warn!("skipping UTXO {} ({})", utxo.utxo_id, e);
+                continue;
+            }
+        };
+        owners.insert(utxo_id, output_owners.clone());
+    }
+    owners
+}
+
+/// Returns the owners of the transfer output of the UTXO, looking into
+/// stakeable locked outputs.
+fn output_owners(utxo: &txs::utxo::Utxo) -> Option<&key::secp256k1::txs::OutputOwners> {
+    if let Some(out) = &utxo.transfer_output {
+        Some(&out.output_owners)
+    } else {
+        utxo.stakeable_lock_out
+            .as_ref()
+            .map(|out| &out.transfer_output.output_owners)
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- wallet::test_utxo_owners --exact --show-output
+#[test]
+fn test_utxo_owners() {
+    let owner1 = key::secp256k1::txs::OutputOwners::new(0, 1, &[short::Id::from_slice(&[1; 20])]);
+    let owner2 = key::secp256k1::txs::OutputOwners::new(
+        0,
+        2,
+        &[
+            short::Id::from_slice(&[2; 20]),
+            short::Id::from_slice(&[3; 20]),
+        ],
+    );
+
+    let transfer = txs::utxo::Id::new(&[1; 32], 0, false).unwrap();
+    let locked = txs::utxo::Id::new(&[1; 32], 1, false).unwrap();
+    let mint = txs::utxo::Id::new(&[2; 32], 0, false).unwrap();
+    let utxos = vec![
+        txs::utxo::Utxo {
+            utxo_id: transfer.clone(),
+            transfer_output: Some(key::secp256k1::txs::transfer::Output::new(
+                1_000,
+                owner1.clone(),
+            )),
+            ..Default::default()
+        },
+        txs::utxo::Utxo {
+            utxo_id: locked.clone(),
+            stakeable_lock_out: Some(crate::platformvm::txs::StakeableLockOut {
+                locktime: 1_000_000,
+                transfer_output: key::secp256k1::txs::transfer::Output::new(2_000, owner2.clone()),
+            }),
+            ..Default::default()
+        },
+        // mint outputs are not decoded into transfer outputs
+        txs::utxo::Utxo {
+            utxo_id: mint.clone(),
+            ..Default::default()
+        },
+    ];
+
+    let owners = utxo_owners(&utxos);
+    assert_eq!(owners.len(), 2);
+    assert_eq!(owners.get(&transfer.id), Some(&owner1));
+    assert_eq!(owners.get(&locked.id), Some(&owner2));
+    assert!(!owners.contains_key(&mint.id));
+
+    // the ID does not depend on the (skipped) cached field
+    let mut utxo = utxos[0].clone();
+    utxo.utxo_id.id = ids::Id::empty();
+    assert_eq!(utxo_owners(&[utxo]).get(&transfer.id), Some(&owner1));
+}
+
+/// Returns the amount of the transfer output of the UTXO, looking into
+/// stakeable locked outputs. Returns "None" for other outputs.
+fn transfer_amount(utxo: &txs::utxo::Utxo) -> Option<u64> {
+    if let Some(out) = &utxo.transfer_output {
+        Some(out.amount)
+    } else {
+        utxo.stakeable_lock_out
+            .as_ref()
+            .map(|out| out.transfer_output.amount)
+    }
+}
+
+/// Returns the UTXOs of the asset, in their original order. UTXOs whose
+/// output is not a (possibly stakeable locked) transfer output are skipped.
+pub fn filter_by_asset<'a>(
+    utxos: &'a [txs::utxo::Utxo],
+    asset_id: &ids::Id,
+) -> Vec<&'a txs::utxo::Utxo> {
+    utxos
+        .iter()
+        .filter(|utxo| utxo.asset_id == *asset_id && transfer_amount(utxo).is_some())
+        .collect()
+}
+
+/// Returns the total amount of the asset held in the UTXOs, as selected by
+/// [`filter_by_asset`]. Saturates at "u64::MAX".
+pub fn balance_of_asset(utxos: &[txs::utxo::Utxo], asset_id: &ids::Id) -> u64 {
+    filter_by_asset(utxos, asset_id)
+        .into_iter()
+        .filter_map(transfer_amount)
+        .fold(0u64, u64::saturating_add)
+}
+
+/// Returns the total amount held in the UTXOs for each asset, sorted by
+/// asset ID, with the UTXOs selected as in [`filter_by_asset`]. Saturates
+/// at "u64::MAX".
+pub fn list_assets(utxos: &[txs::utxo::Utxo]) -> Vec<(ids::Id, u64)> {
+    let mut balances: BTreeMap<ids::Id, u64> = BTreeMap::new();
+    for utxo in utxos.iter() {
+        if let Some(amount) = transfer_amount(utxo) {
+            let balance = balances.entry(utxo.asset_id).or_default();
+            *balance = balance.saturating_add(amount);
+        }
+    }
+    balances.into_iter().collect()
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- wallet::test_filter_by_asset --exact --show-output
+#[test]
+fn test_filter_by_asset() {
+    let owner = key::secp256k1::txs::OutputOwners::new(0, 1, &[short::Id::from_slice(&[1; 20])]);
+    let avax = ids::Id::from_slice(&[1; 32]);
+    let token = ids::Id::from_slice(&[2; 32]);
+
+    let utxo = |index: u32, asset_id: ids::Id, amount: Option<u64>, locked: bool| {
+        let mut utxo = txs::test_utxo(index, asset_id, amount, &owner);
+        if locked {
+            utxo.stakeable_lock_out = utxo.transfer_output.take().map(|transfer_output| {
+                crate::platformvm::txs::StakeableLockOut {
+                    locktime: 1_000_000,
+                    transfer_output,
+                }
+            });
+        }
+        utxo
+    };
+    let utxos = vec![
+        utxo(0, avax, Some(1_000), false),
+        utxo(1, token, Some(50), false),
+        utxo(2, avax, Some(2_000), true),
+        // mint outputs are not decoded into transfer outputs
+        utxo(3, token, None, false),
+        utxo(4, token, Some(70), false),
+    ];
+
+    let tokens = filter_by_asset(&utxos, &token);
+    assert_eq!(
+        tokens
+            .iter()
+            .map(|u| u.utxo_id.output_index)
+            .collect::<Vec<_>>(),
+        vec![1, 4]
+    );
+    assert_eq!(balance_of_asset(&utxos, &token), 120);
+    assert_eq!(balance_of_asset(&utxos, &avax), 3_000);
+
+    assert!(filter_by_asset(&utxos, &ids::Id::empty()).is_empty());
+    assert_eq!(balance_of_asset(&[], &avax), 0);
+
+    let whales = vec![
+        utxo(0, avax, Some(u64::MAX), false),
+        utxo(1, avax, Some(1), false),
+    ];
+    assert_eq!(balance_of_asset(&whales, &avax), u64::MAX);
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- wallet::test_list_assets --exact --show-output
+#[test]
+fn test_list_assets() {
+    let owner = key::secp256k1::txs::OutputOwners::new(0, 1, &[short::Id::from_slice(&[1; 20])]);
+    let avax = ids::Id::from_slice(&[1; 32]);
+    let token = ids::Id::from_slice(&[2; 32]);
+    let nft = ids::Id::from_slice(&[3; 32]);
+
+    let utxo = |index: u32, asset_id: ids::Id, amount: Option<u64>| {
+        txs::test_utxo(index, asset_id, amount, &owner)
+    };
+    let utxos = vec![
+        utxo(0, token, Some(50)),
+        utxo(1, nft, Some(1)),
+        utxo(2, avax, Some(1_000)),
+        utxo(3, token, Some(70)),
+        utxo(4, avax, Some(2_000)),
+        // mint outputs are not decoded into transfer outputs
+        utxo(5, token, None),
+        utxo(6, ids::Id::empty(), None),
+    ];
+    assert_eq!(
+        list_assets(&utxos),
+        vec![(avax, 3_000), (token, 120), (nft, 1)]
+    );
+    assert!(list_assets(&[]).is_empty());
+}
+
+/// Returns true if the keys can sign for at least "threshold" of the owners
+/// of the UTXO output, so that a transaction spending it can be signed.
+/// Time locks are not checked. UTXOs without a transfer output are not
+/// controllable.
+pub fn verify_controllable(
+    utxo: &txs::utxo::Utxo,
+    available_keys: &[key::secp256k1::private_key::Key],
+) -> bool {
+    let owners = match output_owners(utxo) {
+        Some(owners) => owners,
+        None => return false,
+    };
+
+    let mut signers: BTreeSet<short::Id> = BTreeSet::new();
+    for k in available_keys.iter() {
+        match k.to_public_key().to_short_id() {
+            Ok(addr) => {
+                signers.insert(addr);
+            }
+            Err(e) => log::warn!("skipping key without short address ({})", e),
+        }
+    }
+    let signable = owners
+        .addresses
+        .iter()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter(|addr| signers.contains(*addr))
+        .count();
+    signable >= owners.threshold as usize
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- wallet::test_verify_controllable --exact --show-output
+#[test]
+fn test_verify_controllable() {
+    let keys: Vec<key::secp256k1::private_key::Key> = (0..4)
+        .map(|_| key::secp256k1::private_key::Key::generate().unwrap())
+        .collect();
+    let addrs: Vec<short::Id> = keys
+        .iter()
+        .map(|k| k.to_public_key().to_short_id().unwrap())
+        .collect();
+
+    // 2-of-3 multisig
+    let owners = key::secp256k1::txs::OutputOwners::new(0, 2, &addrs[..3]);
+    let utxo = txs::utxo::Utxo {
+        transfer_output: Some(key::secp256k1::txs::transfer::Output::new(
+            1_000,
+            owners.clone(),
+        )),
+        ..Default::default()
+    };
+    assert!(verify_controllable(&utxo, &keys[..2]));
+    assert!(verify_controllable(
+        &utxo,
+        &[keys[2].clone(), keys[0].clone()]
+    ));
+    assert!(!verify_controllable(&utxo, &keys[..1]));
+    // the same key twice is a single signer
+    assert!(!verify_controllable(
+        &utxo,
+        &[keys[0].clone(), keys[0].clone()]
+    ));
+    // keys outside of the owners do not count
+    assert!(!verify_controllable(
+        &utxo,
+        &[keys[0].clone(), keys[3].clone()]
+    ));
+
+    // stakeable locked outputs are controlled by their inner owners
+    let locked = txs::utxo::Utxo {
+        stakeable_lock_out: Some(crate::platformvm::txs::StakeableLockOut {
+            locktime: 1_000_000,
+            transfer_output: key::secp256k1::txs::transfer::Output::new(1_000, owners),
+        }),
+        ..Default::default()
+    };
+    assert!(verify_controllable(&locked, &keys[1..3]));
+    assert!(!verify_controllable(&txs::utxo::Utxo::default(), &keys));
+}
@@ -254,6 +254,16 @@ where
             None
         };
 
+        let validator = platformvm::txs::Validator {
+            node_id: self.node_id.clone(),
+            start: self.start_time.timestamp() as u64,
+            end: self.end_time.timestamp() as u64,
+            weight: self.stake_amount,
+        };
+        let reward_locktime = platformvm::staking::reward_unlock_time(
+            &platformvm::staking::StakingPeriod::from(&validator),
+        );
+
         let mut tx = platformvm::txs::add_permissionless_validator::Tx {
             base_tx: txs::Tx {
                 network_id: self.inner.inner.network_id,
@@ -262,12 +272,7 @@ where
                 transferable_inputs: Some(ins),
                 ..Default::default()
             },
-            validator: platformvm::txs::Validator {
-                node_id: self.node_id.clone(),
-                start: self.start_time.timestamp() as u64,
-                end: self.end_time.timestamp() as u64,
-                weight: self.stake_amount,
-            },
+            validator,
 
             // empty id for primary network
             subnet_id: self.subnet_id,
@@ -280,13 +285,13 @@ where
 
             // TODO: make this configurable
             validator_rewards_owner: key::secp256k1::txs::OutputOwners {
-                locktime: 0,
+                locktime: reward_locktime,
                 threshold: 1,
                 addresses: vec![self.inner.inner.short_address.clone()],
             },
             // TODO: make this configurable
             delegator_rewards_owner: key::secp256k1::txs::OutputOwners {
-                locktime: 0,
+                locktime: reward_locktime,
                 threshold: 1,
                 addresses: vec![self.inner.inner.short_address.clone()],
             },
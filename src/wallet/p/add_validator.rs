@@ -227,6 +227,16 @@ where
             )
             .await?;
 
+        let validator = platformvm::txs::Validator {
+            node_id: self.node_id.clone(),
+            start: self.start_time.timestamp() as u64,
+            end: self.end_time.timestamp() as u64,
+            weight: self.stake_amount,
+        };
+        let reward_locktime = platformvm::staking::reward_unlock_time(
+            &platformvm::staking::StakingPeriod::from(&validator),
+        );
+
         let mut tx = platformvm::txs::add_validator::Tx {
             base_tx: txs::Tx {
                 network_id: self.inner.inner.network_id,
@@ -235,15 +245,10 @@ where
                 transferable_inputs: Some(ins),
                 ..Default::default()
             },
-            validator: platformvm::txs::Validator {
-                node_id: self.node_id.clone(),
-                start: self.start_time.timestamp() as u64,
-                end: self.end_time.timestamp() as u64,
-                weight: self.stake_amount,
-            },
+            validator,
             stake_transferable_outputs: Some(staked_outs),
             rewards_owner: key::secp256k1::txs::OutputOwners {
-                locktime: 0,
+                locktime: reward_locktime,
                 threshold: 1,
                 addresses: vec![self.inner.inner.short_address.clone()],
             },
@@ -79,6 +79,13 @@ where
         Ok(utxos)
     }
 
+    /// Checks each of "utxo_ids" against the current P-chain UTXO set of the
+    /// wallet owner, fetched once for all IDs, returning per-ID presence.
+    pub async fn verify_utxos_unspent(&self, utxo_ids: &[txs::utxo::Id]) -> Result<Vec<bool>> {
+        let utxos = self.utxos().await?;
+        Ok(wallet::verify_utxos_unspent(&utxos, utxo_ids))
+    }
+
     /// Returns "true" if the node_id is a current primary network validator.
     pub async fn is_primary_network_validator(&self, node_id: &node::Id) -> Result<bool> {
         let resp =
@@ -2,7 +2,12 @@ pub mod export;
 pub mod import;
 pub mod transfer;
 
-use crate::{errors::Result, jsonrpc::client::x as client_x, key, txs, wallet};
+use crate::{
+    errors::{Error, Result},
+    ids::{self, short},
+    jsonrpc::client::x as client_x,
+    key, txs, wallet,
+};
 
 impl<T> wallet::Wallet<T>
 where
@@ -73,11 +78,43 @@ where
         Ok(utxos)
     }
 
+    /// Checks each of "utxo_ids" against the current X-chain UTXO set of the
+    /// wallet owner, fetched once for all IDs, returning per-ID presence.
+    pub async fn verify_utxos_unspent(&self, utxo_ids: &[txs::utxo::Id]) -> Result<Vec<bool>> {
+        let utxos = self.utxos().await?;
+        Ok(wallet::verify_utxos_unspent(&utxos, utxo_ids))
+    }
+
     #[must_use]
     pub fn transfer(&self) -> transfer::Tx<T> {
         transfer::Tx::new(self)
     }
 
+    /// Transfers "amount" of AVAX to "receiver" on the X-chain, returning the
+    /// issued transaction Id. Fails before issuing on insufficient funds.
+    pub async fn send(&self, receiver: short::Id, amount: u64) -> Result<ids::Id> {
+        self.transfer()
+            .receiver(receiver)
+            .amount(amount)
+            .issue()
+            .await
+    }
+
+    /// Transfers "amount" of AVAX to the bech32 X-chain address (e.g.,
+    /// "X-avax1..."), failing if the address belongs to another network.
+    pub async fn send_to_address(&self, receiver: &str, amount: u64) -> Result<ids::Id> {
+        let map_err = |e: std::io::Error| Error::Other {
+            message: format!("invalid receiver address: {}", e),
+            retryable: false,
+        };
+        key::secp256k1::address::validate_network(receiver, self.inner.network_id)
+            .map_err(map_err)?;
+        let (_, short_bytes) =
+            key::secp256k1::address::avax_address_to_short_bytes("X", receiver).map_err(map_err)?;
+
+        self.send(short::Id::from_slice(&short_bytes), amount).await
+    }
+
     #[must_use]
     pub fn export(&self) -> export::Tx<T> {
         export::Tx::new(self)
@@ -1,4 +1,4 @@
-use std::{cmp, time::SystemTime};
+use std::time::SystemTime;
 
 use crate::{
     avm,
@@ -24,6 +24,10 @@ where
     /// Transfer amount.
     pub amount: u64,
 
+    /// Fee per byte used to skip dust UTXOs when selecting inputs.
+    /// Zero (default) only skips empty outputs.
+    pub fee_per_byte: u64,
+
     /// Set "true" to poll transfer status after issuance for its acceptance.
     pub check_acceptance: bool,
 
@@ -47,6 +51,7 @@ where
             inner: x.clone(),
             receiver: short::Id::empty(),
             amount: 0,
+            fee_per_byte: 0,
             check_acceptance: false,
             poll_initial_wait: Duration::from_millis(500),
             poll_interval: Duration::from_millis(700),
@@ -69,6 +74,13 @@ where
         self
     }
 
+    /// Sets the fee per byte for dust filtering.
+    #[must_use]
+    pub fn fee_per_byte(mut self, fee_per_byte: u64) -> Self {
+        self.fee_per_byte = fee_per_byte;
+        self
+    }
+
     /// Sets the check acceptance boolean flag.
     #[must_use]
     pub fn check_acceptance(mut self, check_acceptance: bool) -> Self {
@@ -131,26 +143,28 @@ where
             utxos.len()
         );
 
-        let mut inputs: Vec<txs::transferable::Input> = Vec::new();
-        let mut outputs: Vec<txs::transferable::Output> = vec![
-            // receiver
-            txs::transferable::Output {
-                asset_id: self.inner.inner.avax_asset_id.clone(),
-                transfer_output: Some(key::secp256k1::txs::transfer::Output {
-                    amount: self.amount,
-                    output_owners: key::secp256k1::txs::OutputOwners {
-                        locktime: 0,
-                        threshold: 1,
-                        addresses: vec![self.receiver.clone()],
-                    },
-                }),
-                ..Default::default()
-            },
-        ];
+        let receiver_output = txs::transferable::Output {
+            asset_id: self.inner.inner.avax_asset_id,
+            transfer_output: Some(key::secp256k1::txs::transfer::Output {
+                amount: self.amount,
+                output_owners: key::secp256k1::txs::OutputOwners {
+                    locktime: 0,
+                    threshold: 1,
+                    addresses: vec![self.receiver.clone()],
+                },
+            }),
+            ..Default::default()
+        };
 
         // ref. "avalanchego/wallet/chain/x"
         // "math.Add64(toBurn[assetID], out.Out.Amount())"
-        let mut remaining_amount_to_burn = self.amount + self.inner.inner.tx_fee;
+        let amount_to_burn = self
+            .amount
+            .checked_add(self.inner.inner.tx_fee)
+            .ok_or_else(|| Error::Other {
+                message: "transfer amount plus fee overflows".to_string(),
+                retryable: false,
+            })?;
 
         // ref. "avalanchego/vms/avm#Service.SendMultiple"
         let now_unix = SystemTime::now()
@@ -158,51 +172,18 @@ where
             .expect("unexpected None duration_since")
             .as_secs();
 
-        for utxo in utxos.iter() {
-            if utxo.asset_id != self.inner.inner.avax_asset_id {
-                continue;
-            }
-
-            // consumed enough, no need to burn more
-            if remaining_amount_to_burn == 0 {
-                continue;
-            }
+        // fails on insufficient funds before signing and issuing anything
+        let (mut inputs, mut outputs) = select_inputs(
+            &self.inner.inner.keychain,
+            &utxos,
+            self.inner.inner.avax_asset_id,
+            amount_to_burn,
+            &self.inner.inner.short_address,
+            now_unix,
+            self.fee_per_byte,
+        )?;
+        outputs.push(receiver_output);
 
-            if let Some(out) = &utxo.transfer_output {
-                let (input, _) = self.inner.inner.keychain.spend(out, now_unix).unwrap();
-
-                inputs.push(txs::transferable::Input {
-                    utxo_id: utxo.utxo_id.clone(),
-                    asset_id: utxo.asset_id.clone(),
-                    transfer_input: Some(input),
-                    ..Default::default()
-                });
-
-                // burn any value that should be burned
-                let amount_to_burn = cmp::min(
-                    remaining_amount_to_burn, // amount we still need to burn
-                    out.amount,               // amount available to burn
-                );
-                remaining_amount_to_burn -= amount_to_burn;
-
-                let remaining_amount = out.amount - amount_to_burn;
-                if remaining_amount > 0 {
-                    // this input had extra value, so some must be returned
-                    outputs.push(txs::transferable::Output {
-                        asset_id: self.inner.inner.avax_asset_id.clone(),
-                        transfer_output: Some(key::secp256k1::txs::transfer::Output {
-                            amount: remaining_amount,
-                            output_owners: key::secp256k1::txs::OutputOwners {
-                                locktime: 0,
-                                threshold: 1,
-                                addresses: vec![self.inner.inner.short_address.clone()],
-                            },
-                        }),
-                        ..Default::default()
-                    })
-                }
-            }
-        }
         inputs.sort();
         outputs.sort();
 
@@ -297,3 +278,126 @@ where
         Ok(tx_id)
     }
 }
+
+/// Selects the UTXOs of "asset_id" spendable by the keychain until "amount_to_burn"
+/// is covered, returning the inputs and the change output back to "change_address".
+/// Skips dust UTXOs that cost more to spend than they are worth at "fee_per_byte".
+/// Returns an error if the spendable UTXOs do not cover the amount.
+pub fn select_inputs<T>(
+    keychain: &key::secp256k1::keychain::Keychain<T>,
+    utxos: &[txs::utxo::Utxo],
+    asset_id: ids::Id,
+    amount_to_burn: u64,
+    change_address: &short::Id,
+    now_unix: u64,
+    fee_per_byte: u64,
+) -> Result<(
+    Vec<txs::transferable::Input>,
+    Vec<txs::transferable::Output>,
+)>
+where
+    T: key::secp256k1::ReadOnly + key::secp256k1::SignOnly + Clone,
+{
+    let mut inputs: Vec<txs::transferable::Input> = Vec::new();
+
+    let mut selected_total: u64 = 0;
+    for utxo in utxos.iter() {
+        if utxo.asset_id != asset_id {
+            continue;
+        }
+
+        // consumed enough, no need to burn more
+        if selected_total >= amount_to_burn {
+            break;
+        }
+
+        let out = match &utxo.transfer_output {
+            Some(out) => out,
+            None => continue,
+        };
+        if crate::wallet::is_dust(out.amount, fee_per_byte) {
+            continue;
+        }
+        let input = match keychain.spend(out, now_unix) {
+            Some((input, _)) => input,
+            None => continue, // not spendable by this keychain (yet)
+        };
+
+        inputs.push(txs::transferable::Input {
+            utxo_id: utxo.utxo_id.clone(),
+            asset_id: utxo.asset_id,
+            transfer_input: Some(input),
+            ..Default::default()
+        });
+        selected_total = selected_total.saturating_add(out.amount);
+    }
+
+    // any extra value of the inputs is returned in one change output
+    let change_owner =
+        key::secp256k1::txs::OutputOwners::new(0, 1, std::slice::from_ref(change_address));
+    let outputs = crate::wallet::build_change_output(
+        selected_total,
+        amount_to_burn,
+        0,
+        &change_owner,
+        asset_id,
+    )?
+    .into_iter()
+    .collect();
+
+    Ok((inputs, outputs))
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features wallet -- wallet::x::transfer::test_select_inputs --exact --show-output
+#[test]
+fn test_select_inputs() {
+    use crate::key::secp256k1::ReadOnly;
+
+    let test_key = key::secp256k1::private_key::Key::from_cb58(
+        "PrivateKey-2kqWNDaqUKQyE4ZsV5GLCGeizE6sHAJVyjnfjXoXrtcZpK9M67",
+    )
+    .unwrap();
+    let owner = test_key.short_address().unwrap();
+    let keychain = key::secp256k1::keychain::Keychain::new(vec![test_key]);
+
+    let asset_id = ids::Id::from_slice(&[1; 32]);
+    let utxo = |tx_id: u8, amount: u64, owner: short::Id| txs::utxo::Utxo {
+        utxo_id: txs::utxo::Id::new(&[tx_id; 32], 0, false).unwrap(),
+        asset_id,
+        transfer_output: Some(key::secp256k1::txs::transfer::Output {
+            amount,
+            output_owners: key::secp256k1::txs::OutputOwners {
+                locktime: 0,
+                threshold: 1,
+                addresses: vec![owner],
+            },
+        }),
+        ..Default::default()
+    };
+    let utxos = vec![
+        utxo(1, 600, owner.clone()),
+        // not spendable by the keychain
+        utxo(2, 1_000_000, short::Id::from_slice(&[9; 20])),
+        utxo(3, 600, owner),
+    ];
+    let change_address = short::Id::from_slice(&[7; 20]);
+
+    // successful send of 1,000 with 1 fee
+    let (inputs, outputs) =
+        select_inputs(&keychain, &utxos, asset_id, 1_001, &change_address, 0, 0).unwrap();
+    assert_eq!(inputs.len(), 2);
+    assert_eq!(inputs[0].utxo_id, utxos[0].utxo_id);
+    assert_eq!(inputs[1].utxo_id, utxos[2].utxo_id);
+    assert_eq!(outputs.len(), 1);
+    let change = outputs[0].transfer_output.as_ref().unwrap();
+    assert_eq!(change.amount, 199);
+    assert_eq!(change.output_owners.addresses, vec![change_address.clone()]);
+
+    // insufficient funds
+    let err = select_inputs(&keychain, &utxos, asset_id, 1_201, &change_address, 0, 0).unwrap_err();
+    assert!(err.to_string().contains("insufficient funds"));
+
+    // both 600 UTXOs are dust at 4 per byte, leaving nothing to spend
+    let err = select_inputs(&keychain, &utxos, asset_id, 1, &change_address, 0, 4).unwrap_err();
+    assert!(err.to_string().contains("insufficient funds"));
+}
@@ -0,0 +1,198 @@
+//! Unsigned warp messages, as sent between chains.
+use std::io::{Error, ErrorKind, Result};
+
+use crate::ids;
+
+/// Codec version of the unsigned message encoding.
+pub const CODEC_VERSION: u16 = 0;
+
+/// Message sent by the source chain to the destination chain, before the
+/// source subnet validators sign it, as the warp signer of the node takes
+/// it.
+///
+/// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/vms/platformvm/warp#UnsignedMessage>
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct UnsignedMessage {
+    pub network_id: u32,
+    pub source_chain_id: ids::Id,
+    pub destination_chain_id: ids::Id,
+    pub payload: Vec<u8>,
+}
+
+/// Length of the encoding before the payload: the codec version, the
+/// network ID, both chain IDs and the payload length.
+const HEADER_LEN: usize = 2 + 4 + ids::LEN * 2 + 4;
+
+impl UnsignedMessage {
+    /// Returns the bytes the validators sign: the codec version, the network
+    /// ID, both chain IDs and the length-prefixed payload.
+    pub fn bytes(&self) -> Vec<u8> {
+        let mut b = Vec::with_capacity(HEADER_LEN + self.payload.len());
+        b.extend_from_slice(&CODEC_VERSION.to_be_bytes());
+        b.extend_from_slice(&self.network_id.to_be_bytes());
+        b.extend_from_slice(self.source_chain_id.as_ref());
+        b.extend_from_slice(self.destination_chain_id.as_ref());
+        b.extend_from_slice(&(self.payload.len() as u32).to_be_bytes());
+        b.extend_from_slice(&self.payload);
+        b
+    }
+
+    /// Parses the message from the bytes of [`UnsignedMessage::bytes`].
+    pub fn from_bytes(d: &[u8]) -> Result<Self> {
+        if d.len() < HEADER_LEN {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("message too short ({} bytes)", d.len()),
+            ));
+        }
+        let version = u16::from_be_bytes([d[0], d[1]]);
+        if version != CODEC_VERSION {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("unknown codec version {}", version),
+            ));
+        }
+        let payload_len = u32::from_be_bytes([d[70], d[71], d[72], d[73]]) as usize;
+        if d.len() - HEADER_LEN != payload_len {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "payload length {} does not match the remaining {} bytes",
+                    payload_len,
+                    d.len() - HEADER_LEN
+                ),
+            ));
+        }
+        Ok(Self {
+            network_id: u32::from_be_bytes([d[2], d[3], d[4], d[5]]),
+            source_chain_id: ids::Id::from_slice(&d[6..38]),
+            destination_chain_id: ids::Id::from_slice(&d[38..70]),
+            payload: d[HEADER_LEN..].to_vec(),
+        })
+    }
+}
+
+/// Verifies that the message was sent on the expected network by one of the
+/// allowed source chains, so that a receiving VM does not act on messages
+/// from chains it does not trust, or replayed from another network.
+pub fn verify_source(
+    msg: &UnsignedMessage,
+    expected_network: u32,
+    allowed_source_chains: &[ids::Id],
+) -> Result<()> {
+    if msg.network_id != expected_network {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "message network ID {} does not match expected network ID {}",
+                msg.network_id, expected_network
+            ),
+        ));
+    }
+    if !allowed_source_chains.contains(&msg.source_chain_id) {
+        return Err(Error::new(
+            ErrorKind::PermissionDenied,
+            format!(
+                "message source chain {} is not allowed",
+                msg.source_chain_id
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Verifies that the message is addressed to this chain, so that a
+/// receiving VM does not act on messages meant for another chain.
+pub fn verify_destination(msg: &UnsignedMessage, chain_id: &ids::Id) -> Result<()> {
+    if msg.destination_chain_id != *chain_id {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "message destination chain {} does not match chain {}",
+                msg.destination_chain_id, chain_id
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- warp::message::test_bytes --exact --show-output
+#[test]
+fn test_bytes() {
+    let msg = UnsignedMessage {
+        network_id: 5,
+        source_chain_id: ids::Id::from_slice(&[1; 32]),
+        destination_chain_id: ids::Id::from_slice(&[2; 32]),
+        payload: b"hello".to_vec(),
+    };
+
+    let b = msg.bytes();
+    assert_eq!(b.len(), 2 + 4 + 32 + 32 + 4 + 5);
+    assert_eq!(&b[..2], &[0, 0]);
+    assert_eq!(&b[2..6], &[0, 0, 0, 5]);
+    assert_eq!(&b[6..38], &[1; 32]);
+    assert_eq!(&b[70..74], &[0, 0, 0, 5]);
+    assert_eq!(&b[74..], b"hello");
+    assert_eq!(UnsignedMessage::from_bytes(&b).unwrap(), msg);
+
+    assert!(UnsignedMessage::from_bytes(&b[..b.len() - 1]).is_err());
+    assert!(UnsignedMessage::from_bytes(&b[..73]).is_err());
+    let mut unknown_version = b.clone();
+    unknown_version[1] = 1;
+    assert!(UnsignedMessage::from_bytes(&unknown_version).is_err());
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- warp::message::test_verify_source --exact --show-output
+#[test]
+fn test_verify_source() {
+    let c_chain = ids::Id::from_slice(&[1; 32]);
+    let subnet_evm = ids::Id::from_slice(&[2; 32]);
+    let unknown = ids::Id::from_slice(&[3; 32]);
+    let allowed = [c_chain, subnet_evm];
+
+    let msg = UnsignedMessage {
+        network_id: 5,
+        source_chain_id: subnet_evm,
+        payload: b"hello".to_vec(),
+        ..Default::default()
+    };
+    assert!(verify_source(&msg, 5, &allowed).is_ok());
+
+    let err = verify_source(
+        &UnsignedMessage {
+            source_chain_id: unknown,
+            ..msg.clone()
+        },
+        5,
+        &allowed,
+    )
+    .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+    assert!(err.to_string().contains(&unknown.to_string()));
+
+    // replayed from another network
+    let err = verify_source(&msg, 1, &allowed).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(err.to_string().contains("network ID 5"));
+    assert!(err.to_string().contains("expected network ID 1"));
+
+    assert!(verify_source(&msg, 5, &[]).is_err());
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- warp::message::test_verify_destination --exact --show-output
+#[test]
+fn test_verify_destination() {
+    let this_chain = ids::Id::from_slice(&[4; 32]);
+    let msg = UnsignedMessage {
+        network_id: 5,
+        source_chain_id: ids::Id::from_slice(&[2; 32]),
+        destination_chain_id: this_chain,
+        payload: b"hello".to_vec(),
+    };
+    assert!(verify_destination(&msg, &this_chain).is_ok());
+
+    let other_chain = ids::Id::from_slice(&[1; 32]);
+    let err = verify_destination(&msg, &other_chain).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(err.to_string().contains(&this_chain.to_string()));
+}
@@ -0,0 +1,10 @@
+//! Avalanche Warp Messaging.
+//!
+//! ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/vms/platformvm/warp>
+pub mod message;
+#[cfg(feature = "subnet")]
+#[cfg_attr(docsrs, doc(cfg(feature = "subnet")))]
+pub mod signer;
+pub mod validators;
+
+pub use message::{verify_destination, verify_source, UnsignedMessage};
@@ -0,0 +1,134 @@
+//! Requests to the local node to sign warp messages.
+use std::io::{Error, ErrorKind, Result};
+
+use bytes::Bytes;
+use tonic::transport::Channel;
+
+use crate::{
+    key::bls::{self, signature::Sig},
+    proto::warp::{signer_client, SignRequest, SignResponse},
+    warp::UnsignedMessage,
+};
+
+/// Request for the node to sign an unsigned warp message with its BLS key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureRequest {
+    pub message: UnsignedMessage,
+}
+
+impl SignatureRequest {
+    pub fn new(message: UnsignedMessage) -> Self {
+        Self { message }
+    }
+
+    /// Returns the bytes the node signs, see [`UnsignedMessage::bytes`].
+    pub fn bytes(&self) -> Vec<u8> {
+        self.message.bytes()
+    }
+
+    /// Asks the node to sign the message, and checks the signature against
+    /// the public key of the node before returning it. The sign request has
+    /// no network ID, as the node signs on its own network: a message for
+    /// another network fails the signature check.
+    pub async fn request_signature(&self, client: &SignerClient) -> Result<Sig> {
+        let mut inner = client.inner.clone();
+        let resp = inner
+            .sign(SignRequest {
+                source_chain_id: Bytes::from(self.message.source_chain_id.to_vec()),
+                destination_chain_id: Bytes::from(self.message.destination_chain_id.to_vec()),
+                payload: Bytes::from(self.message.payload.clone()),
+            })
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, format!("sign failed: {e}")))?
+            .into_inner();
+
+        self.verify_response(&resp, &client.public_key)
+    }
+
+    /// Parses the signature of the response and verifies it signs the
+    /// message under the public key.
+    pub fn verify_response(&self, resp: &SignResponse, public_key: &bls::PublicKey) -> Result<Sig> {
+        let sig = Sig::from_bytes(&resp.signature)?;
+        if !sig.verify(&self.bytes(), public_key) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "signature does not match the message under public key {}",
+                    public_key.to_hex()
+                ),
+            ));
+        }
+        Ok(sig)
+    }
+}
+
+/// Client of the warp signer of the local node, whose BLS public key
+/// verifies the signatures it returns.
+#[derive(Clone, Debug)]
+pub struct SignerClient {
+    inner: signer_client::SignerClient<Channel>,
+    public_key: bls::PublicKey,
+}
+
+impl SignerClient {
+    pub fn new(client_conn: Channel, public_key: bls::PublicKey) -> Self {
+        Self {
+            inner: signer_client::SignerClient::new(client_conn),
+            public_key,
+        }
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib --features subnet -- warp::signer::test_verify_response --exact --show-output
+#[test]
+fn test_verify_response() {
+    use crate::ids;
+
+    let key = bls::private_key::Key::from_bytes(&[7; 32]).unwrap();
+    let req = SignatureRequest::new(UnsignedMessage {
+        network_id: 5,
+        source_chain_id: ids::Id::from_slice(&[1; 32]),
+        destination_chain_id: ids::Id::from_slice(&[2; 32]),
+        payload: b"hello".to_vec(),
+    });
+
+    let b = req.bytes();
+
+    // fixture of what the signer returns
+    let resp = SignResponse {
+        signature: Bytes::from(key.sign(&b).to_compressed_bytes().to_vec()),
+    };
+    let sig = req.verify_response(&resp, &key.to_public_key()).unwrap();
+    assert_eq!(sig.to_compressed_bytes().to_vec(), resp.signature.to_vec());
+
+    // signed by another key
+    let other = bls::private_key::Key::from_bytes(&[8; 32]).unwrap();
+    let err = req
+        .verify_response(&resp, &other.to_public_key())
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+    // signature of another message
+    let tampered = SignatureRequest::new(UnsignedMessage {
+        payload: b"hellO".to_vec(),
+        ..req.message.clone()
+    });
+    assert!(tampered
+        .verify_response(&resp, &key.to_public_key())
+        .is_err());
+
+    // signature over another network
+    let other_network = SignatureRequest::new(UnsignedMessage {
+        network_id: 1,
+        ..req.message.clone()
+    });
+    assert!(other_network
+        .verify_response(&resp, &key.to_public_key())
+        .is_err());
+
+    // not a signature
+    let garbage = SignResponse {
+        signature: Bytes::from_static(&[1; 10]),
+    };
+    assert!(req.verify_response(&garbage, &key.to_public_key()).is_err());
+}
@@ -0,0 +1,268 @@
+//! Validator sets that sign warp messages.
+use std::io::{self, Error, ErrorKind};
+
+use crate::{hash, ids, key::bls};
+
+/// Validator of a canonical set, which may stand for several nodes
+/// registered with the same BLS public key.
+///
+/// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/vms/platformvm/warp#Validator>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Validator {
+    pub public_key: bls::PublicKey,
+    pub weight: u64,
+    pub node_ids: Vec<ids::node::Id>,
+}
+
+/// Validators with a BLS public key, merged by public key and sorted by
+/// the compressed public key bytes, so that equal sets are laid out the
+/// same way.
+///
+/// ref. <https://pkg.go.dev/github.com/ava-labs/avalanchego/vms/platformvm/warp#CanonicalValidatorSet>
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CanonicalValidatorSet {
+    pub validators: Vec<Validator>,
+    pub total_weight: u64,
+}
+
+impl CanonicalValidatorSet {
+    /// Builds the canonical set from the validators, in any order. Fails if
+    /// the total weight overflows u64.
+    pub fn new(validators: Vec<Validator>) -> io::Result<Self> {
+        let overflow = || Error::new(ErrorKind::InvalidInput, "total weight overflows u64");
+
+        let mut merged: Vec<Validator> = Vec::with_capacity(validators.len());
+        let mut total_weight = 0u64;
+        for vdr in validators {
+            total_weight = total_weight.checked_add(vdr.weight).ok_or_else(overflow)?;
+            match merged.iter_mut().find(|v| v.public_key == vdr.public_key) {
+                Some(existing) => {
+                    existing.weight = existing
+                        .weight
+                        .checked_add(vdr.weight)
+                        .ok_or_else(overflow)?;
+                    existing.node_ids.extend(vdr.node_ids);
+                }
+                None => merged.push(vdr),
+            }
+        }
+        for vdr in merged.iter_mut() {
+            vdr.node_ids.sort();
+        }
+        merged.sort_by_key(|vdr| vdr.public_key.to_compressed_bytes());
+
+        Ok(Self {
+            validators: merged,
+            total_weight,
+        })
+    }
+
+    /// Returns the SHA256 digest of the compressed public key and the
+    /// big-endian weight of each validator, in canonical order. Equal sets
+    /// have the same fingerprint (e.g., to cache warp verification results).
+    pub fn fingerprint(&self) -> [u8; 32] {
+        let mut b = Vec::with_capacity(self.validators.len() * (bls::public_key::LEN + 8));
+        for vdr in self.validators.iter() {
+            b.extend_from_slice(&vdr.public_key.to_compressed_bytes());
+            b.extend_from_slice(&vdr.weight.to_be_bytes());
+        }
+        hash::sha256(&b)
+            .try_into()
+            .expect("SHA256 digest is 32 bytes")
+    }
+}
+
+/// Change in the weight of a validator between two sets, where a weight
+/// of zero stands for a validator missing from the set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeightChange {
+    pub public_key: bls::PublicKey,
+    /// Nodes of the validator, as of the latest set it is in.
+    pub node_ids: Vec<ids::node::Id>,
+    pub before: u64,
+    pub after: u64,
+}
+
+impl WeightChange {
+    pub fn is_added(&self) -> bool {
+        self.before == 0
+    }
+
+    pub fn is_removed(&self) -> bool {
+        self.after == 0
+    }
+
+    /// Returns the signed weight change (e.g., negative when slashed).
+    pub fn delta(&self) -> i128 {
+        i128::from(self.after) - i128::from(self.before)
+    }
+}
+
+/// Returns the validators whose weight differs between the sets, including
+/// the ones added and removed, in canonical order. Validators are matched
+/// by BLS public key, not by node: the nodes sharing a key change as one,
+/// and a node moving to another key shows as the removal of its old key
+/// and the addition of the new one.
+pub fn weight_diff(
+    before: &CanonicalValidatorSet,
+    after: &CanonicalValidatorSet,
+) -> Vec<WeightChange> {
+    let mut changes = Vec::new();
+    for vdr in after.validators.iter() {
+        let prev = before
+            .validators
+            .iter()
+            .find(|v| v.public_key == vdr.public_key)
+            .map_or(0, |v| v.weight);
+        if prev != vdr.weight {
+            changes.push(WeightChange {
+                public_key: vdr.public_key,
+                node_ids: vdr.node_ids.clone(),
+                before: prev,
+                after: vdr.weight,
+            });
+        }
+    }
+    for vdr in before.validators.iter() {
+        if !after
+            .validators
+            .iter()
+            .any(|v| v.public_key == vdr.public_key)
+        {
+            changes.push(WeightChange {
+                public_key: vdr.public_key,
+                node_ids: vdr.node_ids.clone(),
+                before: vdr.weight,
+                after: 0,
+            });
+        }
+    }
+    changes.sort_by_key(|c| c.public_key.to_compressed_bytes());
+    changes
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- warp::validators::test_fingerprint --exact --show-output
+#[test]
+fn test_fingerprint() {
+    let keys: Vec<bls::PublicKey> = (0..3)
+        .map(|_| bls::private_key::Key::generate().unwrap().to_public_key())
+        .collect();
+    let vdr = |i: usize, weight: u64| Validator {
+        public_key: keys[i],
+        weight,
+        node_ids: vec![ids::node::Id::from_slice(&[i as u8; 20])],
+    };
+
+    // same validators in any order
+    let a = CanonicalValidatorSet::new(vec![vdr(0, 10), vdr(1, 20), vdr(2, 30)]).unwrap();
+    let b = CanonicalValidatorSet::new(vec![vdr(2, 30), vdr(0, 10), vdr(1, 20)]).unwrap();
+    assert_eq!(a, b);
+    assert_eq!(a.total_weight, 60);
+    assert_eq!(a.fingerprint(), b.fingerprint());
+
+    // a weight change is a different set
+    let c = CanonicalValidatorSet::new(vec![vdr(0, 10), vdr(1, 21), vdr(2, 30)]).unwrap();
+    assert_ne!(a.fingerprint(), c.fingerprint());
+
+    // so is a missing validator
+    let d = CanonicalValidatorSet::new(vec![vdr(0, 10), vdr(1, 20)]).unwrap();
+    assert_ne!(a.fingerprint(), d.fingerprint());
+
+    // nodes sharing a public key are merged
+    let e =
+        CanonicalValidatorSet::new(vec![vdr(0, 10), vdr(1, 5), vdr(1, 15), vdr(2, 30)]).unwrap();
+    assert_eq!(e.validators.len(), 3);
+    assert_eq!(e.fingerprint(), a.fingerprint());
+
+    assert!(CanonicalValidatorSet::new(vec![vdr(0, u64::MAX), vdr(1, 1)]).is_err());
+    assert_eq!(
+        CanonicalValidatorSet::default().fingerprint(),
+        <[u8; 32]>::try_from(hash::sha256([])).unwrap()
+    );
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- warp::validators::test_weight_diff --exact --show-output
+#[test]
+fn test_weight_diff() {
+    let keys: Vec<bls::PublicKey> = (0..4)
+        .map(|_| bls::private_key::Key::generate().unwrap().to_public_key())
+        .collect();
+    let vdr = |i: usize, weight: u64| Validator {
+        public_key: keys[i],
+        weight,
+        node_ids: vec![ids::node::Id::from_slice(&[i as u8; 20])],
+    };
+
+    let before = CanonicalValidatorSet::new(vec![vdr(0, 10), vdr(1, 20), vdr(2, 30)]).unwrap();
+    assert!(weight_diff(&before, &before).is_empty());
+
+    // 1 is slashed, 2 leaves, 3 joins
+    let after = CanonicalValidatorSet::new(vec![vdr(0, 10), vdr(1, 15), vdr(3, 40)]).unwrap();
+    let changes = weight_diff(&before, &after);
+    assert_eq!(changes.len(), 3);
+    let change = |i: usize| {
+        changes
+            .iter()
+            .find(|c| c.public_key == keys[i])
+            .unwrap()
+            .clone()
+    };
+
+    let slashed = change(1);
+    assert_eq!((slashed.before, slashed.after), (20, 15));
+    assert_eq!(slashed.delta(), -5);
+    assert!(!slashed.is_added() && !slashed.is_removed());
+
+    let removed = change(2);
+    assert!(removed.is_removed());
+    assert_eq!(removed.delta(), -30);
+    assert_eq!(removed.node_ids, vec![ids::node::Id::from_slice(&[2; 20])]);
+
+    let added = change(3);
+    assert!(added.is_added());
+    assert_eq!(added.delta(), 40);
+
+    // canonical order
+    let mut sorted = changes.clone();
+    sorted.sort_by_key(|c| c.public_key.to_compressed_bytes());
+    assert_eq!(changes, sorted);
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-types --lib -- warp::validators::test_weight_diff_shared_key --exact --show-output
+#[test]
+fn test_weight_diff_shared_key() {
+    let keys: Vec<bls::PublicKey> = (0..2)
+        .map(|_| bls::private_key::Key::generate().unwrap().to_public_key())
+        .collect();
+    let node = |i: u8| ids::node::Id::from_slice(&[i; 20]);
+    let vdr = |key: usize, node_id: ids::node::Id, weight: u64| Validator {
+        public_key: keys[key],
+        weight,
+        node_ids: vec![node_id],
+    };
+
+    // nodes 1 and 2 share key 0
+    let before =
+        CanonicalValidatorSet::new(vec![vdr(0, node(1), 10), vdr(0, node(2), 20)]).unwrap();
+
+    // weight moving between the nodes of a key is not a change
+    let after = CanonicalValidatorSet::new(vec![vdr(0, node(1), 20), vdr(0, node(2), 10)]).unwrap();
+    assert!(weight_diff(&before, &after).is_empty());
+
+    // node 2 leaves: one change for the key, listing the remaining node
+    let after = CanonicalValidatorSet::new(vec![vdr(0, node(1), 10)]).unwrap();
+    let changes = weight_diff(&before, &after);
+    assert_eq!(changes.len(), 1);
+    assert_eq!((changes[0].before, changes[0].after), (30, 10));
+    assert_eq!(changes[0].node_ids, vec![node(1)]);
+
+    // node 2 moves to key 1: its old key loses weight, the new one is added
+    let after = CanonicalValidatorSet::new(vec![vdr(0, node(1), 10), vdr(1, node(2), 20)]).unwrap();
+    let changes = crate::platformvm::validators::weight_diff(&before, &after);
+    assert_eq!(changes.len(), 2);
+    let moved_from = changes.iter().find(|c| c.public_key == keys[0]).unwrap();
+    assert_eq!(moved_from.delta(), -20);
+    let moved_to = changes.iter().find(|c| c.public_key == keys[1]).unwrap();
+    assert!(moved_to.is_added());
+    assert_eq!(moved_to.node_ids, vec![node(2)]);
+}
@@ -42,3 +42,80 @@ async fn rpcdb_mutation_test() {
         assert!(!res.is_err());
     }
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rpcdb_max_in_flight_test() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use avalanche_types::subnet::rpc::database::{
+        hookdb::{self, Database as HookDb},
+        BoxedDatabase,
+    };
+
+    const MAX_IN_FLIGHT: usize = 4;
+
+    let current = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    // tracks the "get" calls being served at once
+    let in_flight = HookDb::new(MemDb::new())
+        .with_before({
+            let (current, peak) = (Arc::clone(&current), Arc::clone(&peak));
+            move |method, _| {
+                if method == hookdb::Method::Get {
+                    let current = current.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(current, Ordering::SeqCst);
+                }
+                Ok(())
+            }
+        })
+        .with_after({
+            let current = Arc::clone(&current);
+            move |method| {
+                if method == hookdb::Method::Get {
+                    current.fetch_sub(1, Ordering::SeqCst);
+                }
+            }
+        })
+        .with_delay(hookdb::Method::Get, Duration::from_millis(20));
+    let server = RpcDb::new(Box::new(in_flight));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        serve_test_database(server, listener).await.unwrap();
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client_conn = Channel::builder(format!("http://{}", addr).parse().unwrap())
+        .connect()
+        .await
+        .unwrap();
+
+    let mut client: BoxedDatabase =
+        Box::new(DatabaseClient::new_client(client_conn).with_max_in_flight(MAX_IN_FLIGHT));
+    client
+        .put("foo".as_bytes(), "bar".as_bytes())
+        .await
+        .unwrap();
+
+    let mut futures = FuturesUnordered::new();
+    // 8 times more requests than the limit
+    for _ in 0..(MAX_IN_FLIGHT * 8) {
+        let client = client.clone();
+        futures.push(async move { client.get("foo".as_bytes()).await });
+    }
+
+    while let Some(res) = futures.next().await {
+        assert!(res.is_ok());
+    }
+    let peak = peak.load(Ordering::SeqCst);
+    assert!(
+        (1..=MAX_IN_FLIGHT).contains(&peak),
+        "peak in flight {}",
+        peak
+    );
+    assert_eq!(current.load(Ordering::SeqCst), 0);
+}
@@ -3,6 +3,7 @@ use std::time::Duration;
 use crate::rpc::common::*;
 use avalanche_types::subnet::rpc::database::{
     corruptabledb::Database as CorruptableDb,
+    iterator::{new_iterator_with_prefix_and_order, Order},
     memdb::Database as MemDb,
     rpcdb::{client::DatabaseClient, server::Server as RpcDb},
 };
@@ -146,6 +147,51 @@ async fn iterator_prefix_test() {
     let _ = iterator.release().await;
 }
 
+// Test to make sure a prefix scan over rpcdb can be reversed.
+#[tokio::test]
+async fn iterator_prefix_order_test() {
+    let server = RpcDb::new(MemDb::new());
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        serve_test_database(server, listener).await.unwrap();
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client_conn = Channel::builder(format!("http://{}", addr).parse().unwrap())
+        .connect()
+        .await
+        .unwrap();
+
+    let mut db = DatabaseClient::new(client_conn);
+    for height in [2u64, 10, 1] {
+        let mut key = b"height/".to_vec();
+        key.extend_from_slice(&height.to_be_bytes());
+        db.put(&key, &height.to_be_bytes()).await.unwrap();
+    }
+    db.put(b"other", b"skipped").await.unwrap();
+
+    for (order, expected) in [
+        (Order::Ascending, vec![1u64, 2, 10]),
+        (Order::Descending, vec![10, 2, 1]),
+    ] {
+        let mut iterator = new_iterator_with_prefix_and_order(&db, b"height/", order)
+            .await
+            .unwrap();
+        let mut heights = Vec::new();
+        while iterator.next().await.unwrap() {
+            heights.push(u64::from_be_bytes(
+                iterator.value().await.unwrap().try_into().unwrap(),
+            ));
+        }
+        assert_eq!(heights, expected);
+
+        // cleanup
+        let _ = iterator.release().await;
+    }
+}
+
 // Tests to make sure that an iterator on a database will report itself as being
 // exhausted and return [ErrClosed] to indicate that the iteration was not
 // successful. Additionally tests that an iterator that has already called
@@ -88,6 +88,30 @@ async fn rpcdb_mutation_test() {
     assert!(resp.unwrap_err().to_string().contains("database closed"));
 }
 
+#[tokio::test]
+async fn rpcdb_compact_test() {
+    use avalanche_types::subnet::rpc::database::Compacter;
+
+    let server = RpcDb::new(MemDb::new());
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        serve_test_database(server, listener).await.unwrap();
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client_conn = Channel::builder(format!("http://{}", addr).parse().unwrap())
+        .connect()
+        .await
+        .unwrap();
+    let client = DatabaseClient::new_client(client_conn);
+
+    // the request reaches the server, which cannot compact a boxed database
+    let err = client.compact(b"a", b"z").await.unwrap_err();
+    assert!(err.to_string().contains("compact"));
+}
+
 #[tokio::test]
 async fn corruptibledb_mutation_test() {
     let _ = env_logger::builder()
@@ -277,3 +301,167 @@ async fn test_db_manager() {
     assert!(resp.is_err());
     assert!(resp.unwrap_err().to_string().contains("database closed"));
 }
+
+#[tokio::test]
+async fn rpcdb_consistency_level_test() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use avalanche_types::subnet::rpc::database::{
+        batch::Batcher,
+        hookdb::{self, Database as HookDb},
+        rpcdb::client::ConsistencyLevel,
+        Closer, KeyValueReaderWriterDeleter,
+    };
+
+    let gets = Arc::new(AtomicUsize::new(0));
+    // counts the "get" calls reaching the server's database
+    let server = RpcDb::new(Box::new(HookDb::new(MemDb::new()).with_before({
+        let gets = Arc::clone(&gets);
+        move |method, _| {
+            if method == hookdb::Method::Get {
+                gets.fetch_add(1, Ordering::SeqCst);
+            }
+            Ok(())
+        }
+    })));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        serve_test_database(server, listener).await.unwrap();
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client_conn = Channel::builder(format!("http://{}", addr).parse().unwrap())
+        .connect()
+        .await
+        .unwrap();
+    let mut client = DatabaseClient::new_client(client_conn);
+    client.put(b"foo", b"bar").await.unwrap();
+
+    // first cached read goes to the server, later ones are served locally
+    let value = client
+        .get_with_consistency(b"foo", ConsistencyLevel::CachedOk)
+        .await
+        .unwrap();
+    assert_eq!(value, b"bar");
+    assert_eq!(gets.load(Ordering::SeqCst), 1);
+    for _ in 0..3 {
+        let value = client
+            .get_with_consistency(b"foo", ConsistencyLevel::CachedOk)
+            .await
+            .unwrap();
+        assert_eq!(value, b"bar");
+    }
+    assert_eq!(gets.load(Ordering::SeqCst), 1);
+
+    // strong reads always re-fetch
+    let value = client
+        .get_with_consistency(b"foo", ConsistencyLevel::Strong)
+        .await
+        .unwrap();
+    assert_eq!(value, b"bar");
+    assert_eq!(gets.load(Ordering::SeqCst), 2);
+    assert_eq!(client.get(b"foo").await.unwrap(), b"bar");
+    assert_eq!(gets.load(Ordering::SeqCst), 3);
+
+    // writes through the client evict the cached value
+    client.put(b"foo", b"baz").await.unwrap();
+    let value = client
+        .get_with_consistency(b"foo", ConsistencyLevel::CachedOk)
+        .await
+        .unwrap();
+    assert_eq!(value, b"baz");
+    assert_eq!(gets.load(Ordering::SeqCst), 4);
+
+    let mut batch = client.new_batch().await.unwrap();
+    batch.delete(b"foo").await.unwrap();
+    batch.write().await.unwrap();
+    let err = client
+        .get_with_consistency(b"foo", ConsistencyLevel::CachedOk)
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    assert_eq!(gets.load(Ordering::SeqCst), 5);
+
+    // the cache is bounded, evicting the oldest value first
+    let mut bounded = client.clone().with_max_cached_values(1);
+    bounded.put(b"a", b"1").await.unwrap();
+    bounded.put(b"b", b"2").await.unwrap();
+    for key in [b"a", b"b", b"a"] {
+        bounded
+            .get_with_consistency(key, ConsistencyLevel::CachedOk)
+            .await
+            .unwrap();
+    }
+    assert_eq!(gets.load(Ordering::SeqCst), 8);
+
+    client.close().await.unwrap();
+    let err = client
+        .get_with_consistency(b"foo", ConsistencyLevel::CachedOk)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("database closed"));
+}
+
+#[tokio::test]
+async fn rpcdb_circuit_breaker_test() {
+    use std::time::Instant;
+
+    use avalanche_types::subnet::rpc::database::{
+        rpcdb::client::breaker::{is_circuit_open, CircuitBreaker, State},
+        KeyValueReaderWriterDeleter,
+    };
+
+    const COOLDOWN: Duration = Duration::from_millis(200);
+
+    // nothing listens on the address until the server comes back
+    let addr = TcpListener::bind("127.0.0.1:0")
+        .await
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let client_conn = Channel::builder(format!("http://{}", addr).parse().unwrap())
+        .connect_timeout(Duration::from_millis(100))
+        .connect_lazy();
+    let breaker = CircuitBreaker::new(3, COOLDOWN);
+    let mut client = DatabaseClient::new_client(client_conn).with_circuit_breaker(breaker.clone());
+
+    for _ in 0..3 {
+        let err = client.get(b"foo").await.unwrap_err();
+        assert!(!is_circuit_open(&err), "{}", err);
+    }
+    assert_eq!(breaker.state("get"), State::Open);
+
+    // rejected without a request while open
+    let started = Instant::now();
+    for _ in 0..10 {
+        let err = client.get(b"foo").await.unwrap_err();
+        assert!(is_circuit_open(&err), "{}", err);
+    }
+    assert!(started.elapsed() < COOLDOWN);
+
+    // other operations keep their own circuit
+    let err = client.put(b"foo", b"bar").await.unwrap_err();
+    assert!(!is_circuit_open(&err), "{}", err);
+
+    let listener = TcpListener::bind(addr).await.unwrap();
+    tokio::spawn(async move {
+        serve_test_database(RpcDb::new(MemDb::new()), listener)
+            .await
+            .unwrap();
+    });
+    tokio::time::sleep(COOLDOWN).await;
+
+    // the probe after the cooldown finds the server back
+    assert_eq!(breaker.state("get"), State::HalfOpen);
+    let err = client.get(b"foo").await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    assert_eq!(breaker.state("get"), State::Closed);
+
+    client.put(b"foo", b"bar").await.unwrap();
+    assert_eq!(client.get(b"foo").await.unwrap(), b"bar");
+}